@@ -0,0 +1,153 @@
+use anyhow::Result;
+use criterion::black_box;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::Criterion;
+use futuresdr::blocks::Head;
+use futuresdr::blocks::NullSink;
+use futuresdr::blocks::NullSource;
+use futuresdr::macros::async_trait;
+use futuresdr::runtime::buffer::circular::Circular;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Runtime;
+use futuresdr::runtime::StreamIo;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::SyncKernel;
+use futuresdr::runtime::TypedBlock;
+use futuresdr::runtime::WorkIo;
+
+const N_COPY: usize = 6;
+
+fn copy(io: &mut WorkIo, sio: &mut StreamIo) {
+    let i = sio.input(0).slice::<f32>();
+    let o = sio.output(0).slice::<f32>();
+
+    let m = std::cmp::min(i.len(), o.len());
+    if m > 0 {
+        o[..m].copy_from_slice(&i[..m]);
+        sio.input(0).consume(m);
+        sio.output(0).produce(m);
+    }
+
+    if sio.input(0).finished() && m == i.len() {
+        io.finished = true;
+    }
+}
+
+fn stream_io() -> StreamIo {
+    StreamIoBuilder::new()
+        .add_input::<f32>("in")
+        .add_output::<f32>("out")
+        .build()
+}
+
+/// Copy block, implementing the boxed, async work function
+struct AsyncCopy;
+
+impl AsyncCopy {
+    fn new() -> TypedBlock<Self> {
+        TypedBlock::new(
+            BlockMetaBuilder::new("AsyncCopy").build(),
+            stream_io(),
+            MessageIoBuilder::new().build(),
+            AsyncCopy,
+        )
+    }
+}
+
+#[async_trait]
+impl Kernel for AsyncCopy {
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> futuresdr::runtime::Result<()> {
+        copy(io, sio);
+        Ok(())
+    }
+}
+
+/// Copy block, implementing the synchronous work function
+struct SyncCopy;
+
+impl SyncCopy {
+    fn new() -> TypedBlock<Self> {
+        TypedBlock::new(
+            BlockMetaBuilder::new("SyncCopy").build(),
+            stream_io(),
+            MessageIoBuilder::new().build(),
+            SyncCopy,
+        )
+    }
+}
+
+impl SyncKernel for SyncCopy {
+    fn work(
+        &mut self,
+        io: &mut WorkIo,
+        sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> futuresdr::runtime::Result<()> {
+        copy(io, sio);
+        Ok(())
+    }
+}
+
+fn run_fg(n_samp: u64, sync: bool) -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let mut last = fg.add_block(NullSource::<f32>::new())?;
+    let head = fg.add_block(Head::<f32>::new(n_samp))?;
+    // use the smallest possible buffers to maximize the number of work calls
+    fg.connect_stream_with_type(last, "out", head, "in", Circular::with_size(1))?;
+    last = head;
+
+    for _ in 0..N_COPY {
+        let copy = if sync {
+            fg.add_block(SyncCopy::new())?
+        } else {
+            fg.add_block(AsyncCopy::new())?
+        };
+        fg.connect_stream_with_type(last, "out", copy, "in", Circular::with_size(1))?;
+        last = copy;
+    }
+
+    let snk = fg.add_block(NullSink::<f32>::new())?;
+    fg.connect_stream_with_type(last, "out", snk, "in", Circular::with_size(1))?;
+
+    Runtime::new().run(fg)?;
+    Ok(())
+}
+
+pub fn work(c: &mut Criterion) {
+    let n_samp = 1_000_000;
+
+    let mut group = c.benchmark_group("work");
+
+    group.throughput(criterion::Throughput::Elements(n_samp));
+
+    group.bench_function(format!("async-{N_COPY}-copy-{n_samp}"), |b| {
+        b.iter(|| {
+            run_fg(black_box(n_samp), false).unwrap();
+        });
+    });
+
+    group.bench_function(format!("sync-{N_COPY}-copy-{n_samp}"), |b| {
+        b.iter(|| {
+            run_fg(black_box(n_samp), true).unwrap();
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, work);
+criterion_main!(benches);
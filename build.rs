@@ -32,8 +32,41 @@ fn gen_lttng_tracepoints() {
     std::fs::write(output_file_name, bindings).expect("writing back bindings failed");
 }
 
+/// FNV-1a hash of the enabled features and the profile
+///
+/// Blocks have a different layout depending on the features, so plugins have to be built with the
+/// same ones as the host.
+fn build_fingerprint() -> String {
+    let mut features = std::env::vars()
+        .filter_map(|(k, _)| k.strip_prefix("CARGO_FEATURE_").map(str::to_string))
+        .collect::<Vec<_>>();
+    features.sort();
+    let profile = std::env::var("PROFILE").unwrap();
+
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for s in std::iter::once(&profile).chain(features.iter()) {
+        for b in s.bytes().chain(std::iter::once(0)) {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    format!("{hash:016x}")
+}
+
 fn main() {
-    match version_meta().unwrap().channel {
+    println!(
+        "cargo:rustc-env=FUTURESDR_BUILD_FINGERPRINT={}",
+        build_fingerprint()
+    );
+
+    let version = version_meta().unwrap();
+    // Plugins are only compatible with hosts that were compiled with the same rustc.
+    println!(
+        "cargo:rustc-env=FUTURESDR_RUSTC_VERSION={}",
+        version.short_version_string
+    );
+
+    match version.channel {
         Channel::Stable => {
             println!("cargo:rustc-cfg=RUSTC_IS_STABLE");
         }
@@ -0,0 +1,336 @@
+//! Command line tool to inspect and control FutureSDR runtimes through their control port.
+use anyhow::anyhow;
+use anyhow::Result;
+use clap::Parser;
+use clap::Subcommand;
+use clap::ValueEnum;
+use futures::StreamExt;
+use futuresdr_remote::Block;
+use futuresdr_remote::Handler;
+use futuresdr_remote::Remote;
+use futuresdr_types::HandlerSchema;
+use futuresdr_types::Pmt;
+use futuresdr_types::PmtKind;
+use futuresdr_types::PortId;
+use std::time::Duration;
+use std::time::Instant;
+
+mod render;
+
+#[derive(Parser, Debug)]
+#[clap(version, about = "Inspect and control FutureSDR runtimes")]
+struct Args {
+    /// URL of the control port of the runtime
+    #[clap(
+        short,
+        long,
+        env = "FUTURESDR_URL",
+        default_value = "http://127.0.0.1:1337"
+    )]
+    url: String,
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// List flowgraphs
+    #[clap(alias = "ls")]
+    Flowgraphs,
+    /// List blocks of a flowgraph
+    Blocks {
+        /// Flowgraph id
+        fg: usize,
+    },
+    /// Print blocks and connections of a flowgraph
+    Describe {
+        /// Flowgraph id
+        fg: usize,
+        /// Output format
+        #[clap(short, long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+    /// Print ports and handlers of a block
+    Block {
+        /// Flowgraph id
+        fg: usize,
+        /// Block id or instance name
+        block: String,
+    },
+    /// Call a message handler, printing its return value
+    Call {
+        /// Flowgraph id
+        fg: usize,
+        /// Block id or instance name
+        block: String,
+        /// Handler id or name
+        handler: String,
+        /// Argument, `Null` if not set
+        value: Option<String>,
+        /// Pmt kind of the argument, e.g., `F64` or `VecF32`
+        #[clap(short = 't', long = "type")]
+        kind: Option<String>,
+    },
+    /// Call a message handler periodically with `Null`, printing its return value
+    Watch {
+        /// Flowgraph id
+        fg: usize,
+        /// Block id or instance name
+        block: String,
+        /// Handler id or name
+        handler: String,
+        /// Interval in seconds
+        #[clap(short, long, default_value = "1", value_parser = parse_interval)]
+        interval: Duration,
+    },
+    /// Print messages that a block posts to a message output
+    Tap {
+        /// Flowgraph id
+        fg: usize,
+        /// Block id or instance name
+        block: String,
+        /// Message output id or name
+        port: String,
+    },
+    /// Print events of a flowgraph
+    Events {
+        /// Flowgraph id
+        fg: usize,
+    },
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Format {
+    Table,
+    Mermaid,
+    Dot,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let remote = Remote::new(args.url);
+
+    match args.command {
+        Command::Flowgraphs => {
+            let rows: Vec<Vec<String>> = remote
+                .flowgraphs()
+                .await?
+                .iter()
+                .map(|fg| {
+                    let d = fg.description();
+                    vec![
+                        fg.id().to_string(),
+                        d.blocks.len().to_string(),
+                        d.stream_edges.len().to_string(),
+                        d.message_edges.len().to_string(),
+                    ]
+                })
+                .collect();
+            print!(
+                "{}",
+                render::table(&["Id", "Blocks", "Stream Edges", "Message Edges"], &rows)
+            );
+        }
+        Command::Blocks { fg } => {
+            let fg = remote.flowgraph(fg).await?;
+            print!("{}", render::blocks(fg.description()));
+        }
+        Command::Describe { fg, format } => {
+            let fg = remote.flowgraph(fg).await?;
+            let d = fg.description();
+            match format {
+                Format::Table => print!("{}", render::flowgraph(d)),
+                Format::Mermaid => print!("{}", d.to_mermaid()),
+                Format::Dot => print!("{}", d.to_dot()),
+            }
+        }
+        Command::Block { fg, block } => {
+            let b = find_block(&remote, fg, &block).await?;
+            print!("{}", render::block(b.description()));
+        }
+        Command::Call {
+            fg,
+            block,
+            handler,
+            value,
+            kind,
+        } => {
+            let b = find_block(&remote, fg, &block).await?;
+            let handler = parse_handler(&handler);
+            let kind = kind
+                .map(|k| k.parse::<PmtKind>())
+                .transpose()
+                .map_err(|_| anyhow!("unknown Pmt kind"))?;
+            let arg = parse_value(value.as_deref(), kind, b.schema(&handler))?;
+            println!("{}", b.callback(handler, arg).await?);
+        }
+        Command::Watch {
+            fg,
+            block,
+            handler,
+            interval,
+        } => {
+            let b = find_block(&remote, fg, &block).await?;
+            let handler = parse_handler(&handler);
+            let start = Instant::now();
+            let mut ticks = tokio::time::interval(interval);
+            loop {
+                ticks.tick().await;
+                let p = b.callback(handler.clone(), Pmt::Null).await?;
+                println!("{:>10.3}s  {}", start.elapsed().as_secs_f64(), p);
+            }
+        }
+        Command::Tap { fg, block, port } => {
+            let b = find_block(&remote, fg, &block).await?;
+            let port = match port.parse::<usize>() {
+                Ok(i) => PortId::Index(i),
+                Err(_) => PortId::Name(port),
+            };
+            let mut messages = b.tap(port).await?;
+            while let Some(p) = messages.next().await {
+                match p? {
+                    Pmt::Finished => break,
+                    p => println!("{p}"),
+                }
+            }
+        }
+        Command::Events { fg } => {
+            let fg = remote.flowgraph(fg).await?;
+            let mut events = fg.events().await?;
+            while let Some(e) = events.next().await {
+                println!("{:?}", e?);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Get block by id or instance name
+async fn find_block(remote: &Remote, fg: usize, block: &str) -> Result<Block> {
+    let fg = remote.flowgraph(fg).await?;
+    match block.parse::<usize>() {
+        Ok(id) => fg.block(id),
+        Err(_) => fg.block_by_name(block),
+    }
+    .ok_or_else(|| anyhow!("flowgraph {} has no block '{block}'", fg.id()))
+}
+
+fn parse_handler(s: &str) -> Handler {
+    match s.parse::<usize>() {
+        Ok(i) => Handler::Id(i),
+        Err(_) => Handler::Name(s.to_string()),
+    }
+}
+
+/// Parse the argument of a handler
+///
+/// Uses the given kind or else the first kind, accepted by the handler, that can be parsed. If the
+/// handler does not declare what it accepts, the kind is guessed.
+fn parse_value(
+    value: Option<&str>,
+    kind: Option<PmtKind>,
+    schema: Option<&HandlerSchema>,
+) -> Result<Pmt> {
+    let Some(value) = value else {
+        return Ok(Pmt::Null);
+    };
+
+    if let Some(k) = kind {
+        return Pmt::from_string(value, &k).ok_or_else(|| anyhow!("cannot parse '{value}' as {k}"));
+    }
+
+    match schema {
+        Some(s) if !s.accepts.is_empty() => {
+            // Prefer lossless kinds, e.g., a frequency of 2412000001 is not representable as F32,
+            // and only fall back to a string if nothing else matches.
+            let mut kinds = s.accepts.iter().collect::<Vec<_>>();
+            kinds.sort_by_key(|k| match k {
+                PmtKind::F32 => 1,
+                PmtKind::String => 2,
+                _ => 0,
+            });
+            kinds
+                .into_iter()
+                .find_map(|k| Pmt::from_string(value, k))
+                .ok_or_else(|| anyhow!("cannot parse '{value}' as any of {:?}", s.accepts))
+        }
+        _ => Ok([PmtKind::Bool, PmtKind::U64, PmtKind::Isize, PmtKind::F64]
+            .iter()
+            .find_map(|k| Pmt::from_string(value, k))
+            .unwrap_or_else(|| Pmt::String(value.to_string()))),
+    }
+}
+
+fn parse_interval(s: &str) -> std::result::Result<Duration, String> {
+    match s.parse::<f64>() {
+        Ok(i) if i > 0.0 => Duration::try_from_secs_f64(i).map_err(|e| e.to_string()),
+        _ => Err(format!("'{s}' is not a positive number of seconds")),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(parse_value(None, None, None).unwrap(), Pmt::Null);
+        assert_eq!(
+            parse_value(Some("true"), None, None).unwrap(),
+            Pmt::Bool(true)
+        );
+        assert_eq!(parse_value(Some("42"), None, None).unwrap(), Pmt::U64(42));
+        assert_eq!(parse_value(Some("-1"), None, None).unwrap(), Pmt::Isize(-1));
+        assert_eq!(parse_value(Some("1e6"), None, None).unwrap(), Pmt::F64(1e6));
+        assert_eq!(
+            parse_value(Some("foo"), None, None).unwrap(),
+            Pmt::String("foo".to_string())
+        );
+
+        let schema = HandlerSchema::new().accepts([PmtKind::F32, PmtKind::String]);
+        assert_eq!(
+            parse_value(Some("42"), None, Some(&schema)).unwrap(),
+            Pmt::F32(42.0)
+        );
+        assert_eq!(
+            parse_value(Some("42"), Some(PmtKind::U32), Some(&schema)).unwrap(),
+            Pmt::U32(42)
+        );
+        assert!(parse_value(Some("x"), Some(PmtKind::U32), None).is_err());
+
+        let schema = HandlerSchema::new().accepts([PmtKind::F32, PmtKind::U64]);
+        assert_eq!(
+            parse_value(Some("2412000001"), None, Some(&schema)).unwrap(),
+            Pmt::U64(2412000001)
+        );
+        let schema = HandlerSchema::new().accepts([PmtKind::F32, PmtKind::F64]);
+        assert_eq!(
+            parse_value(Some("2.412000001e9"), None, Some(&schema)).unwrap(),
+            Pmt::F64(2412000001.0)
+        );
+        assert_eq!(
+            parse_value(Some("0.5"), None, Some(&schema)).unwrap(),
+            Pmt::F64(0.5)
+        );
+    }
+
+    #[test]
+    fn interval() {
+        assert_eq!(parse_interval("0.5").unwrap(), Duration::from_millis(500));
+        assert!(parse_interval("0").is_err());
+        assert!(parse_interval("-1").is_err());
+        assert!(parse_interval("NaN").is_err());
+        assert!(parse_interval("1e300").is_err());
+    }
+
+    #[test]
+    fn table() {
+        let t = render::table(
+            &["Id", "Name"],
+            &[vec!["0".to_string(), "source".to_string()]],
+        );
+        assert_eq!(t, "Id  Name\n--  ------\n0   source\n");
+    }
+}
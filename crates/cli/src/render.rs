@@ -0,0 +1,191 @@
+use futuresdr_types::BlockDescription;
+use futuresdr_types::FlowgraphDescription;
+use futuresdr_types::HandlerSchema;
+
+/// Format rows as table with aligned columns
+pub fn table(header: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = header.iter().map(|h| h.len()).collect();
+    for row in rows {
+        for (w, c) in widths.iter_mut().zip(row) {
+            *w = (*w).max(c.len());
+        }
+    }
+
+    let line = |cells: &[&str]| {
+        let s: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(c, w)| format!("{c:w$}"))
+            .collect();
+        s.join("  ").trim_end().to_string() + "\n"
+    };
+
+    let mut t = line(header);
+    let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+    t.push_str(&line(&rule.iter().map(|r| r.as_str()).collect::<Vec<_>>()));
+    for row in rows {
+        t.push_str(&line(&row.iter().map(|c| c.as_str()).collect::<Vec<_>>()));
+    }
+    t
+}
+
+/// Table of the blocks of a flowgraph
+pub fn blocks(fg: &FlowgraphDescription) -> String {
+    let rows: Vec<Vec<String>> = fg
+        .blocks
+        .iter()
+        .map(|b| {
+            vec![
+                b.id.to_string(),
+                b.instance_name.clone(),
+                b.type_name.clone(),
+                b.stream_inputs.join(", "),
+                b.stream_outputs.join(", "),
+                b.message_inputs.join(", "),
+                b.message_outputs.join(", "),
+            ]
+        })
+        .collect();
+    table(
+        &[
+            "Id",
+            "Name",
+            "Type",
+            "Stream In",
+            "Stream Out",
+            "Message In",
+            "Message Out",
+        ],
+        &rows,
+    )
+}
+
+/// Blocks and connections of a flowgraph
+pub fn flowgraph(fg: &FlowgraphDescription) -> String {
+    let mut s = blocks(fg);
+
+    let rows: Vec<Vec<String>> = fg
+        .stream_edges
+        .iter()
+        .map(|(src, src_port, dst, dst_port)| {
+            let buffer = fg
+                .stream_buffers
+                .iter()
+                .find(|b| b.0 == *src && b.1 == *src_port)
+                .map(|b| b.2.clone())
+                .unwrap_or_default();
+            vec![
+                endpoint(fg, *src, |b| &b.stream_outputs, *src_port),
+                endpoint(fg, *dst, |b| &b.stream_inputs, *dst_port),
+                buffer,
+            ]
+        })
+        .collect();
+    s.push('\n');
+    s.push_str(&table(&["Stream Output", "Stream Input", "Buffer"], &rows));
+
+    let rows: Vec<Vec<String>> = fg
+        .message_edges
+        .iter()
+        .map(|(src, src_port, dst, dst_port)| {
+            vec![
+                endpoint(fg, *src, |b| &b.message_outputs, *src_port),
+                endpoint(fg, *dst, |b| &b.message_inputs, *dst_port),
+            ]
+        })
+        .collect();
+    s.push('\n');
+    s.push_str(&table(&["Message Output", "Message Input"], &rows));
+    s
+}
+
+/// Ports and handlers of a block
+pub fn block(b: &BlockDescription) -> String {
+    let mut s = format!(
+        "{} ({}, id {}{})\n\n",
+        b.instance_name,
+        b.type_name,
+        b.id,
+        if b.blocking { ", blocking" } else { "" }
+    );
+    s.push_str(&format!(
+        "Stream inputs:   {}\n",
+        b.stream_inputs.join(", ")
+    ));
+    s.push_str(&format!(
+        "Stream outputs:  {}\n",
+        b.stream_outputs.join(", ")
+    ));
+    s.push_str(&format!(
+        "Message outputs: {}\n\n",
+        b.message_outputs.join(", ")
+    ));
+
+    let rows: Vec<Vec<String>> = b
+        .message_inputs
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let schema = b
+                .message_input_schemas
+                .get(i)
+                .cloned()
+                .flatten()
+                .unwrap_or_default();
+            handler(i, name, &schema)
+        })
+        .collect();
+    s.push_str(&table(
+        &[
+            "Id",
+            "Handler",
+            "Accepts",
+            "Returns",
+            "Range",
+            "Description",
+        ],
+        &rows,
+    ));
+    s
+}
+
+fn handler(id: usize, name: &str, schema: &HandlerSchema) -> Vec<String> {
+    let kinds = |k: &[futuresdr_types::PmtKind]| {
+        k.iter()
+            .map(|k| k.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let range = match (schema.min, schema.max) {
+        (None, None) => String::new(),
+        (min, max) => format!(
+            "[{}, {}]",
+            min.map(|m| m.to_string()).unwrap_or_default(),
+            max.map(|m| m.to_string()).unwrap_or_default()
+        ),
+    };
+    vec![
+        id.to_string(),
+        name.to_string(),
+        kinds(&schema.accepts),
+        kinds(&schema.returns),
+        range,
+        schema.description.clone().unwrap_or_default(),
+    ]
+}
+
+fn endpoint(
+    fg: &FlowgraphDescription,
+    block: usize,
+    ports: impl Fn(&BlockDescription) -> &Vec<String>,
+    port: usize,
+) -> String {
+    match fg.blocks.iter().find(|b| b.id == block) {
+        Some(b) => format!(
+            "{}.{}",
+            b.instance_name,
+            ports(b).get(port).cloned().unwrap_or(port.to_string())
+        ),
+        None => format!("{block}.{port}"),
+    }
+}
@@ -0,0 +1,25 @@
+use std::env;
+use std::path::PathBuf;
+
+/// Generate the C header to `OUT_DIR`
+///
+/// With `FUTURESDR_FFI_UPDATE_HEADER` set, the header is also written to `include/futuresdr.h`,
+/// which is checked in.
+fn main() {
+    let dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap());
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-env-changed=FUTURESDR_FFI_UPDATE_HEADER");
+
+    let config = cbindgen::Config::from_file(dir.join("cbindgen.toml")).unwrap();
+    let bindings = cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(dir.join("src/lib.rs"))
+        .generate()
+        .expect("Unable to generate C header");
+    bindings.write_to_file(out.join("futuresdr.h"));
+    if env::var_os("FUTURESDR_FFI_UPDATE_HEADER").is_some() {
+        bindings.write_to_file(dir.join("include/futuresdr.h"));
+    }
+}
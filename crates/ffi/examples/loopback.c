@@ -0,0 +1,69 @@
+// Push samples through a FIR filter and read them back.
+//
+// cargo build --release --manifest-path=crates/ffi/Cargo.toml
+// cc crates/ffi/examples/loopback.c -Icrates/ffi/include -Lcrates/ffi/target/release -lfuturesdr_ffi -o loopback
+// LD_LIBRARY_PATH=crates/ffi/target/release ./loopback
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "futuresdr.h"
+
+#define CHECK(x)                                                                    \
+    do {                                                                            \
+        FsdrStatus s = (x);                                                         \
+        if (s != FSDR_STATUS_OK) {                                                  \
+            fprintf(stderr, "%s failed (%d): %s\n", #x, s, fsdr_last_error());     \
+            exit(1);                                                                \
+        }                                                                           \
+    } while (0)
+
+int main(void) {
+    FsdrRuntime *rt;
+    FsdrFlowgraph *fg;
+    FsdrSource *src;
+    FsdrSink *snk;
+    FsdrHandle *handle;
+
+    printf("FutureSDR %s\n", fsdr_version());
+
+    CHECK(fsdr_runtime_new(&rt));
+    CHECK(fsdr_flowgraph_new(&fg));
+    CHECK(fsdr_flowgraph_add_source(fg, "src", FSDR_ITEM_TYPE_F32, 4096, &src));
+    CHECK(fsdr_flowgraph_add_sink(fg, "snk", FSDR_ITEM_TYPE_F32, 4096, &snk));
+    CHECK(fsdr_flowgraph_connect(fg,
+                                 "fir = Fir(taps = {\"VecF32\": [0.5, 0.5]})\n"
+                                 "msg = MessageSink\n"
+                                 "src > fir > snk\n"));
+    CHECK(fsdr_runtime_start(rt, fg, &handle));
+
+    char *result;
+    CHECK(fsdr_handle_call_json(handle, "msg", "in", "{\"String\": \"hello\"}", &result));
+    printf("handler returned %s\n", result);
+    fsdr_string_free(result);
+
+    float in[1024];
+    for (int i = 0; i < 1024; i++) {
+        in[i] = (float)i;
+    }
+    CHECK(fsdr_source_push(src, in, 1024, -1, NULL));
+    fsdr_source_free(src);
+
+    float out[256];
+    size_t total = 0;
+    for (;;) {
+        size_t n;
+        FsdrStatus s = fsdr_sink_pull(snk, out, 256, 1000, &n);
+        if (s == FSDR_STATUS_FINISHED) {
+            break;
+        }
+        CHECK(s);
+        total += n;
+    }
+    printf("received %zu samples\n", total);
+
+    CHECK(fsdr_handle_terminate(handle));
+    fsdr_sink_free(snk);
+    fsdr_handle_free(handle);
+    fsdr_runtime_free(rt);
+    return 0;
+}
@@ -0,0 +1,412 @@
+#ifndef FUTURESDR_H
+#define FUTURESDR_H
+
+/* Generated by cbindgen from the futuresdr-ffi crate. Do not edit. */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * Item type of a bridge port
+ */
+typedef enum FsdrItemType {
+  /**
+   * `uint8_t`
+   */
+  FSDR_ITEM_TYPE_U8 = 0,
+  /**
+   * `uint16_t`
+   */
+  FSDR_ITEM_TYPE_U16 = 1,
+  /**
+   * `uint32_t`
+   */
+  FSDR_ITEM_TYPE_U32 = 2,
+  /**
+   * `uint64_t`
+   */
+  FSDR_ITEM_TYPE_U64 = 3,
+  /**
+   * `int8_t`
+   */
+  FSDR_ITEM_TYPE_I8 = 4,
+  /**
+   * `int16_t`
+   */
+  FSDR_ITEM_TYPE_I16 = 5,
+  /**
+   * `int32_t`
+   */
+  FSDR_ITEM_TYPE_I32 = 6,
+  /**
+   * `int64_t`
+   */
+  FSDR_ITEM_TYPE_I64 = 7,
+  /**
+   * `float`
+   */
+  FSDR_ITEM_TYPE_F32 = 8,
+  /**
+   * `double`
+   */
+  FSDR_ITEM_TYPE_F64 = 9,
+  /**
+   * Complex `float`, i.e., interleaved real and imaginary parts
+   */
+  FSDR_ITEM_TYPE_C32 = 10,
+} FsdrItemType;
+
+/**
+ * Status code returned by the fallible functions of the API
+ *
+ * The message of the last error of the calling thread is available through [`fsdr_last_error`].
+ */
+typedef enum FsdrStatus {
+  /**
+   * Success
+   */
+  FSDR_STATUS_OK = 0,
+  /**
+   * Null pointer, invalid UTF-8, or otherwise invalid argument
+   */
+  FSDR_STATUS_INVALID_ARGUMENT = 1,
+  /**
+   * Invalid flowgraph description, e.g., a syntax error or an unknown block type or port
+   */
+  FSDR_STATUS_DESCRIPTION = 2,
+  /**
+   * Block or handler does not exist or the handler failed
+   */
+  FSDR_STATUS_HANDLER = 3,
+  /**
+   * [`Pmt`](futuresdr::runtime::Pmt) could not be decoded or encoded
+   */
+  FSDR_STATUS_PMT = 4,
+  /**
+   * Timeout expired before the operation completed
+   */
+  FSDR_STATUS_TIMEOUT = 5,
+  /**
+   * Flowgraph or sample stream finished
+   */
+  FSDR_STATUS_FINISHED = 6,
+  /**
+   * Other error of the runtime
+   */
+  FSDR_STATUS_RUNTIME = 7,
+  /**
+   * Panic inside the library, caught at the API boundary
+   */
+  FSDR_STATUS_PANIC = 8,
+} FsdrStatus;
+
+/**
+ * Flowgraph under construction
+ */
+typedef struct FsdrFlowgraph FsdrFlowgraph;
+
+/**
+ * Handle to a running flowgraph
+ */
+typedef struct FsdrHandle FsdrHandle;
+
+/**
+ * Runtime that executes flowgraphs
+ */
+typedef struct FsdrRuntime FsdrRuntime;
+
+/**
+ * C side of a bridge sink, pulling samples out of a flowgraph
+ */
+typedef struct FsdrSink FsdrSink;
+
+/**
+ * C side of a bridge source, pushing samples into a flowgraph
+ */
+typedef struct FsdrSource FsdrSource;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Version of the library as a static, NUL-terminated string
+ */
+const char *fsdr_version(void);
+
+/**
+ * Create a runtime
+ *
+ * This also starts the control port, if it is enabled in the configuration.
+ *
+ * # Safety
+ *
+ * `runtime` has to be a valid pointer. The returned runtime is released with
+ * [`fsdr_runtime_free`].
+ */
+enum FsdrStatus fsdr_runtime_new(struct FsdrRuntime **runtime);
+
+/**
+ * Release a runtime
+ *
+ * Running flowgraphs are not terminated and keep their handles valid.
+ *
+ * # Safety
+ *
+ * `runtime` has to be `NULL` or a runtime created with [`fsdr_runtime_new`] that is not used
+ * afterwards.
+ */
+void fsdr_runtime_free(struct FsdrRuntime *runtime);
+
+/**
+ * Create an empty flowgraph
+ *
+ * # Safety
+ *
+ * `fg` has to be a valid pointer. The flowgraph is consumed by [`fsdr_runtime_start`] or
+ * released with [`fsdr_flowgraph_free`].
+ */
+enum FsdrStatus fsdr_flowgraph_new(struct FsdrFlowgraph **fg);
+
+/**
+ * Add and connect blocks, using the syntax of [`Flowgraph::connect_str`]
+ *
+ * Blocks are created with the core block registry. Bridge ports added before are referred to by
+ * their name.
+ *
+ * # Safety
+ *
+ * `fg` has to be a valid flowgraph and `description` a NUL-terminated string.
+ */
+enum FsdrStatus fsdr_flowgraph_connect(struct FsdrFlowgraph *fg, const char *description);
+
+/**
+ * Add a source block with an `out` port, fed with samples through [`fsdr_source_push`]
+ *
+ * `item_type` is one of the [`FsdrItemType`] values. The block queues up to `capacity` items. It
+ * finishes, once the source is released with [`fsdr_source_free`] and all queued items are
+ * consumed.
+ *
+ * # Safety
+ *
+ * `fg` has to be a valid flowgraph, `name` a NUL-terminated string, and `source` a valid
+ * pointer. The returned source is released with [`fsdr_source_free`].
+ */
+enum FsdrStatus fsdr_flowgraph_add_source(struct FsdrFlowgraph *fg,
+                                          const char *name,
+                                          uint32_t item_type,
+                                          size_t capacity,
+                                          struct FsdrSource **source);
+
+/**
+ * Add a sink block with an `in` port, whose samples are read through [`fsdr_sink_pull`]
+ *
+ * `item_type` is one of the [`FsdrItemType`] values. The block queues up to `capacity` items and
+ * waits, while the queue is full.
+ *
+ * # Safety
+ *
+ * `fg` has to be a valid flowgraph, `name` a NUL-terminated string, and `sink` a valid pointer.
+ * The returned sink is released with [`fsdr_sink_free`].
+ */
+enum FsdrStatus fsdr_flowgraph_add_sink(struct FsdrFlowgraph *fg,
+                                        const char *name,
+                                        uint32_t item_type,
+                                        size_t capacity,
+                                        struct FsdrSink **sink);
+
+/**
+ * Release a flowgraph that was not started
+ *
+ * # Safety
+ *
+ * `fg` has to be `NULL` or a flowgraph created with [`fsdr_flowgraph_new`] that is not used
+ * afterwards.
+ */
+void fsdr_flowgraph_free(struct FsdrFlowgraph *fg);
+
+/**
+ * Start a flowgraph on the runtime
+ *
+ * The flowgraph is consumed, also if starting fails.
+ *
+ * # Safety
+ *
+ * `runtime` has to be a valid runtime, `fg` a valid flowgraph, and `handle` a valid pointer. The
+ * returned handle is released with [`fsdr_handle_free`].
+ */
+enum FsdrStatus fsdr_runtime_start(struct FsdrRuntime *runtime,
+                                   struct FsdrFlowgraph *fg,
+                                   struct FsdrHandle **handle);
+
+/**
+ * Get the description of a running flowgraph as JSON
+ *
+ * # Safety
+ *
+ * `handle` has to be a valid handle and `json` a valid pointer. The returned string is released
+ * with [`fsdr_string_free`].
+ */
+enum FsdrStatus fsdr_handle_description(struct FsdrHandle *handle, char **json);
+
+/**
+ * Call a message handler with a JSON-encoded [`Pmt`], e.g., `{"F64": 0.5}`
+ *
+ * `block` is the instance name or the id of the block and `handler` the name or the index of the
+ * message input. A `NULL` `json` calls the handler with `"Null"`. If `result` is not `NULL`, it
+ * receives the JSON-encoded return value of the handler.
+ *
+ * # Safety
+ *
+ * `handle` has to be a valid handle, `block`, `handler`, and `json` NUL-terminated strings, and
+ * `result` `NULL` or a valid pointer. The returned string is released with [`fsdr_string_free`].
+ */
+enum FsdrStatus fsdr_handle_call_json(struct FsdrHandle *handle,
+                                      const char *block,
+                                      const char *handler,
+                                      const char *json,
+                                      char **result);
+
+/**
+ * Call a message handler with a [`Pmt`] in the binary format of [`Pmt::to_bytes`]
+ *
+ * `block` and `handler` are resolved like for [`fsdr_handle_call_json`]. If `result` is not
+ * `NULL`, it receives the encoded return value of the handler and `result_len` its length.
+ *
+ * # Safety
+ *
+ * `handle` has to be a valid handle, `block` and `handler` NUL-terminated strings, `data` has to
+ * point to `len` bytes, and `result` and `result_len` have to be `NULL` or valid pointers. The
+ * returned buffer is released with [`fsdr_bytes_free`].
+ */
+enum FsdrStatus fsdr_handle_call_binary(struct FsdrHandle *handle,
+                                        const char *block,
+                                        const char *handler,
+                                        const uint8_t *data,
+                                        size_t len,
+                                        uint8_t **result,
+                                        size_t *result_len);
+
+/**
+ * Terminate a flowgraph and wait until it is shut down
+ *
+ * Terminating a flowgraph that already finished succeeds.
+ *
+ * # Safety
+ *
+ * `handle` has to be a valid handle.
+ */
+enum FsdrStatus fsdr_handle_terminate(struct FsdrHandle *handle);
+
+/**
+ * Check if a flowgraph is terminated
+ *
+ * # Safety
+ *
+ * `handle` has to be `NULL`, which is reported as terminated, or a valid handle.
+ */
+bool fsdr_handle_is_terminated(const struct FsdrHandle *handle);
+
+/**
+ * Release a handle
+ *
+ * The flowgraph keeps running until it finishes or is terminated through another handle.
+ *
+ * # Safety
+ *
+ * `handle` has to be `NULL` or a handle created with [`fsdr_runtime_start`] that is not used
+ * afterwards.
+ */
+void fsdr_handle_free(struct FsdrHandle *handle);
+
+/**
+ * Push `n` items into a source
+ *
+ * Blocks until all items are queued, the flowgraph finished, or the timeout expired. A negative
+ * `timeout_ms` waits forever. `written`, if not `NULL`, receives the number of queued items,
+ * which is smaller than `n` for [`FsdrStatus::Timeout`] and [`FsdrStatus::Finished`].
+ *
+ * # Safety
+ *
+ * `source` has to be a valid source, `items` has to point to `n` items of the type of the
+ * source, and `written` has to be `NULL` or a valid pointer.
+ */
+enum FsdrStatus fsdr_source_push(struct FsdrSource *source,
+                                 const void *items,
+                                 size_t n,
+                                 int64_t timeout_ms,
+                                 size_t *written);
+
+/**
+ * Release a source, which ends its stream
+ *
+ * # Safety
+ *
+ * `source` has to be `NULL` or a source created with [`fsdr_flowgraph_add_source`] that is not
+ * used afterwards.
+ */
+void fsdr_source_free(struct FsdrSource *source);
+
+/**
+ * Pull up to `max` items from a sink
+ *
+ * Blocks until at least one item is available, the stream finished, or the timeout expired. A
+ * negative `timeout_ms` waits forever. `read` receives the number of items that were written to
+ * `items`. Returns [`FsdrStatus::Finished`] once the stream ended and all items were read.
+ *
+ * # Safety
+ *
+ * `sink` has to be a valid sink, `items` has to point to memory for `max` items of the type of
+ * the sink, and `read` has to be a valid pointer.
+ */
+enum FsdrStatus fsdr_sink_pull(struct FsdrSink *sink,
+                               void *items,
+                               size_t max,
+                               int64_t timeout_ms,
+                               size_t *read);
+
+/**
+ * Release a sink
+ *
+ * The sink block finishes and drops all further items.
+ *
+ * # Safety
+ *
+ * `sink` has to be `NULL` or a sink created with [`fsdr_flowgraph_add_sink`] that is not used
+ * afterwards.
+ */
+void fsdr_sink_free(struct FsdrSink *sink);
+
+/**
+ * Release a string returned by the library
+ *
+ * # Safety
+ *
+ * `s` has to be `NULL` or a string returned by the library that is not used afterwards.
+ */
+void fsdr_string_free(char *s);
+
+/**
+ * Release a buffer returned by [`fsdr_handle_call_binary`]
+ *
+ * # Safety
+ *
+ * `data` has to be `NULL` or a buffer of `len` bytes returned by the library that is not used
+ * afterwards.
+ */
+void fsdr_bytes_free(uint8_t *data, size_t len);
+
+/**
+ * Message of the last error of the calling thread
+ *
+ * Returns `NULL` if no error occurred. The string is owned by the library and stays valid until
+ * the next failing call on the same thread.
+ */
+const char *fsdr_last_error(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* FUTURESDR_H */
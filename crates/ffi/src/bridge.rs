@@ -0,0 +1,425 @@
+//! Blocks that move samples between C and a flowgraph
+//!
+//! Both directions use a bounded queue. The C side blocks on a condition variable, optionally
+//! with a timeout, while the block waits asynchronously through [`WorkIo::block_on`], so it keeps
+//! handling messages and can be terminated while no samples are available.
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::task::Poll;
+use std::time::Duration;
+use std::time::Instant;
+
+use futuresdr::futures::future::poll_fn;
+use futuresdr::futures::task::AtomicWaker;
+use futuresdr::macros::async_trait;
+use futuresdr::runtime::Block;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Result;
+use futuresdr::runtime::StreamIo;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::TypedBlock;
+use futuresdr::runtime::WorkIo;
+
+use crate::FsdrStatus;
+
+/// C side of a [`BridgeSource`]
+pub(crate) trait Push: Send + Sync {
+    /// Push `n` items from `items`, returning the number of items written
+    ///
+    /// # Safety
+    ///
+    /// `items` has to point to `n` valid items of the type of the bridge.
+    unsafe fn push(
+        &self,
+        items: *const u8,
+        n: usize,
+        timeout: Option<Duration>,
+    ) -> (usize, FsdrStatus);
+    /// Close the stream, the source finishes once all queued items are consumed
+    fn close(&self);
+}
+
+/// C side of a [`BridgeSink`]
+pub(crate) trait Pull: Send + Sync {
+    /// Pull up to `max` items into `items`, returning the number of items read
+    ///
+    /// # Safety
+    ///
+    /// `items` has to point to memory for `max` items of the type of the bridge.
+    unsafe fn pull(
+        &self,
+        items: *mut u8,
+        max: usize,
+        timeout: Option<Duration>,
+    ) -> (usize, FsdrStatus);
+    /// Close the stream, the sink finishes and drops all further items
+    fn close(&self);
+}
+
+struct State<T> {
+    items: VecDeque<T>,
+    capacity: usize,
+    closed: bool,
+}
+
+/// Queue shared between the C side and the block
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    cond: Condvar,
+    waker: AtomicWaker,
+}
+
+impl<T: Copy + Send + 'static> Shared<T> {
+    fn new(capacity: usize) -> Arc<Self> {
+        Arc::new(Self {
+            state: Mutex::new(State {
+                items: VecDeque::with_capacity(capacity),
+                capacity: capacity.max(1),
+                closed: false,
+            }),
+            cond: Condvar::new(),
+            waker: AtomicWaker::new(),
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Wait on the C side until `ready` holds, returns `None` on timeout
+    fn wait(
+        &self,
+        timeout: Option<Duration>,
+        ready: impl Fn(&State<T>) -> bool,
+    ) -> Option<MutexGuard<'_, State<T>>> {
+        let deadline = timeout.map(|t| Instant::now() + t);
+        let mut state = self.lock();
+        while !ready(&state) {
+            match deadline {
+                Some(d) => {
+                    let now = Instant::now();
+                    if now >= d {
+                        return None;
+                    }
+                    state = self
+                        .cond
+                        .wait_timeout(state, d - now)
+                        .unwrap_or_else(|e| e.into_inner())
+                        .0;
+                }
+                None => state = self.cond.wait(state).unwrap_or_else(|e| e.into_inner()),
+            }
+        }
+        Some(state)
+    }
+
+    /// Future for the block side that resolves once `ready` holds
+    fn ready(
+        self: &Arc<Self>,
+        ready: fn(&State<T>) -> bool,
+    ) -> impl Future<Output = ()> + Send + 'static {
+        let shared = self.clone();
+        poll_fn(move |cx| {
+            shared.waker.register(cx.waker());
+            if ready(&shared.lock()) {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+    }
+
+    /// Wake both sides after the queue changed
+    fn notify(&self) {
+        self.cond.notify_all();
+        self.waker.wake();
+    }
+
+    fn finish(&self) {
+        self.lock().closed = true;
+        self.notify();
+    }
+}
+
+impl<T: Copy + Send + 'static> Push for Shared<T> {
+    unsafe fn push(
+        &self,
+        items: *const u8,
+        n: usize,
+        timeout: Option<Duration>,
+    ) -> (usize, FsdrStatus) {
+        let items = std::slice::from_raw_parts(items as *const T, n);
+        let deadline = timeout.map(|t| Instant::now() + t);
+        let mut written = 0;
+
+        while written < n {
+            let timeout = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+            let Some(mut state) = self.wait(timeout, |s| s.closed || s.items.len() < s.capacity)
+            else {
+                return (written, FsdrStatus::Timeout);
+            };
+            if state.closed {
+                return (written, FsdrStatus::Finished);
+            }
+            let k = std::cmp::min(n - written, state.capacity - state.items.len());
+            state.items.extend(&items[written..written + k]);
+            written += k;
+            drop(state);
+            self.notify();
+        }
+
+        (written, FsdrStatus::Ok)
+    }
+
+    fn close(&self) {
+        self.finish()
+    }
+}
+
+impl<T: Copy + Send + 'static> Pull for Shared<T> {
+    unsafe fn pull(
+        &self,
+        items: *mut u8,
+        max: usize,
+        timeout: Option<Duration>,
+    ) -> (usize, FsdrStatus) {
+        let items = std::slice::from_raw_parts_mut(items as *mut T, max);
+        let Some(mut state) = self.wait(timeout, |s| s.closed || !s.items.is_empty()) else {
+            return (0, FsdrStatus::Timeout);
+        };
+        if state.items.is_empty() {
+            return (0, FsdrStatus::Finished);
+        }
+        let n = std::cmp::min(max, state.items.len());
+        for (o, i) in items.iter_mut().zip(state.items.drain(..n)) {
+            *o = i;
+        }
+        drop(state);
+        self.notify();
+
+        (n, FsdrStatus::Ok)
+    }
+
+    fn close(&self) {
+        self.finish()
+    }
+}
+
+/// Push samples from C into a stream connection.
+///
+/// # Outputs
+///
+/// `out`: Samples pushed through the C API
+pub(crate) struct BridgeSource<T: Copy + Send + 'static> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T: Copy + Send + 'static> BridgeSource<T> {
+    /// Create BridgeSource block and the C side of the bridge, queueing up to `capacity` items
+    pub(crate) fn new(capacity: usize) -> (TypedBlock<Self>, Arc<dyn Push>) {
+        let shared = Shared::new(capacity);
+        let block = TypedBlock::new(
+            BlockMetaBuilder::new("BridgeSource").build(),
+            StreamIoBuilder::new().add_output::<T>("out").build(),
+            MessageIoBuilder::new().build(),
+            Self {
+                shared: shared.clone(),
+            },
+        );
+        (block, shared)
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl<T: Copy + Send + 'static> Kernel for BridgeSource<T> {
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let out = sio.output(0).slice::<T>();
+        let mut state = self.shared.lock();
+        let n = std::cmp::min(out.len(), state.items.len());
+        for (o, i) in out.iter_mut().zip(state.items.drain(..n)) {
+            *o = i;
+        }
+        let empty = state.items.is_empty();
+        let closed = state.closed;
+        drop(state);
+
+        if n > 0 {
+            self.shared.cond.notify_all();
+            sio.output(0).produce(n);
+        }
+
+        if empty && closed {
+            io.finished = true;
+        } else if empty {
+            io.block_on(self.shared.ready(|s| s.closed || !s.items.is_empty()));
+        }
+
+        Ok(())
+    }
+
+    async fn deinit(
+        &mut self,
+        _sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        self.shared.finish();
+        Ok(())
+    }
+}
+
+/// Pull samples from a stream connection into C.
+///
+/// # Inputs
+///
+/// `in`: Samples pulled through the C API
+pub(crate) struct BridgeSink<T: Copy + Send + 'static> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T: Copy + Send + 'static> BridgeSink<T> {
+    /// Create BridgeSink block and the C side of the bridge, queueing up to `capacity` items
+    pub(crate) fn new(capacity: usize) -> (TypedBlock<Self>, Arc<dyn Pull>) {
+        let shared = Shared::new(capacity);
+        let block = TypedBlock::new(
+            BlockMetaBuilder::new("BridgeSink").build(),
+            StreamIoBuilder::new().add_input::<T>("in").build(),
+            MessageIoBuilder::new().build(),
+            Self {
+                shared: shared.clone(),
+            },
+        );
+        (block, shared)
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl<T: Copy + Send + 'static> Kernel for BridgeSink<T> {
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = sio.input(0).slice::<T>();
+        let mut state = self.shared.lock();
+        if state.closed {
+            io.finished = true;
+            return Ok(());
+        }
+        let n = std::cmp::min(i.len(), state.capacity - state.items.len());
+        state.items.extend(&i[..n]);
+        drop(state);
+
+        if n > 0 {
+            self.shared.cond.notify_all();
+        }
+        sio.input(0).consume(n);
+
+        if n < i.len() {
+            io.block_on(
+                self.shared
+                    .ready(|s| s.closed || s.items.len() < s.capacity),
+            );
+        } else if sio.input(0).finished() {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+
+    async fn deinit(
+        &mut self,
+        _sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        self.shared.finish();
+        Ok(())
+    }
+}
+
+/// Create a [`BridgeSource`] for items of type `T`
+pub(crate) fn source<T: Copy + Send + 'static>(capacity: usize) -> (Block, Arc<dyn Push>) {
+    let (block, push) = BridgeSource::<T>::new(capacity);
+    (block.into(), push)
+}
+
+/// Create a [`BridgeSink`] for items of type `T`
+pub(crate) fn sink<T: Copy + Send + 'static>(capacity: usize) -> (Block, Arc<dyn Pull>) {
+    let (block, pull) = BridgeSink::<T>::new(capacity);
+    (block.into(), pull)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn push_pull() {
+        let shared = Shared::<u32>::new(4);
+        let items = [1u32, 2, 3, 4, 5, 6];
+        let timeout = Some(Duration::from_millis(10));
+
+        let (n, status) = unsafe { Push::push(&*shared, items.as_ptr() as _, 6, timeout) };
+        assert_eq!((n, status), (4, FsdrStatus::Timeout));
+
+        let mut out = [0u32; 3];
+        let (n, status) = unsafe { Pull::pull(&*shared, out.as_mut_ptr() as _, 3, timeout) };
+        assert_eq!((n, status), (3, FsdrStatus::Ok));
+        assert_eq!(out, [1, 2, 3]);
+
+        shared.finish();
+        let (n, status) = unsafe { Push::push(&*shared, items.as_ptr() as _, 1, None) };
+        assert_eq!((n, status), (0, FsdrStatus::Finished));
+        let (n, status) = unsafe { Pull::pull(&*shared, out.as_mut_ptr() as _, 3, None) };
+        assert_eq!((n, status), (1, FsdrStatus::Ok));
+        assert_eq!(out[0], 4);
+        let (n, status) = unsafe { Pull::pull(&*shared, out.as_mut_ptr() as _, 3, None) };
+        assert_eq!((n, status), (0, FsdrStatus::Finished));
+    }
+
+    #[test]
+    fn blocking_push() {
+        let shared = Shared::<f32>::new(2);
+        let items: Vec<f32> = (0..100).map(|i| i as f32).collect();
+
+        let reader = {
+            let shared = shared.clone();
+            std::thread::spawn(move || {
+                let mut received = Vec::new();
+                let mut buf = [0f32; 3];
+                loop {
+                    let (n, status) =
+                        unsafe { Pull::pull(&*shared, buf.as_mut_ptr() as _, 3, None) };
+                    if status == FsdrStatus::Finished {
+                        return received;
+                    }
+                    received.extend_from_slice(&buf[..n]);
+                }
+            })
+        };
+
+        let (n, status) = unsafe { Push::push(&*shared, items.as_ptr() as _, 100, None) };
+        assert_eq!((n, status), (100, FsdrStatus::Ok));
+        Push::close(&*shared);
+        assert_eq!(reader.join().unwrap(), items);
+    }
+}
@@ -0,0 +1,109 @@
+//! Status codes and error reporting
+use std::any::Any;
+use std::cell::RefCell;
+use std::ffi::c_char;
+use std::ffi::CString;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::ptr;
+
+use futuresdr::runtime::Error;
+
+/// Status code returned by the fallible functions of the API
+///
+/// The message of the last error of the calling thread is available through [`fsdr_last_error`].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FsdrStatus {
+    /// Success
+    Ok = 0,
+    /// Null pointer, invalid UTF-8, or otherwise invalid argument
+    InvalidArgument = 1,
+    /// Invalid flowgraph description, e.g., a syntax error or an unknown block type or port
+    Description = 2,
+    /// Block or handler does not exist or the handler failed
+    Handler = 3,
+    /// [`Pmt`](futuresdr::runtime::Pmt) could not be decoded or encoded
+    Pmt = 4,
+    /// Timeout expired before the operation completed
+    Timeout = 5,
+    /// Flowgraph or sample stream finished
+    Finished = 6,
+    /// Other error of the runtime
+    Runtime = 7,
+    /// Panic inside the library, caught at the API boundary
+    Panic = 8,
+}
+
+/// Error of an API function, reported through its status code and [`fsdr_last_error`]
+#[derive(Debug)]
+pub(crate) struct FfiError {
+    pub(crate) status: FsdrStatus,
+    pub(crate) message: String,
+}
+
+impl FfiError {
+    pub(crate) fn new(status: FsdrStatus, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<Error> for FfiError {
+    fn from(e: Error) -> Self {
+        let status = match &e {
+            Error::InvalidBlock(_) | Error::InvalidMessagePort(..) | Error::HandlerError(_) => {
+                FsdrStatus::Handler
+            }
+            Error::PmtConversionError => FsdrStatus::Pmt,
+            Error::FlowgraphTerminated | Error::BlockTerminated => FsdrStatus::Finished,
+            Error::InvalidStreamPort(..)
+            | Error::ConnectError(_)
+            | Error::ValidationError(_)
+            | Error::DuplicateBlockName(_)
+            | Error::InvalidBufferProfile(_)
+            | Error::PortCountMismatch(..)
+            | Error::UnknownBlockType(_)
+            | Error::DslError(..) => FsdrStatus::Description,
+            _ => FsdrStatus::Runtime,
+        };
+        Self::new(status, e.to_string())
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Run the body of an API function, turning errors and panics into status codes
+pub(crate) fn ffi(f: impl FnOnce() -> Result<(), FfiError>) -> FsdrStatus {
+    let e = match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => return FsdrStatus::Ok,
+        Ok(Err(e)) => e,
+        Err(payload) => FfiError::new(FsdrStatus::Panic, panic_message(payload)),
+    };
+    let message = CString::new(e.message.replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.with(|l| *l.borrow_mut() = Some(message));
+    e.status
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        format!("panic: {s}")
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        format!("panic: {s}")
+    } else {
+        "panic".to_string()
+    }
+}
+
+/// Message of the last error of the calling thread
+///
+/// Returns `NULL` if no error occurred. The string is owned by the library and stays valid until
+/// the next failing call on the same thread.
+#[no_mangle]
+pub extern "C" fn fsdr_last_error() -> *const c_char {
+    LAST_ERROR.with(|l| l.borrow().as_ref().map_or(ptr::null(), |s| s.as_ptr()))
+}
@@ -0,0 +1,711 @@
+//! C API to embed the FutureSDR runtime
+//!
+//! The library exposes opaque handles for a runtime, flowgraphs under construction, running
+//! flowgraphs, and bridge ports that move samples between C and a flowgraph. Flowgraphs are
+//! built from the textual description of [`Flowgraph::connect_str`], using the core blocks of
+//! [`futuresdr::blocks::register`]. Message handlers are called with [`Pmt`]s, encoded as JSON or
+//! in the binary format of [`Pmt::to_bytes`].
+//!
+//! Fallible functions return an [`FsdrStatus`] and report the error message through
+//! [`fsdr_last_error`]. Panics are caught at the boundary and reported as
+//! [`FsdrStatus::Panic`]. The C header is generated with cbindgen to `include/futuresdr.h` (see
+//! `build.rs`).
+use std::ffi::c_char;
+use std::ffi::c_void;
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::ptr;
+use std::sync::Arc;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use futuresdr::async_io::block_on;
+use futuresdr::num_complex::Complex32;
+use futuresdr::runtime::scheduler::SmolScheduler;
+use futuresdr::runtime::Block;
+use futuresdr::runtime::BlockRegistry;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::FlowgraphHandle;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::PortId;
+use futuresdr::runtime::Runtime;
+
+mod bridge;
+mod error;
+pub use error::fsdr_last_error;
+pub use error::FsdrStatus;
+
+use bridge::Pull;
+use bridge::Push;
+use error::ffi;
+use error::FfiError;
+
+/// Runtime that executes flowgraphs
+pub struct FsdrRuntime {
+    runtime: Runtime<'static, SmolScheduler>,
+}
+
+/// Flowgraph under construction
+pub struct FsdrFlowgraph {
+    fg: Flowgraph,
+}
+
+/// Handle to a running flowgraph
+pub struct FsdrHandle {
+    handle: FlowgraphHandle,
+}
+
+/// C side of a bridge source, pushing samples into a flowgraph
+pub struct FsdrSource {
+    bridge: Arc<dyn Push>,
+}
+
+/// C side of a bridge sink, pulling samples out of a flowgraph
+pub struct FsdrSink {
+    bridge: Arc<dyn Pull>,
+}
+
+/// Item type of a bridge port
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FsdrItemType {
+    /// `uint8_t`
+    U8 = 0,
+    /// `uint16_t`
+    U16 = 1,
+    /// `uint32_t`
+    U32 = 2,
+    /// `uint64_t`
+    U64 = 3,
+    /// `int8_t`
+    I8 = 4,
+    /// `int16_t`
+    I16 = 5,
+    /// `int32_t`
+    I32 = 6,
+    /// `int64_t`
+    I64 = 7,
+    /// `float`
+    F32 = 8,
+    /// `double`
+    F64 = 9,
+    /// Complex `float`, i.e., interleaved real and imaginary parts
+    C32 = 10,
+}
+
+impl TryFrom<u32> for FsdrItemType {
+    type Error = u32;
+
+    fn try_from(v: u32) -> Result<Self, Self::Error> {
+        Ok(match v {
+            0 => Self::U8,
+            1 => Self::U16,
+            2 => Self::U32,
+            3 => Self::U64,
+            4 => Self::I8,
+            5 => Self::I16,
+            6 => Self::I32,
+            7 => Self::I64,
+            8 => Self::F32,
+            9 => Self::F64,
+            10 => Self::C32,
+            v => return Err(v),
+        })
+    }
+}
+
+macro_rules! typed {
+    ($t:expr, $m:ident::$f:ident, $($arg:expr),*) => {
+        match $t {
+            FsdrItemType::U8 => $m::$f::<u8>($($arg),*),
+            FsdrItemType::U16 => $m::$f::<u16>($($arg),*),
+            FsdrItemType::U32 => $m::$f::<u32>($($arg),*),
+            FsdrItemType::U64 => $m::$f::<u64>($($arg),*),
+            FsdrItemType::I8 => $m::$f::<i8>($($arg),*),
+            FsdrItemType::I16 => $m::$f::<i16>($($arg),*),
+            FsdrItemType::I32 => $m::$f::<i32>($($arg),*),
+            FsdrItemType::I64 => $m::$f::<i64>($($arg),*),
+            FsdrItemType::F32 => $m::$f::<f32>($($arg),*),
+            FsdrItemType::F64 => $m::$f::<f64>($($arg),*),
+            FsdrItemType::C32 => $m::$f::<Complex32>($($arg),*),
+        }
+    };
+}
+
+/// Version of the library as a static, NUL-terminated string
+#[no_mangle]
+pub extern "C" fn fsdr_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char
+}
+
+/// Create a runtime
+///
+/// This also starts the control port, if it is enabled in the configuration.
+///
+/// # Safety
+///
+/// `runtime` has to be a valid pointer. The returned runtime is released with
+/// [`fsdr_runtime_free`].
+#[no_mangle]
+pub unsafe extern "C" fn fsdr_runtime_new(runtime: *mut *mut FsdrRuntime) -> FsdrStatus {
+    ffi(|| {
+        let out = out_arg(runtime, "runtime")?;
+        *out = Box::into_raw(Box::new(FsdrRuntime {
+            runtime: Runtime::new(),
+        }));
+        Ok(())
+    })
+}
+
+/// Release a runtime
+///
+/// Running flowgraphs are not terminated and keep their handles valid.
+///
+/// # Safety
+///
+/// `runtime` has to be `NULL` or a runtime created with [`fsdr_runtime_new`] that is not used
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn fsdr_runtime_free(runtime: *mut FsdrRuntime) {
+    ffi(|| {
+        free(runtime);
+        Ok(())
+    });
+}
+
+/// Create an empty flowgraph
+///
+/// # Safety
+///
+/// `fg` has to be a valid pointer. The flowgraph is consumed by [`fsdr_runtime_start`] or
+/// released with [`fsdr_flowgraph_free`].
+#[no_mangle]
+pub unsafe extern "C" fn fsdr_flowgraph_new(fg: *mut *mut FsdrFlowgraph) -> FsdrStatus {
+    ffi(|| {
+        let out = out_arg(fg, "fg")?;
+        *out = Box::into_raw(Box::new(FsdrFlowgraph {
+            fg: Flowgraph::new(),
+        }));
+        Ok(())
+    })
+}
+
+/// Add and connect blocks, using the syntax of [`Flowgraph::connect_str`]
+///
+/// Blocks are created with the core block registry. Bridge ports added before are referred to by
+/// their name.
+///
+/// # Safety
+///
+/// `fg` has to be a valid flowgraph and `description` a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn fsdr_flowgraph_connect(
+    fg: *mut FsdrFlowgraph,
+    description: *const c_char,
+) -> FsdrStatus {
+    ffi(|| {
+        let fg = ref_arg(fg, "fg")?;
+        let description = str_arg(description, "description")?;
+        fg.fg
+            .connect_str(registry(), description)
+            .map_err(|e| FfiError::new(FsdrStatus::Description, e.to_string()))
+    })
+}
+
+/// Add a source block with an `out` port, fed with samples through [`fsdr_source_push`]
+///
+/// `item_type` is one of the [`FsdrItemType`] values. The block queues up to `capacity` items. It
+/// finishes, once the source is released with [`fsdr_source_free`] and all queued items are
+/// consumed.
+///
+/// # Safety
+///
+/// `fg` has to be a valid flowgraph, `name` a NUL-terminated string, and `source` a valid
+/// pointer. The returned source is released with [`fsdr_source_free`].
+#[no_mangle]
+pub unsafe extern "C" fn fsdr_flowgraph_add_source(
+    fg: *mut FsdrFlowgraph,
+    name: *const c_char,
+    item_type: u32,
+    capacity: usize,
+    source: *mut *mut FsdrSource,
+) -> FsdrStatus {
+    ffi(|| {
+        let fg = ref_arg(fg, "fg")?;
+        let name = str_arg(name, "name")?;
+        let item_type = item_type_arg(item_type)?;
+        let out = out_arg(source, "source")?;
+        let (block, bridge) = typed!(item_type, bridge::source, capacity);
+        add_block(&mut fg.fg, block, name)?;
+        *out = Box::into_raw(Box::new(FsdrSource { bridge }));
+        Ok(())
+    })
+}
+
+/// Add a sink block with an `in` port, whose samples are read through [`fsdr_sink_pull`]
+///
+/// `item_type` is one of the [`FsdrItemType`] values. The block queues up to `capacity` items and
+/// waits, while the queue is full.
+///
+/// # Safety
+///
+/// `fg` has to be a valid flowgraph, `name` a NUL-terminated string, and `sink` a valid pointer.
+/// The returned sink is released with [`fsdr_sink_free`].
+#[no_mangle]
+pub unsafe extern "C" fn fsdr_flowgraph_add_sink(
+    fg: *mut FsdrFlowgraph,
+    name: *const c_char,
+    item_type: u32,
+    capacity: usize,
+    sink: *mut *mut FsdrSink,
+) -> FsdrStatus {
+    ffi(|| {
+        let fg = ref_arg(fg, "fg")?;
+        let name = str_arg(name, "name")?;
+        let item_type = item_type_arg(item_type)?;
+        let out = out_arg(sink, "sink")?;
+        let (block, bridge) = typed!(item_type, bridge::sink, capacity);
+        add_block(&mut fg.fg, block, name)?;
+        *out = Box::into_raw(Box::new(FsdrSink { bridge }));
+        Ok(())
+    })
+}
+
+/// Release a flowgraph that was not started
+///
+/// # Safety
+///
+/// `fg` has to be `NULL` or a flowgraph created with [`fsdr_flowgraph_new`] that is not used
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn fsdr_flowgraph_free(fg: *mut FsdrFlowgraph) {
+    ffi(|| {
+        free(fg);
+        Ok(())
+    });
+}
+
+/// Start a flowgraph on the runtime
+///
+/// The flowgraph is consumed, also if starting fails.
+///
+/// # Safety
+///
+/// `runtime` has to be a valid runtime, `fg` a valid flowgraph, and `handle` a valid pointer. The
+/// returned handle is released with [`fsdr_handle_free`].
+#[no_mangle]
+pub unsafe extern "C" fn fsdr_runtime_start(
+    runtime: *mut FsdrRuntime,
+    fg: *mut FsdrFlowgraph,
+    handle: *mut *mut FsdrHandle,
+) -> FsdrStatus {
+    ffi(|| {
+        if fg.is_null() {
+            return Err(FfiError::new(FsdrStatus::InvalidArgument, "fg is NULL"));
+        }
+        let fg = Box::from_raw(fg);
+        let runtime = ref_arg(runtime, "runtime")?;
+        let out = out_arg(handle, "handle")?;
+        let h = block_on(runtime.runtime.handle().start(fg.fg))?;
+        *out = Box::into_raw(Box::new(FsdrHandle { handle: h }));
+        Ok(())
+    })
+}
+
+/// Get the description of a running flowgraph as JSON
+///
+/// # Safety
+///
+/// `handle` has to be a valid handle and `json` a valid pointer. The returned string is released
+/// with [`fsdr_string_free`].
+#[no_mangle]
+pub unsafe extern "C" fn fsdr_handle_description(
+    handle: *mut FsdrHandle,
+    json: *mut *mut c_char,
+) -> FsdrStatus {
+    ffi(|| {
+        let handle = ref_arg(handle, "handle")?;
+        let out = out_arg(json, "json")?;
+        let description = block_on(handle.handle.description())?;
+        let s = serde_json::to_string(&description)
+            .map_err(|e| FfiError::new(FsdrStatus::Runtime, e.to_string()))?;
+        *out = c_string(s)?;
+        Ok(())
+    })
+}
+
+/// Call a message handler with a JSON-encoded [`Pmt`], e.g., `{"F64": 0.5}`
+///
+/// `block` is the instance name or the id of the block and `handler` the name or the index of the
+/// message input. A `NULL` `json` calls the handler with `"Null"`. If `result` is not `NULL`, it
+/// receives the JSON-encoded return value of the handler.
+///
+/// # Safety
+///
+/// `handle` has to be a valid handle, `block`, `handler`, and `json` NUL-terminated strings, and
+/// `result` `NULL` or a valid pointer. The returned string is released with [`fsdr_string_free`].
+#[no_mangle]
+pub unsafe extern "C" fn fsdr_handle_call_json(
+    handle: *mut FsdrHandle,
+    block: *const c_char,
+    handler: *const c_char,
+    json: *const c_char,
+    result: *mut *mut c_char,
+) -> FsdrStatus {
+    ffi(|| {
+        let handle = ref_arg(handle, "handle")?;
+        let block = str_arg(block, "block")?;
+        let handler = str_arg(handler, "handler")?;
+        let pmt = if json.is_null() {
+            Pmt::Null
+        } else {
+            serde_json::from_str(str_arg(json, "json")?)
+                .map_err(|e| FfiError::new(FsdrStatus::Pmt, e.to_string()))?
+        };
+        let ret = call(&mut handle.handle, block, handler, pmt)?;
+        if !result.is_null() {
+            let s = serde_json::to_string(&ret)
+                .map_err(|e| FfiError::new(FsdrStatus::Pmt, e.to_string()))?;
+            *result = c_string(s)?;
+        }
+        Ok(())
+    })
+}
+
+/// Call a message handler with a [`Pmt`] in the binary format of [`Pmt::to_bytes`]
+///
+/// `block` and `handler` are resolved like for [`fsdr_handle_call_json`]. If `result` is not
+/// `NULL`, it receives the encoded return value of the handler and `result_len` its length.
+///
+/// # Safety
+///
+/// `handle` has to be a valid handle, `block` and `handler` NUL-terminated strings, `data` has to
+/// point to `len` bytes, and `result` and `result_len` have to be `NULL` or valid pointers. The
+/// returned buffer is released with [`fsdr_bytes_free`].
+#[no_mangle]
+pub unsafe extern "C" fn fsdr_handle_call_binary(
+    handle: *mut FsdrHandle,
+    block: *const c_char,
+    handler: *const c_char,
+    data: *const u8,
+    len: usize,
+    result: *mut *mut u8,
+    result_len: *mut usize,
+) -> FsdrStatus {
+    ffi(|| {
+        let handle = ref_arg(handle, "handle")?;
+        let block = str_arg(block, "block")?;
+        let handler = str_arg(handler, "handler")?;
+        if data.is_null() {
+            return Err(FfiError::new(FsdrStatus::InvalidArgument, "data is NULL"));
+        }
+        // check the out-pointers before the handler is called, since it might have side effects
+        let out = if result.is_null() {
+            None
+        } else {
+            Some((
+                out_arg(result, "result")?,
+                ref_arg(result_len, "result_len")?,
+            ))
+        };
+        let pmt = Pmt::from_bytes(std::slice::from_raw_parts(data, len))
+            .map_err(|e| FfiError::new(FsdrStatus::Pmt, e.to_string()))?;
+        let ret = call(&mut handle.handle, block, handler, pmt)?;
+        if let Some((result, result_len)) = out {
+            let bytes = ret
+                .to_bytes()
+                .map_err(|e| FfiError::new(FsdrStatus::Pmt, e.to_string()))?
+                .into_boxed_slice();
+            *result_len = bytes.len();
+            *result = Box::into_raw(bytes) as *mut u8;
+        }
+        Ok(())
+    })
+}
+
+/// Terminate a flowgraph and wait until it is shut down
+///
+/// Terminating a flowgraph that already finished succeeds.
+///
+/// # Safety
+///
+/// `handle` has to be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn fsdr_handle_terminate(handle: *mut FsdrHandle) -> FsdrStatus {
+    ffi(|| {
+        let handle = ref_arg(handle, "handle")?;
+        if handle.handle.is_terminated() {
+            return Ok(());
+        }
+        match block_on(handle.handle.terminate_and_wait()) {
+            Ok(()) | Err(futuresdr::runtime::Error::FlowgraphTerminated) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    })
+}
+
+/// Check if a flowgraph is terminated
+///
+/// # Safety
+///
+/// `handle` has to be `NULL`, which is reported as terminated, or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn fsdr_handle_is_terminated(handle: *const FsdrHandle) -> bool {
+    match handle.as_ref() {
+        Some(h) => h.handle.is_terminated(),
+        None => true,
+    }
+}
+
+/// Release a handle
+///
+/// The flowgraph keeps running until it finishes or is terminated through another handle.
+///
+/// # Safety
+///
+/// `handle` has to be `NULL` or a handle created with [`fsdr_runtime_start`] that is not used
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn fsdr_handle_free(handle: *mut FsdrHandle) {
+    ffi(|| {
+        free(handle);
+        Ok(())
+    });
+}
+
+/// Push `n` items into a source
+///
+/// Blocks until all items are queued, the flowgraph finished, or the timeout expired. A negative
+/// `timeout_ms` waits forever. `written`, if not `NULL`, receives the number of queued items,
+/// which is smaller than `n` for [`FsdrStatus::Timeout`] and [`FsdrStatus::Finished`].
+///
+/// # Safety
+///
+/// `source` has to be a valid source, `items` has to point to `n` items of the type of the
+/// source, and `written` has to be `NULL` or a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn fsdr_source_push(
+    source: *mut FsdrSource,
+    items: *const c_void,
+    n: usize,
+    timeout_ms: i64,
+    written: *mut usize,
+) -> FsdrStatus {
+    ffi(|| {
+        let source = ref_arg(source, "source")?;
+        if items.is_null() && n > 0 {
+            return Err(FfiError::new(FsdrStatus::InvalidArgument, "items is NULL"));
+        }
+        let (k, status) = if n == 0 {
+            (0, FsdrStatus::Ok)
+        } else {
+            source
+                .bridge
+                .push(items as *const u8, n, timeout(timeout_ms))
+        };
+        if let Some(w) = written.as_mut() {
+            *w = k;
+        }
+        match status {
+            FsdrStatus::Ok => Ok(()),
+            FsdrStatus::Timeout => Err(FfiError::new(status, "timeout while pushing items")),
+            _ => Err(FfiError::new(status, "flowgraph finished")),
+        }
+    })
+}
+
+/// Release a source, which ends its stream
+///
+/// # Safety
+///
+/// `source` has to be `NULL` or a source created with [`fsdr_flowgraph_add_source`] that is not
+/// used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn fsdr_source_free(source: *mut FsdrSource) {
+    ffi(|| {
+        if let Some(source) = source.as_mut() {
+            source.bridge.close();
+        }
+        free(source);
+        Ok(())
+    });
+}
+
+/// Pull up to `max` items from a sink
+///
+/// Blocks until at least one item is available, the stream finished, or the timeout expired. A
+/// negative `timeout_ms` waits forever. `read` receives the number of items that were written to
+/// `items`. Returns [`FsdrStatus::Finished`] once the stream ended and all items were read.
+///
+/// # Safety
+///
+/// `sink` has to be a valid sink, `items` has to point to memory for `max` items of the type of
+/// the sink, and `read` has to be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn fsdr_sink_pull(
+    sink: *mut FsdrSink,
+    items: *mut c_void,
+    max: usize,
+    timeout_ms: i64,
+    read: *mut usize,
+) -> FsdrStatus {
+    ffi(|| {
+        let sink = ref_arg(sink, "sink")?;
+        let read = ref_arg(read, "read")?;
+        *read = 0;
+        if items.is_null() || max == 0 {
+            return Err(FfiError::new(
+                FsdrStatus::InvalidArgument,
+                "items is NULL or max is 0",
+            ));
+        }
+        let (n, status) = sink.bridge.pull(items as *mut u8, max, timeout(timeout_ms));
+        *read = n;
+        match status {
+            FsdrStatus::Ok => Ok(()),
+            FsdrStatus::Timeout => Err(FfiError::new(status, "timeout while pulling items")),
+            _ => Err(FfiError::new(status, "stream finished")),
+        }
+    })
+}
+
+/// Release a sink
+///
+/// The sink block finishes and drops all further items.
+///
+/// # Safety
+///
+/// `sink` has to be `NULL` or a sink created with [`fsdr_flowgraph_add_sink`] that is not used
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn fsdr_sink_free(sink: *mut FsdrSink) {
+    ffi(|| {
+        if let Some(sink) = sink.as_mut() {
+            sink.bridge.close();
+        }
+        free(sink);
+        Ok(())
+    });
+}
+
+/// Release a string returned by the library
+///
+/// # Safety
+///
+/// `s` has to be `NULL` or a string returned by the library that is not used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn fsdr_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Release a buffer returned by [`fsdr_handle_call_binary`]
+///
+/// # Safety
+///
+/// `data` has to be `NULL` or a buffer of `len` bytes returned by the library that is not used
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn fsdr_bytes_free(data: *mut u8, len: usize) {
+    if !data.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(data, len)));
+    }
+}
+
+fn registry() -> &'static BlockRegistry {
+    static REGISTRY: OnceLock<BlockRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let mut registry = BlockRegistry::new();
+        futuresdr::blocks::register(&mut registry);
+        registry
+    })
+}
+
+fn add_block(fg: &mut Flowgraph, mut block: Block, name: &str) -> Result<(), FfiError> {
+    block.set_instance_name(name);
+    fg.add_block(block)
+        .map_err(|e| FfiError::new(FsdrStatus::Description, e.to_string()))?;
+    Ok(())
+}
+
+/// Call a handler, resolving block and port names
+fn call(
+    handle: &mut FlowgraphHandle,
+    block: &str,
+    handler: &str,
+    pmt: Pmt,
+) -> Result<Pmt, FfiError> {
+    block_on(async {
+        let block_id = match block.parse::<usize>() {
+            Ok(id) => id,
+            Err(_) => handle
+                .description()
+                .await?
+                .blocks
+                .iter()
+                .find(|b| b.instance_name == block)
+                .map(|b| b.id)
+                .ok_or_else(|| {
+                    FfiError::new(
+                        FsdrStatus::Handler,
+                        format!("Block '{block}' does not exist"),
+                    )
+                })?,
+        };
+        let port_id = match handler.parse::<usize>() {
+            Ok(i) => PortId::Index(i),
+            Err(_) => PortId::Name(handler.to_string()),
+        };
+        Ok(handle.callback(block_id, port_id, pmt).await?)
+    })
+}
+
+fn timeout(ms: i64) -> Option<Duration> {
+    u64::try_from(ms).ok().map(Duration::from_millis)
+}
+
+unsafe fn str_arg<'a>(s: *const c_char, name: &str) -> Result<&'a str, FfiError> {
+    if s.is_null() {
+        return Err(FfiError::new(
+            FsdrStatus::InvalidArgument,
+            format!("{name} is NULL"),
+        ));
+    }
+    CStr::from_ptr(s).to_str().map_err(|_| {
+        FfiError::new(
+            FsdrStatus::InvalidArgument,
+            format!("{name} is not valid UTF-8"),
+        )
+    })
+}
+
+fn item_type_arg(t: u32) -> Result<FsdrItemType, FfiError> {
+    FsdrItemType::try_from(t).map_err(|t| {
+        FfiError::new(
+            FsdrStatus::InvalidArgument,
+            format!("invalid item type {t}"),
+        )
+    })
+}
+
+unsafe fn ref_arg<'a, T>(p: *mut T, name: &str) -> Result<&'a mut T, FfiError> {
+    p.as_mut()
+        .ok_or_else(|| FfiError::new(FsdrStatus::InvalidArgument, format!("{name} is NULL")))
+}
+
+unsafe fn out_arg<'a, T>(p: *mut *mut T, name: &str) -> Result<&'a mut *mut T, FfiError> {
+    ref_arg(p, name)
+}
+
+unsafe fn free<T>(p: *mut T) {
+    if !p.is_null() {
+        drop(Box::from_raw(p));
+    }
+}
+
+fn c_string(s: String) -> Result<*mut c_char, FfiError> {
+    CString::new(s)
+        .map(CString::into_raw)
+        .map_err(|e| FfiError::new(FsdrStatus::Runtime, e.to_string()))
+}
@@ -0,0 +1,257 @@
+use futuresdr::runtime::Pmt;
+use futuresdr_ffi::*;
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::ptr;
+
+fn c(s: &str) -> CString {
+    CString::new(s).unwrap()
+}
+
+fn last_error() -> String {
+    unsafe { CStr::from_ptr(fsdr_last_error()) }
+        .to_str()
+        .unwrap()
+        .to_string()
+}
+
+fn runtime() -> *mut FsdrRuntime {
+    let mut rt = ptr::null_mut();
+    assert_eq!(unsafe { fsdr_runtime_new(&mut rt) }, FsdrStatus::Ok);
+    rt
+}
+
+fn flowgraph(description: &str) -> *mut FsdrFlowgraph {
+    let mut fg = ptr::null_mut();
+    unsafe {
+        assert_eq!(fsdr_flowgraph_new(&mut fg), FsdrStatus::Ok);
+        assert_eq!(
+            fsdr_flowgraph_connect(fg, c(description).as_ptr()),
+            FsdrStatus::Ok
+        );
+    }
+    fg
+}
+
+struct Source(*mut FsdrSource);
+unsafe impl Send for Source {}
+
+#[test]
+fn loopback() {
+    let rt = runtime();
+    let mut fg = ptr::null_mut();
+    let mut src = ptr::null_mut();
+    let mut snk = ptr::null_mut();
+    let mut handle = ptr::null_mut();
+    let items: Vec<f32> = (0..10_000).map(|i| i as f32).collect();
+
+    unsafe {
+        assert_eq!(fsdr_flowgraph_new(&mut fg), FsdrStatus::Ok);
+        assert_eq!(
+            fsdr_flowgraph_add_source(
+                fg,
+                c("src").as_ptr(),
+                FsdrItemType::F32 as u32,
+                64,
+                &mut src
+            ),
+            FsdrStatus::Ok
+        );
+        assert_eq!(
+            fsdr_flowgraph_add_sink(
+                fg,
+                c("snk").as_ptr(),
+                FsdrItemType::F32 as u32,
+                64,
+                &mut snk
+            ),
+            FsdrStatus::Ok
+        );
+        let description = c("copy = Copy(type = \"f32\")\nsrc > copy > snk");
+        assert_eq!(
+            fsdr_flowgraph_connect(fg, description.as_ptr()),
+            FsdrStatus::Ok
+        );
+        assert_eq!(fsdr_runtime_start(rt, fg, &mut handle), FsdrStatus::Ok);
+    }
+
+    let pusher = {
+        let src = Source(src);
+        let items = items.clone();
+        std::thread::spawn(move || {
+            let src = src;
+            for chunk in items.chunks(1000) {
+                let mut written = 0;
+                let status = unsafe {
+                    fsdr_source_push(src.0, chunk.as_ptr() as _, chunk.len(), -1, &mut written)
+                };
+                assert_eq!(status, FsdrStatus::Ok);
+                assert_eq!(written, chunk.len());
+            }
+            unsafe { fsdr_source_free(src.0) };
+        })
+    };
+
+    let mut received = Vec::new();
+    let mut buf = [0f32; 256];
+    loop {
+        let mut read = 0;
+        match unsafe { fsdr_sink_pull(snk, buf.as_mut_ptr() as _, buf.len(), 5000, &mut read) } {
+            FsdrStatus::Ok => received.extend_from_slice(&buf[..read]),
+            FsdrStatus::Finished => break,
+            s => panic!("unexpected status {s:?}: {}", last_error()),
+        }
+    }
+    pusher.join().unwrap();
+    assert_eq!(received, items);
+
+    unsafe {
+        assert_eq!(fsdr_handle_terminate(handle), FsdrStatus::Ok);
+        assert!(fsdr_handle_is_terminated(handle));
+        fsdr_sink_free(snk);
+        fsdr_handle_free(handle);
+        fsdr_runtime_free(rt);
+    }
+}
+
+#[test]
+fn handlers() {
+    let rt = runtime();
+    let fg = flowgraph("msg = MessageSink");
+    let mut handle = ptr::null_mut();
+
+    unsafe {
+        assert_eq!(fsdr_runtime_start(rt, fg, &mut handle), FsdrStatus::Ok);
+
+        let mut result = ptr::null_mut();
+        let status = fsdr_handle_call_json(
+            handle,
+            c("msg").as_ptr(),
+            c("in").as_ptr(),
+            c(r#"{"U32": 1}"#).as_ptr(),
+            &mut result,
+        );
+        assert_eq!(status, FsdrStatus::Ok);
+        assert_eq!(CStr::from_ptr(result).to_str().unwrap(), r#"{"U64":1}"#);
+        fsdr_string_free(result);
+
+        let data = Pmt::U32(2).to_bytes().unwrap();
+        let mut result = ptr::null_mut();
+        let mut result_len = 0;
+        let status = fsdr_handle_call_binary(
+            handle,
+            c("0").as_ptr(),
+            c("0").as_ptr(),
+            data.as_ptr(),
+            data.len(),
+            &mut result,
+            &mut result_len,
+        );
+        assert_eq!(status, FsdrStatus::Ok);
+        let bytes = std::slice::from_raw_parts(result, result_len);
+        assert_eq!(Pmt::from_bytes(bytes).unwrap(), Pmt::U64(2));
+        fsdr_bytes_free(result, result_len);
+
+        // the handler is not called, if an out-pointer is invalid
+        let mut result = ptr::null_mut();
+        let status = fsdr_handle_call_binary(
+            handle,
+            c("0").as_ptr(),
+            c("0").as_ptr(),
+            data.as_ptr(),
+            data.len(),
+            &mut result,
+            ptr::null_mut(),
+        );
+        assert_eq!(status, FsdrStatus::InvalidArgument);
+        assert_eq!(last_error(), "result_len is NULL");
+        assert!(result.is_null());
+        let mut json = ptr::null_mut();
+        let status = fsdr_handle_call_json(
+            handle,
+            c("msg").as_ptr(),
+            c("in").as_ptr(),
+            ptr::null(),
+            &mut json,
+        );
+        assert_eq!(status, FsdrStatus::Ok);
+        assert_eq!(CStr::from_ptr(json).to_str().unwrap(), r#"{"U64":3}"#);
+        fsdr_string_free(json);
+
+        let status = fsdr_handle_call_json(
+            handle,
+            c("nope").as_ptr(),
+            c("in").as_ptr(),
+            ptr::null(),
+            ptr::null_mut(),
+        );
+        assert_eq!(status, FsdrStatus::Handler);
+        assert!(last_error().contains("nope"));
+
+        let status = fsdr_handle_call_json(
+            handle,
+            c("msg").as_ptr(),
+            c("in").as_ptr(),
+            c("{").as_ptr(),
+            ptr::null_mut(),
+        );
+        assert_eq!(status, FsdrStatus::Pmt);
+
+        let mut json = ptr::null_mut();
+        assert_eq!(fsdr_handle_description(handle, &mut json), FsdrStatus::Ok);
+        assert!(CStr::from_ptr(json)
+            .to_str()
+            .unwrap()
+            .contains("MessageSink"));
+        fsdr_string_free(json);
+
+        assert_eq!(fsdr_handle_terminate(handle), FsdrStatus::Ok);
+        assert_eq!(fsdr_handle_terminate(handle), FsdrStatus::Ok);
+        fsdr_handle_free(handle);
+        fsdr_runtime_free(rt);
+    }
+}
+
+#[test]
+fn errors() {
+    let mut fg = ptr::null_mut();
+    unsafe {
+        assert_eq!(
+            fsdr_flowgraph_new(ptr::null_mut()),
+            FsdrStatus::InvalidArgument
+        );
+        assert_eq!(last_error(), "fg is NULL");
+
+        assert_eq!(fsdr_flowgraph_new(&mut fg), FsdrStatus::Ok);
+        let status = fsdr_flowgraph_connect(fg, c("foo = Foo").as_ptr());
+        assert_eq!(status, FsdrStatus::Description);
+        assert!(last_error().contains("Foo"));
+
+        let mut src = ptr::null_mut();
+        assert_eq!(
+            fsdr_flowgraph_add_source(fg, c("src").as_ptr(), FsdrItemType::U8 as u32, 16, &mut src),
+            FsdrStatus::Ok
+        );
+        let mut other = ptr::null_mut();
+        assert_eq!(
+            fsdr_flowgraph_add_sink(
+                fg,
+                c("src").as_ptr(),
+                FsdrItemType::U8 as u32,
+                16,
+                &mut other
+            ),
+            FsdrStatus::Description
+        );
+        assert!(other.is_null());
+        assert_eq!(
+            fsdr_flowgraph_add_sink(fg, c("snk").as_ptr(), 42, 16, &mut other),
+            FsdrStatus::InvalidArgument
+        );
+        assert_eq!(last_error(), "invalid item type 42");
+        assert!(other.is_null());
+
+        fsdr_source_free(src);
+        fsdr_flowgraph_free(fg);
+    }
+}
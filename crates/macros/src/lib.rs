@@ -82,18 +82,56 @@ use std::iter::Peekable;
 /// connect!(fg, src [Slab::new()] snk);
 /// ```
 ///
+/// A string literal in the brackets selects a named buffer profile from the config, e.g.:
+///
+/// ```ignore
+/// connect!(fg, src ["hot"] snk);
+/// ```
+///
+/// Numbered ports can be connected in bulk. A port followed by an index range
+/// selects `out0`, `out1`, ..., a port followed by `*` selects all numbered
+/// ports the block has, and a block followed by `[..]` (or any range) refers to
+/// a `Vec` or array of blocks. Ranges are zipped pairwise; a single port on one
+/// side fans out to (or in from) all ports on the other side.
+///
+/// ```ignore
+/// connect!(fg,
+///     chan.out[0..8] > decoders[..].in;
+///     deint.out* > comb.in*;
+///     srcs[..] | snk.in;
+/// );
+/// ```
+///
+/// Literal ranges are expanded at compile time, a mismatch in their length is a
+/// compile error. Everything else is resolved when the flowgraph is built and
+/// returns `Error::PortCountMismatch` if the counts do not match.
+///
+#[proc_macro]
+pub fn connect_external(attr: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    expand_connect(attr.into(), quote!(futuresdr)).into()
+}
+
+/// Avoid boilerplate when setting up the flowgraph.
+///
+/// For technical reasons the `connect` macro for use inside and outside the
+/// main crate need to be different. For the user this does not matter, since
+/// [`macro@connect_external`] gets re-exported as `futuresdr::macros::connect`.
 #[proc_macro]
 pub fn connect(attr: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    expand_connect(attr.into(), quote!(crate)).into()
+}
+
+fn expand_connect(attr: TokenStream, krate: TokenStream) -> TokenStream {
     // println!("{}", attr.clone());
     // for a in attr.clone().into_iter() {
     //     println!("{:?}", a);
     // }
-    let mut attrs = TokenStream::from(attr).into_iter().peekable();
+    let mut attrs = attr.into_iter().peekable();
     let mut out = TokenStream::new();
 
     let mut blocks = IndexSet::<Ident>::new();
-    let mut message_connections = Vec::<(Ident, String, Ident, String)>::new();
-    let mut stream_connections = Vec::<(Ident, String, Ident, String, Option<TokenStream>)>::new();
+    let mut message_connections = Vec::<(Side, Side)>::new();
+    let mut stream_connections = Vec::<(Side, Side, Option<TokenStream>)>::new();
 
     // search flowgraph variable
     let fg = match attrs.next() {
@@ -102,13 +140,11 @@ pub fn connect(attr: proc_macro::TokenStream) -> proc_macro::TokenStream {
             return quote_spanned! {
                 t.span() => compile_error!("Connect macro expects flowgraph as first argument.")
             }
-            .into()
         }
         None => {
             return quote! {
                 compile_error!("Connect macro expects flowgraph and connections as arguments.")
             }
-            .into()
         }
     };
 
@@ -117,8 +153,7 @@ pub fn connect(attr: proc_macro::TokenStream) -> proc_macro::TokenStream {
     if n.is_none() || !matches!(n.as_ref().unwrap(), &TokenTree::Punct(_)) {
         return quote_spanned! {
             n.unwrap().span() => compile_error!("Connect macro expects separator after flowgraph")
-        }
-        .into();
+        };
     }
 
     // search for connections
@@ -131,13 +166,13 @@ pub fn connect(attr: proc_macro::TokenStream) -> proc_macro::TokenStream {
                 blocks: b,
             } => {
                 for c in stream.into_iter() {
-                    blocks.insert(c.0.clone());
-                    blocks.insert(c.2.clone());
+                    blocks.insert(c.0.block.clone());
+                    blocks.insert(c.1.block.clone());
                     stream_connections.push(c);
                 }
                 for c in message.into_iter() {
-                    blocks.insert(c.0.clone());
-                    blocks.insert(c.2.clone());
+                    blocks.insert(c.0.block.clone());
+                    blocks.insert(c.1.block.clone());
                     message_connections.push(c);
                 }
                 for block in b.into_iter() {
@@ -149,45 +184,63 @@ pub fn connect(attr: proc_macro::TokenStream) -> proc_macro::TokenStream {
                 if let Some(span) = span {
                     return quote_spanned! {
                         span => compile_error!(#string)
-                    }
-                    .into();
+                    };
                 } else {
                     return quote! {
                         compile_error!(#string)
-                    }
-                    .into();
+                    };
                 }
             }
         }
     }
 
     out.extend(quote! {
-        use futuresdr::runtime::Block;
-        use futuresdr::runtime::Error;
-        use futuresdr::runtime::Flowgraph;
-        use futuresdr::runtime::Kernel;
-        use futuresdr::runtime::TypedBlock;
+        use #krate::runtime::Block;
+        use #krate::runtime::Error;
+        use #krate::runtime::Flowgraph;
+        use #krate::runtime::Kernel;
+        use #krate::runtime::TypedBlock;
         use std::result::Result;
 
         struct FgOp;
         trait Add<T> {
-            fn add(fg: &mut Flowgraph, b: T) -> Result<usize, Error>;
+            type Id;
+            fn add(fg: &mut Flowgraph, b: T) -> Result<Self::Id, Error>;
         }
         impl Add<usize> for FgOp {
+            type Id = usize;
             fn add(_fg: &mut Flowgraph, b: usize) -> Result<usize, Error> {
                 Ok(b)
             }
         }
         impl Add<Block> for FgOp {
+            type Id = usize;
             fn add(fg: &mut Flowgraph, b: Block) -> Result<usize, Error> {
                 fg.add_block(b)
             }
         }
         impl<T: Kernel + 'static> Add<TypedBlock<T>> for FgOp {
+            type Id = usize;
             fn add(fg: &mut Flowgraph, b: TypedBlock<T>) -> Result<usize, Error> {
                 fg.add_block(b)
             }
         }
+        impl<T> Add<Vec<T>> for FgOp where FgOp: Add<T> {
+            type Id = Vec<<FgOp as Add<T>>::Id>;
+            fn add(fg: &mut Flowgraph, b: Vec<T>) -> Result<Self::Id, Error> {
+                b.into_iter().map(|b| FgOp::add(fg, b)).collect()
+            }
+        }
+        impl<T, const N: usize> Add<[T; N]> for FgOp where FgOp: Add<T> {
+            type Id = [<FgOp as Add<T>>::Id; N];
+            fn add(fg: &mut Flowgraph, b: [T; N]) -> Result<Self::Id, Error> {
+                let ids = b
+                    .into_iter()
+                    .map(|b| FgOp::add(fg, b))
+                    .collect::<Result<Vec<_>, Error>>()?;
+                Ok(ids.try_into().ok().unwrap())
+            }
+        }
     });
 
     // Add the blocks to the flowgraph
@@ -197,40 +250,99 @@ pub fn connect(attr: proc_macro::TokenStream) -> proc_macro::TokenStream {
             let #blk_id = FgOp::add(#fg.as_mut(), #blk_id)?;
         });
     }
+
+    let mut dynamic = false;
+    let mut connections = TokenStream::new();
     // Stream connections
-    for (src, src_port, dst, dst_port, buffer) in stream_connections.into_iter() {
-        let src_port = match src_port.parse::<usize>() {
-            Ok(s) => quote!(#s),
-            Err(_) => quote!(#src_port),
-        };
-        let dst_port = match dst_port.parse::<usize>() {
-            Ok(s) => quote!(#s),
-            Err(_) => quote!(#dst_port),
+    for (src, dst, buffer) in stream_connections.into_iter() {
+        let connect = |src: TokenStream,
+                       src_port: TokenStream,
+                       dst: TokenStream,
+                       dst_port: TokenStream| {
+            if let Some(profile) = buffer
+                .as_ref()
+                .and_then(|b| syn::parse2::<syn::LitStr>(b.clone()).ok())
+            {
+                quote! {
+                    #fg.connect_stream_with_profile(#src, #src_port, #dst, #dst_port, #profile)?;
+                }
+            } else if let Some(b) = buffer.as_ref() {
+                quote! {
+                    #fg.connect_stream_with_type(#src, #src_port, #dst, #dst_port, #b)?;
+                }
+            } else {
+                quote! {
+                    #fg.connect_stream(#src, #src_port, #dst, #dst_port)?;
+                }
+            }
         };
-        if let Some(b) = buffer {
-            out.extend(quote! {
-                #fg.connect_stream_with_type(#src, #src_port, #dst, #dst_port, #b)?;
-            });
-        } else {
-            out.extend(quote! {
-                #fg.connect_stream(#src, #src_port, #dst, #dst_port)?;
-            });
+        match connect_ports(
+            &fg,
+            &krate,
+            &src,
+            &dst,
+            quote!(StreamOutput),
+            quote!(StreamInput),
+            connect,
+        ) {
+            Ok((c, d)) => {
+                connections.extend(c);
+                dynamic |= d;
+            }
+            Err(e) => return quote!(compile_error!(#e)),
         }
     }
     // Message connections
-    for (src, src_port, dst, dst_port) in message_connections.into_iter() {
-        let src_port = match src_port.parse::<usize>() {
-            Ok(s) => quote!(#s),
-            Err(_) => quote!(#src_port),
-        };
-        let dst_port = match dst_port.parse::<usize>() {
-            Ok(s) => quote!(#s),
-            Err(_) => quote!(#dst_port),
-        };
+    for (src, dst) in message_connections.into_iter() {
+        let connect =
+            |src: TokenStream, src_port: TokenStream, dst: TokenStream, dst_port: TokenStream| {
+                quote! {
+                    #fg.connect_message(#src, #src_port, #dst, #dst_port)?;
+                }
+            };
+        match connect_ports(
+            &fg,
+            &krate,
+            &src,
+            &dst,
+            quote!(MessageOutput),
+            quote!(MessageInput),
+            connect,
+        ) {
+            Ok((c, d)) => {
+                connections.extend(c);
+                dynamic |= d;
+            }
+            Err(e) => return quote!(compile_error!(#e)),
+        }
+    }
+
+    if dynamic {
         out.extend(quote! {
-            #fg.connect_message(#src, #src_port, #dst, #dst_port)?;
+            fn connect_pairs(
+                src: Vec<(usize, #krate::runtime::PortId)>,
+                dst: Vec<(usize, #krate::runtime::PortId)>,
+                src_name: &str,
+                dst_name: &str,
+            ) -> Result<Vec<((usize, #krate::runtime::PortId), (usize, #krate::runtime::PortId))>, Error> {
+                if src.len() == dst.len() {
+                    Ok(src.into_iter().zip(dst).collect())
+                } else if src.len() == 1 {
+                    Ok(dst.into_iter().map(|d| (src[0].clone(), d)).collect())
+                } else if dst.len() == 1 {
+                    Ok(src.into_iter().map(|s| (s, dst[0].clone())).collect())
+                } else {
+                    Err(Error::PortCountMismatch(
+                        src_name.to_string(),
+                        src.len(),
+                        dst_name.to_string(),
+                        dst.len(),
+                    ))
+                }
+            }
         });
     }
+    out.extend(connections);
 
     let b = blocks.clone().into_iter();
     out.extend(quote! {
@@ -246,13 +358,76 @@ pub fn connect(attr: proc_macro::TokenStream) -> proc_macro::TokenStream {
     ];
 
     // println!("code {}", out);
-    out.into()
+    out
+}
+
+/// Generate the connections between two sides, returning the code and whether it is resolved at
+/// runtime.
+fn connect_ports(
+    fg: &Ident,
+    krate: &TokenStream,
+    src: &Side,
+    dst: &Side,
+    src_kind: TokenStream,
+    dst_kind: TokenStream,
+    connect: impl Fn(TokenStream, TokenStream, TokenStream, TokenStream) -> TokenStream,
+) -> Result<(TokenStream, bool), String> {
+    let port = |p: &str| match p.parse::<usize>() {
+        Ok(s) => quote!(#s),
+        Err(_) => quote!(#p),
+    };
+
+    if let (Some(s), Some(d)) = (expand_static(src), expand_static(dst)) {
+        let pairs: Vec<_> = if s.len() == d.len() {
+            s.into_iter().zip(d).collect()
+        } else if s.len() == 1 {
+            d.into_iter().map(|d| (s[0].clone(), d)).collect()
+        } else if d.len() == 1 {
+            s.into_iter().map(|s| (s, d[0].clone())).collect()
+        } else {
+            return Err(format!(
+                "Cannot connect {} ports of '{}' to {} ports of '{}'",
+                s.len(),
+                src,
+                d.len(),
+                dst
+            ));
+        };
+        let mut out = TokenStream::new();
+        for ((src, src_port), (dst, dst_port)) in pairs {
+            out.extend(connect(
+                quote!(#src),
+                port(&src_port),
+                quote!(#dst),
+                port(&dst_port),
+            ));
+        }
+        return Ok((out, false));
+    }
+
+    let src_ports = expand_dynamic(fg, krate, src, src_kind)?;
+    let dst_ports = expand_dynamic(fg, krate, dst, dst_kind)?;
+    let src_name = src.to_string();
+    let dst_name = dst.to_string();
+    let c = connect(quote!(__s), quote!(__sp), quote!(__d), quote!(__dp));
+    Ok((
+        quote! {
+            let __src_ports = #src_ports;
+            let __dst_ports = #dst_ports;
+            for ((__s, __sp), (__d, __dp)) in
+                connect_pairs(__src_ports, __dst_ports, #src_name, #dst_name)?
+            {
+                #c
+            }
+        },
+        true,
+    ))
 }
 
 enum ParseResult {
     Connections {
-        stream: Vec<(Ident, String, Ident, String, Option<TokenStream>)>,
-        message: Vec<(Ident, String, Ident, String)>,
+        stream: Vec<(Side, Side, Option<TokenStream>)>,
+        message: Vec<(Side, Side)>,
         blocks: IndexSet<Ident>,
     },
     Done,
@@ -261,8 +436,8 @@ enum ParseResult {
 
 fn parse_connections(attrs: &mut Peekable<impl Iterator<Item = TokenTree>>) -> ParseResult {
     let mut blocks = IndexSet::<Ident>::new();
-    let mut stream = Vec::<(Ident, String, Ident, String, Option<TokenStream>)>::new();
-    let mut message = Vec::<(Ident, String, Ident, String)>::new();
+    let mut stream = Vec::<(Side, Side, Option<TokenStream>)>::new();
+    let mut message = Vec::<(Side, Side)>::new();
 
     let mut prev = match next_endpoint(attrs) {
         EndpointResult::Point(e) => e,
@@ -306,10 +481,10 @@ fn parse_connections(attrs: &mut Peekable<impl Iterator<Item = TokenTree>>) -> P
 
         match con {
             Connection::Stream(s) => {
-                stream.push((prev.block, prev.output, e.block.clone(), e.input.clone(), s));
+                stream.push((prev.output(), e.input(), s));
             }
             Connection::Message => {
-                message.push((prev.block, prev.output, e.block.clone(), e.input.clone()));
+                message.push((prev.output(), e.input()));
             }
         }
 
@@ -317,61 +492,65 @@ fn parse_connections(attrs: &mut Peekable<impl Iterator<Item = TokenTree>>) -> P
     }
 }
 
-struct Endpoint {
+/// Port of an endpoint
+#[derive(Clone)]
+enum Port {
+    /// Single port
+    Name(String),
+    /// Numbered ports `<prefix><i>` for `i` in the range
+    Range(String, TokenStream),
+    /// All numbered ports `<prefix><i>` of the block
+    Wildcard(String),
+}
+
+impl std::fmt::Display for Port {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Port::Name(n) => write!(f, "{n}"),
+            Port::Range(n, r) => write!(f, "{n}[{r}]"),
+            Port::Wildcard(n) => write!(f, "{n}*"),
+        }
+    }
+}
+
+/// One side of a connection
+struct Side {
     block: Ident,
-    input: String,
-    output: String,
+    blocks: Option<TokenStream>,
+    port: Port,
 }
 
-impl Endpoint {
-    #[allow(clippy::new_ret_no_self)]
-    fn new(block: Ident) -> EndpointResult {
-        EndpointResult::Point(Self {
-            block,
-            input: "in".to_string(),
-            output: "out".to_string(),
-        })
+impl std::fmt::Display for Side {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.blocks {
+            Some(r) => write!(f, "{}[{}].{}", self.block, r, self.port),
+            None => write!(f, "{}.{}", self.block, self.port),
+        }
     }
+}
 
-    fn with_port(block: Ident, port: TokenTree) -> EndpointResult {
-        let i = match port {
-            TokenTree::Ident(i) => i.to_string(),
-            TokenTree::Literal(l) => l.to_string().replace('"', ""),
-            _ => return EndpointResult::Error(None, format!("invalid endpoint port {port}")),
-        };
-        EndpointResult::Point(Self {
-            block,
-            input: i.clone(),
-            output: i,
-        })
+struct Endpoint {
+    block: Ident,
+    blocks: Option<TokenStream>,
+    input: Port,
+    output: Port,
+}
+
+impl Endpoint {
+    fn input(&self) -> Side {
+        Side {
+            block: self.block.clone(),
+            blocks: self.blocks.clone(),
+            port: self.input.clone(),
+        }
     }
 
-    fn with_ports(block: Ident, in_port: TokenTree, out_port: TokenTree) -> EndpointResult {
-        let input = match in_port {
-            TokenTree::Ident(i) => i.to_string(),
-            TokenTree::Literal(l) => l.to_string().replace('"', ""),
-            _ => {
-                return EndpointResult::Error(
-                    None,
-                    format!("invalid endpoint input port {in_port}"),
-                )
-            }
-        };
-        let output = match out_port {
-            TokenTree::Ident(i) => i.to_string(),
-            TokenTree::Literal(l) => l.to_string().replace('"', ""),
-            _ => {
-                return EndpointResult::Error(
-                    None,
-                    format!("invalid endpoint output port {out_port}"),
-                )
-            }
-        };
-        EndpointResult::Point(Self {
-            block,
-            input,
-            output,
-        })
+    fn output(&self) -> Side {
+        Side {
+            block: self.block.clone(),
+            blocks: self.blocks.clone(),
+            port: self.output.clone(),
+        }
     }
 }
 
@@ -381,115 +560,238 @@ enum EndpointResult {
     Done,
 }
 
-fn next_endpoint(attrs: &mut Peekable<impl Iterator<Item = TokenTree>>) -> EndpointResult {
-    use TokenTree::*;
+/// Block or port of an endpoint with optional index range and wildcard
+struct Item {
+    token: TokenTree,
+    index: Option<TokenStream>,
+    wildcard: bool,
+}
 
-    let i1 = match attrs.next() {
-        Some(Ident(s)) => Ident(s),
-        Some(Literal(s)) => Literal(s),
-        Some(t) => {
-            return EndpointResult::Error(
-                Some(t.span()),
-                "Expected block identifier or port".into(),
-            );
+impl Item {
+    fn block(self) -> Result<(Ident, Option<TokenStream>), (Option<Span>, String)> {
+        match self.token {
+            TokenTree::Ident(i) if !self.wildcard => Ok((i, self.index)),
+            TokenTree::Ident(i) => Err((
+                Some(i.span()),
+                "Wildcards (*) are only supported for ports".into(),
+            )),
+            t => Err((Some(t.span()), "Expected block identifier".into())),
         }
-        None => {
-            return EndpointResult::Done;
+    }
+
+    fn port(self) -> Result<Port, (Option<Span>, String)> {
+        let span = self.token.span();
+        let name = match self.token {
+            TokenTree::Ident(i) => i.to_string(),
+            TokenTree::Literal(l) => l.to_string().replace('"', ""),
+            t => return Err((Some(t.span()), format!("invalid endpoint port {t}"))),
+        };
+        match (self.index, self.wildcard) {
+            (None, false) => Ok(Port::Name(name)),
+            (None, true) => Ok(Port::Wildcard(name)),
+            (Some(r), false) => Ok(Port::Range(name, r)),
+            (Some(_), true) => Err((
+                Some(span),
+                "Port can either have a range or a wildcard (*)".into(),
+            )),
         }
-    };
+    }
+}
+
+// Index ranges are brackets with a range expression, all other brackets are custom buffers
+fn is_index(g: &proc_macro2::Group) -> bool {
+    g.delimiter() == proc_macro2::Delimiter::Bracket
+        && syn::parse2::<syn::ExprRange>(g.stream()).is_ok()
+}
 
-    match (i1.clone(), attrs.peek()) {
-        (Ident(i), Some(Punct(p))) => {
-            if [";", ">", "|"].contains(&p.to_string().as_str()) {
-                return Endpoint::new(i);
-            } else if p.to_string() != "." {
+fn next_endpoint(attrs: &mut Peekable<impl Iterator<Item = TokenTree>>) -> EndpointResult {
+    use TokenTree::*;
+
+    let mut items = Vec::<Item>::new();
+    loop {
+        let token = match attrs.next() {
+            Some(Ident(s)) => Ident(s),
+            Some(Literal(s)) => Literal(s),
+            Some(t) => {
                 return EndpointResult::Error(
-                    Some(p.span()),
-                    "Expected dot or connection separator or terminator after block".into(),
+                    Some(t.span()),
+                    if items.is_empty() {
+                        "Expected block identifier or port".into()
+                    } else {
+                        "Expected block or port identifier".into()
+                    },
                 );
-            } else {
-                let _ = attrs.next();
             }
-        }
-        (Ident(i), Some(Group(_))) => return Endpoint::new(i),
-        (_, Some(t)) => {
-            return EndpointResult::Error(
-                Some(t.span()),
-                "Expected dot, connection separator, or terminator after block".into(),
-            );
-        }
-        (Ident(i), None) => {
-            return Endpoint::new(i);
-        }
-        (_, None) => {
-            return EndpointResult::Error(None, "Endpoint consists only of string literal".into());
-        }
-    }
+            None if items.is_empty() => {
+                return EndpointResult::Done;
+            }
+            None => {
+                return EndpointResult::Error(None, "Connections stopped unexpectedly".into());
+            }
+        };
 
-    let i2 = match attrs.next() {
-        Some(TokenTree::Ident(p)) => TokenTree::Ident(p),
-        Some(TokenTree::Literal(l)) => TokenTree::Literal(l),
-        Some(t) => {
-            return EndpointResult::Error(
-                Some(t.span()),
-                "Expected block or port identifier".into(),
-            );
+        let mut item = Item {
+            token,
+            index: None,
+            wildcard: false,
+        };
+        if let Some(Group(g)) = attrs.peek() {
+            if is_index(g) {
+                item.index = Some(g.stream());
+                let _ = attrs.next();
+            }
         }
-        None => {
-            return EndpointResult::Error(None, "Connections stopped unexpectedly".into());
+        if matches!(attrs.peek(), Some(Punct(p)) if p.as_char() == '*') {
+            item.wildcard = true;
+            let _ = attrs.next();
         }
-    };
+        items.push(item);
 
-    match (i1.clone(), attrs.peek()) {
-        (Ident(i), Some(TokenTree::Punct(p))) => {
-            if [";", ">", "|"].contains(&p.to_string().as_str()) {
-                return Endpoint::with_port(i, i2);
-            } else if p.to_string() != "." {
+        match attrs.peek() {
+            Some(Punct(p)) if [';', '>', '|'].contains(&p.as_char()) => break,
+            Some(Punct(p)) if p.as_char() == '.' && items.len() < 3 => {
+                let _ = attrs.next();
+            }
+            Some(Punct(p)) => {
                 return EndpointResult::Error(
                     Some(p.span()),
                     "Expected dot or connection separator or terminator after block".into(),
                 );
-            } else {
-                let _ = attrs.next();
             }
-        }
-        (Ident(i), Some(TokenTree::Group(_))) => {
-            return Endpoint::with_port(i, i2);
-        }
-        (_, Some(t)) => {
-            return EndpointResult::Error(
-                Some(t.span()),
-                "Expected dot, connection separator, or terminator after block".into(),
-            );
-        }
-        (TokenTree::Ident(i), None) => {
-            return Endpoint::with_port(i, i2);
-        }
-        (_, None) => {
-            return EndpointResult::Error(None, "Endpoint consists only of string literal".into());
+            Some(Group(_)) | None => break,
+            Some(t) => {
+                return EndpointResult::Error(
+                    Some(t.span()),
+                    "Expected dot, connection separator, or terminator after block".into(),
+                );
+            }
         }
     }
 
-    let i3 = match attrs.next() {
-        Some(TokenTree::Ident(p)) => TokenTree::Ident(p),
-        Some(TokenTree::Literal(l)) => TokenTree::Literal(l),
-        Some(t) => {
-            return EndpointResult::Error(Some(t.span()), "Expected port identifier".into());
-        }
-        None => {
-            return EndpointResult::Error(None, "Connections stopped unexpectedly".into());
+    let endpoint = || -> Result<Endpoint, (Option<Span>, String)> {
+        let mut items = items.into_iter();
+        match (items.next(), items.next(), items.next()) {
+            (Some(b), None, None) => {
+                if matches!(b.token, Literal(_)) {
+                    return Err((None, "Endpoint consists only of string literal".into()));
+                }
+                let (block, blocks) = b.block()?;
+                Ok(Endpoint {
+                    block,
+                    blocks,
+                    input: Port::Name("in".to_string()),
+                    output: Port::Name("out".to_string()),
+                })
+            }
+            (Some(b), Some(p), None) => {
+                let (block, blocks) = b.block()?;
+                let port = p.port()?;
+                Ok(Endpoint {
+                    block,
+                    blocks,
+                    input: port.clone(),
+                    output: port,
+                })
+            }
+            (Some(i), Some(b), Some(o)) => {
+                if !matches!(b.token, Ident(_)) {
+                    return Err((
+                        None,
+                        "Middle token of endpoint triple should be the block Ident".into(),
+                    ));
+                }
+                let (block, blocks) = b.block()?;
+                Ok(Endpoint {
+                    block,
+                    blocks,
+                    input: i.port()?,
+                    output: o.port()?,
+                })
+            }
+            _ => unreachable!(),
         }
     };
 
-    match i2 {
-        Ident(i) => Endpoint::with_ports(i, i1, i3),
-        _ => EndpointResult::Error(
-            None,
-            "Middle token of endpoint triple should be the block Ident".into(),
-        ),
+    match endpoint() {
+        Ok(e) => EndpointResult::Point(e),
+        Err((span, string)) => EndpointResult::Error(span, string),
     }
 }
 
+// Expand a side of a connection into (block, port) pairs, if possible at compile time
+fn expand_static(side: &Side) -> Option<Vec<(Ident, String)>> {
+    if side.blocks.is_some() {
+        return None;
+    }
+    match &side.port {
+        Port::Name(n) => Some(vec![(side.block.clone(), n.clone())]),
+        Port::Wildcard(_) => None,
+        Port::Range(prefix, r) => {
+            let lit = |e: &Option<Box<syn::Expr>>| match e.as_deref() {
+                Some(syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Int(i),
+                    ..
+                })) => i.base10_parse::<usize>().ok(),
+                _ => None,
+            };
+            let r = syn::parse2::<syn::ExprRange>(r.clone()).ok()?;
+            let start = lit(&r.start)?;
+            let end = match r.limits {
+                syn::RangeLimits::HalfOpen(_) => lit(&r.end)?,
+                syn::RangeLimits::Closed(_) => lit(&r.end)? + 1,
+            };
+            Some(
+                (start..end)
+                    .map(|i| (side.block.clone(), format!("{prefix}{i}")))
+                    .collect(),
+            )
+        }
+    }
+}
+
+// Expression evaluating to a `Vec<(usize, PortId)>` with the ports of a side at runtime
+fn expand_dynamic(
+    fg: &Ident,
+    krate: &TokenStream,
+    side: &Side,
+    kind: TokenStream,
+) -> Result<TokenStream, String> {
+    let block = &side.block;
+    let blocks = match &side.blocks {
+        Some(r) => quote!(#block[#r].iter().copied()),
+        None => quote!([#block]),
+    };
+    let ports = match &side.port {
+        Port::Name(n) => match n.parse::<usize>() {
+            Ok(i) => quote!([#krate::runtime::PortId::from(#i)]),
+            Err(_) => quote!([#krate::runtime::PortId::from(#n)]),
+        },
+        Port::Range(prefix, r) => {
+            let range = syn::parse2::<syn::ExprRange>(r.clone()).map_err(|e| e.to_string())?;
+            if range.start.is_none() || range.end.is_none() {
+                return Err(format!("Port range of {side} needs start and end"));
+            }
+            quote! {
+                (#r).map(|i| #krate::runtime::PortId::from(format!("{}{}", #prefix, i)))
+            }
+        }
+        Port::Wildcard(prefix) => quote! {
+            #fg.numbered_ports(__b, #prefix, #krate::runtime::PortKind::#kind)?
+        },
+    };
+    Ok(quote! {
+        {
+            let mut __ports = Vec::new();
+            for __b in #blocks {
+                for __p in #ports {
+                    __ports.push((__b, __p));
+                }
+            }
+            __ports
+        }
+    })
+}
+
 enum ConnectionResult {
     Stream(Option<TokenStream>),
     Message,
@@ -532,11 +834,14 @@ fn next_connection(attrs: &mut Peekable<impl Iterator<Item = TokenTree>>) -> Con
 /// See [`macro@message_handler_external`] for a more information on how to use the macro.
 #[proc_macro_attribute]
 pub fn message_handler(
-    _attr: proc_macro::TokenStream,
+    attr: proc_macro::TokenStream,
     fun: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
     let handler: syn::ItemFn = syn::parse(fun).unwrap();
-    let mut out = TokenStream::new();
+    let mut out = match handler_schema(attr.into(), &handler.sig.ident, quote!(crate)) {
+        Ok(o) => o,
+        Err(e) => return e.to_compile_error().into(),
+    };
 
     let name = handler.sig.ident;
     let io = get_parameter_ident(&handler.sig.inputs[1]).unwrap();
@@ -620,13 +925,41 @@ pub fn message_handler(
 ///     Ok(Pmt::Null)
 /// }
 /// ```
+///
+/// The [`HandlerSchema`](futuresdr::runtime::HandlerSchema) of the handler can be declared
+/// through attributes. In this case, the macro also generates a `<handler>_schema()` function,
+/// returning the schema, which can be passed to `MessageIoBuilder::add_input_with_schema`.
+///
+/// ```ignore
+/// #[message_handler(
+///     accepts(F64, U32),
+///     returns(F64),
+///     range(70e6, 6e9),
+///     description = "Center frequency in Hz"
+/// )]
+/// async fn freq(
+///     // ...
+/// ) -> Result<Pmt> {
+///     // ...
+/// }
+///
+/// MessageIoBuilder::new()
+///     .add_input_with_schema("freq", Self::freq_schema(), Self::freq)
+/// ```
+///
+/// Blocks that derive [`macro@BlockExternal`] register their handlers through
+/// [`macro@message_handlers_external`]. There, `name = ".."` sets the name of the
+/// port, which defaults to the name of the handler.
 #[proc_macro_attribute]
 pub fn message_handler_external(
-    _attr: proc_macro::TokenStream,
+    attr: proc_macro::TokenStream,
     fun: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
     let handler: syn::ItemFn = syn::parse(fun).unwrap();
-    let mut out = TokenStream::new();
+    let mut out = match handler_schema(attr.into(), &handler.sig.ident, quote!(futuresdr)) {
+        Ok(o) => o,
+        Err(e) => return e.to_compile_error().into(),
+    };
 
     let name = handler.sig.ident;
     let io = get_parameter_ident(&handler.sig.inputs[1]).unwrap();
@@ -673,6 +1006,115 @@ pub fn message_handler_external(
     out.into()
 }
 
+/// Generate the `<handler>_schema()` function from the attributes of a message handler.
+fn handler_schema(attr: TokenStream, name: &Ident, krate: TokenStream) -> syn::Result<TokenStream> {
+    use syn::punctuated::Punctuated;
+
+    let (_, metas) = handler_attrs(attr)?;
+    if metas.is_empty() {
+        return Ok(TokenStream::new());
+    }
+
+    let mut schema = quote!(#krate::runtime::HandlerSchema::new());
+    for meta in metas {
+        match meta {
+            syn::Meta::List(l) if l.path.is_ident("accepts") || l.path.is_ident("returns") => {
+                let method = l.path.get_ident().unwrap();
+                let kinds =
+                    l.parse_args_with(Punctuated::<Ident, syn::Token![,]>::parse_terminated)?;
+                let kinds = kinds.iter();
+                schema.extend(quote! {
+                    .#method([#(#krate::runtime::PmtKind::#kinds),*])
+                });
+            }
+            syn::Meta::List(l) if l.path.is_ident("range") => {
+                let bounds =
+                    l.parse_args_with(Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated)?;
+                if bounds.len() != 2 {
+                    return Err(syn::Error::new_spanned(l, "expected range(min, max)"));
+                }
+                let (min, max) = (&bounds[0], &bounds[1]);
+                schema.extend(quote! {
+                    .range((#min) as f64, (#max) as f64)
+                });
+            }
+            syn::Meta::NameValue(nv) if nv.path.is_ident("description") => {
+                let d = nv.value;
+                schema.extend(quote! {
+                    .description(#d)
+                });
+            }
+            m => {
+                return Err(syn::Error::new_spanned(
+                    m,
+                    "expected name = \"..\", accepts(..), returns(..), range(min, max), or description = \"..\"",
+                ))
+            }
+        }
+    }
+
+    let fn_name = Ident::new(&format!("{name}_schema"), name.span());
+    Ok(quote! {
+        #[allow(dead_code)]
+        fn #fn_name() -> #krate::runtime::HandlerSchema {
+            #schema
+        }
+    })
+}
+
+/// Split the attributes of a message handler into the port name and the schema attributes.
+fn handler_attrs(attr: TokenStream) -> syn::Result<(Option<String>, Vec<syn::Meta>)> {
+    use syn::punctuated::Punctuated;
+
+    if attr.is_empty() {
+        return Ok((None, Vec::new()));
+    }
+
+    let metas = syn::parse::Parser::parse2(
+        Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+        attr,
+    )?;
+
+    let mut name = None;
+    let mut schema = Vec::new();
+    for meta in metas {
+        match meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                path,
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(s),
+                        ..
+                    }),
+                ..
+            }) if path.is_ident("name") => name = Some(s.value()),
+            m => schema.push(m),
+        }
+    }
+    Ok((name, schema))
+}
+
+/// Name of the constant of a port, e.g., `FREQ_HZ` for `freq-hz`
+fn const_ident(name: &str, tokens: impl quote::ToTokens) -> syn::Result<Ident> {
+    let c = name
+        .trim_start_matches("r#")
+        .chars()
+        .flat_map(|c| {
+            if c.is_alphanumeric() || c == '_' {
+                c.to_uppercase().collect::<Vec<_>>()
+            } else {
+                vec!['_']
+            }
+        })
+        .collect::<String>();
+    syn::parse_str::<Ident>(&c).map_err(|_| {
+        syn::Error::new_spanned(
+            tokens,
+            format!("port name `{name}` does not map to a valid constant name"),
+        )
+    })
+}
+
 fn get_parameter_ident(arg: &syn::FnArg) -> Option<syn::Ident> {
     if let syn::FnArg::Typed(syn::PatType { pat, .. }) = arg {
         if let syn::Pat::Ident(ref i) = **pat {
@@ -682,6 +1124,846 @@ fn get_parameter_ident(arg: &syn::FnArg) -> Option<syn::Ident> {
     None
 }
 
+//=========================================================================
+// MESSAGE_HANDLERS
+//=========================================================================
+
+/// Register the message handlers of a block.
+///
+/// For technical reasons the `message_handlers` macro for use inside and outside the
+/// main crate need to be different. For the user this does not matter, since
+/// [`macro@message_handlers_external`] gets re-exported as `futuresdr::macros::message_handlers`.
+#[proc_macro_attribute]
+pub fn message_handlers(
+    _attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let item = syn::parse_macro_input!(item as syn::ItemImpl);
+    expand_message_handlers(item, quote!(crate))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Register the message handlers of a block.
+///
+/// Annotates the `impl` block of a block that derives [`macro@BlockExternal`].
+/// All methods of the `impl` block that are annotated with
+/// [`macro@message_handler_external`] become message inputs of the block. The
+/// port name is the name of the handler or, if given, the `name` of the handler
+/// attribute.
+///
+/// ```ignore
+/// #[message_handlers]
+/// impl MyBlock {
+///     #[message_handler(name = "gain")]
+///     async fn set_gain(
+///         // ...
+///     ) -> Result<Pmt> {
+///         // ...
+///     }
+/// }
+/// ```
+///
+/// It generates constants with the names of the ports (`MyBlock::GAIN`).
+#[proc_macro_attribute]
+pub fn message_handlers_external(
+    _attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let item = syn::parse_macro_input!(item as syn::ItemImpl);
+    expand_message_handlers(item, quote!(futuresdr))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_message_handlers(
+    mut item: syn::ItemImpl,
+    krate: TokenStream,
+) -> syn::Result<TokenStream> {
+    use syn::ext::IdentExt;
+
+    let mut consts = Vec::new();
+    let mut inputs = Vec::new();
+    for i in item.items.iter() {
+        let syn::ImplItem::Fn(f) = i else {
+            continue;
+        };
+        let Some(attr) = f.attrs.iter().find(|a| {
+            a.path()
+                .segments
+                .last()
+                .is_some_and(|s| s.ident == "message_handler")
+        }) else {
+            continue;
+        };
+        let args = match &attr.meta {
+            syn::Meta::List(l) => l.tokens.clone(),
+            _ => TokenStream::new(),
+        };
+        let (name, schema) = handler_attrs(args)?;
+        let handler = &f.sig.ident;
+        let name = name.unwrap_or_else(|| handler.unraw().to_string());
+
+        let mut c = const_ident(&name, attr)?;
+        c.set_span(handler.span());
+        let doc = format!("Name of message port `{name}`");
+        consts.push(quote! {
+            #[doc = #doc]
+            pub const #c: &'static str = #name;
+        });
+
+        if schema.is_empty() {
+            inputs.push(quote!(.add_input(#name, Self::#handler)));
+        } else {
+            let schema = Ident::new(&format!("{}_schema", handler.unraw()), handler.span());
+            inputs.push(quote!(.add_input_with_schema(#name, Self::#schema(), Self::#handler)));
+        }
+    }
+
+    // shadows the default of `DefaultMessageInputs`, which is used by the `Block` derive
+    item.items.push(syn::parse_quote! {
+        #[doc(hidden)]
+        fn __message_inputs(
+            builder: #krate::runtime::MessageIoBuilder<Self>,
+        ) -> #krate::runtime::MessageIoBuilder<Self> {
+            builder #(#inputs)*
+        }
+    });
+    for c in consts {
+        item.items.push(syn::parse2(c)?);
+    }
+
+    Ok(quote!(#item))
+}
+
+//=========================================================================
+// BLOCK
+//=========================================================================
+
+/// Avoid boilerplate when creating blocks.
+///
+/// For technical reasons the `Block` derive macro for use inside and outside the
+/// main crate need to be different. For the user this does not matter, since
+/// [`macro@BlockExternal`] gets re-exported as `futuresdr::macros::Block`.
+#[proc_macro_derive(Block, attributes(block, input, output))]
+pub fn block(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    derive_block(input, quote!(crate))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Avoid boilerplate when creating blocks.
+///
+/// Stream ports are declared as fields of type `StreamIn<T>` and `StreamOut<T>`,
+/// annotated with `#[input]` and `#[output]`. The port name is the field name,
+/// unless it is set explicitly. Message outputs and block properties are set
+/// through the `#[block(..)]` attribute of the struct. Message inputs are the
+/// handlers of the block, registered through [`macro@message_handlers_external`].
+///
+/// ```ignore
+/// #[derive(Block)]
+/// #[block(type_name = "MyApply", message_outputs(info))]
+/// pub struct MyBlock {
+///     #[input(name = "in")]
+///     input: StreamIn<f32>,
+///     #[output]
+///     out: StreamOut<f32>,
+///     gain: f32,
+/// }
+/// ```
+///
+/// It generates
+///
+/// - constants with the names of the stream ports and message outputs
+///   (`MyBlock::INPUT`, `MyBlock::OUT`, `MyBlock::INFO`),
+/// - typed accessors for the stream ports, which return a `TypedStreamInput`
+///   or `TypedStreamOutput`, e.g., `self.input(sio).slice()` is a `&[f32]`,
+/// - `From` implementations that create a `TypedBlock<MyBlock>` or a `Block`
+///   from the kernel, e.g., `MyBlock { .. }.into()`.
+///
+/// `#[block(blocking)]` marks the block as blocking.
+#[proc_macro_derive(BlockExternal, attributes(block, input, output))]
+pub fn block_external(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    derive_block(input, quote!(futuresdr))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+struct StreamPort {
+    field: Ident,
+    vis: syn::Visibility,
+    name: String,
+    ty: syn::Type,
+    input: bool,
+}
+
+fn derive_block(input: syn::DeriveInput, krate: TokenStream) -> syn::Result<TokenStream> {
+    use syn::ext::IdentExt;
+
+    let ident = &input.ident;
+    let fields = match &input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(f),
+            ..
+        }) => &f.named,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input,
+                "Block can only be derived for structs with named fields",
+            ))
+        }
+    };
+
+    // block attributes
+    let mut type_name = ident.unraw().to_string();
+    let mut blocking = false;
+    let mut message_outputs: Vec<String> = Vec::new();
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("block")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("type_name") {
+                type_name = meta.value()?.parse::<syn::LitStr>()?.value();
+            } else if meta.path.is_ident("blocking") {
+                blocking = true;
+            } else if meta.path.is_ident("message_inputs") {
+                return Err(meta
+                    .error("message inputs are the handlers of a #[message_handlers] impl block"));
+            } else if meta.path.is_ident("message_outputs") {
+                meta.parse_nested_meta(|m| {
+                    message_outputs.push(m.path.require_ident()?.unraw().to_string());
+                    Ok(())
+                })?;
+            } else {
+                return Err(meta.error("unsupported block attribute"));
+            }
+            Ok(())
+        })?;
+    }
+
+    // stream ports
+    let mut ports = Vec::new();
+    for field in fields.iter() {
+        for attr in field.attrs.iter() {
+            let input = if attr.path().is_ident("input") {
+                true
+            } else if attr.path().is_ident("output") {
+                false
+            } else {
+                continue;
+            };
+            let field_ident = field.ident.clone().unwrap();
+            let mut name = field_ident.unraw().to_string();
+            if let syn::Meta::List(_) = attr.meta {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("name") {
+                        name = meta.value()?.parse::<syn::LitStr>()?.value();
+                        Ok(())
+                    } else {
+                        Err(meta.error("unsupported port attribute"))
+                    }
+                })?;
+            }
+            ports.push(StreamPort {
+                field: field_ident,
+                vis: field.vis.clone(),
+                name,
+                ty: port_item_type(&field.ty, if input { "StreamIn" } else { "StreamOut" })?,
+                input,
+            });
+        }
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // port name constants and accessors
+    let mut items = Vec::new();
+    let port_consts = ports
+        .iter()
+        .map(|p| (p.field.unraw(), &p.name, "stream"))
+        .chain(
+            message_outputs
+                .iter()
+                .map(|n| (Ident::new(n, Span::call_site()), n, "message")),
+        );
+    for (field, name, kind) in port_consts {
+        let mut c = const_ident(&field.to_string(), &field)?;
+        c.set_span(field.span());
+        let doc = format!("Name of {kind} port `{name}`");
+        items.push(quote! {
+            #[doc = #doc]
+            pub const #c: &'static str = #name;
+        });
+    }
+    let mut n_inputs = 0usize;
+    let mut n_outputs = 0usize;
+    for p in ports.iter() {
+        let StreamPort {
+            field,
+            vis,
+            name,
+            ty,
+            ..
+        } = p;
+        if p.input {
+            let doc = format!("Stream input `{name}`");
+            let id = n_inputs;
+            n_inputs += 1;
+            items.push(quote! {
+                #[doc = #doc]
+                #[allow(dead_code)]
+                #vis fn #field<'a>(
+                    &self,
+                    sio: &'a mut #krate::runtime::StreamIo,
+                ) -> #krate::runtime::TypedStreamInput<'a, #ty> {
+                    let _ = self.#field;
+                    #krate::runtime::TypedStreamInput::new(sio.input(#id))
+                }
+            });
+        } else {
+            let doc = format!("Stream output `{name}`");
+            let id = n_outputs;
+            n_outputs += 1;
+            items.push(quote! {
+                #[doc = #doc]
+                #[allow(dead_code)]
+                #vis fn #field<'a>(
+                    &self,
+                    sio: &'a mut #krate::runtime::StreamIo,
+                ) -> #krate::runtime::TypedStreamOutput<'a, #ty> {
+                    let _ = self.#field;
+                    #krate::runtime::TypedStreamOutput::new(sio.output(#id))
+                }
+            });
+        }
+    }
+
+    // constructor
+    let mut generics = input.generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote!(#ident #ty_generics: #krate::runtime::Kernel + Send + 'static));
+    let (_, _, kernel_where_clause) = generics.split_for_impl();
+
+    let blocking = if blocking {
+        quote!(.blocking())
+    } else {
+        quote!()
+    };
+    let stream_ports = ports.iter().map(|p| {
+        let StreamPort { name, ty, .. } = p;
+        if p.input {
+            quote!(.add_input::<#ty>(#name))
+        } else {
+            quote!(.add_output::<#ty>(#name))
+        }
+    });
+    let message_outputs = message_outputs
+        .iter()
+        .map(|name| quote!(.add_output(#name)));
+
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #(#items)*
+        }
+
+        impl #impl_generics ::core::convert::From<#ident #ty_generics>
+            for #krate::runtime::TypedBlock<#ident #ty_generics> #kernel_where_clause
+        {
+            fn from(kernel: #ident #ty_generics) -> Self {
+                // blocks without `#[message_handlers]` impl block use the default
+                #[allow(unused_imports)]
+                use #krate::runtime::DefaultMessageInputs as _;
+
+                #krate::runtime::TypedBlock::new(
+                    #krate::runtime::BlockMetaBuilder::new(#type_name)#blocking.build(),
+                    #krate::runtime::StreamIoBuilder::new()
+                        #(#stream_ports)*
+                        .build(),
+                    <#ident #ty_generics>::__message_inputs(
+                        #krate::runtime::MessageIoBuilder::<#ident #ty_generics>::new(),
+                    )
+                    #(#message_outputs)*
+                    .build(),
+                    kernel,
+                )
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<#ident #ty_generics>
+            for #krate::runtime::Block #kernel_where_clause
+        {
+            fn from(kernel: #ident #ty_generics) -> Self {
+                #krate::runtime::Block::from_typed(#krate::runtime::TypedBlock::from(kernel))
+            }
+        }
+    })
+}
+
+// item type `T` of a `StreamIn<T>` or `StreamOut<T>` field
+fn port_item_type(ty: &syn::Type, marker: &str) -> syn::Result<syn::Type> {
+    if let syn::Type::Path(p) = ty {
+        if let Some(seg) = p.path.segments.last() {
+            if seg.ident == marker {
+                if let syn::PathArguments::AngleBracketed(args) = &seg.arguments {
+                    if let Some(syn::GenericArgument::Type(t)) = args.args.first() {
+                        return Ok(t.clone());
+                    }
+                }
+            }
+        }
+    }
+    Err(syn::Error::new_spanned(
+        ty,
+        format!("expected port of type {marker}<T>"),
+    ))
+}
+
+//=========================================================================
+// PMT
+//=========================================================================
+
+/// Convert structs and enums into a `Pmt`.
+///
+/// For technical reasons the `IntoPmt` derive macro for use inside and outside the
+/// main crate need to be different. For the user this does not matter, since
+/// [`macro@IntoPmtExternal`] gets re-exported as `futuresdr::macros::IntoPmt`.
+#[proc_macro_derive(IntoPmt, attributes(pmt))]
+pub fn into_pmt(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    derive_into_pmt(input, quote!(crate::runtime))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Convert structs and enums into a `Pmt`.
+///
+/// Implements `IntoPmt` and `From<T> for Pmt`. All fields have to implement
+/// `IntoPmt`, which is the case for primitive types, `String`, `Option`, `Vec`,
+/// `HashMap<String, _>`, and other types that derive it.
+///
+/// ```ignore
+/// #[derive(IntoPmt, FromPmt)]
+/// struct Config {
+///     freq: f64,
+///     #[pmt(rename = "sample_rate")]
+///     rate: f64,
+///     gain: Option<f64>,
+///     channels: Vec<Channel>,
+/// }
+///
+/// #[derive(IntoPmt, FromPmt)]
+/// enum Channel {
+///     Off,
+///     Rx(u32),
+///     Tx { port: u32, power: f32 },
+/// }
+/// ```
+///
+/// - Structs with named fields map to `Pmt::MapStrPmt` with the field names
+///   (or the name given by `#[pmt(rename = "..")]`) as keys.
+/// - Newtype structs map to their inner value, other tuple structs to a
+///   `Pmt::VecPmt`, and unit structs to `Pmt::Null`.
+/// - Unit enum variants map to `Pmt::String` with the variant name, variants with
+///   data to a `Pmt::MapStrPmt` with the variant name as single key and the data,
+///   mapped like a struct, as value, e.g., `{"Rx": 1}`.
+///
+/// The generated code refers to `futuresdr::runtime::Pmt`. Crates that only
+/// depend on `futuresdr-types` get `futuresdr_types::Pmt` instead.
+#[proc_macro_derive(IntoPmtExternal, attributes(pmt))]
+pub fn into_pmt_external(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    derive_into_pmt(input, pmt_types_path())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Convert a `Pmt` into structs and enums.
+///
+/// For technical reasons the `FromPmt` derive macro for use inside and outside the
+/// main crate need to be different. For the user this does not matter, since
+/// [`macro@FromPmtExternal`] gets re-exported as `futuresdr::macros::FromPmt`.
+#[proc_macro_derive(FromPmt, attributes(pmt))]
+pub fn from_pmt(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    derive_from_pmt(input, quote!(crate::runtime))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Convert a `Pmt` into structs and enums.
+///
+/// Implements `FromPmt` and `TryFrom<Pmt>` with `FromPmtError` as error type.
+/// The mapping is the same as for [`macro@IntoPmtExternal`].
+///
+/// Fields of type `Option` may be missing in the `Pmt::MapStrPmt`. Fields
+/// annotated with `#[pmt(default)]` are set to their `Default` value if they are
+/// missing. Keys that do not correspond to a field are ignored.
+///
+/// If the conversion fails, the error includes the path of the offending value,
+/// e.g., `channels[2].Tx.power: expected f32, found String (foo)`.
+#[proc_macro_derive(FromPmtExternal, attributes(pmt))]
+pub fn from_pmt_external(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    derive_from_pmt(input, pmt_types_path())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+// Path to the Pmt types in crates using the external derives. This is the
+// `runtime` module of `futuresdr` if the crate depends on it (possibly
+// renamed) and the `futuresdr-types` crate otherwise.
+fn pmt_types_path() -> TokenStream {
+    use proc_macro_crate::crate_name;
+    use proc_macro_crate::FoundCrate;
+
+    match crate_name("futuresdr") {
+        Ok(FoundCrate::Name(name)) => {
+            let name = Ident::new(&name, Span::call_site());
+            return quote!(#name::runtime);
+        }
+        Ok(FoundCrate::Itself) => return quote!(futuresdr::runtime),
+        Err(_) => {}
+    }
+    match crate_name("futuresdr-types") {
+        Ok(FoundCrate::Name(name)) => {
+            let name = Ident::new(&name, Span::call_site());
+            quote!(#name)
+        }
+        Ok(FoundCrate::Itself) => quote!(futuresdr_types),
+        Err(_) => quote!(futuresdr::runtime),
+    }
+}
+
+/// Options set through `#[pmt(..)]` attributes
+#[derive(Default)]
+struct PmtAttrs {
+    rename: Option<String>,
+    default: bool,
+}
+
+fn pmt_attrs(attrs: &[syn::Attribute]) -> syn::Result<PmtAttrs> {
+    let mut out = PmtAttrs::default();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("pmt")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                out.rename = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+            } else if meta.path.is_ident("default") {
+                out.default = true;
+            } else {
+                return Err(meta.error("unsupported pmt attribute"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(out)
+}
+
+// key of a named field or variant
+fn pmt_name(ident: &Ident, attrs: &[syn::Attribute]) -> syn::Result<String> {
+    use syn::ext::IdentExt;
+    Ok(pmt_attrs(attrs)?
+        .rename
+        .unwrap_or_else(|| ident.unraw().to_string()))
+}
+
+// generics with an additional `T: bound` for all field types, if the type is generic
+fn pmt_generics(input: &syn::DeriveInput, bound: TokenStream) -> syn::Generics {
+    let mut generics = input.generics.clone();
+    if generics.type_params().next().is_none() {
+        return generics;
+    }
+    let types: Vec<&syn::Type> = match &input.data {
+        syn::Data::Struct(s) => s.fields.iter().map(|f| &f.ty).collect(),
+        syn::Data::Enum(e) => e
+            .variants
+            .iter()
+            .flat_map(|v| v.fields.iter().map(|f| &f.ty))
+            .collect(),
+        syn::Data::Union(_) => Vec::new(),
+    };
+    let where_clause = generics.make_where_clause();
+    for ty in types {
+        where_clause.predicates.push(syn::parse_quote!(#ty: #bound));
+    }
+    generics
+}
+
+// expression converting the bound fields into a `Pmt`
+fn fields_into_pmt(
+    fields: &syn::Fields,
+    bindings: &[Ident],
+    types: &TokenStream,
+) -> syn::Result<TokenStream> {
+    let into = quote!(#types::IntoPmt::into_pmt);
+    Ok(match fields {
+        syn::Fields::Named(f) => {
+            let names = f
+                .named
+                .iter()
+                .map(|f| pmt_name(f.ident.as_ref().unwrap(), &f.attrs))
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! {
+                #types::Pmt::MapStrPmt(std::collections::HashMap::from([
+                    #((#names.to_string(), #into(#bindings)),)*
+                ]))
+            }
+        }
+        syn::Fields::Unnamed(_) if bindings.len() == 1 => {
+            let b = &bindings[0];
+            quote!(#into(#b))
+        }
+        syn::Fields::Unnamed(_) => {
+            quote!(#types::Pmt::VecPmt(vec![#(#into(#bindings)),*]))
+        }
+        syn::Fields::Unit => quote!(#types::Pmt::Null),
+    })
+}
+
+// bindings for the fields in patterns
+fn field_bindings(fields: &syn::Fields) -> Vec<Ident> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| match &f.ident {
+            Some(ident) => ident.clone(),
+            None => Ident::new(&format!("f{i}"), Span::call_site()),
+        })
+        .collect()
+}
+
+// pattern destructuring `path` into the field bindings
+fn fields_pattern(path: TokenStream, fields: &syn::Fields, bindings: &[Ident]) -> TokenStream {
+    match fields {
+        syn::Fields::Named(_) => quote!(#path { #(#bindings),* }),
+        syn::Fields::Unnamed(_) => quote!(#path ( #(#bindings),* )),
+        syn::Fields::Unit => quote!(#path),
+    }
+}
+
+fn derive_into_pmt(input: syn::DeriveInput, types: TokenStream) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+    if let Some(attr) = input.attrs.iter().find(|a| a.path().is_ident("pmt")) {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "pmt attributes are only supported on fields and variants",
+        ));
+    }
+    let generics = pmt_generics(&input, quote!(#types::IntoPmt));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let body = match &input.data {
+        syn::Data::Struct(s) => {
+            let bindings = field_bindings(&s.fields);
+            let pattern = fields_pattern(quote!(Self), &s.fields, &bindings);
+            let pmt = fields_into_pmt(&s.fields, &bindings, &types)?;
+            quote! {
+                let #pattern = self;
+                #pmt
+            }
+        }
+        syn::Data::Enum(e) => {
+            let mut arms = Vec::new();
+            for v in e.variants.iter() {
+                let name = pmt_name(&v.ident, &v.attrs)?;
+                let var = &v.ident;
+                let bindings = field_bindings(&v.fields);
+                let pattern = fields_pattern(quote!(Self::#var), &v.fields, &bindings);
+                if matches!(v.fields, syn::Fields::Unit) {
+                    arms.push(quote! {
+                        #pattern => #types::Pmt::String(#name.to_string()),
+                    });
+                } else {
+                    let pmt = fields_into_pmt(&v.fields, &bindings, &types)?;
+                    arms.push(quote! {
+                        #pattern => #types::Pmt::MapStrPmt(std::collections::HashMap::from([
+                            (#name.to_string(), #pmt),
+                        ])),
+                    });
+                }
+            }
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        syn::Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                &input,
+                "IntoPmt can only be derived for structs and enums",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics #types::IntoPmt for #ident #ty_generics #where_clause {
+            fn into_pmt(self) -> #types::Pmt {
+                #body
+            }
+        }
+
+        impl #impl_generics From<#ident #ty_generics> for #types::Pmt #where_clause {
+            fn from(value: #ident #ty_generics) -> Self {
+                #types::IntoPmt::into_pmt(value)
+            }
+        }
+    })
+}
+
+// expression converting the `Pmt` `p` into `path`, returning on error
+fn fields_from_pmt(
+    path: TokenStream,
+    expected: &str,
+    fields: &syn::Fields,
+    types: &TokenStream,
+) -> syn::Result<TokenStream> {
+    let pmt = quote!(#types::Pmt);
+    let err = quote!(#types::FromPmtError);
+    let from = quote!(#types::FromPmt);
+
+    Ok(match fields {
+        syn::Fields::Named(f) => {
+            let mut values = Vec::new();
+            for field in f.named.iter() {
+                let ident = field.ident.as_ref().unwrap();
+                let attrs = pmt_attrs(&field.attrs)?;
+                let name = pmt_name(ident, &field.attrs)?;
+                let missing = if attrs.default {
+                    quote!(Default::default())
+                } else {
+                    quote!(#from::from_missing().ok_or_else(|| #err::missing(#name))?)
+                };
+                values.push(quote! {
+                    #ident: match m.remove(#name) {
+                        Some(p) => #from::from_pmt(p).map_err(|e| e.at(#name))?,
+                        None => #missing,
+                    }
+                });
+            }
+            quote! {
+                match p {
+                    #pmt::MapStrPmt(mut m) => #path { #(#values),* },
+                    p => return Err(#err::new(#expected, &p)),
+                }
+            }
+        }
+        syn::Fields::Unnamed(f) if f.unnamed.len() == 1 => {
+            quote!(#path(#from::from_pmt(p)?))
+        }
+        syn::Fields::Unnamed(f) => {
+            let n = f.unnamed.len();
+            let values = (0..n)
+                .map(|i| quote!(#from::from_pmt(v.next().unwrap()).map_err(|e| e.at_index(#i))?));
+            quote! {
+                match p {
+                    #pmt::VecPmt(v) if v.len() == #n => {
+                        let mut v = v.into_iter();
+                        #path(#(#values),*)
+                    }
+                    p => return Err(#err::new(#expected, &p)),
+                }
+            }
+        }
+        syn::Fields::Unit => quote! {
+            match p {
+                #pmt::Null => #path,
+                p => return Err(#err::new(#expected, &p)),
+            }
+        },
+    })
+}
+
+fn derive_from_pmt(input: syn::DeriveInput, types: TokenStream) -> syn::Result<TokenStream> {
+    use syn::ext::IdentExt;
+
+    let ident = &input.ident;
+    let type_name = ident.unraw().to_string();
+    if let Some(attr) = input.attrs.iter().find(|a| a.path().is_ident("pmt")) {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "pmt attributes are only supported on fields and variants",
+        ));
+    }
+    let generics = pmt_generics(&input, quote!(#types::FromPmt));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let pmt = quote!(#types::Pmt);
+    let err = quote!(#types::FromPmtError);
+
+    let body = match &input.data {
+        syn::Data::Struct(s) => {
+            let expected = match s.fields {
+                syn::Fields::Named(_) => format!("MapStrPmt ({type_name})"),
+                syn::Fields::Unnamed(_) => format!("VecPmt of {} ({type_name})", s.fields.len()),
+                syn::Fields::Unit => format!("Null ({type_name})"),
+            };
+            let value = fields_from_pmt(quote!(Self), &expected, &s.fields, &types)?;
+            quote!(Ok(#value))
+        }
+        syn::Data::Enum(e) => {
+            let expected = format!("variant of {type_name}");
+            let mut unit_arms = Vec::new();
+            let mut data_arms = Vec::new();
+            for v in e.variants.iter() {
+                let name = pmt_name(&v.ident, &v.attrs)?;
+                let var = &v.ident;
+                if matches!(v.fields, syn::Fields::Unit) {
+                    unit_arms.push(quote!(#name => Ok(Self::#var),));
+                } else {
+                    let expected = match v.fields {
+                        syn::Fields::Named(_) => format!("MapStrPmt ({type_name}::{name})"),
+                        _ => format!("VecPmt of {} ({type_name}::{name})", v.fields.len()),
+                    };
+                    let value = fields_from_pmt(quote!(Self::#var), &expected, &v.fields, &types)?;
+                    data_arms.push(quote! {
+                        #name => (|| -> Result<Self, #err> { Ok(#value) })().map_err(|e| e.at(#name)),
+                    });
+                }
+            }
+            quote! {
+                match p {
+                    #pmt::String(s) => match s.as_str() {
+                        #(#unit_arms)*
+                        _ => Err(#err::new(#expected, &#pmt::String(s))),
+                    },
+                    #pmt::MapStrPmt(m) if m.len() == 1 => {
+                        let (k, p) = m.into_iter().next().unwrap();
+                        match k.as_str() {
+                            #(#data_arms)*
+                            _ => Err(#err::new(#expected, &#pmt::String(k))),
+                        }
+                    }
+                    p => Err(#err::new(#expected, &p)),
+                }
+            }
+        }
+        syn::Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                &input,
+                "FromPmt can only be derived for structs and enums",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics #types::FromPmt for #ident #ty_generics #where_clause {
+            #[allow(clippy::redundant_closure_call)]
+            fn from_pmt(p: #pmt) -> Result<Self, #err> {
+                #body
+            }
+        }
+
+        impl #impl_generics TryFrom<#pmt> for #ident #ty_generics #where_clause {
+            type Error = #err;
+
+            fn try_from(p: #pmt) -> Result<Self, Self::Error> {
+                #types::FromPmt::from_pmt(p)
+            }
+        }
+    })
+}
+
 //=========================================================================
 // ASYNC_TRAIT
 //=========================================================================
@@ -1,3 +1,4 @@
+use futuresdr_types::HandlerSchema;
 use futuresdr_types::Pmt;
 use futuresdr_types::PmtKind;
 use leptos::html::Input;
@@ -19,16 +20,28 @@ pub fn Pmt(
                 Pmt::String(_) => "pmt-string",
                 Pmt::Bool(_) => "pmt-bool",
                 Pmt::Usize(_) => "pmt-usize",
+                Pmt::Isize(_) => "pmt-isize",
                 Pmt::U32(_) => "pmt-u32",
                 Pmt::U64(_) => "pmt-u64",
                 Pmt::F32(_) => "pmt-f32",
                 Pmt::F64(_) => "pmt-f64",
+                Pmt::CF32(_) => "pmt-cf32",
+                Pmt::CF64(_) => "pmt-cf64",
+                Pmt::VecCF32(_) => "pmt-veccf32",
+                Pmt::VecCF64(_) => "pmt-veccf64",
                 Pmt::VecF32(_) => "pmt-vecf32",
+                Pmt::VecF64(_) => "pmt-vecf64",
+                Pmt::VecI8(_) => "pmt-veci8",
+                Pmt::VecI16(_) => "pmt-veci16",
+                Pmt::VecI32(_) => "pmt-veci32",
+                Pmt::VecI64(_) => "pmt-veci64",
+                Pmt::VecU16(_) => "pmt-vecu16",
                 Pmt::VecU64(_) => "pmt-vecu64",
                 Pmt::Blob(_) => "pmt-blob",
                 Pmt::VecPmt(_) => "pmt-vecpmt",
                 Pmt::Finished => "pmt-finished",
                 Pmt::MapStrPmt(_) => "pmt-mapstrpmt",
+                Pmt::Time(_) => "pmt-time",
                 Pmt::Any(_) => "pmt-any",
                 _ => "",
             };
@@ -43,8 +56,11 @@ const ENTER_KEY: u32 = 13;
 
 #[component]
 /// Input a PMT
+///
+/// If a [`HandlerSchema`] is given, input that does not match it is marked as error.
 pub fn PmtInput(
     set_pmt: WriteSignal<Pmt>,
+    #[prop(optional)] schema: Option<HandlerSchema>,
     #[prop(default = false)] button: bool,
     #[prop(into, optional)] input_class: String,
     #[prop(into, optional)] error_class: String,
@@ -60,14 +76,17 @@ pub fn PmtInput(
         }
     });
 
+    let title = schema.as_ref().and_then(|s| s.description.clone());
+    let schema = StoredValue::new(schema);
     let input_ref = NodeRef::<Input>::new();
     let parse_pmt = move || {
         let input = input_ref.get().unwrap();
         let v = input.value();
-        if let Ok(p) = v.parse::<Pmt>() {
-            set_pmt(p);
-        } else {
-            set_error(true);
+        match v.parse::<Pmt>() {
+            Ok(p) if schema.with_value(|s| s.as_ref().map_or(true, |s| s.validate(&p).is_ok())) => {
+                set_pmt(p)
+            }
+            _ => set_error(true),
         }
     };
 
@@ -81,7 +100,7 @@ pub fn PmtInput(
     };
 
     view! {
-        <input class=classes node_ref=input_ref on:keydown=on_input />
+        <input class=classes title=title node_ref=input_ref on:keydown=on_input />
         {move || {
             button
                 .then({
@@ -101,8 +120,12 @@ pub fn PmtInput(
 
 #[component]
 /// PMT Input with list for type selection
+///
+/// If a [`HandlerSchema`] is given, the list only offers the kinds it accepts and values outside
+/// of its range are marked as error.
 pub fn PmtInputList(
     set_pmt: WriteSignal<Pmt>,
+    #[prop(optional)] schema: Option<HandlerSchema>,
     #[prop(default = vec![
             PmtKind::Ok,
             PmtKind::InvalidValue,
@@ -114,12 +137,23 @@ pub fn PmtInputList(
             PmtKind::U64,
             PmtKind::F32,
             PmtKind::F64,
+            PmtKind::CF32,
+            PmtKind::CF64,
+            PmtKind::VecCF32,
+            PmtKind::VecCF64,
             PmtKind::VecF32,
+            PmtKind::VecF64,
+            PmtKind::VecI8,
+            PmtKind::VecI16,
+            PmtKind::VecI32,
+            PmtKind::VecI64,
+            PmtKind::VecU16,
             PmtKind::VecU64,
             PmtKind::Blob,
             PmtKind::VecPmt,
             PmtKind::Finished,
             PmtKind::MapStrPmt,
+            PmtKind::Time,
     ])]
     types: Vec<PmtKind>,
     #[prop(default = false)] button: bool,
@@ -138,6 +172,11 @@ pub fn PmtInputList(
         }
     });
 
+    let types = match &schema {
+        Some(s) if !s.accepts.is_empty() => s.accepts.clone(),
+        _ => types,
+    };
+    let schema = StoredValue::new(schema);
     let input_ref = NodeRef::<Input>::new();
     let select_ref = NodeRef::<Select>::new();
 
@@ -185,12 +224,15 @@ pub fn PmtInputList(
             PmtKind::MapStrPmt => serde_json::from_str::<Pmt>(&format!("{{\"MapStrPmt\": {v}}}"))
                 .or_else(|_| serde_json::from_str::<Pmt>(&format!("{{\"MapStrPmt\": {{{v}}}}}")))
                 .ok(),
-            _ => None,
+            t => Pmt::from_string(&v, &t),
         };
-        if let Some(pmt) = pmt {
-            set_pmt(pmt);
-        } else {
-            set_error(true);
+        match pmt {
+            Some(p)
+                if schema.with_value(|s| s.as_ref().map_or(true, |s| s.validate(&p).is_ok())) =>
+            {
+                set_pmt(p)
+            }
+            _ => set_error(true),
         }
     };
 
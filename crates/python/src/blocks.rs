@@ -0,0 +1,219 @@
+//! Blocks that can be added to a [`Flowgraph`](crate::Flowgraph)
+use futuresdr::blocks::VectorSink as RustVectorSink;
+use futuresdr::blocks::VectorSource as RustVectorSource;
+use futuresdr::num_complex::Complex32;
+use futuresdr::runtime::Block as RustBlock;
+use futuresdr::runtime::BlockArgs;
+use futuresdr::runtime::BlockRegistry;
+use numpy::Element;
+use numpy::PyArray1;
+use numpy::PyArrayMethods;
+use numpy::PyUntypedArray;
+use numpy::PyUntypedArrayMethods;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::exceptions::PyTypeError;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+
+use crate::error;
+use crate::pmt;
+
+/// Item type of a stream, named like the `type` argument of the block registry
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ItemType {
+    U8,
+    U16,
+    U32,
+    U64,
+    I8,
+    I16,
+    I32,
+    I64,
+    F32,
+    F64,
+    C32,
+}
+
+impl ItemType {
+    fn parse(s: &str) -> PyResult<Self> {
+        Ok(match s {
+            "u8" => Self::U8,
+            "u16" => Self::U16,
+            "u32" => Self::U32,
+            "u64" => Self::U64,
+            "i8" => Self::I8,
+            "i16" => Self::I16,
+            "i32" => Self::I32,
+            "i64" => Self::I64,
+            "f32" => Self::F32,
+            "f64" => Self::F64,
+            "c32" => Self::C32,
+            t => {
+                return Err(PyValueError::new_err(format!(
+                    "unsupported item type '{t}'"
+                )))
+            }
+        })
+    }
+}
+
+/// Dispatch on an [`ItemType`], calling a generic function with the corresponding type
+macro_rules! typed {
+    ($t:expr, $f:ident, $($arg:expr),*) => {
+        match $t {
+            ItemType::U8 => $f::<u8>($($arg),*),
+            ItemType::U16 => $f::<u16>($($arg),*),
+            ItemType::U32 => $f::<u32>($($arg),*),
+            ItemType::U64 => $f::<u64>($($arg),*),
+            ItemType::I8 => $f::<i8>($($arg),*),
+            ItemType::I16 => $f::<i16>($($arg),*),
+            ItemType::I32 => $f::<i32>($($arg),*),
+            ItemType::I64 => $f::<i64>($($arg),*),
+            ItemType::F32 => $f::<f32>($($arg),*),
+            ItemType::F64 => $f::<f64>($($arg),*),
+            ItemType::C32 => $f::<futuresdr::num_complex::Complex32>($($arg),*),
+        }
+    };
+}
+pub(crate) use typed;
+
+/// Registry with the core blocks of [`futuresdr::blocks::register`]
+pub fn registry() -> &'static BlockRegistry {
+    static REGISTRY: OnceLock<BlockRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let mut registry = BlockRegistry::new();
+        futuresdr::blocks::register(&mut registry);
+        registry
+    })
+}
+
+/// Block that is not yet added to a flowgraph
+///
+/// `Block(type_name, **args)` creates a block from the registry of core blocks, e.g.,
+/// `Block("Head", type="c32", n=1000)`.
+#[pyclass(module = "futuresdr", subclass, frozen)]
+pub struct Block {
+    block: Mutex<Option<RustBlock>>,
+    vector_sink: Option<ItemType>,
+}
+
+impl Block {
+    /// Take the block to add it to a flowgraph
+    pub fn take(&self) -> PyResult<(RustBlock, Option<ItemType>)> {
+        let block = self
+            .block
+            .lock()
+            .unwrap()
+            .take()
+            .ok_or_else(|| PyRuntimeError::new_err("block was already added to a flowgraph"))?;
+        Ok((block, self.vector_sink))
+    }
+}
+
+#[pymethods]
+impl Block {
+    #[new]
+    #[pyo3(signature = (type_name, **args))]
+    fn new(type_name: &str, args: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let mut block_args = BlockArgs::new();
+        if let Some(args) = args {
+            for (k, v) in args.iter() {
+                block_args.insert(k.extract()?, pmt::from_py(&v)?);
+            }
+        }
+        let block = registry().create(type_name, &block_args).map_err(error)?;
+        Ok(Self {
+            block: Mutex::new(Some(block)),
+            vector_sink: None,
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        match self.block.lock().unwrap().as_ref() {
+            Some(b) => format!("Block({})", b.type_name()),
+            None => "Block(<added>)".to_string(),
+        }
+    }
+}
+
+/// Source that streams the items of a one-dimensional NumPy array
+///
+/// The items are copied when the block is created, i.e., later changes to the array do not affect
+/// the block.
+#[pyclass(module = "futuresdr", extends = Block, frozen)]
+pub struct VectorSource;
+
+#[pymethods]
+impl VectorSource {
+    #[new]
+    fn new(array: &Bound<'_, PyUntypedArray>) -> PyResult<(Self, Block)> {
+        fn source<T: Element + Copy + Send + 'static>(
+            array: &Bound<'_, PyUntypedArray>,
+        ) -> Option<PyResult<RustBlock>> {
+            let array = array.cast::<PyArray1<T>>().ok()?;
+            Some(
+                array
+                    .try_readonly()
+                    .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+                    .map(|a| RustVectorSource::new(a.as_array().iter().copied().collect()).into()),
+            )
+        }
+
+        let block = source::<u8>(array)
+            .or_else(|| source::<u16>(array))
+            .or_else(|| source::<u32>(array))
+            .or_else(|| source::<u64>(array))
+            .or_else(|| source::<i8>(array))
+            .or_else(|| source::<i16>(array))
+            .or_else(|| source::<i32>(array))
+            .or_else(|| source::<i64>(array))
+            .or_else(|| source::<f32>(array))
+            .or_else(|| source::<f64>(array))
+            .or_else(|| source::<Complex32>(array))
+            .ok_or_else(|| {
+                PyTypeError::new_err(format!(
+                    "unsupported array of {} dimension(s) and type {}",
+                    array.ndim(),
+                    array.dtype()
+                ))
+            })??;
+
+        Ok((
+            Self,
+            Block {
+                block: Mutex::new(Some(block)),
+                vector_sink: None,
+            },
+        ))
+    }
+}
+
+/// Sink that stores all received items
+///
+/// After the flowgraph finished, the items are available as NumPy array through
+/// `Flowgraph.items()`.
+#[pyclass(module = "futuresdr", extends = Block, frozen)]
+pub struct VectorSink;
+
+#[pymethods]
+impl VectorSink {
+    #[new]
+    #[pyo3(signature = (dtype = "f32", capacity = 8192))]
+    fn new(dtype: &str, capacity: usize) -> PyResult<(Self, Block)> {
+        fn sink<T: Clone + std::fmt::Debug + Send + Sync + 'static>(capacity: usize) -> RustBlock {
+            RustVectorSink::<T>::new(capacity).into()
+        }
+
+        let item_type = ItemType::parse(dtype)?;
+        Ok((
+            Self,
+            Block {
+                block: Mutex::new(Some(typed!(item_type, sink, capacity))),
+                vector_sink: Some(item_type),
+            },
+        ))
+    }
+}
@@ -0,0 +1,334 @@
+//! Flowgraphs and handles to running flowgraphs
+use futuresdr::async_io::block_on;
+use futuresdr::blocks::VectorSink as RustVectorSink;
+use futuresdr::futures::future::BoxFuture;
+use futuresdr::runtime::Error;
+use futuresdr::runtime::Flowgraph as RustFlowgraph;
+use futuresdr::runtime::FlowgraphHandle as RustFlowgraphHandle;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::PortId;
+use numpy::ndarray::ArrayView1;
+use numpy::Element;
+use numpy::PyArray1;
+use pyo3::exceptions::PyKeyError;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::blocks::registry;
+use crate::blocks::typed;
+use crate::blocks::Block;
+use crate::blocks::ItemType;
+use crate::error;
+use crate::pmt;
+
+enum State {
+    Building(RustFlowgraph),
+    Running,
+    Done(RustFlowgraph),
+    Failed,
+}
+
+/// Flowgraph of blocks, connected by streams and message edges
+///
+/// A flowgraph is run once. Afterwards, the items of its `VectorSink`s are available through
+/// `items()`.
+#[pyclass(module = "futuresdr", frozen)]
+pub struct Flowgraph {
+    state: Mutex<State>,
+    vector_sinks: Mutex<HashMap<usize, ItemType>>,
+}
+
+impl Flowgraph {
+    /// Take the flowgraph to start it
+    pub fn take(&self) -> PyResult<RustFlowgraph> {
+        let mut state = self.state.lock().unwrap();
+        match std::mem::replace(&mut *state, State::Running) {
+            State::Building(fg) => Ok(fg),
+            s => {
+                *state = s;
+                Err(PyRuntimeError::new_err(
+                    "flowgraph was already started and cannot be run again",
+                ))
+            }
+        }
+    }
+
+    /// Store the flowgraph after it terminated
+    pub fn finish(&self, result: Result<RustFlowgraph, Error>) -> PyResult<()> {
+        let mut state = self.state.lock().unwrap();
+        match result {
+            Ok(fg) => {
+                *state = State::Done(fg);
+                Ok(())
+            }
+            Err(e) => {
+                *state = State::Failed;
+                Err(error(e))
+            }
+        }
+    }
+
+    fn building<R>(&self, f: impl FnOnce(&mut RustFlowgraph) -> PyResult<R>) -> PyResult<R> {
+        match &mut *self.state.lock().unwrap() {
+            State::Building(fg) => f(fg),
+            _ => Err(PyRuntimeError::new_err(
+                "flowgraph was already started and cannot be modified",
+            )),
+        }
+    }
+}
+
+/// Resolve a block given by its id or instance name
+fn block_id(fg: &RustFlowgraph, block: &Bound<'_, PyAny>) -> PyResult<usize> {
+    if let Ok(id) = block.extract::<usize>() {
+        return Ok(id);
+    }
+    let name: String = block
+        .extract()
+        .map_err(|_| PyTypeError::new_err("block has to be an id or an instance name"))?;
+    fg.block_id(&name)
+        .ok_or_else(|| PyKeyError::new_err(format!("block '{name}' does not exist")))
+}
+
+fn port_id(port: &Bound<'_, PyAny>) -> PyResult<PortId> {
+    if let Ok(i) = port.extract::<usize>() {
+        return Ok(PortId::Index(i));
+    }
+    port.extract::<String>()
+        .map(PortId::Name)
+        .map_err(|_| PyTypeError::new_err("port has to be an index or a name"))
+}
+
+#[pymethods]
+impl Flowgraph {
+    #[new]
+    fn new() -> Self {
+        Self {
+            state: Mutex::new(State::Building(RustFlowgraph::new())),
+            vector_sinks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Add a block, optionally setting its instance name, and return its id
+    #[pyo3(signature = (block, name = None))]
+    fn add(&self, block: &Bound<'_, Block>, name: Option<&str>) -> PyResult<usize> {
+        self.building(|fg| {
+            let (mut b, vector_sink) = block.get().take()?;
+            if let Some(name) = name {
+                b.set_instance_name(name);
+            }
+            let id = fg.add_block(b).map_err(error)?;
+            if let Some(t) = vector_sink {
+                self.vector_sinks.lock().unwrap().insert(id, t);
+            }
+            Ok(id)
+        })
+    }
+
+    /// Add blocks and connections from a textual description
+    ///
+    /// Blocks that were added with `add()` are referred to by their instance name, e.g.,
+    /// `"src > Fir(taps = {\"VecF32\": [0.5, 0.5]}) > snk"`.
+    fn connect(&self, description: &str) -> PyResult<()> {
+        self.building(|fg| fg.connect_str(registry(), description).map_err(error))
+    }
+
+    /// Connect a stream output to a stream input
+    ///
+    /// Blocks are given by their id or instance name and ports by their index or name.
+    #[pyo3(signature = (src, dst, src_port = None, dst_port = None))]
+    fn connect_stream(
+        &self,
+        src: &Bound<'_, PyAny>,
+        dst: &Bound<'_, PyAny>,
+        src_port: Option<&Bound<'_, PyAny>>,
+        dst_port: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<()> {
+        self.building(|fg| {
+            let src = block_id(fg, src)?;
+            let dst = block_id(fg, dst)?;
+            let src_port = src_port
+                .map(port_id)
+                .transpose()?
+                .unwrap_or(PortId::Index(0));
+            let dst_port = dst_port
+                .map(port_id)
+                .transpose()?
+                .unwrap_or(PortId::Index(0));
+            fg.connect_stream(src, src_port, dst, dst_port)
+                .map_err(error)
+        })
+    }
+
+    /// Connect a message output to a message input
+    fn connect_message(
+        &self,
+        src: &Bound<'_, PyAny>,
+        src_port: &Bound<'_, PyAny>,
+        dst: &Bound<'_, PyAny>,
+        dst_port: &Bound<'_, PyAny>,
+    ) -> PyResult<()> {
+        self.building(|fg| {
+            let src = block_id(fg, src)?;
+            let dst = block_id(fg, dst)?;
+            fg.connect_message(src, port_id(src_port)?, dst, port_id(dst_port)?)
+                .map_err(error)
+        })
+    }
+
+    /// Items received by a `VectorSink` as read-only NumPy array
+    ///
+    /// The array is a view of the items in the flowgraph and, therefore, only available after the
+    /// flowgraph terminated.
+    fn items<'py>(
+        slf: &Bound<'py, Self>,
+        block: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        fn view<'py, T: Element + Clone + std::fmt::Debug + Send + Sync + 'static>(
+            fg: &RustFlowgraph,
+            id: usize,
+            container: &Bound<'py, PyAny>,
+        ) -> PyResult<Bound<'py, PyAny>> {
+            let items = fg
+                .kernel::<RustVectorSink<T>>(id)
+                .ok_or_else(|| PyTypeError::new_err(format!("block {id} is not a VectorSink")))?
+                .items();
+            let view = ArrayView1::from(items.as_slice());
+            // The flowgraph is not modified after it terminated and the array keeps it alive.
+            let array = unsafe { PyArray1::borrow_from_array(&view, container.clone()) };
+            array.getattr("flags")?.setattr("writeable", false)?;
+            Ok(array.into_any())
+        }
+
+        let this = slf.get();
+        let state = this.state.lock().unwrap();
+        let fg = match &*state {
+            State::Done(fg) => fg,
+            _ => {
+                return Err(PyRuntimeError::new_err(
+                    "items are only available after the flowgraph terminated",
+                ))
+            }
+        };
+        let id = block_id(fg, block)?;
+        let item_type = *this
+            .vector_sinks
+            .lock()
+            .unwrap()
+            .get(&id)
+            .ok_or_else(|| PyTypeError::new_err(format!("block {id} is not a VectorSink")))?;
+        typed!(item_type, view, fg, id, slf.as_any())
+    }
+}
+
+/// Handle to a running flowgraph
+///
+/// Message handlers of the blocks can be called, while the flowgraph is running. `terminate()`
+/// stops the flowgraph and `wait()` waits for it to finish.
+#[pyclass(module = "futuresdr", frozen)]
+pub struct FlowgraphHandle {
+    handle: Mutex<RustFlowgraphHandle>,
+    task: Mutex<Option<BoxFuture<'static, Result<RustFlowgraph, Error>>>>,
+    fg: Py<Flowgraph>,
+}
+
+impl FlowgraphHandle {
+    pub fn new(
+        handle: RustFlowgraphHandle,
+        task: BoxFuture<'static, Result<RustFlowgraph, Error>>,
+        fg: Py<Flowgraph>,
+    ) -> Self {
+        Self {
+            handle: Mutex::new(handle),
+            task: Mutex::new(Some(task)),
+            fg,
+        }
+    }
+
+    fn handle(&self) -> RustFlowgraphHandle {
+        self.handle.lock().unwrap().clone()
+    }
+}
+
+#[pymethods]
+impl FlowgraphHandle {
+    /// Call a message handler and return its result
+    ///
+    /// The block is given by its id or instance name, the handler by its index or name.
+    #[pyo3(signature = (block, port, data = None))]
+    fn call<'py>(
+        &self,
+        py: Python<'py>,
+        block: &Bound<'py, PyAny>,
+        port: &Bound<'py, PyAny>,
+        data: Option<&Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let block = match block.extract::<usize>() {
+            Ok(id) => Err(id),
+            Err(_) => Ok(block
+                .extract::<String>()
+                .map_err(|_| PyTypeError::new_err("block has to be an id or an instance name"))?),
+        };
+        let port = port_id(port)?;
+        let data = data.map(pmt::from_py).transpose()?.unwrap_or(Pmt::Null);
+        let mut handle = self.handle();
+
+        let result = py.detach(|| {
+            block_on(async {
+                let id = match block {
+                    Err(id) => id,
+                    Ok(name) => handle
+                        .description()
+                        .await
+                        .map_err(error)?
+                        .blocks
+                        .iter()
+                        .find(|b| b.instance_name == name)
+                        .map(|b| b.id)
+                        .ok_or_else(|| {
+                            PyKeyError::new_err(format!("block '{name}' does not exist"))
+                        })?,
+                };
+                handle.callback(id, port, data).await.map_err(error)
+            })
+        })?;
+        pmt::to_py(py, &result)
+    }
+
+    /// Description of the flowgraph as dict, like the one served by the control port
+    fn description<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let mut handle = self.handle();
+        let description = py.detach(|| block_on(handle.description()).map_err(error))?;
+        let json = serde_json::to_string(&description).map_err(error)?;
+        py.import("json")?.call_method1("loads", (json,))
+    }
+
+    /// Whether the flowgraph terminated
+    fn is_terminated(&self) -> bool {
+        self.handle.lock().unwrap().is_terminated()
+    }
+
+    /// Terminate the flowgraph and wait for it to finish
+    fn terminate(&self, py: Python<'_>) -> PyResult<()> {
+        let mut handle = self.handle();
+        if !handle.is_terminated() {
+            py.detach(|| block_on(handle.terminate_and_wait()))
+                .map_err(error)?;
+        }
+        self.wait(py)
+    }
+
+    /// Wait for the flowgraph to finish
+    ///
+    /// Afterwards, the items of its `VectorSink`s are available through `Flowgraph.items()`.
+    fn wait(&self, py: Python<'_>) -> PyResult<()> {
+        let Some(task) = self.task.lock().unwrap().take() else {
+            return Ok(());
+        };
+        let result = py.detach(|| block_on(task));
+        self.fg.get().finish(result)
+    }
+}
@@ -0,0 +1,88 @@
+//! Python bindings for FutureSDR
+//!
+//! The extension module `futuresdr` exposes flowgraphs, the core blocks of
+//! [`futuresdr::blocks::register`], the runtime, and handles to running flowgraphs. [`Pmt`]s are
+//! converted to and from Python objects, and stream data is exchanged with NumPy arrays: a
+//! `VectorSource` copies the array it was created with and `Flowgraph.items()` returns a read-only
+//! view of the items of a `VectorSink` without copying.
+//!
+//! [`Pmt`]: futuresdr::runtime::Pmt
+use futuresdr::async_io::block_on;
+use futuresdr::futures::FutureExt;
+use futuresdr::runtime::scheduler::SmolScheduler;
+use futuresdr::runtime::Runtime as RustRuntime;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+mod blocks;
+mod flowgraph;
+mod pmt;
+
+pub use blocks::Block;
+pub use blocks::VectorSink;
+pub use blocks::VectorSource;
+pub use flowgraph::Flowgraph;
+pub use flowgraph::FlowgraphHandle;
+pub use pmt::PmtMarker;
+pub use pmt::TimeSpec;
+
+fn error(e: impl Display) -> PyErr {
+    PyRuntimeError::new_err(e.to_string())
+}
+
+/// Runtime of the process
+///
+/// Running flowgraphs borrow the runtime, so all `Runtime` objects share one instance that lives
+/// until the interpreter exits.
+fn runtime() -> &'static RustRuntime<'static, SmolScheduler> {
+    static RUNTIME: OnceLock<RustRuntime<'static, SmolScheduler>> = OnceLock::new();
+    RUNTIME.get_or_init(RustRuntime::new)
+}
+
+/// Runtime that executes flowgraphs
+#[pyclass(module = "futuresdr", frozen)]
+pub struct Runtime;
+
+#[pymethods]
+impl Runtime {
+    #[new]
+    fn new() -> Self {
+        runtime();
+        Self
+    }
+
+    /// Run a flowgraph and block until it terminates
+    fn run(&self, py: Python<'_>, fg: &Bound<'_, Flowgraph>) -> PyResult<()> {
+        let f = fg.get().take()?;
+        let result = py.detach(|| runtime().run(f));
+        fg.get().finish(result)
+    }
+
+    /// Start a flowgraph and return a handle, once it is running
+    fn start(&self, py: Python<'_>, fg: &Bound<'_, Flowgraph>) -> PyResult<FlowgraphHandle> {
+        let f = fg.get().take()?;
+        let (task, handle) = py.detach(|| block_on(runtime().start(f)));
+        Ok(FlowgraphHandle::new(
+            handle,
+            task.boxed(),
+            fg.clone().unbind(),
+        ))
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "futuresdr")]
+fn futuresdr_python(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    m.add_class::<Runtime>()?;
+    m.add_class::<Flowgraph>()?;
+    m.add_class::<FlowgraphHandle>()?;
+    m.add_class::<Block>()?;
+    m.add_class::<VectorSource>()?;
+    m.add_class::<VectorSink>()?;
+    m.add_class::<PmtMarker>()?;
+    m.add_class::<TimeSpec>()?;
+    Ok(())
+}
@@ -0,0 +1,228 @@
+//! Conversion of [`Pmt`]s to and from Python objects
+use futuresdr::num_complex::Complex32;
+use futuresdr::num_complex::Complex64;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::TimeSpec as RustTimeSpec;
+use numpy::Element;
+use numpy::PyArray1;
+use numpy::PyArrayMethods;
+use numpy::PyUntypedArray;
+use pyo3::exceptions::PyTypeError;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBool;
+use pyo3::types::PyBytes;
+use pyo3::types::PyComplex;
+use pyo3::types::PyDict;
+use pyo3::types::PyFloat;
+use pyo3::types::PyInt;
+use pyo3::types::PyList;
+use pyo3::types::PyString;
+use pyo3::types::PyTuple;
+use std::collections::HashMap;
+
+/// [`Pmt`] variants without a corresponding Python type
+#[pyclass(module = "futuresdr", eq, eq_int, frozen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PmtMarker {
+    /// `Pmt::Ok`
+    Ok,
+    /// `Pmt::InvalidValue`
+    InvalidValue,
+    /// `Pmt::Finished`
+    Finished,
+}
+
+/// Point in time with full and fractional seconds
+#[pyclass(module = "futuresdr", eq, frozen)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimeSpec(pub RustTimeSpec);
+
+#[pymethods]
+impl TimeSpec {
+    #[new]
+    #[pyo3(signature = (secs, frac = 0.0))]
+    fn new(secs: i64, frac: f64) -> PyResult<Self> {
+        RustTimeSpec::checked_new(secs, frac)
+            .map(Self)
+            .ok_or_else(|| PyValueError::new_err("time out of range"))
+    }
+
+    /// Full seconds
+    #[getter]
+    fn secs(&self) -> i64 {
+        self.0.full_secs()
+    }
+
+    /// Fractional seconds in `[0, 1)`
+    #[getter]
+    fn frac(&self) -> f64 {
+        self.0.frac_secs()
+    }
+
+    /// Time in seconds as float, losing precision for large values
+    fn as_secs_f64(&self) -> f64 {
+        self.0.as_secs_f64()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("TimeSpec({}, {})", self.0.full_secs(), self.0.frac_secs())
+    }
+}
+
+/// Convert a [`Pmt`] to a Python object
+///
+/// Vectors become NumPy arrays that take ownership of the data, maps become dicts, and `Null`
+/// becomes `None`.
+pub fn to_py<'py>(py: Python<'py>, p: &Pmt) -> PyResult<Bound<'py, PyAny>> {
+    Ok(match p {
+        Pmt::Ok => PmtMarker::Ok.into_pyobject(py)?.into_any(),
+        Pmt::InvalidValue => PmtMarker::InvalidValue.into_pyobject(py)?.into_any(),
+        Pmt::Finished => PmtMarker::Finished.into_pyobject(py)?.into_any(),
+        Pmt::Null => py.None().into_bound(py),
+        Pmt::String(s) => s.into_pyobject(py)?.into_any(),
+        Pmt::Bool(b) => b.into_pyobject(py)?.to_owned().into_any(),
+        Pmt::Usize(v) => v.into_pyobject(py)?.into_any(),
+        Pmt::Isize(v) => v.into_pyobject(py)?.into_any(),
+        Pmt::U32(v) => v.into_pyobject(py)?.into_any(),
+        Pmt::U64(v) => v.into_pyobject(py)?.into_any(),
+        Pmt::F32(v) => v.into_pyobject(py)?.into_any(),
+        Pmt::F64(v) => v.into_pyobject(py)?.into_any(),
+        Pmt::CF32(v) => PyComplex::from_doubles(py, v.re as f64, v.im as f64).into_any(),
+        Pmt::CF64(v) => PyComplex::from_doubles(py, v.re, v.im).into_any(),
+        Pmt::VecCF32(v) => PyArray1::from_vec(py, v.clone()).into_any(),
+        Pmt::VecCF64(v) => PyArray1::from_vec(py, v.clone()).into_any(),
+        Pmt::VecF32(v) => PyArray1::from_vec(py, v.clone()).into_any(),
+        Pmt::VecF64(v) => PyArray1::from_vec(py, v.clone()).into_any(),
+        Pmt::VecU16(v) => PyArray1::from_vec(py, v.clone()).into_any(),
+        Pmt::VecU64(v) => PyArray1::from_vec(py, v.clone()).into_any(),
+        Pmt::VecI8(v) => PyArray1::from_vec(py, v.clone()).into_any(),
+        Pmt::VecI16(v) => PyArray1::from_vec(py, v.clone()).into_any(),
+        Pmt::VecI32(v) => PyArray1::from_vec(py, v.clone()).into_any(),
+        Pmt::VecI64(v) => PyArray1::from_vec(py, v.clone()).into_any(),
+        Pmt::Blob(v) => PyBytes::new(py, v).into_any(),
+        Pmt::VecPmt(v) => {
+            let items = v
+                .iter()
+                .map(|p| to_py(py, p))
+                .collect::<PyResult<Vec<_>>>()?;
+            PyList::new(py, items)?.into_any()
+        }
+        Pmt::MapStrPmt(m) => {
+            let dict = PyDict::new(py);
+            for (k, v) in m.iter() {
+                dict.set_item(k, to_py(py, v)?)?;
+            }
+            dict.into_any()
+        }
+        Pmt::Time(t) => TimeSpec(*t).into_pyobject(py)?.into_any(),
+        p => {
+            return Err(PyTypeError::new_err(format!(
+                "cannot convert {:?} Pmt to a Python object",
+                p.kind()
+            )))
+        }
+    })
+}
+
+/// Convert a Python object to a [`Pmt`]
+///
+/// Integers become `Usize` or, if negative, `Isize` and floats `F64`, like in flowgraph
+/// descriptions. One-dimensional NumPy arrays become the vector of the corresponding type, lists
+/// and tuples `VecPmt`, and dicts with string keys `MapStrPmt`.
+pub fn from_py(o: &Bound<'_, PyAny>) -> PyResult<Pmt> {
+    if o.is_none() {
+        return Ok(Pmt::Null);
+    }
+    if let Ok(m) = o.cast::<PmtMarker>() {
+        return Ok(match *m.get() {
+            PmtMarker::Ok => Pmt::Ok,
+            PmtMarker::InvalidValue => Pmt::InvalidValue,
+            PmtMarker::Finished => Pmt::Finished,
+        });
+    }
+    if let Ok(t) = o.cast::<TimeSpec>() {
+        return Ok(Pmt::Time(t.get().0));
+    }
+    if o.is_instance_of::<PyBool>() {
+        return Ok(Pmt::Bool(o.extract()?));
+    }
+    if o.is_instance_of::<PyInt>() {
+        if let Ok(v) = o.extract::<usize>() {
+            return Ok(Pmt::Usize(v));
+        }
+        return Ok(Pmt::Isize(o.extract()?));
+    }
+    if o.is_instance_of::<PyFloat>() {
+        return Ok(Pmt::F64(o.extract()?));
+    }
+    if let Ok(c) = o.cast::<PyComplex>() {
+        return Ok(Pmt::CF64(Complex64::new(c.real(), c.imag())));
+    }
+    if o.is_instance_of::<PyString>() {
+        return Ok(Pmt::String(o.extract()?));
+    }
+    if let Ok(b) = o.cast::<PyBytes>() {
+        return Ok(Pmt::Blob(b.as_bytes().to_vec()));
+    }
+    if o.is_instance_of::<PyUntypedArray>() {
+        return from_array(o);
+    }
+    if o.is_instance_of::<PyList>() || o.is_instance_of::<PyTuple>() {
+        return Ok(Pmt::VecPmt(
+            o.try_iter()?
+                .map(|i| from_py(&i?))
+                .collect::<PyResult<Vec<_>>>()?,
+        ));
+    }
+    if let Ok(d) = o.cast::<PyDict>() {
+        let mut map = HashMap::new();
+        for (k, v) in d.iter() {
+            let k: String = k
+                .extract()
+                .map_err(|_| PyTypeError::new_err("dict keys have to be strings"))?;
+            map.insert(k, from_py(&v)?);
+        }
+        return Ok(Pmt::MapStrPmt(map));
+    }
+    Err(PyTypeError::new_err(format!(
+        "cannot convert {} to a Pmt",
+        o.get_type().name()?
+    )))
+}
+
+fn from_array(o: &Bound<'_, PyAny>) -> PyResult<Pmt> {
+    fn vec<T: Element + Clone>(o: &Bound<'_, PyAny>) -> Option<Vec<T>> {
+        o.cast::<PyArray1<T>>()
+            .ok()
+            .map(|a| a.to_owned_array().iter().cloned().collect())
+    }
+
+    if let Some(v) = vec::<f32>(o) {
+        Ok(Pmt::VecF32(v))
+    } else if let Some(v) = vec::<f64>(o) {
+        Ok(Pmt::VecF64(v))
+    } else if let Some(v) = vec::<Complex32>(o) {
+        Ok(Pmt::VecCF32(v))
+    } else if let Some(v) = vec::<Complex64>(o) {
+        Ok(Pmt::VecCF64(v))
+    } else if let Some(v) = vec::<u8>(o) {
+        Ok(Pmt::Blob(v))
+    } else if let Some(v) = vec::<u16>(o) {
+        Ok(Pmt::VecU16(v))
+    } else if let Some(v) = vec::<u64>(o) {
+        Ok(Pmt::VecU64(v))
+    } else if let Some(v) = vec::<i8>(o) {
+        Ok(Pmt::VecI8(v))
+    } else if let Some(v) = vec::<i16>(o) {
+        Ok(Pmt::VecI16(v))
+    } else if let Some(v) = vec::<i32>(o) {
+        Ok(Pmt::VecI32(v))
+    } else if let Some(v) = vec::<i64>(o) {
+        Ok(Pmt::VecI64(v))
+    } else {
+        Err(PyTypeError::new_err(
+            "only one-dimensional arrays of u8, u16, u64, i8, i16, i32, i64, f32, f64, c64, or c128 can be converted to a Pmt",
+        ))
+    }
+}
@@ -0,0 +1,98 @@
+import numpy as np
+import pytest
+
+import futuresdr as fsdr
+
+
+def test_vector_loopback():
+    items = np.arange(10_000, dtype=np.float32)
+
+    fg = fsdr.Flowgraph()
+    fg.add(fsdr.VectorSource(items), "src")
+    fg.add(fsdr.VectorSink("f32", len(items)), "snk")
+    fg.connect('src > Copy(type = "f32") > snk')
+    fsdr.Runtime().run(fg)
+
+    out = fg.items("snk")
+    assert out.dtype == np.float32
+    assert not out.flags.writeable
+    np.testing.assert_array_equal(out, items)
+
+
+def test_vector_source_copies():
+    items = np.arange(100, dtype=np.int32)
+
+    fg = fsdr.Flowgraph()
+    fg.add(fsdr.VectorSource(items), "src")
+    fg.add(fsdr.VectorSink("i32", len(items)), "snk")
+    fg.connect("src > snk")
+    items[:] = 0
+    fsdr.Runtime().run(fg)
+
+    assert items.flags.writeable
+    np.testing.assert_array_equal(fg.items("snk"), np.arange(100, dtype=np.int32))
+
+    fg = fsdr.Flowgraph()
+    fg.add(fsdr.VectorSource(np.arange(10, dtype=np.float32)[::2]), "src")
+    fg.add(fsdr.VectorSink("f32"), "snk")
+    fg.connect("src > snk")
+    fsdr.Runtime().run(fg)
+    np.testing.assert_array_equal(fg.items("snk"), np.arange(0, 10, 2, dtype=np.float32))
+
+
+def test_fir():
+    items = np.ones(1000, dtype=np.complex64)
+
+    fg = fsdr.Flowgraph()
+    src = fg.add(fsdr.VectorSource(items))
+    snk = fg.add(fsdr.VectorSink("c32"))
+    fir = fg.add(fsdr.Block("Fir", type="c32", taps=np.array([0.5, 0.5], dtype=np.float32)))
+    fg.connect_stream(src, fir)
+    fg.connect_stream(fir, snk)
+    fsdr.Runtime().run(fg)
+
+    out = fg.items(snk)
+    assert len(out) >= len(items) - 1
+    np.testing.assert_allclose(out[-100:], np.ones(100))
+
+
+def test_handles():
+    fg = fsdr.Flowgraph()
+    fg.connect("msg = MessageSink")
+    handle = fsdr.Runtime().start(fg)
+
+    assert handle.call("msg", "in", 1.5) == 1
+    assert handle.call(0, 0, [1, "foo", {"a": None}]) == 2
+    assert handle.description()["blocks"][0]["type_name"] == "MessageSink"
+    with pytest.raises(KeyError):
+        handle.call("nope", "in")
+
+    handle.terminate()
+    assert handle.is_terminated()
+    with pytest.raises(RuntimeError):
+        fsdr.Runtime().run(fg)
+
+
+def test_pmt_markers():
+    fg = fsdr.Flowgraph()
+    fg.connect("copy = MessageCopy")
+    handle = fsdr.Runtime().start(fg)
+    assert handle.call("copy", "in", fsdr.TimeSpec(3, 0.25)) == fsdr.PmtMarker.Ok
+    handle.terminate()
+
+
+def test_errors():
+    fg = fsdr.Flowgraph()
+    with pytest.raises(RuntimeError, match="Foo"):
+        fg.connect("foo = Foo")
+    with pytest.raises(ValueError):
+        fsdr.VectorSink("f16")
+    with pytest.raises(ValueError):
+        fsdr.TimeSpec(0, float("nan"))
+
+    block = fsdr.Block("NullSink", type="u8")
+    fg.add(block)
+    with pytest.raises(RuntimeError):
+        fg.add(block)
+    with pytest.raises(RuntimeError):
+        fg.items(0)
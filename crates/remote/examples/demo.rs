@@ -1,6 +1,7 @@
 use futuresdr_remote::Error;
 use futuresdr_remote::Handler;
 use futuresdr_remote::Remote;
+use futuresdr_types::Pmt;
 
 #[tokio::main]
 async fn main() -> Result<(), Error> {
@@ -26,7 +27,7 @@ async fn main() -> Result<(), Error> {
         println!("{c}");
     }
 
-    let p = b.call(Handler::Id(0)).await?;
+    let p: Pmt = b.get(Handler::Id(0)).await?;
     println!("result: {p:?}");
 
     Ok(())
@@ -20,6 +20,38 @@
 //!     let p = blocks[0].callback(Handler::Id(0), Pmt::U32(123)).await?;
 //!     println!("result: {:?}", p);
 //!
+//!     let n = blocks[0].call::<u64>("in", 123u32).await?;
+//!     println!("typed result: {n}");
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! ## Features
+//!
+//! The `tokio` feature (enabled by default) adds retries with backoff and the WebSocket streams
+//! of [`Flowgraph::events`] and [`Block::tap`]. It requires a Tokio runtime. Without it, e.g.,
+//! in the browser, retries are not delayed.
+//!
+//! ## Events
+//! ```no_run
+//! use futures::StreamExt;
+//! use futuresdr_remote::Error;
+//! use futuresdr_remote::Remote;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Error> {
+//!     let remote = Remote::new("http://127.0.0.1:1337");
+//!     let fg = remote
+//!         .start("src = NullSource\nsnk = NullSink\nsrc > snk")
+//!         .await?;
+//!
+//!     let mut events = fg.events().await?;
+//!     fg.terminate().await?;
+//!     while let Some(e) = events.next().await {
+//!         println!("event: {:?}", e?);
+//!     }
+//!
 //!     Ok(())
 //! }
 //! ```
@@ -31,16 +63,34 @@ pub use remote::ConnectionType;
 pub use remote::Flowgraph;
 pub use remote::Handler;
 pub use remote::Remote;
+pub use remote::RemoteBuilder;
 
 use thiserror::Error;
 
 /// FutureSDR Remote Error
 #[derive(Debug, Error)]
 pub enum Error {
-    /// Error in [`hyper`] crate.
+    /// Error in [`reqwest`] crate.
     #[error("Reqwest")]
     Reqwest(#[from] reqwest::Error),
+    /// Runtime answered with an error status.
+    #[error("Request failed with status {0}: {1}")]
+    Status(u16, String),
+    /// Error in WebSocket connection.
+    #[cfg(feature = "tokio")]
+    #[error("WebSocket: {0}")]
+    WebSocket(Box<tokio_tungstenite::tungstenite::Error>),
+    /// Invalid JSON.
+    #[error("Json: {0}")]
+    Json(#[from] serde_json::Error),
+    /// Result of a handler cannot be converted to the requested type.
+    #[error("Conversion: {0}")]
+    Conversion(#[from] futuresdr_types::FromPmtError),
     /// Wrong [`Flowgraph`] ID.
     #[error("Wrong flowgraph id")]
     FlowgraphId(usize),
+    /// Argument does not match the [`HandlerSchema`](futuresdr_types::HandlerSchema) of the
+    /// handler.
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(#[from] futuresdr_types::SchemaError),
 }
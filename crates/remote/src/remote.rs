@@ -1,64 +1,254 @@
+#[cfg(feature = "tokio")]
+use futures::stream::BoxStream;
+#[cfg(feature = "tokio")]
+use futures::StreamExt;
 use futuresdr_types::BlockDescription;
 use futuresdr_types::FlowgraphDescription;
+#[cfg(feature = "tokio")]
+use futuresdr_types::FlowgraphEvent;
+use futuresdr_types::FromPmt;
+use futuresdr_types::HandlerSchema;
+use futuresdr_types::IntoPmt;
 use futuresdr_types::Pmt;
+#[cfg(feature = "tokio")]
+use futuresdr_types::PortId;
 use reqwest::Client;
-use reqwest::IntoUrl;
-use serde::Deserialize;
+use reqwest::RequestBuilder;
+use reqwest::Response;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+#[cfg(feature = "tokio")]
+use tokio_tungstenite::tungstenite::Message;
 
 use crate::Error;
 
-async fn get<T: for<'a> Deserialize<'a>>(client: Client, url: impl IntoUrl) -> Result<T, Error> {
-    Ok(client.get(url).send().await?.json::<T>().await?)
+/// Default timeout of requests
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// Default number of retries
+const RETRIES: u32 = 3;
+/// Delay before the first retry, doubled for each further retry
+#[cfg(feature = "tokio")]
+const RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Access to the REST API, shared by all objects of a [`Remote`]
+#[derive(Clone, Debug)]
+struct Api {
+    client: Client,
+    url: String,
+    retries: u32,
+}
+
+impl Api {
+    /// Send a request, retrying if the runtime cannot be reached.
+    ///
+    /// Requests that time out are only retried, if they are `idempotent`, since the runtime
+    /// might have received them.
+    async fn send(
+        &self,
+        idempotent: bool,
+        request: impl Fn(&Client) -> RequestBuilder,
+    ) -> Result<Response, Error> {
+        let mut attempt = 0;
+        loop {
+            match request(&self.client).send().await {
+                Ok(r) if r.status().is_success() => return Ok(r),
+                Ok(r) => {
+                    let status = r.status().as_u16();
+                    let text = r.text().await.unwrap_or_default();
+                    return Err(Error::Status(status, text));
+                }
+                Err(e)
+                    if attempt < self.retries
+                        && (e.is_connect() || idempotent && e.is_timeout()) =>
+                {
+                    #[cfg(feature = "tokio")]
+                    tokio::time::sleep(RETRY_DELAY * 2u32.pow(attempt)).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, Error> {
+        let url = format!("{}{}", self.url, path);
+        Ok(self.send(true, |c| c.get(&url)).await?.json::<T>().await?)
+    }
+
+    /// Connect to a WebSocket endpoint, streaming JSON values
+    #[cfg(feature = "tokio")]
+    async fn ws<T: DeserializeOwned + Send + 'static>(
+        &self,
+        path: &str,
+    ) -> Result<BoxStream<'static, Result<T, Error>>, Error> {
+        let url = format!("{}{}", self.ws_url(), path);
+        let (ws, _) = tokio_tungstenite::connect_async(url)
+            .await
+            .map_err(|e| Error::WebSocket(Box::new(e)))?;
+        Ok(ws
+            .filter_map(|m| async move {
+                match m {
+                    Ok(Message::Text(t)) => Some(serde_json::from_str(&t).map_err(Error::from)),
+                    Ok(_) => None,
+                    Err(e) => Some(Err(Error::WebSocket(Box::new(e)))),
+                }
+            })
+            .boxed())
+    }
+
+    /// URL of the WebSocket endpoints
+    #[cfg(feature = "tokio")]
+    fn ws_url(&self) -> String {
+        match self.url.strip_prefix("http") {
+            Some(rest) => format!("ws{rest}"),
+            None => self.url.clone(),
+        }
+    }
+}
+
+type Cache = Arc<Mutex<HashMap<usize, FlowgraphDescription>>>;
+
+/// Builder for a [`Remote`].
+pub struct RemoteBuilder {
+    url: String,
+    timeout: Option<Duration>,
+    retries: u32,
+}
+
+impl RemoteBuilder {
+    /// Timeout of requests (default: 10s).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Do not time out requests.
+    pub fn no_timeout(mut self) -> Self {
+        self.timeout = None;
+        self
+    }
+
+    /// Number of retries, if the runtime cannot be reached (default: 3).
+    ///
+    /// Retries are delayed with exponential backoff, starting at 100ms. Without the `tokio`
+    /// feature, they are not delayed.
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Create the [`Remote`].
+    pub fn build(self) -> Remote {
+        let mut client = Client::builder();
+        if let Some(t) = self.timeout {
+            client = client.timeout(t);
+        }
+        Remote {
+            api: Api {
+                client: client.build().expect("failed to create HTTP client"),
+                url: self.url,
+                retries: self.retries,
+            },
+            cache: Cache::default(),
+        }
+    }
 }
 
 /// Connection to a remote runtime.
+///
+/// [`FlowgraphDescriptions`](FlowgraphDescription) are cached. Use [`Remote::refresh`] or
+/// [`Flowgraph::update`] to get the current state.
+#[derive(Clone, Debug)]
 pub struct Remote {
-    client: Client,
-    url: String,
+    api: Api,
+    cache: Cache,
 }
 
 impl Remote {
-    /// Create a [`Remote`].
+    /// Create a [`Remote`] with default settings.
     pub fn new<I: Into<String>>(url: I) -> Self {
-        Self {
-            client: Client::new(),
+        Self::builder(url).build()
+    }
+
+    /// Create a [`RemoteBuilder`] to configure timeouts and retries.
+    pub fn builder<I: Into<String>>(url: I) -> RemoteBuilder {
+        RemoteBuilder {
             url: url.into(),
+            timeout: Some(TIMEOUT),
+            retries: RETRIES,
         }
     }
 
     /// Get a specific [`Flowgraph`].
     pub async fn flowgraph(&self, id: usize) -> Result<Flowgraph, Error> {
-        let fgs = self.flowgraphs().await?;
-        fgs.iter()
-            .find(|x| x.id == id)
-            .cloned()
-            .ok_or(Error::FlowgraphId(id))
+        let cached = self.cache.lock().unwrap().get(&id).cloned();
+        let description = match cached {
+            Some(d) => d,
+            None => self.fetch(id).await?,
+        };
+        Ok(self.make_flowgraph(id, description))
     }
 
     /// Get a list of all running [`Flowgraphs`](Flowgraph).
     pub async fn flowgraphs(&self) -> Result<Vec<Flowgraph>, Error> {
-        let ids: Vec<usize> = get(self.client.clone(), format!("{}/api/fg/", self.url)).await?;
-        let mut v = Vec::new();
+        let ids: Vec<usize> = self.api.get("/api/fg/").await?;
+        self.cache.lock().unwrap().retain(|id, _| ids.contains(id));
 
-        for i in ids.into_iter() {
-            let fg: FlowgraphDescription =
-                get(self.client.clone(), format!("{}/api/fg/{}/", self.url, i)).await?;
-            v.push(fg);
+        let mut v = Vec::new();
+        for id in ids.into_iter() {
+            match self.flowgraph(id).await {
+                Ok(fg) => v.push(fg),
+                // terminated after it was listed
+                Err(Error::FlowgraphId(_)) => (),
+                Err(e) => return Err(e),
+            }
         }
 
-        let v = v
-            .into_iter()
-            .enumerate()
-            .map(|(i, f)| Flowgraph {
-                id: i,
-                description: f,
-                client: self.client.clone(),
-                url: self.url.clone(),
-            })
-            .collect();
-
         Ok(v)
     }
+
+    /// Clear the cached [`FlowgraphDescriptions`](FlowgraphDescription).
+    ///
+    /// Descriptions are fetched again, when flowgraphs are requested the next time.
+    pub fn refresh(&self) {
+        self.cache.lock().unwrap().clear();
+    }
+
+    /// Start a [`Flowgraph`] on the runtime.
+    ///
+    /// The flowgraph is given in the textual description format of `Flowgraph::connect_str`,
+    /// using block types that are registered in the runtime.
+    pub async fn start(&self, description: &str) -> Result<Flowgraph, Error> {
+        let url = format!("{}/api/fg/", self.api.url);
+        let id: usize = self
+            .api
+            .send(false, |c| c.post(&url).body(description.to_string()))
+            .await?
+            .json()
+            .await?;
+        self.flowgraph(id).await
+    }
+
+    async fn fetch(&self, id: usize) -> Result<FlowgraphDescription, Error> {
+        let d: FlowgraphDescription = match self.api.get(&format!("/api/fg/{id}/")).await {
+            Err(Error::Status(..)) => return Err(Error::FlowgraphId(id)),
+            r => r?,
+        };
+        self.cache.lock().unwrap().insert(id, d.clone());
+        Ok(d)
+    }
+
+    fn make_flowgraph(&self, id: usize, description: FlowgraphDescription) -> Flowgraph {
+        Flowgraph {
+            id,
+            description,
+            api: self.api.clone(),
+            cache: self.cache.clone(),
+        }
+    }
 }
 
 /// A remote Flowgraph.
@@ -66,32 +256,56 @@ impl Remote {
 pub struct Flowgraph {
     id: usize,
     description: FlowgraphDescription,
-    client: Client,
-    url: String,
+    api: Api,
+    cache: Cache,
 }
 
 impl Flowgraph {
     /// Update the [`Flowgraph`], getting current blocks and connections.
     pub async fn update(&mut self) -> Result<(), Error> {
-        self.description = get(
-            self.client.clone(),
-            format!("{}/api/fg/{}/", self.url, self.id),
-        )
-        .await?;
+        self.description = self.api.get(&format!("/api/fg/{}/", self.id)).await?;
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(self.id, self.description.clone());
         Ok(())
     }
 
+    /// Id of the [`Flowgraph`] in the runtime
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// FlowgraphDescription
+    pub fn description(&self) -> &FlowgraphDescription {
+        &self.description
+    }
+
+    /// Terminate the [`Flowgraph`].
+    ///
+    /// Does not wait until the [`Flowgraph`] is actually terminated. Subscribe to its
+    /// [`events`](Self::events) to get notified.
+    pub async fn terminate(&self) -> Result<(), Error> {
+        let url = format!("{}/api/fg/{}/terminate/", self.api.url, self.id);
+        self.api.send(true, |c| c.post(&url)).await?;
+        self.cache.lock().unwrap().remove(&self.id);
+        Ok(())
+    }
+
+    /// Subscribe to [`FlowgraphEvents`](FlowgraphEvent).
+    ///
+    /// The stream ends after [`FlowgraphEvent::Terminated`]. Requires the `tokio` feature.
+    #[cfg(feature = "tokio")]
+    pub async fn events(&self) -> Result<BoxStream<'static, Result<FlowgraphEvent, Error>>, Error> {
+        self.api.ws(&format!("/api/fg/{}/events/", self.id)).await
+    }
+
     /// Get a list of the [`Blocks`](Block) of the [`Flowgraph`].
     pub fn blocks(&self) -> Vec<Block> {
         self.description
             .blocks
             .iter()
-            .map(|d| Block {
-                description: d.clone(),
-                client: self.client.clone(),
-                url: self.url.clone(),
-                flowgraph_id: self.id,
-            })
+            .map(|d| self.make_block(d))
             .collect()
     }
 
@@ -118,12 +332,15 @@ impl Flowgraph {
             .blocks
             .iter()
             .find(|d| pred(d))
-            .map(|d| Block {
-                description: d.clone(),
-                client: self.client.clone(),
-                url: self.url.clone(),
-                flowgraph_id: self.id,
-            })
+            .map(|d| self.make_block(d))
+    }
+
+    fn make_block(&self, description: &BlockDescription) -> Block {
+        Block {
+            description: description.clone(),
+            api: self.api.clone(),
+            flowgraph_id: self.id,
+        }
     }
 
     /// Get a list of all message [`Connections`](Connection) of the [`Flowgraph`].
@@ -179,64 +396,130 @@ pub enum Handler {
     Name(String),
 }
 
+impl From<usize> for Handler {
+    fn from(id: usize) -> Self {
+        Handler::Id(id)
+    }
+}
+
+impl From<&str> for Handler {
+    fn from(name: &str) -> Self {
+        Handler::Name(name.to_string())
+    }
+}
+
+impl From<String> for Handler {
+    fn from(name: String) -> Self {
+        Handler::Name(name)
+    }
+}
+
 /// A [`Block`] of a [`Flowgraph`].
 #[derive(Clone, Debug)]
 pub struct Block {
     description: BlockDescription,
-    client: Client,
-    url: String,
+    api: Api,
     flowgraph_id: usize,
 }
 
 impl Block {
     /// Update the [`Block`], retrieving a new [`BlockDescription`] from the [`Flowgraph`].
     pub async fn update(&mut self) -> Result<(), Error> {
-        self.description = get(
-            self.client.clone(),
-            format!(
-                "{}/api/fg/{}/block/{}/",
-                self.url, self.flowgraph_id, self.description.id
-            ),
-        )
-        .await?;
+        self.description = self
+            .api
+            .get(&format!(
+                "/api/fg/{}/block/{}/",
+                self.flowgraph_id, self.description.id
+            ))
+            .await?;
         Ok(())
     }
 
+    /// Call a message handler of a [`Block`] with a typed argument, converting the result.
+    ///
+    /// ```no_run
+    /// # async fn f(block: futuresdr_remote::Block) -> Result<(), futuresdr_remote::Error> {
+    /// let freq = block.call::<f64>("freq", 100e6).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn call<R: FromPmt>(
+        &self,
+        handler: impl Into<Handler>,
+        arg: impl IntoPmt,
+    ) -> Result<R, Error> {
+        let p = self.callback(handler, arg.into_pmt()).await?;
+        Ok(R::from_pmt(p)?)
+    }
+
     /// Call a message handler of a [`Block`], providing it a [`Pmt::Null`](futuresdr_types::Pmt).
     ///
     /// This is usually used, when the caller is only interested in the return value. The handler
     /// might, for example, just return a parameter (think `get_frequency`, `get_gain`, etc).
-    pub async fn call(&self, handler: Handler) -> Result<Pmt, Error> {
-        self.callback(handler, Pmt::Null).await
+    pub async fn get<R: FromPmt>(&self, handler: impl Into<Handler>) -> Result<R, Error> {
+        self.call(handler, Pmt::Null).await
     }
 
     /// Call a message handler of a [`Block`] with the given [`Pmt`](futuresdr_types::Pmt).
-    pub async fn callback(&self, handler: Handler, pmt: Pmt) -> Result<Pmt, Error> {
+    ///
+    /// If the handler declares a [`HandlerSchema`], the [`Pmt`] is validated before it is sent.
+    pub async fn callback(&self, handler: impl Into<Handler>, pmt: Pmt) -> Result<Pmt, Error> {
+        let handler = handler.into();
+        if let Some(schema) = self.schema(&handler) {
+            schema.validate(&pmt)?;
+        }
+
         let url = match handler {
             Handler::Name(n) => format!(
                 "{}/api/fg/{}/block/{}/call/{}/",
-                &self.url, self.flowgraph_id, self.description.id, n
+                &self.api.url, self.flowgraph_id, self.description.id, n
             ),
             Handler::Id(i) => format!(
                 "{}/api/fg/{}/block/{}/call/{}/",
-                &self.url, self.flowgraph_id, self.description.id, i
+                &self.api.url, self.flowgraph_id, self.description.id, i
             ),
         };
 
         Ok(self
-            .client
-            .post(url)
-            .json(&pmt)
-            .send()
+            .api
+            .send(false, |c| c.post(&url).json(&pmt))
             .await?
             .json::<Pmt>()
             .await?)
     }
 
+    /// Tap a message output of the [`Block`].
+    ///
+    /// Streams the messages that the block posts to the output. Messages are dropped, if the
+    /// receiver does not keep up. The stream ends with [`Pmt::Finished`], when the block
+    /// terminates. Requires the `tokio` feature.
+    #[cfg(feature = "tokio")]
+    pub async fn tap(
+        &self,
+        port: impl Into<PortId>,
+    ) -> Result<BoxStream<'static, Result<Pmt, Error>>, Error> {
+        self.api
+            .ws(&format!(
+                "/api/fg/{}/block/{}/tap/{}/",
+                self.flowgraph_id,
+                self.description.id,
+                port.into()
+            ))
+            .await
+    }
+
     /// BlockDescription
     pub fn description(&self) -> &BlockDescription {
         &self.description
     }
+
+    /// Get the [`HandlerSchema`] of a message handler, if it declares one
+    pub fn schema(&self, handler: &Handler) -> Option<&HandlerSchema> {
+        match handler {
+            Handler::Name(n) => self.description.message_input_schema(n),
+            Handler::Id(i) => self.description.message_input_schemas.get(*i)?.as_ref(),
+        }
+    }
 }
 
 impl std::fmt::Display for Block {
@@ -316,9 +599,25 @@ impl std::fmt::Display for Connection {
 
 #[cfg(test)]
 mod tests {
+    use super::Api;
+    use crate::Block;
+    use crate::Error;
     use crate::Flowgraph;
+    use crate::Handler;
     use futuresdr_types::BlockDescription;
     use futuresdr_types::FlowgraphDescription;
+    use futuresdr_types::HandlerSchema;
+    use futuresdr_types::Pmt;
+    use futuresdr_types::PmtKind;
+    use futuresdr_types::SchemaError;
+
+    fn api(url: &str) -> Api {
+        Api {
+            client: reqwest::Client::new(),
+            url: url.to_string(),
+            retries: 0,
+        }
+    }
 
     fn block(id: usize, name: &str) -> BlockDescription {
         BlockDescription {
@@ -329,6 +628,7 @@ mod tests {
             stream_outputs: vec!["out".to_string()],
             message_inputs: vec!["command".to_string()],
             message_outputs: vec!["message".to_string()],
+            message_input_schemas: vec![None],
             blocking: false,
         }
     }
@@ -341,9 +641,10 @@ mod tests {
                 blocks: vec![block(0, "a"), block(1, "b")],
                 stream_edges: vec![(0, 0, 1, 0)],
                 message_edges: vec![(1, 0, 0, 0)],
+                stream_buffers: vec![(0, 0, "Circular".to_string())],
             },
-            client: reqwest::Client::new(),
-            url: "http://localhost".to_string(),
+            api: api("http://localhost"),
+            cache: Default::default(),
         };
 
         assert_eq!(
@@ -354,4 +655,43 @@ mod tests {
         assert!(fg.block_by(|d| d.type_name == "test_block").is_some());
         assert!(fg.block_by(|d| d.type_name == "foo").is_none());
     }
+
+    #[tokio::test]
+    async fn validate_argument() {
+        let mut desc = block(0, "a");
+        desc.message_input_schemas = vec![Some(
+            HandlerSchema::new().accepts([PmtKind::F64]).range(0.0, 1.0),
+        )];
+        let b = Block {
+            description: desc,
+            api: api("http://localhost:0"),
+            flowgraph_id: 0,
+        };
+
+        assert!(b.schema(&Handler::Id(0)).is_some());
+        assert!(b.schema(&Handler::Name("foo".to_string())).is_none());
+        assert!(matches!(
+            b.callback(Handler::Name("command".to_string()), Pmt::U32(1))
+                .await,
+            Err(Error::InvalidArgument(SchemaError::InvalidKind(
+                _,
+                PmtKind::U32
+            )))
+        ));
+        assert!(matches!(
+            b.callback(Handler::Id(0), Pmt::F64(2.0)).await,
+            Err(Error::InvalidArgument(SchemaError::OutOfRange(..)))
+        ));
+        assert!(matches!(
+            b.call::<f64>("command", 2.0).await,
+            Err(Error::InvalidArgument(SchemaError::OutOfRange(..)))
+        ));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn ws_url() {
+        assert_eq!(api("http://127.0.0.1:1337").ws_url(), "ws://127.0.0.1:1337");
+        assert_eq!(api("https://example.com").ws_url(), "wss://example.com");
+    }
 }
@@ -0,0 +1,157 @@
+use futures::StreamExt;
+use futuresdr::runtime::config;
+use futuresdr::runtime::Runtime;
+use futuresdr_remote::Error;
+use futuresdr_remote::Remote;
+use futuresdr_types::FlowgraphEvent;
+use futuresdr_types::Pmt;
+use std::sync::OnceLock;
+
+const FLOWGRAPH: &str = r#"
+src = NullSource(type = "u8")
+throttle = Throttle(type = "u8", rate = 1000)
+snk = NullSink(type = "u8")
+msg = MessageSink
+
+src > throttle > snk
+"#;
+
+const FILE_FLOWGRAPH: &str = r#"
+src = FileSource(type = "u8", file = "/etc/passwd")
+snk = NullSink(type = "u8")
+
+src > snk
+"#;
+
+/// Start a runtime with the control port bound to localhost, shared by all tests
+fn remote() -> Remote {
+    static URL: OnceLock<String> = OnceLock::new();
+    let url = URL.get_or_init(|| {
+        let addr = "127.0.0.1:13371";
+        config::set("ctrlport_enable", true).unwrap();
+        config::set("ctrlport_bind", addr).unwrap();
+        config::set("ctrlport_start_flowgraphs", true).unwrap();
+        // keep the runtime and, hence, the control port alive until the tests end
+        Box::leak(Box::new(Runtime::new()));
+        format!("http://{addr}")
+    });
+    Remote::new(url.as_str())
+}
+
+#[tokio::test]
+async fn start_and_call() -> Result<(), Error> {
+    let remote = remote();
+    let fg = remote.start(FLOWGRAPH).await?;
+    assert!(remote.flowgraphs().await?.iter().any(|f| f.id() == fg.id()));
+    assert_eq!(fg.blocks().len(), 4);
+    assert_eq!(fg.stream_connections().len(), 2);
+
+    let msg = fg.block_by_name("msg").unwrap();
+    assert_eq!(msg.call::<u64>("in", 1u32).await?, 1);
+    assert_eq!(msg.call::<u64>(0usize, Pmt::Null).await?, 2);
+    assert_eq!(msg.get::<Pmt>("in").await?, Pmt::U64(3));
+    assert!(matches!(
+        msg.call::<String>("in", 1u32).await,
+        Err(Error::Conversion(_))
+    ));
+    assert!(matches!(
+        msg.call::<u64>("foo", 1u32).await,
+        Err(Error::Status(400, _))
+    ));
+
+    fg.terminate().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn events() -> Result<(), Error> {
+    let remote = remote();
+    let fg = remote.start(FLOWGRAPH).await?;
+    let mut events = fg.events().await?;
+
+    let msg = fg.block_by_name("msg").unwrap();
+    msg.call::<u64>("in", 42u32).await?;
+    let id = msg.description().id;
+    match events.next().await {
+        Some(Ok(FlowgraphEvent::HandlerCalled {
+            block_id,
+            handler,
+            data,
+        })) => {
+            assert_eq!(block_id, id);
+            assert_eq!(handler, "in");
+            assert_eq!(data, Pmt::U32(42));
+        }
+        e => panic!("unexpected event {e:?}"),
+    }
+
+    fg.terminate().await?;
+    let mut done = 0;
+    let mut terminated = false;
+    while let Some(e) = events.next().await {
+        match e? {
+            FlowgraphEvent::BlockDone { .. } => done += 1,
+            FlowgraphEvent::Terminated => terminated = true,
+            e => panic!("unexpected event {e:?}"),
+        }
+    }
+    assert_eq!(done, 4);
+    assert!(terminated);
+    Ok(())
+}
+
+#[tokio::test]
+async fn tap() -> Result<(), Error> {
+    let remote = remote();
+    let fg = remote
+        .start("copy = MessageCopy\nsnk = MessageSink\ncopy | snk")
+        .await?;
+    let copy = fg.block_by_name("copy").unwrap();
+    let mut messages = copy.tap("out").await?;
+
+    copy.callback("in", Pmt::U32(1)).await?;
+    copy.callback("in", Pmt::String("foo".to_string())).await?;
+    assert_eq!(messages.next().await.transpose()?, Some(Pmt::U32(1)));
+    assert_eq!(
+        messages.next().await.transpose()?,
+        Some(Pmt::String("foo".to_string()))
+    );
+    assert_eq!(fg.block_by_name("snk").unwrap().get::<u64>("in").await?, 3);
+
+    assert!(copy.tap("foo").await.is_err());
+    fg.terminate().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn cached_descriptions() -> Result<(), Error> {
+    let remote = remote();
+    let fg = remote.start(FLOWGRAPH).await?;
+
+    let mut cached = remote.flowgraph(fg.id()).await?;
+    assert_eq!(cached.blocks().len(), 4);
+    cached.update().await?;
+    remote.refresh();
+    assert_eq!(remote.flowgraph(fg.id()).await?.blocks().len(), 4);
+
+    fg.terminate().await?;
+    assert!(matches!(
+        remote.flowgraph(usize::MAX).await,
+        Err(Error::FlowgraphId(usize::MAX))
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn invalid_flowgraph() {
+    let remote = remote();
+    assert!(matches!(
+        remote.start("src = Foo").await,
+        Err(Error::Status(400, _))
+    ));
+    // blocks that access files are not available remotely
+    assert!(matches!(
+        remote.start(FILE_FLOWGRAPH).await,
+        Err(Error::Status(400, _))
+    ));
+}
@@ -0,0 +1,446 @@
+use num_complex::Complex32;
+use num_complex::Complex64;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::Pmt;
+use crate::PmtConversionError;
+use crate::TimeSpec;
+
+/// Convert a value into a [`Pmt`].
+///
+/// Implemented for primitive types, `String`, `Option`, `Vec`, and `HashMap<String, T>`. Use the
+/// `IntoPmt` derive macro to implement it for structs and enums:
+///
+/// * structs with named fields map to [`Pmt::MapStrPmt`],
+/// * tuple structs map to [`Pmt::VecPmt`] (newtypes to their inner value),
+/// * unit enum variants map to [`Pmt::String`] and variants with data to a
+///   [`Pmt::MapStrPmt`] with the variant name as single key.
+///
+/// `None` maps to [`Pmt::Null`]. `Vec`s of numeric types map to the corresponding vector
+/// variant, e.g., `Vec<u8>` to [`Pmt::Blob`] and `Vec<f32>` to [`Pmt::VecF32`], all other `Vec`s
+/// to [`Pmt::VecPmt`]. `i64` maps to [`Pmt::Isize`] and is, therefore, only implemented on 64-bit
+/// targets.
+pub trait IntoPmt {
+    /// Convert into [`Pmt`]
+    fn into_pmt(self) -> Pmt;
+
+    #[doc(hidden)]
+    fn vec_into_pmt(v: Vec<Self>) -> Pmt
+    where
+        Self: Sized,
+    {
+        Pmt::VecPmt(v.into_iter().map(IntoPmt::into_pmt).collect())
+    }
+}
+
+/// Convert a [`Pmt`] into a value.
+///
+/// Counterpart of [`IntoPmt`]. Numeric types also accept other numeric [`Pmt`] variants, as long
+/// as the value fits.
+pub trait FromPmt: Sized {
+    /// Convert from [`Pmt`]
+    fn from_pmt(p: Pmt) -> Result<Self, FromPmtError>;
+
+    /// Value to use, if a struct field is missing in the [`Pmt::MapStrPmt`]
+    ///
+    /// `None` for all types except `Option`, making the field mandatory.
+    fn from_missing() -> Option<Self> {
+        None
+    }
+
+    #[doc(hidden)]
+    fn vec_from_pmt(p: Pmt) -> Result<Vec<Self>, FromPmtError> {
+        vec_pmt(p, "VecPmt")
+    }
+}
+
+/// Convert the elements of a [`Pmt::VecPmt`].
+fn vec_pmt<T: FromPmt>(p: Pmt, expected: &str) -> Result<Vec<T>, FromPmtError> {
+    match p {
+        Pmt::VecPmt(v) => v
+            .into_iter()
+            .enumerate()
+            .map(|(i, p)| T::from_pmt(p).map_err(|e| e.at_index(i)))
+            .collect(),
+        p => Err(FromPmtError::new(expected, &p)),
+    }
+}
+
+/// Error returned, if a [`Pmt`] cannot be converted with [`FromPmt`].
+///
+/// The error includes the path of the value that failed to convert, e.g., `config.channels[2]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FromPmtError {
+    /// Path of the value in nested structs and vectors
+    pub path: String,
+    /// Description of what was expected
+    pub expected: String,
+    /// Description of what was found
+    pub found: String,
+}
+
+impl FromPmtError {
+    /// Create error for a value that was not what was `expected`.
+    pub fn new(expected: impl Into<String>, found: &Pmt) -> Self {
+        let found = match found {
+            Pmt::Bool(_)
+            | Pmt::F32(_)
+            | Pmt::F64(_)
+            | Pmt::U32(_)
+            | Pmt::U64(_)
+            | Pmt::Usize(_)
+            | Pmt::Isize(_)
+            | Pmt::CF32(_)
+            | Pmt::CF64(_)
+            | Pmt::Time(_)
+            | Pmt::String(_) => format!("{} ({})", found.kind(), found),
+            p => p.kind().to_string(),
+        };
+        Self {
+            path: String::new(),
+            expected: expected.into(),
+            found,
+        }
+    }
+
+    /// Create error for a missing struct field.
+    pub fn missing(field: &str) -> Self {
+        Self {
+            path: field.to_string(),
+            expected: "value".to_string(),
+            found: "nothing".to_string(),
+        }
+    }
+
+    /// Prefix the path with a field name.
+    pub fn at(mut self, field: &str) -> Self {
+        self.path = if self.path.is_empty() {
+            field.to_string()
+        } else if self.path.starts_with('[') {
+            format!("{field}{}", self.path)
+        } else {
+            format!("{field}.{}", self.path)
+        };
+        self
+    }
+
+    /// Prefix the path with an index.
+    pub fn at_index(mut self, i: usize) -> Self {
+        self.path = if self.path.is_empty() || self.path.starts_with('[') {
+            format!("[{i}]{}", self.path)
+        } else {
+            format!("[{i}].{}", self.path)
+        };
+        self
+    }
+}
+
+impl fmt::Display for FromPmtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.path.is_empty() {
+            write!(f, "{}: ", self.path)?;
+        }
+        write!(f, "expected {}, found {}", self.expected, self.found)
+    }
+}
+
+impl std::error::Error for FromPmtError {}
+
+impl From<FromPmtError> for PmtConversionError {
+    fn from(_: FromPmtError) -> Self {
+        PmtConversionError
+    }
+}
+
+impl IntoPmt for Pmt {
+    fn into_pmt(self) -> Pmt {
+        self
+    }
+}
+
+impl FromPmt for Pmt {
+    fn from_pmt(p: Pmt) -> Result<Self, FromPmtError> {
+        Ok(p)
+    }
+}
+
+impl IntoPmt for bool {
+    fn into_pmt(self) -> Pmt {
+        Pmt::Bool(self)
+    }
+}
+
+impl FromPmt for bool {
+    fn from_pmt(p: Pmt) -> Result<Self, FromPmtError> {
+        match p {
+            Pmt::Bool(b) => Ok(b),
+            p => Err(FromPmtError::new("Bool", &p)),
+        }
+    }
+}
+
+impl IntoPmt for String {
+    fn into_pmt(self) -> Pmt {
+        Pmt::String(self)
+    }
+}
+
+impl IntoPmt for &str {
+    fn into_pmt(self) -> Pmt {
+        Pmt::String(self.to_string())
+    }
+}
+
+impl FromPmt for String {
+    fn from_pmt(p: Pmt) -> Result<Self, FromPmtError> {
+        match p {
+            Pmt::String(s) => Ok(s),
+            p => Err(FromPmtError::new("String", &p)),
+        }
+    }
+}
+
+/// Convert integer [`Pmt`] variants, checking the range.
+fn integer<T>(p: Pmt, name: &str) -> Result<T, FromPmtError>
+where
+    T: TryFrom<u64> + TryFrom<i64>,
+{
+    let v = match &p {
+        Pmt::U32(v) => T::try_from(*v as u64).ok(),
+        Pmt::U64(v) => T::try_from(*v).ok(),
+        Pmt::Usize(v) => T::try_from(*v as u64).ok(),
+        Pmt::Isize(v) => T::try_from(*v as i64).ok(),
+        _ => None,
+    };
+    v.ok_or_else(|| FromPmtError::new(name, &p))
+}
+
+macro_rules! impl_integer {
+    ($($t:ty => $v:ident $(, $vec:ident)?);*) => {
+        $(
+            impl IntoPmt for $t {
+                fn into_pmt(self) -> Pmt {
+                    Pmt::$v(self as _)
+                }
+                $(
+                fn vec_into_pmt(v: Vec<Self>) -> Pmt {
+                    Pmt::$vec(v)
+                }
+                )?
+            }
+
+            impl FromPmt for $t {
+                fn from_pmt(p: Pmt) -> Result<Self, FromPmtError> {
+                    integer(p, stringify!($t))
+                }
+                $(
+                fn vec_from_pmt(p: Pmt) -> Result<Vec<Self>, FromPmtError> {
+                    match p {
+                        Pmt::$vec(v) => Ok(v),
+                        p => vec_pmt(p, stringify!($vec)),
+                    }
+                }
+                )?
+            }
+        )*
+    };
+}
+
+impl_integer!(
+    u8 => U32, Blob;
+    u16 => U32, VecU16;
+    u32 => U32;
+    u64 => U64, VecU64;
+    usize => Usize;
+    i8 => Isize, VecI8;
+    i16 => Isize, VecI16;
+    i32 => Isize, VecI32;
+    isize => Isize
+);
+
+// `Pmt::Isize` only holds all `i64` values on 64-bit targets. Elsewhere, `i64` cannot be
+// converted into a `Pmt` to avoid truncating it silently.
+#[cfg(target_pointer_width = "64")]
+impl_integer!(i64 => Isize, VecI64);
+
+#[cfg(not(target_pointer_width = "64"))]
+impl FromPmt for i64 {
+    fn from_pmt(p: Pmt) -> Result<Self, FromPmtError> {
+        integer(p, "i64")
+    }
+
+    fn vec_from_pmt(p: Pmt) -> Result<Vec<Self>, FromPmtError> {
+        match p {
+            Pmt::VecI64(v) => Ok(v),
+            p => vec_pmt(p, "VecI64"),
+        }
+    }
+}
+
+impl IntoPmt for f32 {
+    fn into_pmt(self) -> Pmt {
+        Pmt::F32(self)
+    }
+
+    fn vec_into_pmt(v: Vec<Self>) -> Pmt {
+        Pmt::VecF32(v)
+    }
+}
+
+impl FromPmt for f32 {
+    fn from_pmt(p: Pmt) -> Result<Self, FromPmtError> {
+        match p {
+            Pmt::F32(v) => Ok(v),
+            Pmt::F64(v) => Ok(v as f32),
+            Pmt::U32(v) => Ok(v as f32),
+            Pmt::U64(v) => Ok(v as f32),
+            Pmt::Usize(v) => Ok(v as f32),
+            Pmt::Isize(v) => Ok(v as f32),
+            p => Err(FromPmtError::new("f32", &p)),
+        }
+    }
+
+    fn vec_from_pmt(p: Pmt) -> Result<Vec<Self>, FromPmtError> {
+        match p {
+            Pmt::VecF32(v) => Ok(v),
+            p => vec_pmt(p, "VecF32"),
+        }
+    }
+}
+
+impl IntoPmt for f64 {
+    fn into_pmt(self) -> Pmt {
+        Pmt::F64(self)
+    }
+
+    fn vec_into_pmt(v: Vec<Self>) -> Pmt {
+        Pmt::VecF64(v)
+    }
+}
+
+impl FromPmt for f64 {
+    fn from_pmt(p: Pmt) -> Result<Self, FromPmtError> {
+        f64::try_from(&p).map_err(|_| FromPmtError::new("f64", &p))
+    }
+
+    fn vec_from_pmt(p: Pmt) -> Result<Vec<Self>, FromPmtError> {
+        match p {
+            Pmt::VecF64(v) => Ok(v),
+            p => vec_pmt(p, "VecF64"),
+        }
+    }
+}
+
+impl IntoPmt for TimeSpec {
+    fn into_pmt(self) -> Pmt {
+        Pmt::Time(self)
+    }
+}
+
+impl FromPmt for TimeSpec {
+    fn from_pmt(p: Pmt) -> Result<Self, FromPmtError> {
+        TimeSpec::try_from(&p).map_err(|_| FromPmtError::new("Time", &p))
+    }
+}
+
+impl IntoPmt for Complex32 {
+    fn into_pmt(self) -> Pmt {
+        Pmt::CF32(self)
+    }
+
+    fn vec_into_pmt(v: Vec<Self>) -> Pmt {
+        Pmt::VecCF32(v)
+    }
+}
+
+impl FromPmt for Complex32 {
+    fn from_pmt(p: Pmt) -> Result<Self, FromPmtError> {
+        Complex32::try_from(&p).map_err(|_| FromPmtError::new("Complex32", &p))
+    }
+
+    fn vec_from_pmt(p: Pmt) -> Result<Vec<Self>, FromPmtError> {
+        match p {
+            Pmt::VecCF32(v) => Ok(v),
+            p => vec_pmt(p, "VecCF32"),
+        }
+    }
+}
+
+impl IntoPmt for Complex64 {
+    fn into_pmt(self) -> Pmt {
+        Pmt::CF64(self)
+    }
+
+    fn vec_into_pmt(v: Vec<Self>) -> Pmt {
+        Pmt::VecCF64(v)
+    }
+}
+
+impl FromPmt for Complex64 {
+    fn from_pmt(p: Pmt) -> Result<Self, FromPmtError> {
+        Complex64::try_from(&p).map_err(|_| FromPmtError::new("Complex64", &p))
+    }
+
+    fn vec_from_pmt(p: Pmt) -> Result<Vec<Self>, FromPmtError> {
+        match p {
+            Pmt::VecCF64(v) => Ok(v),
+            p => vec_pmt(p, "VecCF64"),
+        }
+    }
+}
+
+impl<T: IntoPmt> IntoPmt for Option<T> {
+    fn into_pmt(self) -> Pmt {
+        match self {
+            Some(v) => v.into_pmt(),
+            None => Pmt::Null,
+        }
+    }
+}
+
+impl<T: FromPmt> FromPmt for Option<T> {
+    fn from_pmt(p: Pmt) -> Result<Self, FromPmtError> {
+        match p {
+            Pmt::Null => Ok(None),
+            p => T::from_pmt(p).map(Some),
+        }
+    }
+
+    fn from_missing() -> Option<Self> {
+        Some(None)
+    }
+}
+
+impl<T: IntoPmt> IntoPmt for Vec<T> {
+    fn into_pmt(self) -> Pmt {
+        T::vec_into_pmt(self)
+    }
+}
+
+impl<T: FromPmt> FromPmt for Vec<T> {
+    fn from_pmt(p: Pmt) -> Result<Self, FromPmtError> {
+        T::vec_from_pmt(p)
+    }
+}
+
+impl<T: IntoPmt> IntoPmt for HashMap<String, T> {
+    fn into_pmt(self) -> Pmt {
+        Pmt::MapStrPmt(self.into_iter().map(|(k, v)| (k, v.into_pmt())).collect())
+    }
+}
+
+impl<T: FromPmt> FromPmt for HashMap<String, T> {
+    fn from_pmt(p: Pmt) -> Result<Self, FromPmtError> {
+        match p {
+            Pmt::MapStrPmt(m) => m
+                .into_iter()
+                .map(|(k, v)| match T::from_pmt(v) {
+                    Ok(v) => Ok((k, v)),
+                    Err(e) => Err(e.at(&k)),
+                })
+                .collect(),
+            p => Err(FromPmtError::new("MapStrPmt", &p)),
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::HandlerSchema;
+
 /// Description of a `Flowgraph`.
 ///
 /// This struct can be serialized to be used with the REST API.
@@ -12,6 +14,11 @@ pub struct FlowgraphDescription {
     pub stream_edges: Vec<(usize, usize, usize, usize)>,
     /// Message edges
     pub message_edges: Vec<(usize, usize, usize, usize)>,
+    /// Stream buffers
+    ///
+    /// Buffer of each connected stream output (src blk, src port, buffer).
+    #[serde(default)]
+    pub stream_buffers: Vec<(usize, usize, String)>,
 }
 
 /// Description of a `Block`.
@@ -33,9 +40,22 @@ pub struct BlockDescription {
     pub message_inputs: Vec<String>,
     /// Message outputs
     pub message_outputs: Vec<String>,
+    /// Message input schemas
+    ///
+    /// Optional [`HandlerSchema`] of each message input, in the order of `message_inputs`.
+    #[serde(default)]
+    pub message_input_schemas: Vec<Option<HandlerSchema>>,
     /// Blocking
     ///
     /// Blocking blocks have an async API but are spawned in a separate thread, i.e., it is ok to
     /// block inside the async function.
     pub blocking: bool,
 }
+
+impl BlockDescription {
+    /// Get the [`HandlerSchema`] of a message input, given its name
+    pub fn message_input_schema(&self, name: &str) -> Option<&HandlerSchema> {
+        let i = self.message_inputs.iter().position(|n| n == name)?;
+        self.message_input_schemas.get(i)?.as_ref()
+    }
+}
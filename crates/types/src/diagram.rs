@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+
+use crate::BlockDescription;
+use crate::FlowgraphDescription;
+use crate::LatencyHistogram;
+
+impl FlowgraphDescription {
+    /// [Mermaid](https://mermaid.js.org) flowchart of the flowgraph
+    ///
+    /// Nodes show instance name and type of the blocks. Stream edges are labeled with the connected
+    /// ports and the buffer, message edges are dashed.
+    pub fn to_mermaid(&self) -> String {
+        self.to_mermaid_with_stats(&HashMap::new())
+    }
+
+    /// [Mermaid](https://mermaid.js.org) flowchart of the flowgraph, annotating blocks with the
+    /// given latency statistics, e.g., from the `/api/fg/{fg}/latency/` endpoint
+    pub fn to_mermaid_with_stats(&self, stats: &HashMap<usize, LatencyHistogram>) -> String {
+        let escape = |s: &str| s.replace('"', "#quot;");
+        let mut g = String::from("graph LR;\n");
+
+        for b in self.blocks.iter() {
+            let mut label = format!(
+                "<b>{}</b><br/>{}",
+                escape(&b.instance_name),
+                escape(&b.type_name)
+            );
+            if b.blocking {
+                label.push_str("<br/><i>blocking</i>");
+            }
+            if let Some(s) = stats.get(&b.id).and_then(latency) {
+                label.push_str(&format!("<br/>{s}"));
+            }
+            g.push_str(&format!("    N{}[\"{}\"];\n", b.id, label));
+        }
+
+        for (src, src_port, dst, dst_port) in self.stream_edges.iter() {
+            let label = escape(&self.stream_label(*src, *src_port, *dst, *dst_port));
+            g.push_str(&format!("    N{src}-->|\"{label}\"| N{dst};\n"));
+        }
+        for (src, src_port, dst, dst_port) in self.message_edges.iter() {
+            let label = escape(&self.message_label(*src, *src_port, *dst, *dst_port));
+            g.push_str(&format!("    N{src}-.->|\"{label}\"| N{dst};\n"));
+        }
+        g
+    }
+
+    /// [GraphViz](https://graphviz.org) DOT graph of the flowgraph
+    ///
+    /// Nodes show instance name and type of the blocks. Stream edges are labeled with the connected
+    /// ports and the buffer, message edges are dashed.
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with_stats(&HashMap::new())
+    }
+
+    /// [GraphViz](https://graphviz.org) DOT graph of the flowgraph, annotating blocks with the
+    /// given latency statistics, e.g., from the `/api/fg/{fg}/latency/` endpoint
+    pub fn to_dot_with_stats(&self, stats: &HashMap<usize, LatencyHistogram>) -> String {
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut g = String::from("digraph flowgraph {\n    rankdir=LR;\n    node [shape=box];\n");
+
+        for b in self.blocks.iter() {
+            let mut label = format!("{}\\n{}", escape(&b.instance_name), escape(&b.type_name));
+            if let Some(s) = stats.get(&b.id).and_then(latency) {
+                label.push_str(&format!("\\n{s}"));
+            }
+            let style = if b.blocking { ", style=bold" } else { "" };
+            g.push_str(&format!("    N{} [label=\"{}\"{}];\n", b.id, label, style));
+        }
+
+        for (src, src_port, dst, dst_port) in self.stream_edges.iter() {
+            let label = escape(&self.stream_label(*src, *src_port, *dst, *dst_port));
+            g.push_str(&format!("    N{src} -> N{dst} [label=\"{label}\"];\n"));
+        }
+        for (src, src_port, dst, dst_port) in self.message_edges.iter() {
+            let label = escape(&self.message_label(*src, *src_port, *dst, *dst_port));
+            g.push_str(&format!(
+                "    N{src} -> N{dst} [label=\"{label}\", style=dashed];\n"
+            ));
+        }
+        g.push_str("}\n");
+        g
+    }
+
+    fn block(&self, id: usize) -> Option<&BlockDescription> {
+        self.blocks.iter().find(|b| b.id == id)
+    }
+
+    fn stream_label(&self, src: usize, src_port: usize, dst: usize, dst_port: usize) -> String {
+        let label = format!(
+            "{} > {}",
+            port_name(self.block(src).map(|b| &b.stream_outputs), src_port),
+            port_name(self.block(dst).map(|b| &b.stream_inputs), dst_port)
+        );
+        match self
+            .stream_buffers
+            .iter()
+            .find(|b| b.0 == src && b.1 == src_port)
+        {
+            Some((_, _, buffer)) => format!("{label} ({buffer})"),
+            None => label,
+        }
+    }
+
+    fn message_label(&self, src: usize, src_port: usize, dst: usize, dst_port: usize) -> String {
+        format!(
+            "{} > {}",
+            port_name(self.block(src).map(|b| &b.message_outputs), src_port),
+            port_name(self.block(dst).map(|b| &b.message_inputs), dst_port)
+        )
+    }
+}
+
+fn port_name(ports: Option<&Vec<String>>, port: usize) -> String {
+    ports
+        .and_then(|p| p.get(port))
+        .cloned()
+        .unwrap_or(port.to_string())
+}
+
+fn latency(h: &LatencyHistogram) -> Option<String> {
+    Some(format!(
+        "latency mean {:?}, p99 {:?}",
+        h.mean()?,
+        h.quantile(0.99)?
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    fn flowgraph() -> FlowgraphDescription {
+        let block = |id: usize, name: &str, type_name: &str| BlockDescription {
+            id,
+            type_name: type_name.to_string(),
+            instance_name: name.to_string(),
+            stream_inputs: vec!["in".to_string()],
+            stream_outputs: vec!["out".to_string()],
+            message_inputs: vec!["in".to_string()],
+            message_outputs: vec!["out".to_string()],
+            message_input_schemas: Vec::new(),
+            blocking: false,
+        };
+        FlowgraphDescription {
+            blocks: vec![block(0, "src", "NullSource"), block(1, "snk", "NullSink")],
+            stream_edges: vec![(0, 0, 1, 0)],
+            message_edges: vec![(0, 0, 1, 0)],
+            stream_buffers: vec![(0, 0, "Circular".to_string())],
+        }
+    }
+
+    #[test]
+    fn mermaid() {
+        let g = flowgraph().to_mermaid();
+        assert!(g.starts_with("graph LR;\n"));
+        assert!(g.contains("N0[\"<b>src</b><br/>NullSource\"];"));
+        assert!(g.contains("N0-->|\"out > in (Circular)\"| N1;"));
+        assert!(g.contains("N0-.->|\"out > in\"| N1;"));
+    }
+
+    #[test]
+    fn dot() {
+        let mut fg = flowgraph();
+        fg.blocks[1].instance_name = "\"snk\"".to_string();
+        let g = fg.to_dot();
+        assert!(g.starts_with("digraph flowgraph {"));
+        assert!(g.contains("N1 [label=\"\\\"snk\\\"\\nNullSink\"];"));
+        assert!(g.contains("N0 -> N1 [label=\"out > in (Circular)\"];"));
+        assert!(g.contains("N0 -> N1 [label=\"out > in\", style=dashed];"));
+        assert!(g.ends_with("}\n"));
+    }
+
+    #[test]
+    fn stats() {
+        let mut h = LatencyHistogram::new();
+        h.record(Duration::from_micros(3));
+        let stats = HashMap::from([(1, h), (0, LatencyHistogram::new())]);
+
+        let g = flowgraph().to_dot_with_stats(&stats);
+        assert!(g.contains("N0 [label=\"src\\nNullSource\"];"));
+        assert!(g.contains("NullSink\\nlatency mean 3µs, p99 3µs\"];"));
+        assert!(flowgraph()
+            .to_mermaid_with_stats(&stats)
+            .contains("<br/>latency mean 3µs"));
+    }
+}
@@ -0,0 +1,58 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::Pmt;
+
+/// Event of a running `Flowgraph`.
+///
+/// Events are published to subscribers of the flowgraph and streamed through the events
+/// endpoint of the REST API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum FlowgraphEvent {
+    /// A message handler was called through the flowgraph handle or the REST API.
+    ///
+    /// Messages that are sent between blocks over message connections are not reported.
+    HandlerCalled {
+        /// Block Id
+        block_id: usize,
+        /// Name or index of the handler
+        handler: String,
+        /// Input data
+        data: Pmt,
+    },
+    /// A block finished.
+    BlockDone {
+        /// Block Id
+        block_id: usize,
+    },
+    /// A block raised an error.
+    BlockError {
+        /// Block Id
+        block_id: usize,
+    },
+    /// All blocks finished and the flowgraph terminated.
+    ///
+    /// This is the last event of a flowgraph.
+    Terminated,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn event_json() {
+        let e = FlowgraphEvent::HandlerCalled {
+            block_id: 1,
+            handler: "freq".to_string(),
+            data: Pmt::F64(100e6),
+        };
+        let s = serde_json::to_string(&e).unwrap();
+        assert_eq!(serde_json::from_str::<FlowgraphEvent>(&s).unwrap(), e);
+        assert_eq!(
+            serde_json::to_string(&FlowgraphEvent::Terminated).unwrap(),
+            "\"Terminated\""
+        );
+    }
+}
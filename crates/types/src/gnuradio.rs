@@ -0,0 +1,635 @@
+use num_complex::Complex32;
+use num_complex::Complex64;
+use std::collections::HashMap;
+
+use crate::wire::Reader;
+use crate::Pmt;
+use crate::PmtCodecError;
+use crate::TimeSpec;
+
+const ST_TRUE: u8 = 0x00;
+const ST_FALSE: u8 = 0x01;
+const ST_SYMBOL: u8 = 0x02;
+const ST_INT32: u8 = 0x03;
+const ST_DOUBLE: u8 = 0x04;
+const ST_COMPLEX: u8 = 0x05;
+const ST_NULL: u8 = 0x06;
+const ST_PAIR: u8 = 0x07;
+const ST_VECTOR: u8 = 0x08;
+const ST_UNIFORM_VECTOR: u8 = 0x0a;
+const ST_UINT64: u8 = 0x0b;
+const ST_TUPLE: u8 = 0x0c;
+const ST_INT64: u8 = 0x0d;
+
+const UVI_U8: u8 = 0x00;
+const UVI_S8: u8 = 0x01;
+const UVI_U16: u8 = 0x02;
+const UVI_S16: u8 = 0x03;
+const UVI_U32: u8 = 0x04;
+const UVI_S32: u8 = 0x05;
+const UVI_U64: u8 = 0x06;
+const UVI_S64: u8 = 0x07;
+const UVI_F32: u8 = 0x08;
+const UVI_F64: u8 = 0x09;
+const UVI_C32: u8 = 0x0a;
+const UVI_C64: u8 = 0x0b;
+
+/// Maximum nesting depth of pairs, vectors, and tuples when decoding
+const MAX_DEPTH: usize = 128;
+
+/// GNU Radio PMT
+///
+/// Mirrors the PMT types of GNU Radio that can be (de-)serialized with `pmt::serialize_str()` and
+/// `pmt::deserialize_str()`. This allows exchanging messages with GNU Radio, e.g., through its
+/// ZMQ message blocks.
+///
+/// GNU Radio dicts are association lists, i.e., lists of `(key . value)` pairs. Use
+/// [`GrPmt::dict`] to create them. PDUs are pairs of a dict and a uniform vector, see
+/// [`GrPmt::pdu`].
+///
+/// Conversion from and to [`Pmt`] maps symbols to [`Pmt::String`], dicts with symbol keys to
+/// [`Pmt::MapStrPmt`], vectors and tuples to [`Pmt::VecPmt`], and other pairs to a
+/// [`Pmt::VecPmt`] with two elements. Uniform vectors map to the corresponding vector variant,
+/// `u32` vectors, which have none, to a [`Pmt::VecPmt`]. [`Pmt::Time`] maps to a tuple of
+/// `uint64` full seconds and `double` fractional seconds, which is how GNU Radio represents
+/// `rx_time` and `tx_time` tags. Tuples of this form are converted back to [`Pmt::Time`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum GrPmt {
+    /// Boolean
+    Bool(bool),
+    /// Null, also the empty list and the empty dict
+    Null,
+    /// Symbol
+    Symbol(String),
+    /// Integer
+    Integer(i64),
+    /// Unsigned 64-bit integer
+    Uint64(u64),
+    /// Real number
+    Real(f64),
+    /// Complex number
+    Complex(Complex64),
+    /// Pair
+    Pair(Box<GrPmt>, Box<GrPmt>),
+    /// Vector
+    Vector(Vec<GrPmt>),
+    /// Tuple
+    Tuple(Vec<GrPmt>),
+    /// Uniform vector of `u8`
+    U8Vector(Vec<u8>),
+    /// Uniform vector of `i8`
+    S8Vector(Vec<i8>),
+    /// Uniform vector of `u16`
+    U16Vector(Vec<u16>),
+    /// Uniform vector of `i16`
+    S16Vector(Vec<i16>),
+    /// Uniform vector of `u32`
+    U32Vector(Vec<u32>),
+    /// Uniform vector of `i32`
+    S32Vector(Vec<i32>),
+    /// Uniform vector of `u64`
+    U64Vector(Vec<u64>),
+    /// Uniform vector of `i64`
+    S64Vector(Vec<i64>),
+    /// Uniform vector of `f32`
+    F32Vector(Vec<f32>),
+    /// Uniform vector of `f64`
+    F64Vector(Vec<f64>),
+    /// Uniform vector of `Complex32`
+    C32Vector(Vec<Complex32>),
+    /// Uniform vector of `Complex64`
+    C64Vector(Vec<Complex64>),
+}
+
+impl GrPmt {
+    /// Create a pair.
+    pub fn cons(car: GrPmt, cdr: GrPmt) -> Self {
+        GrPmt::Pair(Box::new(car), Box::new(cdr))
+    }
+
+    /// Create a dict from `(key, value)` entries.
+    pub fn dict(entries: impl IntoIterator<Item = (String, GrPmt)>) -> Self {
+        let entries: Vec<_> = entries.into_iter().collect();
+        entries.into_iter().rev().fold(GrPmt::Null, |l, (k, v)| {
+            GrPmt::cons(GrPmt::cons(GrPmt::Symbol(k), v), l)
+        })
+    }
+
+    /// Create a PDU from metadata and data, which should be a uniform vector.
+    pub fn pdu(meta: impl IntoIterator<Item = (String, GrPmt)>, data: GrPmt) -> Self {
+        GrPmt::cons(GrPmt::dict(meta), data)
+    }
+
+    /// Get the entries, if this is a dict with symbol keys.
+    ///
+    /// `Null` is the empty dict.
+    pub fn as_dict(&self) -> Option<Vec<(&str, &GrPmt)>> {
+        let mut entries = Vec::new();
+        let mut l = self;
+        loop {
+            match l {
+                GrPmt::Null => return Some(entries),
+                GrPmt::Pair(car, cdr) => match car.as_ref() {
+                    GrPmt::Pair(k, v) => match k.as_ref() {
+                        GrPmt::Symbol(k) => {
+                            entries.push((k.as_str(), v.as_ref()));
+                            l = cdr;
+                        }
+                        _ => return None,
+                    },
+                    _ => return None,
+                },
+                _ => return None,
+            }
+        }
+    }
+
+    /// Serialize in the GNU Radio PMT format, like `pmt::serialize_str()`.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, PmtCodecError> {
+        let mut buf = Vec::new();
+        serialize(self, &mut buf)?;
+        Ok(buf)
+    }
+
+    /// Deserialize from the GNU Radio PMT format, like `pmt::deserialize_str()`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PmtCodecError> {
+        let mut r = Reader::new(bytes);
+        let p = deserialize(&mut r, 0)?;
+        r.finish()?;
+        Ok(p)
+    }
+}
+
+impl Pmt {
+    /// Serialize in the GNU Radio PMT format.
+    ///
+    /// Shorthand for converting to [`GrPmt`] and serializing it.
+    pub fn to_gr_bytes(&self) -> Result<Vec<u8>, PmtCodecError> {
+        GrPmt::try_from(self)?.to_bytes()
+    }
+
+    /// Deserialize from the GNU Radio PMT format.
+    ///
+    /// Shorthand for deserializing a [`GrPmt`] and converting it.
+    pub fn from_gr_bytes(bytes: &[u8]) -> Result<Pmt, PmtCodecError> {
+        GrPmt::from_bytes(bytes)?.try_into()
+    }
+}
+
+impl TryFrom<&Pmt> for GrPmt {
+    type Error = PmtCodecError;
+
+    fn try_from(p: &Pmt) -> Result<Self, Self::Error> {
+        Ok(match p {
+            Pmt::Null => GrPmt::Null,
+            Pmt::Bool(b) => GrPmt::Bool(*b),
+            Pmt::String(s) => GrPmt::Symbol(s.clone()),
+            Pmt::U32(v) => GrPmt::Integer(*v as i64),
+            Pmt::Isize(v) => GrPmt::Integer(*v as i64),
+            Pmt::U64(v) => GrPmt::Uint64(*v),
+            Pmt::Usize(v) => GrPmt::Uint64(*v as u64),
+            Pmt::F32(v) => GrPmt::Real(*v as f64),
+            Pmt::F64(v) => GrPmt::Real(*v),
+            Pmt::CF32(v) => GrPmt::Complex(Complex64::new(v.re as f64, v.im as f64)),
+            Pmt::CF64(v) => GrPmt::Complex(*v),
+            Pmt::VecCF32(v) => GrPmt::C32Vector(v.clone()),
+            Pmt::VecCF64(v) => GrPmt::C64Vector(v.clone()),
+            Pmt::VecF32(v) => GrPmt::F32Vector(v.clone()),
+            Pmt::VecF64(v) => GrPmt::F64Vector(v.clone()),
+            Pmt::VecI8(v) => GrPmt::S8Vector(v.clone()),
+            Pmt::VecI16(v) => GrPmt::S16Vector(v.clone()),
+            Pmt::VecI32(v) => GrPmt::S32Vector(v.clone()),
+            Pmt::VecI64(v) => GrPmt::S64Vector(v.clone()),
+            Pmt::VecU16(v) => GrPmt::U16Vector(v.clone()),
+            Pmt::Time(t) => GrPmt::Tuple(vec![
+                match u64::try_from(t.full_secs()) {
+                    Ok(s) => GrPmt::Uint64(s),
+                    Err(_) => GrPmt::Integer(t.full_secs()),
+                },
+                GrPmt::Real(t.frac_secs()),
+            ]),
+            Pmt::VecU64(v) => GrPmt::U64Vector(v.clone()),
+            Pmt::Blob(v) => GrPmt::U8Vector(v.clone()),
+            Pmt::VecPmt(v) => {
+                GrPmt::Vector(v.iter().map(GrPmt::try_from).collect::<Result<_, _>>()?)
+            }
+            Pmt::MapStrPmt(m) => GrPmt::dict(
+                m.iter()
+                    .map(|(k, v)| Ok((k.clone(), GrPmt::try_from(v)?)))
+                    .collect::<Result<Vec<_>, PmtCodecError>>()?,
+            ),
+            p => return Err(PmtCodecError::Unsupported(p.kind())),
+        })
+    }
+}
+
+impl TryFrom<Pmt> for GrPmt {
+    type Error = PmtCodecError;
+
+    fn try_from(p: Pmt) -> Result<Self, Self::Error> {
+        GrPmt::try_from(&p)
+    }
+}
+
+impl TryFrom<GrPmt> for Pmt {
+    type Error = PmtCodecError;
+
+    fn try_from(p: GrPmt) -> Result<Self, Self::Error> {
+        if let GrPmt::Pair(..) = p {
+            if let Some(entries) = p.as_dict() {
+                return Ok(Pmt::MapStrPmt(
+                    entries
+                        .into_iter()
+                        .map(|(k, v)| Ok((k.to_string(), Pmt::try_from(v.clone())?)))
+                        .collect::<Result<HashMap<_, _>, PmtCodecError>>()?,
+                ));
+            }
+        }
+
+        Ok(match p {
+            GrPmt::Null => Pmt::Null,
+            GrPmt::Bool(b) => Pmt::Bool(b),
+            GrPmt::Symbol(s) => Pmt::String(s),
+            GrPmt::Integer(v) => Pmt::Isize(v as isize),
+            GrPmt::Uint64(v) => Pmt::U64(v),
+            GrPmt::Real(v) => Pmt::F64(v),
+            GrPmt::Complex(v) => Pmt::CF64(v),
+            GrPmt::Pair(car, cdr) => Pmt::VecPmt(vec![(*car).try_into()?, (*cdr).try_into()?]),
+            GrPmt::Tuple(v) if time_tuple(&v).is_some() => Pmt::Time(time_tuple(&v).unwrap()),
+            GrPmt::Vector(v) | GrPmt::Tuple(v) => {
+                Pmt::VecPmt(v.into_iter().map(Pmt::try_from).collect::<Result<_, _>>()?)
+            }
+            GrPmt::U8Vector(v) => Pmt::Blob(v),
+            GrPmt::S8Vector(v) => Pmt::VecI8(v),
+            GrPmt::U16Vector(v) => Pmt::VecU16(v),
+            GrPmt::S16Vector(v) => Pmt::VecI16(v),
+            GrPmt::U32Vector(v) => Pmt::VecPmt(v.into_iter().map(Pmt::U32).collect()),
+            GrPmt::S32Vector(v) => Pmt::VecI32(v),
+            GrPmt::U64Vector(v) => Pmt::VecU64(v),
+            GrPmt::S64Vector(v) => Pmt::VecI64(v),
+            GrPmt::F32Vector(v) => Pmt::VecF32(v),
+            GrPmt::F64Vector(v) => Pmt::VecF64(v),
+            GrPmt::C32Vector(v) => Pmt::VecCF32(v),
+            GrPmt::C64Vector(v) => Pmt::VecCF64(v),
+        })
+    }
+}
+
+// tuple of full and fractional seconds
+fn time_tuple(v: &[GrPmt]) -> Option<TimeSpec> {
+    let secs = match v {
+        [GrPmt::Uint64(s), GrPmt::Real(_)] => i64::try_from(*s).ok()?,
+        [GrPmt::Integer(s), GrPmt::Real(_)] => *s,
+        _ => return None,
+    };
+    match v[1] {
+        GrPmt::Real(f) if (0.0..1.0).contains(&f) => Some(TimeSpec::new(secs, f)),
+        _ => None,
+    }
+}
+
+fn uniform_vector<T>(
+    utype: u8,
+    v: &[T],
+    buf: &mut Vec<u8>,
+    f: impl Fn(&T, &mut Vec<u8>),
+) -> Result<(), PmtCodecError> {
+    let n = u32::try_from(v.len())
+        .map_err(|_| PmtCodecError::Invalid("vector too long".to_string()))?;
+    buf.push(ST_UNIFORM_VECTOR);
+    buf.push(utype);
+    buf.extend_from_slice(&n.to_be_bytes());
+    // one byte of padding, as GNU Radio does
+    buf.extend_from_slice(&[1, 0]);
+    for x in v {
+        f(x, buf);
+    }
+    Ok(())
+}
+
+fn items(v: &[GrPmt], buf: &mut Vec<u8>) -> Result<(), PmtCodecError> {
+    let n = u32::try_from(v.len())
+        .map_err(|_| PmtCodecError::Invalid("vector too long".to_string()))?;
+    buf.extend_from_slice(&n.to_be_bytes());
+    for p in v {
+        serialize(p, buf)?;
+    }
+    Ok(())
+}
+
+fn serialize(mut p: &GrPmt, buf: &mut Vec<u8>) -> Result<(), PmtCodecError> {
+    // loop instead of recursion over the cdr of lists
+    loop {
+        match p {
+            GrPmt::Bool(true) => buf.push(ST_TRUE),
+            GrPmt::Bool(false) => buf.push(ST_FALSE),
+            GrPmt::Null => buf.push(ST_NULL),
+            GrPmt::Symbol(s) => {
+                let n = u16::try_from(s.len())
+                    .map_err(|_| PmtCodecError::Invalid("symbol too long".to_string()))?;
+                buf.push(ST_SYMBOL);
+                buf.extend_from_slice(&n.to_be_bytes());
+                buf.extend_from_slice(s.as_bytes());
+            }
+            GrPmt::Integer(v) => match i32::try_from(*v) {
+                Ok(v) => {
+                    buf.push(ST_INT32);
+                    buf.extend_from_slice(&v.to_be_bytes());
+                }
+                Err(_) => {
+                    buf.push(ST_INT64);
+                    buf.extend_from_slice(&v.to_be_bytes());
+                }
+            },
+            GrPmt::Uint64(v) => {
+                buf.push(ST_UINT64);
+                buf.extend_from_slice(&v.to_be_bytes());
+            }
+            GrPmt::Real(v) => {
+                buf.push(ST_DOUBLE);
+                buf.extend_from_slice(&v.to_be_bytes());
+            }
+            GrPmt::Complex(v) => {
+                buf.push(ST_COMPLEX);
+                buf.extend_from_slice(&v.re.to_be_bytes());
+                buf.extend_from_slice(&v.im.to_be_bytes());
+            }
+            GrPmt::Pair(car, cdr) => {
+                buf.push(ST_PAIR);
+                serialize(car, buf)?;
+                p = cdr;
+                continue;
+            }
+            GrPmt::Vector(v) => {
+                buf.push(ST_VECTOR);
+                items(v, buf)?;
+            }
+            GrPmt::Tuple(v) => {
+                buf.push(ST_TUPLE);
+                items(v, buf)?;
+            }
+            GrPmt::U8Vector(v) => uniform_vector(UVI_U8, v, buf, |x, b| b.push(*x))?,
+            GrPmt::S8Vector(v) => {
+                uniform_vector(UVI_S8, v, buf, |x, b| b.extend_from_slice(&x.to_be_bytes()))?
+            }
+            GrPmt::U16Vector(v) => uniform_vector(UVI_U16, v, buf, |x, b| {
+                b.extend_from_slice(&x.to_be_bytes())
+            })?,
+            GrPmt::S16Vector(v) => uniform_vector(UVI_S16, v, buf, |x, b| {
+                b.extend_from_slice(&x.to_be_bytes())
+            })?,
+            GrPmt::U32Vector(v) => uniform_vector(UVI_U32, v, buf, |x, b| {
+                b.extend_from_slice(&x.to_be_bytes())
+            })?,
+            GrPmt::S32Vector(v) => uniform_vector(UVI_S32, v, buf, |x, b| {
+                b.extend_from_slice(&x.to_be_bytes())
+            })?,
+            GrPmt::U64Vector(v) => uniform_vector(UVI_U64, v, buf, |x, b| {
+                b.extend_from_slice(&x.to_be_bytes())
+            })?,
+            GrPmt::S64Vector(v) => uniform_vector(UVI_S64, v, buf, |x, b| {
+                b.extend_from_slice(&x.to_be_bytes())
+            })?,
+            GrPmt::F32Vector(v) => uniform_vector(UVI_F32, v, buf, |x, b| {
+                b.extend_from_slice(&x.to_be_bytes())
+            })?,
+            GrPmt::F64Vector(v) => uniform_vector(UVI_F64, v, buf, |x, b| {
+                b.extend_from_slice(&x.to_be_bytes())
+            })?,
+            GrPmt::C32Vector(v) => uniform_vector(UVI_C32, v, buf, |x, b| {
+                b.extend_from_slice(&x.re.to_be_bytes());
+                b.extend_from_slice(&x.im.to_be_bytes());
+            })?,
+            GrPmt::C64Vector(v) => uniform_vector(UVI_C64, v, buf, |x, b| {
+                b.extend_from_slice(&x.re.to_be_bytes());
+                b.extend_from_slice(&x.im.to_be_bytes());
+            })?,
+        }
+        return Ok(());
+    }
+}
+
+fn count(r: &mut Reader, item_size: usize) -> Result<usize, PmtCodecError> {
+    let n = u32::from_be_bytes(r.array()?) as usize;
+    if n.saturating_mul(item_size) > r.remaining() {
+        return Err(PmtCodecError::Eof);
+    }
+    Ok(n)
+}
+
+fn read_items<T, const N: usize>(
+    r: &mut Reader,
+    n: usize,
+    f: impl Fn([u8; N]) -> T,
+) -> Result<Vec<T>, PmtCodecError> {
+    let mut v = Vec::with_capacity(n);
+    for _ in 0..n {
+        v.push(f(r.array()?));
+    }
+    Ok(v)
+}
+
+fn deserialize_items(r: &mut Reader, depth: usize) -> Result<Vec<GrPmt>, PmtCodecError> {
+    let n = count(r, 1)?;
+    let mut v = Vec::with_capacity(n);
+    for _ in 0..n {
+        v.push(deserialize(r, depth + 1)?);
+    }
+    Ok(v)
+}
+
+fn deserialize(r: &mut Reader, depth: usize) -> Result<GrPmt, PmtCodecError> {
+    if depth > MAX_DEPTH {
+        return Err(PmtCodecError::Depth);
+    }
+    Ok(match r.u8()? {
+        ST_TRUE => GrPmt::Bool(true),
+        ST_FALSE => GrPmt::Bool(false),
+        ST_NULL => GrPmt::Null,
+        ST_SYMBOL => {
+            let n = u16::from_be_bytes(r.array()?) as usize;
+            let s = r.bytes(n)?.to_vec();
+            GrPmt::Symbol(
+                String::from_utf8(s)
+                    .map_err(|_| PmtCodecError::Invalid("symbol is not valid UTF-8".to_string()))?,
+            )
+        }
+        ST_INT32 => GrPmt::Integer(i32::from_be_bytes(r.array()?) as i64),
+        ST_INT64 => GrPmt::Integer(i64::from_be_bytes(r.array()?)),
+        ST_UINT64 => GrPmt::Uint64(u64::from_be_bytes(r.array()?)),
+        ST_DOUBLE => GrPmt::Real(f64::from_be_bytes(r.array()?)),
+        ST_COMPLEX => {
+            let re = f64::from_be_bytes(r.array()?);
+            let im = f64::from_be_bytes(r.array()?);
+            GrPmt::Complex(Complex64::new(re, im))
+        }
+        ST_PAIR => {
+            // iterate over the cdrs of lists and build them back to front
+            let mut cars = vec![deserialize(r, depth + 1)?];
+            while r.peek()? == ST_PAIR {
+                r.u8()?;
+                cars.push(deserialize(r, depth + 1)?);
+            }
+            let tail = deserialize(r, depth + 1)?;
+            cars.into_iter()
+                .rev()
+                .fold(tail, |cdr, car| GrPmt::cons(car, cdr))
+        }
+        ST_VECTOR => GrPmt::Vector(deserialize_items(r, depth)?),
+        ST_TUPLE => GrPmt::Tuple(deserialize_items(r, depth)?),
+        ST_UNIFORM_VECTOR => {
+            let utype = r.u8()?;
+            let size = match utype {
+                UVI_U8 | UVI_S8 => 1,
+                UVI_U16 | UVI_S16 => 2,
+                UVI_U32 | UVI_S32 | UVI_F32 => 4,
+                UVI_U64 | UVI_S64 | UVI_F64 | UVI_C32 => 8,
+                UVI_C64 => 16,
+                t => return Err(PmtCodecError::Invalid(format!("uniform vector type {t}"))),
+            };
+            let n = count(r, 0)?;
+            let npad = r.u8()? as usize;
+            r.bytes(npad)?;
+            if n.saturating_mul(size) > r.remaining() {
+                return Err(PmtCodecError::Eof);
+            }
+            match utype {
+                UVI_U8 => GrPmt::U8Vector(r.bytes(n)?.to_vec()),
+                UVI_S8 => GrPmt::S8Vector(read_items(r, n, i8::from_be_bytes)?),
+                UVI_U16 => GrPmt::U16Vector(read_items(r, n, u16::from_be_bytes)?),
+                UVI_S16 => GrPmt::S16Vector(read_items(r, n, i16::from_be_bytes)?),
+                UVI_U32 => GrPmt::U32Vector(read_items(r, n, u32::from_be_bytes)?),
+                UVI_S32 => GrPmt::S32Vector(read_items(r, n, i32::from_be_bytes)?),
+                UVI_U64 => GrPmt::U64Vector(read_items(r, n, u64::from_be_bytes)?),
+                UVI_S64 => GrPmt::S64Vector(read_items(r, n, i64::from_be_bytes)?),
+                UVI_F32 => GrPmt::F32Vector(read_items(r, n, f32::from_be_bytes)?),
+                UVI_F64 => GrPmt::F64Vector(read_items(r, n, f64::from_be_bytes)?),
+                UVI_C32 => GrPmt::C32Vector(read_items(r, n, |b: [u8; 8]| {
+                    Complex32::new(
+                        f32::from_be_bytes(b[..4].try_into().unwrap()),
+                        f32::from_be_bytes(b[4..].try_into().unwrap()),
+                    )
+                })?),
+                _ => GrPmt::C64Vector(read_items(r, n, |b: [u8; 16]| {
+                    Complex64::new(
+                        f64::from_be_bytes(b[..8].try_into().unwrap()),
+                        f64::from_be_bytes(b[8..].try_into().unwrap()),
+                    )
+                })?),
+            }
+        }
+        t => return Err(PmtCodecError::Tag(t)),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn gnuradio_bytes() {
+        let p = GrPmt::Symbol("hello".to_string());
+        assert_eq!(p.to_bytes().unwrap(), b"\x02\x00\x05hello");
+
+        assert_eq!(
+            GrPmt::Integer(1).to_bytes().unwrap(),
+            [ST_INT32, 0, 0, 0, 1]
+        );
+        assert_eq!(
+            GrPmt::Integer(1 << 40).to_bytes().unwrap(),
+            [ST_INT64, 0, 0, 1, 0, 0, 0, 0, 0]
+        );
+
+        let p = GrPmt::dict([("a".to_string(), GrPmt::Integer(1))]);
+        let b = [
+            ST_PAIR, ST_PAIR, ST_SYMBOL, 0, 1, b'a', ST_INT32, 0, 0, 0, 1, ST_NULL,
+        ];
+        assert_eq!(p.to_bytes().unwrap(), b);
+        assert_eq!(GrPmt::from_bytes(&b), Ok(p));
+
+        let p = GrPmt::pdu([], GrPmt::U8Vector(vec![1, 2]));
+        let b = [
+            ST_PAIR,
+            ST_NULL,
+            ST_UNIFORM_VECTOR,
+            UVI_U8,
+            0,
+            0,
+            0,
+            2,
+            1,
+            0,
+            1,
+            2,
+        ];
+        assert_eq!(p.to_bytes().unwrap(), b);
+        assert_eq!(GrPmt::from_bytes(&b), Ok(p));
+    }
+
+    #[test]
+    fn gnuradio_round_trip() {
+        let pmts = vec![
+            GrPmt::Bool(true),
+            GrPmt::Real(1.5),
+            GrPmt::Complex(Complex64::new(1.0, -2.0)),
+            GrPmt::Uint64(u64::MAX),
+            GrPmt::Tuple(vec![GrPmt::Null, GrPmt::Integer(-5)]),
+            GrPmt::Vector(vec![GrPmt::Symbol("x".to_string())]),
+            GrPmt::cons(
+                GrPmt::Integer(1),
+                GrPmt::cons(GrPmt::Integer(2), GrPmt::Integer(3)),
+            ),
+            GrPmt::S16Vector(vec![-1, 2]),
+            GrPmt::F32Vector(vec![0.5, -0.25]),
+            GrPmt::C32Vector(vec![Complex32::new(1.0, 2.0)]),
+            GrPmt::C64Vector(vec![Complex64::new(3.0, 4.0)]),
+        ];
+        for p in pmts {
+            let b = p.to_bytes().unwrap();
+            assert_eq!(GrPmt::from_bytes(&b), Ok(p));
+        }
+    }
+
+    #[test]
+    fn pmt_conversion() {
+        let p = Pmt::MapStrPmt(HashMap::from([
+            ("freq".to_string(), Pmt::F64(2.4e9)),
+            ("name".to_string(), Pmt::String("rx".to_string())),
+            ("data".to_string(), Pmt::Blob(vec![1, 2, 3])),
+            ("taps".to_string(), Pmt::VecF32(vec![1.0])),
+            ("iq".to_string(), Pmt::VecI16(vec![-1, 1])),
+            ("offset".to_string(), Pmt::CF64(Complex64::new(0.5, -0.5))),
+            (
+                "rx_time".to_string(),
+                Pmt::Time(TimeSpec::new(1_700_000_000, 0.5)),
+            ),
+        ]));
+        let b = p.to_gr_bytes().unwrap();
+        assert_eq!(Pmt::from_gr_bytes(&b), Ok(p));
+
+        let pdu = GrPmt::pdu(
+            [("len".to_string(), GrPmt::Integer(2))],
+            GrPmt::U8Vector(vec![7, 8]),
+        );
+        assert_eq!(
+            Pmt::try_from(pdu),
+            Ok(Pmt::VecPmt(vec![
+                Pmt::MapStrPmt(HashMap::from([("len".to_string(), Pmt::Isize(2))])),
+                Pmt::Blob(vec![7, 8]),
+            ]))
+        );
+
+        let t = GrPmt::try_from(Pmt::Time(TimeSpec::new(2, 0.5))).unwrap();
+        assert_eq!(t, GrPmt::Tuple(vec![GrPmt::Uint64(2), GrPmt::Real(0.5)]));
+
+        assert_eq!(
+            Pmt::Finished.to_gr_bytes(),
+            Err(PmtCodecError::Unsupported(crate::PmtKind::Finished))
+        );
+        assert_eq!(GrPmt::from_bytes(&[0x42]), Err(PmtCodecError::Tag(0x42)));
+        assert_eq!(
+            GrPmt::from_bytes(&[ST_SYMBOL, 0, 5, b'a']),
+            Err(PmtCodecError::Eof)
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use serde::Deserialize;
+use serde::Serialize;
+use std::time::Duration;
+
+/// Number of buckets of a [`LatencyHistogram`]
+const N_BUCKETS: usize = 64;
+
+/// Histogram of end-to-end latencies, observed by a sink.
+///
+/// Latencies are recorded in nanoseconds. Bucket `i` counts latencies in `[2^i, 2^(i+1))` ns.
+///
+/// This struct can be serialized to be used with the REST API.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyHistogram {
+    /// Number of recorded latencies
+    pub count: u64,
+    /// Sum of all recorded latencies in ns
+    pub sum_ns: u64,
+    /// Minimum latency in ns
+    pub min_ns: u64,
+    /// Maximum latency in ns
+    pub max_ns: u64,
+    /// Logarithmic buckets
+    pub buckets: Vec<u64>,
+}
+
+impl LatencyHistogram {
+    /// Create empty histogram
+    pub fn new() -> Self {
+        Self {
+            count: 0,
+            sum_ns: 0,
+            min_ns: u64::MAX,
+            max_ns: 0,
+            buckets: vec![0; N_BUCKETS],
+        }
+    }
+
+    /// Record a latency
+    pub fn record(&mut self, latency: Duration) {
+        let ns = u64::try_from(latency.as_nanos()).unwrap_or(u64::MAX);
+        self.count += 1;
+        self.sum_ns = self.sum_ns.saturating_add(ns);
+        self.min_ns = self.min_ns.min(ns);
+        self.max_ns = self.max_ns.max(ns);
+        let bucket = (u64::BITS - 1).saturating_sub(ns.leading_zeros()) as usize;
+        self.buckets[bucket] += 1;
+    }
+
+    /// Mean latency
+    pub fn mean(&self) -> Option<Duration> {
+        self.sum_ns
+            .checked_div(self.count)
+            .map(Duration::from_nanos)
+    }
+
+    /// Upper bound of the given quantile (`0.0..=1.0`), e.g., `0.99` for the 99th percentile
+    pub fn quantile(&self, q: f64) -> Option<Duration> {
+        if self.count == 0 {
+            return None;
+        }
+        let target = ((self.count as f64) * q.clamp(0.0, 1.0)).ceil().max(1.0) as u64;
+        let mut acc = 0;
+        for (i, n) in self.buckets.iter().enumerate() {
+            acc += n;
+            if acc >= target {
+                let upper = 1u64.checked_shl(i as u32 + 1).unwrap_or(u64::MAX);
+                return Some(Duration::from_nanos(upper.min(self.max_ns)));
+            }
+        }
+        Some(Duration::from_nanos(self.max_ns))
+    }
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn histogram() {
+        let mut h = LatencyHistogram::new();
+        assert_eq!(h.mean(), None);
+        assert_eq!(h.quantile(0.5), None);
+
+        h.record(Duration::from_nanos(0));
+        h.record(Duration::from_nanos(100));
+        h.record(Duration::from_nanos(1000));
+        h.record(Duration::from_micros(50));
+
+        assert_eq!(h.count, 4);
+        assert_eq!(h.min_ns, 0);
+        assert_eq!(h.max_ns, 50_000);
+        assert_eq!(h.buckets[0], 1);
+        assert_eq!(h.buckets[6], 1);
+        assert_eq!(h.buckets[9], 1);
+        assert_eq!(h.buckets[15], 1);
+        assert_eq!(h.mean(), Some(Duration::from_nanos(51_100 / 4)));
+        assert_eq!(h.quantile(0.5), Some(Duration::from_nanos(128)));
+        assert_eq!(h.quantile(1.0), Some(Duration::from_nanos(50_000)));
+    }
+}
@@ -3,15 +3,41 @@
 //!
 //! FutureSDR types that are used by the runtime and also exposed for
 //! interaction with the outside world through the flowgraph's REST API.
+mod convert;
+pub use convert::FromPmt;
+pub use convert::FromPmtError;
+pub use convert::IntoPmt;
+
 mod description;
 pub use description::BlockDescription;
 pub use description::FlowgraphDescription;
 
+mod diagram;
+
+mod event;
+pub use event::FlowgraphEvent;
+
+mod gnuradio;
+pub use gnuradio::GrPmt;
+
+mod latency;
+pub use latency::LatencyHistogram;
+
 mod pmt;
 pub use pmt::Pmt;
 pub use pmt::PmtConversionError;
 pub use pmt::PmtKind;
 
+mod schema;
+pub use schema::HandlerSchema;
+pub use schema::SchemaError;
+
+mod time;
+pub use time::TimeSpec;
+
+mod wire;
+pub use wire::PmtCodecError;
+
 mod port_id;
 #[cfg(feature = "seify")]
 mod seify;
@@ -1,5 +1,6 @@
 use dyn_clone::DynClone;
 use num_complex::Complex32;
+use num_complex::Complex64;
 use serde::Deserialize;
 use serde::Serialize;
 use std::any::Any;
@@ -7,6 +8,8 @@ use std::collections::HashMap;
 use std::fmt;
 use thiserror::Error;
 
+use crate::TimeSpec;
+
 /// PMT Any trait
 ///
 /// This trait has to be implemented by types that should be used with [`Pmt::Any`].
@@ -98,7 +101,7 @@ pub enum Pmt {
     VecCF32(Vec<Complex32>),
     /// Vector of 32-bit floats.
     VecF32(Vec<f32>),
-    /// Vector of 64-bit floats.
+    /// Vector of 64-bit unsigned integers.
     VecU64(Vec<u64>),
     /// Binary data blob
     Blob(Vec<u8>),
@@ -110,6 +113,28 @@ pub enum Pmt {
     Finished,
     /// Map (String -> Pmt)
     MapStrPmt(HashMap<String, Pmt>),
+    /// CF32, 32-bit complex float
+    CF32(Complex32),
+    /// CF64, 64-bit complex float
+    CF64(Complex64),
+    /// Vector of 64-bit complex floats.
+    VecCF64(Vec<Complex64>),
+    /// Vector of 64-bit floats.
+    VecF64(Vec<f64>),
+    /// Vector of 8-bit signed integers.
+    VecI8(Vec<i8>),
+    /// Vector of 16-bit signed integers.
+    VecI16(Vec<i16>),
+    /// Vector of 32-bit signed integers.
+    VecI32(Vec<i32>),
+    /// Vector of 64-bit signed integers.
+    VecI64(Vec<i64>),
+    /// Vector of 16-bit unsigned integers.
+    ///
+    /// Vectors of 8-bit unsigned integers are represented as [`Pmt::Blob`].
+    VecU16(Vec<u16>),
+    /// Time, e.g., the timestamp of a sample
+    Time(TimeSpec),
     /// Any type
     ///
     /// Wrap anything that implements [`Any`](std::any::Any) in a Pmt. Use
@@ -140,6 +165,16 @@ impl Pmt {
             Pmt::VecPmt(_) => PmtKind::VecPmt,
             Pmt::Finished => PmtKind::Finished,
             Pmt::MapStrPmt(_) => PmtKind::MapStrPmt,
+            Pmt::CF32(_) => PmtKind::CF32,
+            Pmt::CF64(_) => PmtKind::CF64,
+            Pmt::VecCF64(_) => PmtKind::VecCF64,
+            Pmt::VecF64(_) => PmtKind::VecF64,
+            Pmt::VecI8(_) => PmtKind::VecI8,
+            Pmt::VecI16(_) => PmtKind::VecI16,
+            Pmt::VecI32(_) => PmtKind::VecI32,
+            Pmt::VecI64(_) => PmtKind::VecI64,
+            Pmt::VecU16(_) => PmtKind::VecU16,
+            Pmt::Time(_) => PmtKind::Time,
             Pmt::Any(_) => PmtKind::Any,
         }
     }
@@ -166,6 +201,16 @@ impl fmt::Display for Pmt {
             Pmt::VecPmt(v) => write!(f, "{v:?}"),
             Pmt::Finished => write!(f, "Finished"),
             Pmt::MapStrPmt(v) => write!(f, "{v:?}"),
+            Pmt::CF32(v) => write!(f, "{v}"),
+            Pmt::CF64(v) => write!(f, "{v}"),
+            Pmt::VecCF64(v) => write!(f, "{v:?}"),
+            Pmt::VecF64(v) => write!(f, "{v:?}"),
+            Pmt::VecI8(v) => write!(f, "{v:?}"),
+            Pmt::VecI16(v) => write!(f, "{v:?}"),
+            Pmt::VecI32(v) => write!(f, "{v:?}"),
+            Pmt::VecI64(v) => write!(f, "{v:?}"),
+            Pmt::VecU16(v) => write!(f, "{v:?}"),
+            Pmt::Time(v) => write!(f, "{v}"),
             Pmt::Any(v) => write!(f, "{v:?}"),
         }
     }
@@ -192,6 +237,16 @@ impl PartialEq for Pmt {
             (Pmt::VecPmt(x), Pmt::VecPmt(y)) => x == y,
             (Pmt::Finished, Pmt::Finished) => true,
             (Pmt::MapStrPmt(x), Pmt::MapStrPmt(y)) => x == y,
+            (Pmt::CF32(x), Pmt::CF32(y)) => x == y,
+            (Pmt::CF64(x), Pmt::CF64(y)) => x == y,
+            (Pmt::VecCF64(x), Pmt::VecCF64(y)) => x == y,
+            (Pmt::VecF64(x), Pmt::VecF64(y)) => x == y,
+            (Pmt::VecI8(x), Pmt::VecI8(y)) => x == y,
+            (Pmt::VecI16(x), Pmt::VecI16(y)) => x == y,
+            (Pmt::VecI32(x), Pmt::VecI32(y)) => x == y,
+            (Pmt::VecI64(x), Pmt::VecI64(y)) => x == y,
+            (Pmt::VecU16(x), Pmt::VecU16(y)) => x == y,
+            (Pmt::Time(x), Pmt::Time(y)) => x == y,
             _ => false,
         }
     }
@@ -227,42 +282,53 @@ impl std::str::FromStr for Pmt {
 
 impl Pmt {
     /// Create a [`Pmt`] by parsing a string into a specific [`PmtKind`].
+    ///
+    /// Complex numbers are given as `1+2i`, vectors as comma-separated list with optional
+    /// brackets, e.g., `[1, 2, 3]`.
     pub fn from_string(s: &str, t: &PmtKind) -> Option<Pmt> {
         match t {
-            PmtKind::U32 => {
-                if let Ok(v) = s.parse::<u32>() {
-                    Some(Pmt::U32(v))
-                } else {
-                    None
-                }
-            }
-            PmtKind::U64 => {
-                if let Ok(v) = s.parse::<u64>() {
-                    Some(Pmt::U64(v))
-                } else {
-                    None
-                }
-            }
-            PmtKind::F32 => {
-                if let Ok(v) = s.parse::<f32>() {
-                    Some(Pmt::F32(v))
-                } else {
-                    None
-                }
-            }
-            PmtKind::F64 => {
-                if let Ok(v) = s.parse::<f64>() {
-                    Some(Pmt::F64(v))
-                } else {
-                    None
-                }
-            }
+            PmtKind::Null if s.is_empty() => Some(Pmt::Null),
+            PmtKind::Bool => s.parse().ok().map(Pmt::Bool),
+            PmtKind::Usize => s.parse().ok().map(Pmt::Usize),
+            PmtKind::Isize => s.parse().ok().map(Pmt::Isize),
+            PmtKind::U32 => s.parse().ok().map(Pmt::U32),
+            PmtKind::U64 => s.parse().ok().map(Pmt::U64),
+            PmtKind::F32 => s.parse().ok().map(Pmt::F32),
+            PmtKind::F64 => s.parse().ok().map(Pmt::F64),
+            PmtKind::CF32 => s.parse().ok().map(Pmt::CF32),
+            PmtKind::CF64 => s.parse().ok().map(Pmt::CF64),
+            PmtKind::Time => s.parse().ok().map(Pmt::Time),
             PmtKind::String => Some(Pmt::String(s.to_string())),
+            PmtKind::VecCF32 => parse_vec(s).map(Pmt::VecCF32),
+            PmtKind::VecCF64 => parse_vec(s).map(Pmt::VecCF64),
+            PmtKind::VecF32 => parse_vec(s).map(Pmt::VecF32),
+            PmtKind::VecF64 => parse_vec(s).map(Pmt::VecF64),
+            PmtKind::VecI8 => parse_vec(s).map(Pmt::VecI8),
+            PmtKind::VecI16 => parse_vec(s).map(Pmt::VecI16),
+            PmtKind::VecI32 => parse_vec(s).map(Pmt::VecI32),
+            PmtKind::VecI64 => parse_vec(s).map(Pmt::VecI64),
+            PmtKind::VecU16 => parse_vec(s).map(Pmt::VecU16),
+            PmtKind::VecU64 => parse_vec(s).map(Pmt::VecU64),
+            PmtKind::Blob => parse_vec(s).map(Pmt::Blob),
             _ => None,
         }
     }
 }
 
+/// Parse a comma-separated list, optionally enclosed in brackets.
+fn parse_vec<T: std::str::FromStr>(s: &str) -> Option<Vec<T>> {
+    let s = s.trim();
+    let s = s
+        .strip_prefix('[')
+        .and_then(|s| s.strip_suffix(']'))
+        .unwrap_or(s);
+    s.split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|v| v.parse().ok())
+        .collect()
+}
+
 /// PMT conversion error.
 ///
 /// This error is returned, if conversion to/from PMTs fail.
@@ -279,6 +345,8 @@ impl TryFrom<&Pmt> for f64 {
             Pmt::F64(f) => Ok(*f),
             Pmt::U32(f) => Ok(*f as f64),
             Pmt::U64(f) => Ok(*f as f64),
+            Pmt::Usize(f) => Ok(*f as f64),
+            Pmt::Isize(f) => Ok(*f as f64),
             _ => Err(PmtConversionError),
         }
     }
@@ -370,6 +438,17 @@ impl TryFrom<Pmt> for bool {
     }
 }
 
+impl TryFrom<Pmt> for String {
+    type Error = PmtConversionError;
+
+    fn try_from(value: Pmt) -> Result<String, Self::Error> {
+        match value {
+            Pmt::String(s) => Ok(s),
+            _ => Err(PmtConversionError),
+        }
+    }
+}
+
 impl TryFrom<Pmt> for Vec<f32> {
     type Error = PmtConversionError;
 
@@ -469,11 +548,120 @@ impl From<Vec<Complex32>> for Pmt {
     }
 }
 
+macro_rules! impl_vec {
+    ($($t:ty => $v:ident),*) => {
+        $(
+            impl TryFrom<Pmt> for Vec<$t> {
+                type Error = PmtConversionError;
+
+                fn try_from(value: Pmt) -> Result<Vec<$t>, Self::Error> {
+                    match value {
+                        Pmt::$v(v) => Ok(v),
+                        _ => Err(PmtConversionError),
+                    }
+                }
+            }
+
+            impl From<Vec<$t>> for Pmt {
+                fn from(v: Vec<$t>) -> Self {
+                    Pmt::$v(v)
+                }
+            }
+        )*
+    };
+}
+
+impl_vec!(
+    Complex64 => VecCF64,
+    f64 => VecF64,
+    i8 => VecI8,
+    i16 => VecI16,
+    i32 => VecI32,
+    i64 => VecI64,
+    u16 => VecU16
+);
+
+impl TryFrom<&Pmt> for Complex32 {
+    type Error = PmtConversionError;
+
+    fn try_from(value: &Pmt) -> Result<Complex32, Self::Error> {
+        match value {
+            Pmt::CF32(v) => Ok(*v),
+            _ => Err(PmtConversionError),
+        }
+    }
+}
+
+impl TryFrom<Pmt> for Complex32 {
+    type Error = PmtConversionError;
+
+    fn try_from(value: Pmt) -> Result<Complex32, Self::Error> {
+        (&value).try_into()
+    }
+}
+
+impl TryFrom<&Pmt> for Complex64 {
+    type Error = PmtConversionError;
+
+    fn try_from(value: &Pmt) -> Result<Complex64, Self::Error> {
+        match value {
+            Pmt::CF32(v) => Ok(Complex64::new(v.re as f64, v.im as f64)),
+            Pmt::CF64(v) => Ok(*v),
+            _ => Err(PmtConversionError),
+        }
+    }
+}
+
+impl TryFrom<Pmt> for Complex64 {
+    type Error = PmtConversionError;
+
+    fn try_from(value: Pmt) -> Result<Complex64, Self::Error> {
+        (&value).try_into()
+    }
+}
+
+impl TryFrom<&Pmt> for TimeSpec {
+    type Error = PmtConversionError;
+
+    fn try_from(value: &Pmt) -> Result<TimeSpec, Self::Error> {
+        match value {
+            Pmt::Time(t) => Ok(*t),
+            _ => Err(PmtConversionError),
+        }
+    }
+}
+
+impl TryFrom<Pmt> for TimeSpec {
+    type Error = PmtConversionError;
+
+    fn try_from(value: Pmt) -> Result<TimeSpec, Self::Error> {
+        (&value).try_into()
+    }
+}
+
+impl From<TimeSpec> for Pmt {
+    fn from(t: TimeSpec) -> Self {
+        Pmt::Time(t)
+    }
+}
+
+impl From<Complex32> for Pmt {
+    fn from(v: Complex32) -> Self {
+        Pmt::CF32(v)
+    }
+}
+
+impl From<Complex64> for Pmt {
+    fn from(v: Complex64) -> Self {
+        Pmt::CF64(v)
+    }
+}
+
 /// PMT types that do not wrap values.
 ///
 /// Useful for bindings to other languages that do not support Rust's broad enum features.
 #[non_exhaustive]
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum PmtKind {
     /// Ok
     Ok,
@@ -511,6 +699,26 @@ pub enum PmtKind {
     Finished,
     /// Map String -> Pmt
     MapStrPmt,
+    /// CF32
+    CF32,
+    /// CF64
+    CF64,
+    /// VecCF64
+    VecCF64,
+    /// VecF64
+    VecF64,
+    /// VecI8
+    VecI8,
+    /// VecI16
+    VecI16,
+    /// VecI32
+    VecI32,
+    /// VecI64
+    VecI64,
+    /// VecU16
+    VecU16,
+    /// Time
+    Time,
     /// Any
     Any,
 }
@@ -536,7 +744,7 @@ impl fmt::Display for PmtKind {
             PmtKind::String => write!(f, "String"),
             PmtKind::Bool => write!(f, "Bool"),
             PmtKind::Usize => write!(f, "Usize"),
-            PmtKind::Isize => write!(f, "isize"),
+            PmtKind::Isize => write!(f, "Isize"),
             PmtKind::U32 => write!(f, "U32"),
             PmtKind::U64 => write!(f, "U64"),
             PmtKind::F32 => write!(f, "F32"),
@@ -548,6 +756,16 @@ impl fmt::Display for PmtKind {
             PmtKind::VecPmt => write!(f, "VecPmt"),
             PmtKind::Finished => write!(f, "Finished"),
             PmtKind::MapStrPmt => write!(f, "MapStrPmt"),
+            PmtKind::CF32 => write!(f, "CF32"),
+            PmtKind::CF64 => write!(f, "CF64"),
+            PmtKind::VecCF64 => write!(f, "VecCF64"),
+            PmtKind::VecF64 => write!(f, "VecF64"),
+            PmtKind::VecI8 => write!(f, "VecI8"),
+            PmtKind::VecI16 => write!(f, "VecI16"),
+            PmtKind::VecI32 => write!(f, "VecI32"),
+            PmtKind::VecI64 => write!(f, "VecI64"),
+            PmtKind::VecU16 => write!(f, "VecU16"),
+            PmtKind::Time => write!(f, "Time"),
             PmtKind::Any => write!(f, "Any"),
         }
     }
@@ -569,12 +787,23 @@ impl std::str::FromStr for PmtKind {
             "U64" => return Ok(PmtKind::U64),
             "F32" => return Ok(PmtKind::F32),
             "F64" => return Ok(PmtKind::F64),
+            "VecCF32" => return Ok(PmtKind::VecCF32),
             "VecF32" => return Ok(PmtKind::VecF32),
             "VecU64" => return Ok(PmtKind::VecU64),
             "Blob" => return Ok(PmtKind::Blob),
             "VecPmt" => return Ok(PmtKind::VecPmt),
             "Finished" => return Ok(PmtKind::Finished),
             "MapStrPmt" => return Ok(PmtKind::MapStrPmt),
+            "CF32" => return Ok(PmtKind::CF32),
+            "CF64" => return Ok(PmtKind::CF64),
+            "VecCF64" => return Ok(PmtKind::VecCF64),
+            "VecF64" => return Ok(PmtKind::VecF64),
+            "VecI8" => return Ok(PmtKind::VecI8),
+            "VecI16" => return Ok(PmtKind::VecI16),
+            "VecI32" => return Ok(PmtKind::VecI32),
+            "VecI64" => return Ok(PmtKind::VecI64),
+            "VecU16" => return Ok(PmtKind::VecU16),
+            "Time" => return Ok(PmtKind::Time),
             "Any" => return Ok(PmtKind::Any),
             _ => (),
         }
@@ -746,6 +975,110 @@ mod test {
         assert_eq!(p.try_into(), Ok(e));
     }
 
+    #[test]
+    fn numeric_variants() {
+        let e = Complex32::new(1.0, -2.0);
+        let p = Pmt::from(e);
+        assert_eq!(p, Pmt::CF32(e));
+        assert_eq!((&p).try_into(), Ok(e));
+        assert_eq!(Complex64::try_from(&p), Ok(Complex64::new(1.0, -2.0)));
+
+        let e = vec![Complex64::new(1.0, 2.0)];
+        let p = Pmt::from(e.clone());
+        assert_eq!(p, Pmt::VecCF64(e.clone()));
+        assert_eq!(p.try_into(), Ok(e));
+
+        let e = vec![-1i8, 2];
+        let p = Pmt::from(e.clone());
+        assert_eq!(p, Pmt::VecI8(e.clone()));
+        assert_eq!(p.try_into(), Ok(e));
+
+        let e = vec![1u16, 2];
+        let p = Pmt::from(e.clone());
+        assert_eq!(p, Pmt::VecU16(e.clone()));
+        assert_eq!(p.try_into(), Ok(e));
+        assert_ne!(Pmt::VecU16(vec![1]), Pmt::VecI16(vec![1]));
+
+        let p = Pmt::VecI64(vec![-1]);
+        assert_eq!(PmtKind::VecI64, p.kind());
+        assert_eq!("VecI64".parse::<PmtKind>(), Ok(PmtKind::VecI64));
+        assert_eq!(PmtKind::CF64.to_string(), "CF64");
+        assert_eq!(Pmt::CF32(Complex32::new(1.0, 2.0)).to_string(), "1+2i");
+    }
+
+    #[test]
+    fn pmt_json() {
+        let p = Pmt::VecI16(vec![-1, 2]);
+        let s = serde_json::to_string(&p).unwrap();
+        assert_eq!(s, "{\"VecI16\":[-1,2]}");
+        assert_eq!(s.parse::<Pmt>(), Ok(p));
+
+        assert_eq!(
+            "CF32: [1.0, 2.0]".parse::<Pmt>(),
+            Ok(Pmt::CF32(Complex32::new(1.0, 2.0)))
+        );
+        assert_eq!(
+            serde_json::to_string(&Pmt::VecF32(vec![1.0])).unwrap(),
+            "{\"VecF32\":[1.0]}"
+        );
+    }
+
+    #[test]
+    fn time() {
+        let t = TimeSpec::new(1_700_000_000, 0.25);
+        let p = Pmt::from(t);
+        assert_eq!(p, Pmt::Time(t));
+        assert_eq!(p.kind(), PmtKind::Time);
+        assert_eq!((&p).try_into(), Ok(t));
+        assert_eq!(p.to_string(), "1700000000.250000000");
+        assert_eq!(
+            Pmt::from_string("1700000000.25", &PmtKind::Time),
+            Some(Pmt::Time(t))
+        );
+
+        let s = serde_json::to_string(&p).unwrap();
+        assert_eq!(s.parse::<Pmt>(), Ok(p));
+    }
+
+    #[test]
+    fn from_string() {
+        assert_eq!(
+            Pmt::from_string("1+2i", &PmtKind::CF32),
+            Some(Pmt::CF32(Complex32::new(1.0, 2.0)))
+        );
+        assert_eq!(
+            Pmt::from_string("[1, -2, 3]", &PmtKind::VecI32),
+            Some(Pmt::VecI32(vec![1, -2, 3]))
+        );
+        assert_eq!(
+            Pmt::from_string("0.5,1", &PmtKind::VecF64),
+            Some(Pmt::VecF64(vec![0.5, 1.0]))
+        );
+        assert_eq!(
+            Pmt::from_string("1+1i, 2-1i", &PmtKind::VecCF64),
+            Some(Pmt::VecCF64(vec![
+                Complex64::new(1.0, 1.0),
+                Complex64::new(2.0, -1.0)
+            ]))
+        );
+        assert_eq!(
+            Pmt::from_string("[]", &PmtKind::Blob),
+            Some(Pmt::Blob(vec![]))
+        );
+        assert_eq!(Pmt::from_string("1, 300", &PmtKind::Blob), None);
+        assert_eq!(Pmt::from_string("42", &PmtKind::U32), Some(Pmt::U32(42)));
+        assert_eq!(
+            Pmt::from_string("-1", &PmtKind::Isize),
+            Some(Pmt::Isize(-1))
+        );
+        assert_eq!(Pmt::from_string("-1", &PmtKind::Usize), None);
+        assert_eq!(
+            Pmt::from_string("true", &PmtKind::Bool),
+            Some(Pmt::Bool(true))
+        );
+        assert_eq!(Pmt::from_string("", &PmtKind::Null), Some(Pmt::Null));
+    }
+
     #[test]
     fn pmt_kind() {
         let p = Pmt::U32(42);
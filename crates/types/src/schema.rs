@@ -0,0 +1,139 @@
+use serde::Deserialize;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::Pmt;
+use crate::PmtKind;
+
+/// Schema of a message handler.
+///
+/// Describes which [`Pmt`] kinds a handler accepts and returns, the valid range of numeric
+/// arguments, and what the handler does. All parts are optional, i.e., an empty list of accepted
+/// kinds accepts everything. Clients, like `futuresdr-remote`, use it to validate input before
+/// calling a handler and UIs to render suitable input elements.
+///
+/// ```
+/// use futuresdr_types::HandlerSchema;
+/// use futuresdr_types::Pmt;
+/// use futuresdr_types::PmtKind;
+///
+/// let schema = HandlerSchema::new()
+///     .accepts([PmtKind::F64, PmtKind::U32, PmtKind::Null])
+///     .returns([PmtKind::F64])
+///     .range(70e6, 6e9)
+///     .description("Center frequency in Hz");
+///
+/// assert!(schema.validate(&Pmt::F64(100e6)).is_ok());
+/// assert!(schema.validate(&Pmt::F64(1.0)).is_err());
+/// assert!(schema.validate(&Pmt::String("foo".to_string())).is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HandlerSchema {
+    /// Accepted kinds
+    ///
+    /// Empty, if the handler does not declare what it accepts.
+    #[serde(default)]
+    pub accepts: Vec<PmtKind>,
+    /// Returned kinds
+    #[serde(default)]
+    pub returns: Vec<PmtKind>,
+    /// Minimum of numeric arguments (inclusive)
+    #[serde(default)]
+    pub min: Option<f64>,
+    /// Maximum of numeric arguments (inclusive)
+    #[serde(default)]
+    pub max: Option<f64>,
+    /// Description
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// Error returned when a [`Pmt`] does not match a [`HandlerSchema`].
+#[derive(Debug, Clone, Error, PartialEq)]
+pub enum SchemaError {
+    /// The kind of the [`Pmt`] is not accepted.
+    #[error("Expected one of {0:?}, got {1}")]
+    InvalidKind(Vec<PmtKind>, PmtKind),
+    /// The value is outside of the accepted range.
+    #[error("Value {0} out of range [{1:?}, {2:?}]")]
+    OutOfRange(f64, Option<f64>, Option<f64>),
+}
+
+impl HandlerSchema {
+    /// Create an empty schema, accepting everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add accepted kinds.
+    #[must_use]
+    pub fn accepts(mut self, kinds: impl IntoIterator<Item = PmtKind>) -> Self {
+        self.accepts.extend(kinds);
+        self
+    }
+
+    /// Add returned kinds.
+    #[must_use]
+    pub fn returns(mut self, kinds: impl IntoIterator<Item = PmtKind>) -> Self {
+        self.returns.extend(kinds);
+        self
+    }
+
+    /// Set the valid range of numeric arguments.
+    #[must_use]
+    pub fn range(mut self, min: f64, max: f64) -> Self {
+        self.min = Some(min);
+        self.max = Some(max);
+        self
+    }
+
+    /// Set the description.
+    #[must_use]
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Check if the handler accepts the [`Pmt`].
+    ///
+    /// The range is only checked for arguments that can be converted to `f64`.
+    pub fn validate(&self, p: &Pmt) -> Result<(), SchemaError> {
+        let kind = p.kind();
+        if !self.accepts.is_empty() && !self.accepts.contains(&kind) {
+            return Err(SchemaError::InvalidKind(self.accepts.clone(), kind));
+        }
+
+        if let Ok(v) = f64::try_from(p) {
+            if self.min.map(|m| v < m).unwrap_or(false) || self.max.map(|m| v > m).unwrap_or(false)
+            {
+                return Err(SchemaError::OutOfRange(v, self.min, self.max));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::BlockDescription;
+
+    #[test]
+    fn description_serde() {
+        let json = r#"{"id":0,"type_name":"Tuner","instance_name":"Tuner-0",
+            "stream_inputs":[],"stream_outputs":[],"message_inputs":["freq"],
+            "message_outputs":[],"blocking":false}"#;
+        let mut desc: BlockDescription = serde_json::from_str(json).unwrap();
+        assert!(desc.message_input_schema("freq").is_none());
+
+        let schema = HandlerSchema::new().accepts([PmtKind::F64]).range(0.0, 1.0);
+        desc.message_input_schemas = vec![Some(schema.clone())];
+        let json = serde_json::to_string(&desc).unwrap();
+        let desc: BlockDescription = serde_json::from_str(&json).unwrap();
+        assert_eq!(desc.message_input_schema("freq"), Some(&schema));
+        assert_eq!(
+            schema.validate(&Pmt::F64(2.0)),
+            Err(SchemaError::OutOfRange(2.0, Some(0.0), Some(1.0)))
+        );
+    }
+}
@@ -0,0 +1,433 @@
+use serde::Deserialize;
+use serde::Serialize;
+use std::fmt;
+use std::ops::Add;
+use std::ops::AddAssign;
+use std::ops::Neg;
+use std::ops::Sub;
+use std::ops::SubAssign;
+use std::time::Duration;
+
+use crate::PmtConversionError;
+
+/// Time specification
+///
+/// Absolute or relative time, represented as integer seconds plus a fractional part in `[0, 1)`.
+/// Splitting the time keeps full precision for absolute timestamps, e.g., seconds since the Unix
+/// epoch, where an `f64` would only resolve microseconds. Hardware timestamps given in ticks of a
+/// clock can be converted with [`TimeSpec::from_ticks`] and [`TimeSpec::to_ticks`].
+///
+/// Sample times are attached to streams with `rx_time` and `tx_time` tags.
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(try_from = "RawTimeSpec")]
+pub struct TimeSpec {
+    secs: i64,
+    frac: f64,
+}
+
+/// Serialized [`TimeSpec`], normalized through [`TimeSpec::checked_new`] when deserializing.
+#[derive(Deserialize)]
+struct RawTimeSpec {
+    secs: i64,
+    frac: f64,
+}
+
+impl TryFrom<RawTimeSpec> for TimeSpec {
+    type Error = String;
+
+    fn try_from(t: RawTimeSpec) -> Result<Self, Self::Error> {
+        TimeSpec::checked_new(t.secs, t.frac)
+            .ok_or_else(|| format!("invalid time {} + {}", t.secs, t.frac))
+    }
+}
+
+impl TimeSpec {
+    /// Zero time
+    pub const ZERO: TimeSpec = TimeSpec { secs: 0, frac: 0.0 };
+
+    /// Create time from full and fractional seconds.
+    ///
+    /// The fractional part does not have to be in `[0, 1)`, it is normalized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `frac` is not finite or the full seconds overflow. Use
+    /// [`TimeSpec::checked_new`] for untrusted input.
+    pub fn new(secs: i64, frac: f64) -> Self {
+        Self::checked_new(secs, frac).expect("overflow when creating TimeSpec")
+    }
+
+    /// Create time from full and fractional seconds.
+    ///
+    /// Returns `None` if `frac` is not finite or the full seconds overflow.
+    pub fn checked_new(secs: i64, frac: f64) -> Option<Self> {
+        if !frac.is_finite() {
+            return None;
+        }
+        let full = frac.floor();
+        // `i64::MAX as f64` rounds up to 2^63, which is out of range
+        if full < i64::MIN as f64 || full >= i64::MAX as f64 {
+            return None;
+        }
+        let mut secs = secs.checked_add(full as i64)?;
+        let mut frac = frac - full;
+        // rounding of `frac - full` can yield 1.0 for tiny negative fractions
+        if frac >= 1.0 {
+            secs = secs.checked_add(1)?;
+            frac = 0.0;
+        }
+        Some(Self { secs, frac })
+    }
+
+    /// Create time from seconds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `secs` is not finite or out of range.
+    pub fn from_secs_f64(secs: f64) -> Self {
+        Self::new(0, secs)
+    }
+
+    /// Create time from nanoseconds.
+    pub fn from_nanos(nanos: i64) -> Self {
+        Self {
+            secs: nanos.div_euclid(1_000_000_000),
+            frac: nanos.rem_euclid(1_000_000_000) as f64 / 1e9,
+        }
+    }
+
+    /// Create time from ticks of a clock with the given `rate` in Hz.
+    ///
+    /// Also useful to get the duration of a number of samples at a given sample rate. For integer
+    /// rates, the full seconds are computed exactly and only the remaining ticks are converted
+    /// to a fraction.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rate` is not positive and finite or the time is out of range.
+    pub fn from_ticks(ticks: i64, rate: f64) -> Self {
+        Self::checked_from_ticks(ticks, rate).expect("invalid rate or overflow in TimeSpec")
+    }
+
+    /// Create time from ticks of a clock with the given `rate` in Hz.
+    ///
+    /// Returns `None` if `rate` is not positive and finite or the time is out of range.
+    pub fn checked_from_ticks(ticks: i64, rate: f64) -> Option<Self> {
+        if !(rate.is_finite() && rate > 0.0) {
+            return None;
+        }
+        match integer_rate(rate) {
+            Some(r) => Self::checked_new(ticks.div_euclid(r), ticks.rem_euclid(r) as f64 / rate),
+            None => Self::checked_new(0, ticks as f64 / rate),
+        }
+    }
+
+    /// Full seconds, rounded towards negative infinity.
+    pub fn full_secs(&self) -> i64 {
+        self.secs
+    }
+
+    /// Fractional seconds in `[0, 1)`.
+    pub fn frac_secs(&self) -> f64 {
+        self.frac
+    }
+
+    /// Time in seconds.
+    ///
+    /// Loses precision for large absolute times.
+    pub fn as_secs_f64(&self) -> f64 {
+        self.secs as f64 + self.frac
+    }
+
+    /// Time in nanoseconds, rounded to the nearest nanosecond.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the time does not fit in an `i64` of nanoseconds, i.e., is more than about 292
+    /// years away from zero.
+    pub fn as_nanos(&self) -> i64 {
+        self.checked_as_nanos()
+            .expect("overflow when converting TimeSpec to nanoseconds")
+    }
+
+    /// Time in nanoseconds, rounded to the nearest nanosecond.
+    ///
+    /// Returns `None` if the time does not fit in an `i64` of nanoseconds.
+    pub fn checked_as_nanos(&self) -> Option<i64> {
+        self.secs
+            .checked_mul(1_000_000_000)?
+            .checked_add((self.frac * 1e9).round() as i64)
+    }
+
+    /// Time in ticks of a clock with the given `rate` in Hz, rounded to the nearest tick.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rate` is not positive and finite or the ticks do not fit in an `i64`.
+    pub fn to_ticks(&self, rate: f64) -> i64 {
+        self.checked_to_ticks(rate)
+            .expect("invalid rate or overflow when converting TimeSpec to ticks")
+    }
+
+    /// Time in ticks of a clock with the given `rate` in Hz, rounded to the nearest tick.
+    ///
+    /// Returns `None` if `rate` is not positive and finite or the ticks do not fit in an `i64`.
+    pub fn checked_to_ticks(&self, rate: f64) -> Option<i64> {
+        if !(rate.is_finite() && rate > 0.0) {
+            return None;
+        }
+        let frac = (self.frac * rate).round();
+        let secs = match integer_rate(rate) {
+            Some(r) => self.secs.checked_mul(r)?,
+            None => {
+                let secs = (self.secs as f64 * rate).round();
+                if secs < i64::MIN as f64 || secs >= i64::MAX as f64 {
+                    return None;
+                }
+                secs as i64
+            }
+        };
+        // `frac` is less than `rate`, which is below 2^63 for integer rates
+        if frac >= i64::MAX as f64 {
+            return None;
+        }
+        secs.checked_add(frac as i64)
+    }
+}
+
+/// `rate` as integer, if it has no fractional part.
+fn integer_rate(rate: f64) -> Option<i64> {
+    (rate >= 1.0 && rate.fract() == 0.0 && rate < i64::MAX as f64).then_some(rate as i64)
+}
+
+impl fmt::Display for TimeSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nanos = self.secs as i128 * 1_000_000_000 + (self.frac * 1e9).round() as i128;
+        let sign = if nanos < 0 { "-" } else { "" };
+        let nanos = nanos.abs();
+        write!(
+            f,
+            "{sign}{}.{:09}",
+            nanos / 1_000_000_000,
+            nanos % 1_000_000_000
+        )
+    }
+}
+
+impl std::str::FromStr for TimeSpec {
+    type Err = PmtConversionError;
+
+    /// Parse seconds with optional fractional part, e.g., `1700000000.000000125`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (neg, s) = match s.strip_prefix('-') {
+            Some(s) => (true, s),
+            None => (false, s),
+        };
+        let (secs, frac) = s.split_once('.').unwrap_or((s, ""));
+        if secs.is_empty() && frac.is_empty() || !secs.chars().all(|c| c.is_ascii_digit()) {
+            return Err(PmtConversionError);
+        }
+        let secs = if secs.is_empty() {
+            0
+        } else {
+            secs.parse::<i64>().or(Err(PmtConversionError))?
+        };
+        let frac = if frac.is_empty() {
+            0.0
+        } else if frac.chars().all(|c| c.is_ascii_digit()) {
+            format!("0.{frac}")
+                .parse::<f64>()
+                .or(Err(PmtConversionError))?
+        } else {
+            return Err(PmtConversionError);
+        };
+        let t = TimeSpec::new(secs, frac);
+        Ok(if neg { -t } else { t })
+    }
+}
+
+impl From<Duration> for TimeSpec {
+    fn from(d: Duration) -> Self {
+        let secs = i64::try_from(d.as_secs()).expect("overflow when converting Duration");
+        Self::new(secs, d.subsec_nanos() as f64 / 1e9)
+    }
+}
+
+impl Neg for TimeSpec {
+    type Output = TimeSpec;
+
+    fn neg(self) -> Self::Output {
+        let secs = self
+            .secs
+            .checked_neg()
+            .expect("overflow when negating TimeSpec");
+        TimeSpec::new(secs, -self.frac)
+    }
+}
+
+impl Add for TimeSpec {
+    type Output = TimeSpec;
+
+    fn add(self, rhs: TimeSpec) -> Self::Output {
+        let secs = self
+            .secs
+            .checked_add(rhs.secs)
+            .expect("overflow when adding TimeSpecs");
+        TimeSpec::new(secs, self.frac + rhs.frac)
+    }
+}
+
+impl Sub for TimeSpec {
+    type Output = TimeSpec;
+
+    fn sub(self, rhs: TimeSpec) -> Self::Output {
+        let secs = self
+            .secs
+            .checked_sub(rhs.secs)
+            .expect("overflow when subtracting TimeSpecs");
+        TimeSpec::new(secs, self.frac - rhs.frac)
+    }
+}
+
+impl Add<Duration> for TimeSpec {
+    type Output = TimeSpec;
+
+    fn add(self, rhs: Duration) -> Self::Output {
+        self + TimeSpec::from(rhs)
+    }
+}
+
+impl Sub<Duration> for TimeSpec {
+    type Output = TimeSpec;
+
+    fn sub(self, rhs: Duration) -> Self::Output {
+        self - TimeSpec::from(rhs)
+    }
+}
+
+impl AddAssign for TimeSpec {
+    fn add_assign(&mut self, rhs: TimeSpec) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for TimeSpec {
+    fn sub_assign(&mut self, rhs: TimeSpec) {
+        *self = *self - rhs;
+    }
+}
+
+impl AddAssign<Duration> for TimeSpec {
+    fn add_assign(&mut self, rhs: Duration) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign<Duration> for TimeSpec {
+    fn sub_assign(&mut self, rhs: Duration) {
+        *self = *self - rhs;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn normalize() {
+        let t = TimeSpec::new(1, 1.5);
+        assert_eq!((t.full_secs(), t.frac_secs()), (2, 0.5));
+        let t = TimeSpec::new(1, -0.25);
+        assert_eq!((t.full_secs(), t.frac_secs()), (0, 0.75));
+        let t = TimeSpec::from_nanos(-1);
+        assert_eq!(t.full_secs(), -1);
+        assert_eq!(t.as_nanos(), -1);
+    }
+
+    #[test]
+    fn ticks() {
+        let rate = 61.44e6;
+        let t = TimeSpec::from_ticks(1_000_000_000_000, rate);
+        assert_eq!(t.full_secs(), 16276);
+        assert_eq!(t.to_ticks(rate), 1_000_000_000_000);
+
+        let t = TimeSpec::new(1_700_000_000, 0.0) + TimeSpec::from_ticks(3, 1e9);
+        assert_eq!(t.as_nanos(), 1_700_000_000_000_000_003);
+        assert_eq!(TimeSpec::from_ticks(-1, 4.0), TimeSpec::new(-1, 0.75));
+
+        // exact for ticks beyond the precision of f64
+        let ticks = i64::MAX - 1;
+        let t = TimeSpec::from_ticks(ticks, 1e9);
+        assert_eq!(t.full_secs(), ticks / 1_000_000_000);
+        assert_eq!(t.to_ticks(1e9), ticks);
+
+        let t = TimeSpec::from_ticks(3, 1.5);
+        assert_eq!(t, TimeSpec::new(2, 0.0));
+    }
+
+    #[test]
+    fn deserialize_normalizes() {
+        let t: TimeSpec = serde_json::from_str(r#"{"secs":1,"frac":1.5}"#).unwrap();
+        assert_eq!((t.full_secs(), t.frac_secs()), (2, 0.5));
+        let s = serde_json::to_string(&t).unwrap();
+        assert_eq!(serde_json::from_str::<TimeSpec>(&s).unwrap(), t);
+
+        assert!(
+            serde_json::from_str::<TimeSpec>(r#"{"secs":9223372036854775807,"frac":1.5}"#).is_err()
+        );
+        assert!(serde_json::from_str::<TimeSpec>(r#"{"secs":0,"frac":1e300}"#).is_err());
+    }
+
+    #[test]
+    fn overflow() {
+        assert_eq!(TimeSpec::checked_new(i64::MAX, 1.5), None);
+        assert_eq!(TimeSpec::checked_new(i64::MIN, -0.5), None);
+        assert_eq!(TimeSpec::checked_new(0, f64::NAN), None);
+        assert_eq!(TimeSpec::checked_new(0, f64::INFINITY), None);
+        assert_eq!(TimeSpec::checked_from_ticks(1, 0.0), None);
+        assert_eq!(TimeSpec::checked_from_ticks(1, -1.0), None);
+        assert_eq!(TimeSpec::checked_from_ticks(i64::MAX, 1e-300), None);
+        assert_eq!(TimeSpec::new(i64::MAX, 0.5).checked_as_nanos(), None);
+        assert_eq!(TimeSpec::new(i64::MAX, 0.5).checked_to_ticks(1e9), None);
+        assert_eq!(TimeSpec::new(i64::MAX, 0.5).checked_to_ticks(1.5), None);
+        assert_eq!(TimeSpec::new(1, 0.5).checked_to_ticks(0.0), None);
+        assert_eq!(TimeSpec::new(1, 0.5).checked_to_ticks(4.0), Some(6));
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = TimeSpec::new(10, 0.75);
+        let b = TimeSpec::new(2, 0.5);
+        assert_eq!(a + b, TimeSpec::new(13, 0.25));
+        assert_eq!(a - b, TimeSpec::new(8, 0.25));
+        assert_eq!(b - a, TimeSpec::new(-9, 0.75));
+        assert_eq!(-b, TimeSpec::new(-3, 0.5));
+        assert_eq!(a + Duration::from_millis(250), TimeSpec::new(11, 0.0));
+        assert!(b < a);
+
+        let mut c = TimeSpec::ZERO;
+        c += Duration::from_secs(1);
+        c -= TimeSpec::from_secs_f64(0.5);
+        assert_eq!(c, TimeSpec::new(0, 0.5));
+    }
+
+    #[test]
+    fn display_parse() {
+        let t = TimeSpec::new(1_700_000_000, 0.000000125);
+        assert_eq!(t.to_string(), "1700000000.000000125");
+        assert_eq!(
+            "1700000000.000000125"
+                .parse::<TimeSpec>()
+                .unwrap()
+                .as_nanos(),
+            t.as_nanos()
+        );
+        assert_eq!(TimeSpec::new(-2, 0.5).to_string(), "-1.500000000");
+        assert_eq!("-1.5".parse(), Ok(TimeSpec::new(-2, 0.5)));
+        assert_eq!("3".parse(), Ok(TimeSpec::new(3, 0.0)));
+        assert_eq!(".5".parse(), Ok(TimeSpec::new(0, 0.5)));
+        assert!("1.x".parse::<TimeSpec>().is_err());
+        assert!("".parse::<TimeSpec>().is_err());
+    }
+}
@@ -0,0 +1,529 @@
+use num_complex::Complex32;
+use num_complex::Complex64;
+use std::collections::HashMap;
+use thiserror::Error;
+
+use crate::Pmt;
+use crate::PmtKind;
+use crate::TimeSpec;
+
+/// Version of the binary [`Pmt`] encoding
+const VERSION: u8 = 1;
+
+/// Maximum nesting depth of [`Pmt::VecPmt`] and [`Pmt::MapStrPmt`] when decoding
+const MAX_DEPTH: usize = 128;
+
+const TAG_OK: u8 = 0;
+const TAG_INVALID_VALUE: u8 = 1;
+const TAG_NULL: u8 = 2;
+const TAG_STRING: u8 = 3;
+const TAG_BOOL: u8 = 4;
+const TAG_USIZE: u8 = 5;
+const TAG_ISIZE: u8 = 6;
+const TAG_U32: u8 = 7;
+const TAG_U64: u8 = 8;
+const TAG_F32: u8 = 9;
+const TAG_F64: u8 = 10;
+const TAG_VEC_CF32: u8 = 11;
+const TAG_VEC_F32: u8 = 12;
+const TAG_VEC_U64: u8 = 13;
+const TAG_BLOB: u8 = 14;
+const TAG_VEC_PMT: u8 = 15;
+const TAG_FINISHED: u8 = 16;
+const TAG_MAP_STR_PMT: u8 = 17;
+const TAG_CF32: u8 = 18;
+const TAG_CF64: u8 = 19;
+const TAG_VEC_CF64: u8 = 20;
+const TAG_VEC_F64: u8 = 21;
+const TAG_VEC_I8: u8 = 22;
+const TAG_VEC_I16: u8 = 23;
+const TAG_VEC_I32: u8 = 24;
+const TAG_VEC_I64: u8 = 25;
+const TAG_VEC_U16: u8 = 26;
+const TAG_TIME: u8 = 27;
+
+/// Error encoding or decoding [`Pmts`](Pmt) in a binary format.
+#[derive(Debug, Clone, Error, PartialEq)]
+pub enum PmtCodecError {
+    /// The [`Pmt`] variant cannot be represented in the format.
+    #[error("cannot encode {0} Pmt")]
+    Unsupported(PmtKind),
+    /// The encoding uses an unsupported version.
+    #[error("unsupported encoding version {0}")]
+    Version(u8),
+    /// Unknown type tag.
+    #[error("unknown type tag {0:#04x}")]
+    Tag(u8),
+    /// The input ended before the value was complete.
+    #[error("unexpected end of input")]
+    Eof,
+    /// There is data left after the value.
+    #[error("{0} trailing bytes after value")]
+    Trailing(usize),
+    /// The value is nested too deeply.
+    #[error("nesting too deep")]
+    Depth,
+    /// The input is malformed.
+    #[error("invalid encoding: {0}")]
+    Invalid(String),
+}
+
+impl Pmt {
+    /// Encode in the compact binary format.
+    ///
+    /// The encoding starts with a version byte, followed by a type tag and the value. Integers
+    /// and lengths are LEB128 varints, floats are little endian. [`Pmt::Any`] cannot be encoded.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, PmtCodecError> {
+        let mut buf = vec![VERSION];
+        encode(self, &mut buf)?;
+        Ok(buf)
+    }
+
+    /// Decode from the compact binary format.
+    ///
+    /// Counterpart of [`Pmt::to_bytes`]. The whole input has to be consumed.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Pmt, PmtCodecError> {
+        let mut r = Reader::new(bytes);
+        let version = r.u8()?;
+        if version != VERSION {
+            return Err(PmtCodecError::Version(version));
+        }
+        let p = decode(&mut r, 0)?;
+        r.finish()?;
+        Ok(p)
+    }
+}
+
+fn put_varint(mut v: u64, buf: &mut Vec<u8>) {
+    while v >= 0x80 {
+        buf.push((v as u8) | 0x80);
+        v >>= 7;
+    }
+    buf.push(v as u8);
+}
+
+fn put_str(s: &str, buf: &mut Vec<u8>) {
+    put_varint(s.len() as u64, buf);
+    buf.extend_from_slice(s.as_bytes());
+}
+
+// fixed-size elements, little endian
+fn put_vec<T, const N: usize>(v: &[T], buf: &mut Vec<u8>, f: impl Fn(&T) -> [u8; N]) {
+    put_varint(v.len() as u64, buf);
+    for x in v {
+        buf.extend_from_slice(&f(x));
+    }
+}
+
+fn encode(p: &Pmt, buf: &mut Vec<u8>) -> Result<(), PmtCodecError> {
+    match p {
+        Pmt::Ok => buf.push(TAG_OK),
+        Pmt::InvalidValue => buf.push(TAG_INVALID_VALUE),
+        Pmt::Null => buf.push(TAG_NULL),
+        Pmt::Finished => buf.push(TAG_FINISHED),
+        Pmt::String(s) => {
+            buf.push(TAG_STRING);
+            put_str(s, buf);
+        }
+        Pmt::Bool(b) => {
+            buf.push(TAG_BOOL);
+            buf.push(*b as u8);
+        }
+        Pmt::Usize(v) => {
+            buf.push(TAG_USIZE);
+            put_varint(*v as u64, buf);
+        }
+        Pmt::Isize(v) => {
+            buf.push(TAG_ISIZE);
+            let v = *v as i64;
+            put_varint(((v << 1) ^ (v >> 63)) as u64, buf);
+        }
+        Pmt::U32(v) => {
+            buf.push(TAG_U32);
+            put_varint(*v as u64, buf);
+        }
+        Pmt::U64(v) => {
+            buf.push(TAG_U64);
+            put_varint(*v, buf);
+        }
+        Pmt::F32(v) => {
+            buf.push(TAG_F32);
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        Pmt::F64(v) => {
+            buf.push(TAG_F64);
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        Pmt::CF32(v) => {
+            buf.push(TAG_CF32);
+            buf.extend_from_slice(&cf32_bytes(v));
+        }
+        Pmt::CF64(v) => {
+            buf.push(TAG_CF64);
+            buf.extend_from_slice(&cf64_bytes(v));
+        }
+        Pmt::VecCF32(v) => {
+            buf.push(TAG_VEC_CF32);
+            put_vec(v, buf, cf32_bytes);
+        }
+        Pmt::VecCF64(v) => {
+            buf.push(TAG_VEC_CF64);
+            put_vec(v, buf, cf64_bytes);
+        }
+        Pmt::VecF32(v) => {
+            buf.push(TAG_VEC_F32);
+            put_vec(v, buf, |x| x.to_le_bytes());
+        }
+        Pmt::VecF64(v) => {
+            buf.push(TAG_VEC_F64);
+            put_vec(v, buf, |x| x.to_le_bytes());
+        }
+        Pmt::VecI8(v) => {
+            buf.push(TAG_VEC_I8);
+            put_vec(v, buf, |x| x.to_le_bytes());
+        }
+        Pmt::VecI16(v) => {
+            buf.push(TAG_VEC_I16);
+            put_vec(v, buf, |x| x.to_le_bytes());
+        }
+        Pmt::VecI32(v) => {
+            buf.push(TAG_VEC_I32);
+            put_vec(v, buf, |x| x.to_le_bytes());
+        }
+        Pmt::VecI64(v) => {
+            buf.push(TAG_VEC_I64);
+            put_vec(v, buf, |x| x.to_le_bytes());
+        }
+        Pmt::VecU16(v) => {
+            buf.push(TAG_VEC_U16);
+            put_vec(v, buf, |x| x.to_le_bytes());
+        }
+        Pmt::Time(t) => {
+            buf.push(TAG_TIME);
+            buf.extend_from_slice(&t.full_secs().to_le_bytes());
+            buf.extend_from_slice(&t.frac_secs().to_le_bytes());
+        }
+        Pmt::VecU64(v) => {
+            buf.push(TAG_VEC_U64);
+            put_varint(v.len() as u64, buf);
+            for u in v {
+                put_varint(*u, buf);
+            }
+        }
+        Pmt::Blob(v) => {
+            buf.push(TAG_BLOB);
+            put_varint(v.len() as u64, buf);
+            buf.extend_from_slice(v);
+        }
+        Pmt::VecPmt(v) => {
+            buf.push(TAG_VEC_PMT);
+            put_varint(v.len() as u64, buf);
+            for p in v {
+                encode(p, buf)?;
+            }
+        }
+        Pmt::MapStrPmt(m) => {
+            buf.push(TAG_MAP_STR_PMT);
+            put_varint(m.len() as u64, buf);
+            for (k, v) in m {
+                put_str(k, buf);
+                encode(v, buf)?;
+            }
+        }
+        Pmt::Any(_) => return Err(PmtCodecError::Unsupported(PmtKind::Any)),
+    }
+    Ok(())
+}
+
+fn decode(r: &mut Reader, depth: usize) -> Result<Pmt, PmtCodecError> {
+    if depth > MAX_DEPTH {
+        return Err(PmtCodecError::Depth);
+    }
+    Ok(match r.u8()? {
+        TAG_OK => Pmt::Ok,
+        TAG_INVALID_VALUE => Pmt::InvalidValue,
+        TAG_NULL => Pmt::Null,
+        TAG_FINISHED => Pmt::Finished,
+        TAG_STRING => Pmt::String(r.str()?),
+        TAG_BOOL => match r.u8()? {
+            0 => Pmt::Bool(false),
+            1 => Pmt::Bool(true),
+            v => return Err(PmtCodecError::Invalid(format!("bool value {v}"))),
+        },
+        TAG_USIZE => Pmt::Usize(r.int("usize")?),
+        TAG_ISIZE => {
+            let v = r.varint()?;
+            let v = ((v >> 1) as i64) ^ -((v & 1) as i64);
+            Pmt::Isize(
+                v.try_into()
+                    .map_err(|_| PmtCodecError::Invalid(format!("isize value {v}")))?,
+            )
+        }
+        TAG_U32 => Pmt::U32(r.int("u32")?),
+        TAG_U64 => Pmt::U64(r.varint()?),
+        TAG_F32 => Pmt::F32(f32::from_le_bytes(r.array()?)),
+        TAG_F64 => Pmt::F64(f64::from_le_bytes(r.array()?)),
+        TAG_CF32 => Pmt::CF32(cf32_from_bytes(r.array()?)),
+        TAG_CF64 => Pmt::CF64(cf64_from_bytes(r.array()?)),
+        TAG_VEC_CF32 => Pmt::VecCF32(r.vec(cf32_from_bytes)?),
+        TAG_VEC_CF64 => Pmt::VecCF64(r.vec(cf64_from_bytes)?),
+        TAG_VEC_F32 => Pmt::VecF32(r.vec(f32::from_le_bytes)?),
+        TAG_VEC_F64 => Pmt::VecF64(r.vec(f64::from_le_bytes)?),
+        TAG_VEC_I8 => Pmt::VecI8(r.vec(i8::from_le_bytes)?),
+        TAG_VEC_I16 => Pmt::VecI16(r.vec(i16::from_le_bytes)?),
+        TAG_VEC_I32 => Pmt::VecI32(r.vec(i32::from_le_bytes)?),
+        TAG_VEC_I64 => Pmt::VecI64(r.vec(i64::from_le_bytes)?),
+        TAG_VEC_U16 => Pmt::VecU16(r.vec(u16::from_le_bytes)?),
+        TAG_TIME => {
+            let secs = i64::from_le_bytes(r.array()?);
+            let frac = f64::from_le_bytes(r.array()?);
+            if !(0.0..1.0).contains(&frac) {
+                return Err(PmtCodecError::Invalid(format!("fractional seconds {frac}")));
+            }
+            Pmt::Time(TimeSpec::new(secs, frac))
+        }
+        TAG_VEC_U64 => {
+            let n = r.len(1)?;
+            let mut v = Vec::with_capacity(n);
+            for _ in 0..n {
+                v.push(r.varint()?);
+            }
+            Pmt::VecU64(v)
+        }
+        TAG_BLOB => {
+            let n = r.len(1)?;
+            Pmt::Blob(r.bytes(n)?.to_vec())
+        }
+        TAG_VEC_PMT => {
+            let n = r.len(1)?;
+            let mut v = Vec::with_capacity(n);
+            for _ in 0..n {
+                v.push(decode(r, depth + 1)?);
+            }
+            Pmt::VecPmt(v)
+        }
+        TAG_MAP_STR_PMT => {
+            let n = r.len(2)?;
+            let mut m = HashMap::with_capacity(n);
+            for _ in 0..n {
+                let k = r.str()?;
+                let v = decode(r, depth + 1)?;
+                m.insert(k, v);
+            }
+            Pmt::MapStrPmt(m)
+        }
+        t => return Err(PmtCodecError::Tag(t)),
+    })
+}
+
+fn cf32_bytes(c: &Complex32) -> [u8; 8] {
+    let mut b = [0; 8];
+    b[..4].copy_from_slice(&c.re.to_le_bytes());
+    b[4..].copy_from_slice(&c.im.to_le_bytes());
+    b
+}
+
+fn cf32_from_bytes(b: [u8; 8]) -> Complex32 {
+    Complex32::new(
+        f32::from_le_bytes(b[..4].try_into().unwrap()),
+        f32::from_le_bytes(b[4..].try_into().unwrap()),
+    )
+}
+
+fn cf64_bytes(c: &Complex64) -> [u8; 16] {
+    let mut b = [0; 16];
+    b[..8].copy_from_slice(&c.re.to_le_bytes());
+    b[8..].copy_from_slice(&c.im.to_le_bytes());
+    b
+}
+
+fn cf64_from_bytes(b: [u8; 16]) -> Complex64 {
+    Complex64::new(
+        f64::from_le_bytes(b[..8].try_into().unwrap()),
+        f64::from_le_bytes(b[8..].try_into().unwrap()),
+    )
+}
+
+/// Cursor over the input of a decoder.
+pub(crate) struct Reader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(buf: &'a [u8]) -> Self {
+        Self { buf }
+    }
+
+    pub(crate) fn bytes(&mut self, n: usize) -> Result<&'a [u8], PmtCodecError> {
+        if n > self.buf.len() {
+            return Err(PmtCodecError::Eof);
+        }
+        let (head, tail) = self.buf.split_at(n);
+        self.buf = tail;
+        Ok(head)
+    }
+
+    pub(crate) fn array<const N: usize>(&mut self) -> Result<[u8; N], PmtCodecError> {
+        Ok(self.bytes(N)?.try_into().unwrap())
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, PmtCodecError> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    pub(crate) fn peek(&self) -> Result<u8, PmtCodecError> {
+        self.buf.first().copied().ok_or(PmtCodecError::Eof)
+    }
+
+    pub(crate) fn remaining(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub(crate) fn finish(&self) -> Result<(), PmtCodecError> {
+        match self.buf.len() {
+            0 => Ok(()),
+            n => Err(PmtCodecError::Trailing(n)),
+        }
+    }
+
+    fn varint(&mut self) -> Result<u64, PmtCodecError> {
+        let mut v = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = self.u8()?;
+            v |= ((b & 0x7f) as u64) << shift;
+            if b & 0x80 == 0 {
+                return Ok(v);
+            }
+        }
+        Err(PmtCodecError::Invalid("varint too long".to_string()))
+    }
+
+    fn int<T: TryFrom<u64>>(&mut self, name: &str) -> Result<T, PmtCodecError> {
+        let v = self.varint()?;
+        T::try_from(v).map_err(|_| PmtCodecError::Invalid(format!("{name} value {v}")))
+    }
+
+    // length of a sequence, checked against the remaining input to avoid huge allocations
+    fn len(&mut self, min_item_size: usize) -> Result<usize, PmtCodecError> {
+        let n: usize = self.int("length")?;
+        if n.saturating_mul(min_item_size) > self.remaining() {
+            return Err(PmtCodecError::Eof);
+        }
+        Ok(n)
+    }
+
+    // vector of fixed-size elements
+    fn vec<T, const N: usize>(
+        &mut self,
+        f: impl Fn([u8; N]) -> T,
+    ) -> Result<Vec<T>, PmtCodecError> {
+        let n = self.len(N)?;
+        let mut v = Vec::with_capacity(n);
+        for _ in 0..n {
+            v.push(f(self.array()?));
+        }
+        Ok(v)
+    }
+
+    fn str(&mut self) -> Result<String, PmtCodecError> {
+        let n = self.len(1)?;
+        String::from_utf8(self.bytes(n)?.to_vec())
+            .map_err(|_| PmtCodecError::Invalid("string is not valid UTF-8".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let pmts = vec![
+            Pmt::Ok,
+            Pmt::InvalidValue,
+            Pmt::Null,
+            Pmt::Finished,
+            Pmt::String("foo".to_string()),
+            Pmt::Bool(true),
+            Pmt::Usize(300),
+            Pmt::Isize(-123456),
+            Pmt::Isize(isize::MIN),
+            Pmt::U32(u32::MAX),
+            Pmt::U64(u64::MAX),
+            Pmt::F32(1.5),
+            Pmt::F64(-2.25e100),
+            Pmt::CF32(Complex32::new(1.0, -1.0)),
+            Pmt::CF64(Complex64::new(-1.0, 1.0)),
+            Pmt::VecCF32(vec![Complex32::new(1.0, -1.0)]),
+            Pmt::VecCF64(vec![Complex64::new(1.0, -1.0)]),
+            Pmt::VecF64(vec![1.0, -2.0]),
+            Pmt::VecI8(vec![-128, 127]),
+            Pmt::VecI16(vec![-1, 1]),
+            Pmt::VecI32(vec![i32::MIN]),
+            Pmt::VecI64(vec![i64::MAX]),
+            Pmt::VecU16(vec![u16::MAX]),
+            Pmt::Time(TimeSpec::new(-5, 0.125)),
+            Pmt::VecF32(vec![1.0, 2.0]),
+            Pmt::VecU64(vec![0, 1 << 40]),
+            Pmt::Blob(vec![1, 2, 3]),
+            Pmt::VecPmt(vec![Pmt::Null, Pmt::U32(1)]),
+            Pmt::MapStrPmt(HashMap::from([
+                ("a".to_string(), Pmt::VecPmt(vec![])),
+                ("b".to_string(), Pmt::String("é".to_string())),
+            ])),
+        ];
+        for p in pmts {
+            let b = p.to_bytes().unwrap();
+            assert_eq!(Pmt::from_bytes(&b), Ok(p));
+        }
+    }
+
+    #[test]
+    fn compact() {
+        assert_eq!(Pmt::U32(5).to_bytes().unwrap(), vec![VERSION, TAG_U32, 5]);
+        assert_eq!(
+            Pmt::Usize(300).to_bytes().unwrap(),
+            vec![VERSION, TAG_USIZE, 0xac, 0x02]
+        );
+        assert_eq!(
+            Pmt::Isize(-1).to_bytes().unwrap(),
+            vec![VERSION, TAG_ISIZE, 1]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Pmt::Any(Box::new(1u8)).to_bytes(),
+            Err(PmtCodecError::Unsupported(PmtKind::Any))
+        );
+        assert_eq!(Pmt::from_bytes(&[]), Err(PmtCodecError::Eof));
+        assert_eq!(
+            Pmt::from_bytes(&[7, TAG_NULL]),
+            Err(PmtCodecError::Version(7))
+        );
+        assert_eq!(
+            Pmt::from_bytes(&[VERSION, 0xff]),
+            Err(PmtCodecError::Tag(0xff))
+        );
+        assert_eq!(
+            Pmt::from_bytes(&[VERSION, TAG_NULL, 0]),
+            Err(PmtCodecError::Trailing(1))
+        );
+        assert_eq!(
+            Pmt::from_bytes(&[VERSION, TAG_BLOB, 0xff, 0xff, 0xff, 0x0f]),
+            Err(PmtCodecError::Eof)
+        );
+        assert!(matches!(
+            Pmt::from_bytes(&[VERSION, TAG_U32, 0xff, 0xff, 0xff, 0xff, 0x1f]),
+            Err(PmtCodecError::Invalid(_))
+        ));
+
+        let mut deep = vec![VERSION];
+        for _ in 0..=MAX_DEPTH {
+            deep.extend_from_slice(&[TAG_VEC_PMT, 1]);
+        }
+        deep.push(TAG_NULL);
+        assert_eq!(Pmt::from_bytes(&deep), Err(PmtCodecError::Depth));
+    }
+}
@@ -60,6 +60,10 @@ impl Tracker {
     }
 
     /// This function handles control port messages.
+    ///
+    /// The register is returned as JSON string rather than a derived `Pmt`, since it is read
+    /// directly by the web map (`dist/script.js`) and holds `SystemTime`s and ICAO addresses,
+    /// which have no `Pmt` mapping.
     #[message_handler]
     async fn handle_ctrl_port(
         &mut self,
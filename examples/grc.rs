@@ -0,0 +1,32 @@
+use anyhow::Result;
+use futuresdr::blocks;
+use futuresdr::runtime::BlockRegistry;
+use futuresdr::runtime::Grc;
+use futuresdr::runtime::Runtime;
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+
+    let (file, rust) = match args.as_slice() {
+        [_, file] => (file, false),
+        [_, file, flag] if flag == "--rust" => (file, true),
+        _ => {
+            println!("usage: grc <flowgraph.grc> [--rust]");
+            return Ok(());
+        }
+    };
+
+    let grc = Grc::from_file(file)?;
+    eprint!("{}", grc.report());
+
+    if rust {
+        print!("{}", grc.to_rust()?);
+        return Ok(());
+    }
+
+    let mut registry = BlockRegistry::new();
+    blocks::register(&mut registry);
+    Runtime::new().run(grc.to_flowgraph(&registry)?)?;
+
+    Ok(())
+}
@@ -28,6 +28,7 @@ use std::time::SystemTime;
 use std::time::UNIX_EPOCH;
 
 use crate::utils::*;
+use crate::HeaderInfo;
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 enum DecoderState {
@@ -643,37 +644,23 @@ impl FrameSync {
         p: Pmt,
     ) -> Result<Pmt> {
         if let Pmt::MapStrPmt(mut frame_info) = p {
-            let m_cr: usize = if let Pmt::Usize(temp) = frame_info.get("cr").unwrap() {
-                *temp
-            } else {
-                panic!("invalid cr")
-            };
-            let m_pay_len: usize = if let Pmt::Usize(temp) = frame_info.get("pay_len").unwrap() {
-                *temp
-            } else {
-                panic!("invalid pay_len")
-            };
-            let m_has_crc: bool = if let Pmt::Bool(temp) = frame_info.get("crc").unwrap() {
-                *temp
-            } else {
-                panic!("invalid m_has_crc")
+            let info = match HeaderInfo::try_from(Pmt::MapStrPmt(frame_info.clone())) {
+                Ok(info) => info,
+                Err(e) => {
+                    warn!("invalid frame_info: {e}");
+                    return Ok(Pmt::InvalidValue);
+                }
             };
+            let m_cr = info.cr;
+            let m_pay_len = info.pay_len;
+            let m_has_crc = info.crc;
             // uint8_t
-            let ldro_mode_tmp: LdroMode =
-                if let Pmt::Bool(temp) = frame_info.get("ldro_mode").unwrap() {
-                    if *temp {
-                        LdroMode::ENABLE
-                    } else {
-                        LdroMode::DISABLE
-                    }
-                } else {
-                    panic!("invalid ldro_mode")
-                };
-            let m_invalid_header = if let Pmt::Bool(temp) = frame_info.get("err").unwrap() {
-                *temp
+            let ldro_mode_tmp = if info.ldro_mode {
+                LdroMode::ENABLE
             } else {
-                panic!("invalid err flag")
+                LdroMode::DISABLE
             };
+            let m_invalid_header = info.err;
 
             debug!(
                 "FrameSync: received header info: invalid header {m_invalid_header}, sf{}",
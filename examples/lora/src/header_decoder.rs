@@ -1,4 +1,6 @@
 use futuresdr::macros::async_trait;
+use futuresdr::macros::FromPmt;
+use futuresdr::macros::IntoPmt;
 use futuresdr::runtime::BlockMeta;
 use futuresdr::runtime::BlockMetaBuilder;
 use futuresdr::runtime::ItemTag;
@@ -47,6 +49,16 @@ pub enum HeaderMode {
     },
 }
 
+/// Header information, posted on the `frame_info` port
+#[derive(Debug, Clone, Copy, IntoPmt, FromPmt)]
+pub struct HeaderInfo {
+    pub cr: usize,
+    pub pay_len: usize,
+    pub crc: bool,
+    pub ldro_mode: bool,
+    pub err: bool,
+}
+
 const HEADER_LEN: usize = 5; // size of the header in nibbles
 
 pub struct HeaderDecoder {
@@ -82,16 +94,14 @@ impl HeaderDecoder {
         ldro_mode: bool,
         err: bool,
     ) {
-        let mut header_content: HashMap<String, Pmt> = HashMap::new();
-
-        header_content.insert("cr".to_string(), Pmt::Usize(cr));
-        header_content.insert("pay_len".to_string(), Pmt::Usize(pay_len));
-        header_content.insert("crc".to_string(), Pmt::Bool(crc));
-        header_content.insert("ldro_mode".to_string(), Pmt::Bool(ldro_mode));
-        header_content.insert("err".to_string(), Pmt::Bool(err));
-        mio.output_mut(1)
-            .post(Pmt::MapStrPmt(header_content.clone()))
-            .await;
+        let info = HeaderInfo {
+            cr,
+            pay_len,
+            crc,
+            ldro_mode,
+            err,
+        };
+        mio.output_mut(1).post(Pmt::from(info)).await;
     }
 }
 
@@ -0,0 +1,42 @@
+//! Load the plugin and use its block in a flowgraph description
+//!
+//! cargo build && cargo run --bin host
+use anyhow::Result;
+use futuresdr::blocks;
+use futuresdr::blocks::VectorSink;
+use futuresdr::blocks::VectorSource;
+use futuresdr::runtime::plugin;
+use futuresdr::runtime::Block;
+use futuresdr::runtime::BlockRegistry;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::Runtime;
+
+fn main() -> Result<()> {
+    // Cargo puts the plugin library next to the binary.
+    let dir = std::env::current_exe()?.parent().unwrap().to_path_buf();
+    let path = dir.join(format!(
+        "{}plugin{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_SUFFIX
+    ));
+
+    let mut registry = BlockRegistry::new();
+    blocks::register(&mut registry);
+    let p = unsafe { plugin::load(&path, &mut registry)? };
+    println!("loaded {} {}", p.name, p.version);
+
+    let mut fg = Flowgraph::new();
+    let mut src: Block = VectorSource::<f32>::new(vec![1.0, 2.0, 3.0]).into();
+    src.set_instance_name("src");
+    let mut snk: Block = VectorSink::<f32>::new(3).into();
+    snk.set_instance_name("snk");
+    fg.add_block(src)?;
+    let snk = fg.add_block(snk)?;
+    fg.connect_str(&registry, "src > Scale(factor = 2.0) > snk")?;
+
+    let fg = Runtime::new().run(fg)?;
+    let items = fg.kernel::<VectorSink<f32>>(snk).unwrap().items();
+    println!("{items:?}");
+
+    Ok(())
+}
@@ -0,0 +1,13 @@
+//! Block library that is loaded at runtime
+use futuresdr::blocks::Apply;
+use futuresdr::runtime::registry::arg;
+use futuresdr::runtime::BlockRegistry;
+
+fn register(registry: &mut BlockRegistry) {
+    registry.register("Scale", |args| {
+        let factor = arg::<f64>(args, "factor")? as f32;
+        Ok(Apply::new(move |x: &f32| x * factor).into())
+    });
+}
+
+futuresdr::export_plugin!(register);
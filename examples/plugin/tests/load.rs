@@ -0,0 +1,34 @@
+use futuresdr::blocks;
+use futuresdr::runtime::plugin;
+use futuresdr::runtime::BlockRegistry;
+use futuresdr::runtime::Error;
+use std::path::PathBuf;
+
+/// Path of the plugin library, built with the given profile
+fn library(profile: &str) -> PathBuf {
+    // The test binary is in <target>/<profile>/deps.
+    let exe = std::env::current_exe().unwrap();
+    let target = exe.parent().unwrap().parent().unwrap().parent().unwrap();
+    target.join(profile).join(format!(
+        "{}plugin{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_SUFFIX
+    ))
+}
+
+#[test]
+#[ignore = "requires the plugin library, built with `cargo build --lib` and `cargo build --lib --release`"]
+fn load() {
+    let mut registry = BlockRegistry::new();
+    blocks::register(&mut registry);
+
+    let p = unsafe { plugin::load(library("debug"), &mut registry) }.unwrap();
+    assert_eq!(p.name, "plugin");
+    assert!(registry.type_names().any(|t| t == "Scale"));
+
+    // Same sources and features, but a different profile.
+    let mut registry = BlockRegistry::new();
+    let e = unsafe { plugin::load(library("release"), &mut registry) }.unwrap_err();
+    assert!(matches!(e, Error::PluginError(_, ref m) if m.contains("fingerprint")));
+    assert_eq!(registry.type_names().count(), 0);
+}
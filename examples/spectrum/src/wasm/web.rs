@@ -275,7 +275,7 @@ async fn run(
     let keep = MovingAvg::<FFT_SIZE>::new(0.1, 3);
     let snk = Sink::new(set_time_data, set_waterfall_data);
 
-    futuresdr::runtime::config::set("slab_reserved", 0);
+    futuresdr::runtime::config::set("slab_reserved", 0).unwrap();
     connect!(fg, src > fft > mag_sqr > keep > snk);
 
     let rt = Runtime::new();
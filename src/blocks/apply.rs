@@ -1,11 +1,11 @@
 use crate::runtime::BlockMeta;
 use crate::runtime::BlockMetaBuilder;
-use crate::runtime::Kernel;
 use crate::runtime::MessageIo;
 use crate::runtime::MessageIoBuilder;
 use crate::runtime::Result;
 use crate::runtime::StreamIo;
 use crate::runtime::StreamIoBuilder;
+use crate::runtime::SyncKernel;
 use crate::runtime::TypedBlock;
 use crate::runtime::WorkIo;
 
@@ -85,14 +85,13 @@ where
 }
 
 #[doc(hidden)]
-#[async_trait]
-impl<F, A, B> Kernel for Apply<F, A, B>
+impl<F, A, B> SyncKernel for Apply<F, A, B>
 where
     F: FnMut(&A) -> B + Send + 'static,
     A: Send + 'static,
     B: Send + 'static,
 {
-    async fn work(
+    fn work(
         &mut self,
         io: &mut WorkIo,
         sio: &mut StreamIo,
@@ -8,6 +8,8 @@ use crate::runtime::MessageIoBuilder;
 use crate::runtime::Result;
 use crate::runtime::StreamIo;
 use crate::runtime::StreamIoBuilder;
+use crate::runtime::Tag;
+use crate::runtime::TimeSpec;
 use crate::runtime::TypedBlock;
 use crate::runtime::WorkIo;
 
@@ -25,6 +27,11 @@ use crate::runtime::WorkIo;
 ///
 /// `out`: Output samples
 ///
+/// # Tags
+///
+/// If created with [`FileSource::with_start_time`], the first sample, and the first sample after
+/// each restart of the file when repeating, is tagged with an [`rx_time`](Tag::RX_TIME) tag.
+///
 /// # Usage
 /// ```no_run
 /// use futuresdr::blocks::FileSource;
@@ -41,20 +48,37 @@ pub struct FileSource<T: Send + 'static> {
     file_name: String,
     file: Option<async_fs::File>,
     repeat: bool,
+    start_time: Option<TimeSpec>,
+    tag_time: bool,
     _type: std::marker::PhantomData<T>,
 }
 
 impl<T: Send + 'static> FileSource<T> {
     /// Create FileSource block
     pub fn new<S: Into<String>>(file_name: S, repeat: bool) -> TypedBlock<Self> {
+        Self::create(file_name.into(), repeat, None)
+    }
+
+    /// Create FileSource block for a recording that started at the given time
+    pub fn with_start_time<S: Into<String>>(
+        file_name: S,
+        repeat: bool,
+        start_time: TimeSpec,
+    ) -> TypedBlock<Self> {
+        Self::create(file_name.into(), repeat, Some(start_time))
+    }
+
+    fn create(file_name: String, repeat: bool, start_time: Option<TimeSpec>) -> TypedBlock<Self> {
         TypedBlock::new(
             BlockMetaBuilder::new("FileSource").build(),
             StreamIoBuilder::new().add_output::<T>("out").build(),
             MessageIoBuilder::new().build(),
             FileSource::<T> {
-                file_name: file_name.into(),
+                file_name,
                 file: None,
                 repeat,
+                start_time,
+                tag_time: start_time.is_some(),
                 _type: std::marker::PhantomData,
             },
         )
@@ -84,12 +108,21 @@ impl<T: Send + 'static> Kernel for FileSource<T> {
                     if self.repeat {
                         self.file =
                             Some(async_fs::File::open(self.file_name.clone()).await.unwrap());
+                        if let Some(t) = self.start_time {
+                            sio.output(0).add_tag(i / item_size, Tag::rx_time(t));
+                        }
                     } else {
                         io.finished = true;
                         break;
                     }
                 }
                 Ok(written) => {
+                    if self.tag_time {
+                        if let Some(t) = self.start_time {
+                            sio.output(0).add_tag(0, Tag::rx_time(t));
+                        }
+                        self.tag_time = false;
+                    }
                     i += written;
                 }
                 Err(e) => panic!("FileSource: Error reading from file: {e:?}"),
@@ -1,11 +1,11 @@
 use crate::runtime::BlockMeta;
 use crate::runtime::BlockMetaBuilder;
-use crate::runtime::Kernel;
 use crate::runtime::MessageIo;
 use crate::runtime::MessageIoBuilder;
 use crate::runtime::Result;
 use crate::runtime::StreamIo;
 use crate::runtime::StreamIoBuilder;
+use crate::runtime::SyncKernel;
 use crate::runtime::TypedBlock;
 use crate::runtime::WorkIo;
 
@@ -52,9 +52,8 @@ impl<T: Copy + Send + 'static> Head<T> {
 }
 
 #[doc(hidden)]
-#[async_trait]
-impl<T: Copy + Send + 'static> Kernel for Head<T> {
-    async fn work(
+impl<T: Copy + Send + 'static> SyncKernel for Head<T> {
+    fn work(
         &mut self,
         io: &mut WorkIo,
         sio: &mut StreamIo,
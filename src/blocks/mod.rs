@@ -194,6 +194,8 @@ mod pfb;
 pub use pfb::arb_resampler::PfbArbResampler;
 pub use pfb::channelizer::PfbChannelizer;
 pub use pfb::synthesizer::PfbSynthesizer;
+mod registry;
+pub use registry::register;
 /// Seify hardware driver blocks
 #[cfg(feature = "seify")]
 pub mod seify;
@@ -1,11 +1,11 @@
 use crate::runtime::BlockMeta;
 use crate::runtime::BlockMetaBuilder;
-use crate::runtime::Kernel;
 use crate::runtime::MessageIo;
 use crate::runtime::MessageIoBuilder;
 use crate::runtime::Result;
 use crate::runtime::StreamIo;
 use crate::runtime::StreamIoBuilder;
+use crate::runtime::SyncKernel;
 use crate::runtime::TypedBlock;
 use crate::runtime::WorkIo;
 
@@ -54,9 +54,8 @@ impl<T: Send + 'static> NullSink<T> {
 }
 
 #[doc(hidden)]
-#[async_trait]
-impl<T: Send + 'static> Kernel for NullSink<T> {
-    async fn work(
+impl<T: Send + 'static> SyncKernel for NullSink<T> {
+    fn work(
         &mut self,
         io: &mut WorkIo,
         sio: &mut StreamIo,
@@ -1,11 +1,11 @@
 use crate::runtime::BlockMeta;
 use crate::runtime::BlockMetaBuilder;
-use crate::runtime::Kernel;
 use crate::runtime::MessageIo;
 use crate::runtime::MessageIoBuilder;
 use crate::runtime::Result;
 use crate::runtime::StreamIo;
 use crate::runtime::StreamIoBuilder;
+use crate::runtime::SyncKernel;
 use crate::runtime::TypedBlock;
 use crate::runtime::WorkIo;
 
@@ -48,9 +48,8 @@ impl<T: Send + 'static> NullSource<T> {
 }
 
 #[doc(hidden)]
-#[async_trait]
-impl<T: Send + 'static> Kernel for NullSource<T> {
-    async fn work(
+impl<T: Send + 'static> SyncKernel for NullSource<T> {
+    fn work(
         &mut self,
         _io: &mut WorkIo,
         sio: &mut StreamIo,
@@ -0,0 +1,176 @@
+use num_complex::Complex32;
+use std::time::Duration;
+
+use crate::blocks::Copy;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::blocks::FileSink;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::blocks::FileSource;
+use crate::blocks::FirBuilder;
+use crate::blocks::Head;
+use crate::blocks::MessageCopy;
+use crate::blocks::MessageSink;
+use crate::blocks::MessageSource;
+use crate::blocks::NullSink;
+use crate::blocks::NullSource;
+use crate::blocks::SignalSourceBuilder;
+use crate::blocks::Throttle;
+use crate::runtime::registry::arg;
+use crate::runtime::registry::arg_or;
+use crate::runtime::Block;
+use crate::runtime::BlockArgs;
+use crate::runtime::BlockRegistry;
+use crate::runtime::Error;
+use crate::runtime::Pmt;
+
+/// Dispatch on the `type` argument (defaults to `f32`), calling a generic constructor with the
+/// corresponding item type.
+macro_rules! typed {
+    ($args:expr, $f:ident) => {{
+        let args = $args;
+        match arg_or(args, "type", "f32".to_string())?.as_str() {
+            "u8" => $f::<u8>(args),
+            "u16" => $f::<u16>(args),
+            "u32" => $f::<u32>(args),
+            "u64" => $f::<u64>(args),
+            "i8" => $f::<i8>(args),
+            "i16" => $f::<i16>(args),
+            "i32" => $f::<i32>(args),
+            "i64" => $f::<i64>(args),
+            "f32" => $f::<f32>(args),
+            "f64" => $f::<f64>(args),
+            "c32" => $f::<Complex32>(args),
+            t => Err(Error::ValidationError(format!(
+                "unsupported item type '{t}'"
+            ))),
+        }
+    }};
+}
+
+/// Register constructors for core blocks
+///
+/// Stream blocks take an optional `type` argument, selecting the item type (`u8`, `u16`, `u32`,
+/// `u64`, `i8`, `i16`, `i32`, `i64`, `f32`, `f64`, or `c32`), which defaults to `f32`.
+///
+/// | Type | Arguments |
+/// |---|---|
+/// | `Copy` | |
+/// | `FileSink` | `file` |
+/// | `FileSource` | `file`, `repeat` (default `false`) |
+/// | `Fir` | `taps` (`VecF32`), `decim` (default `1`); `type` is `f32` or `c32` |
+/// | `Head` | `n` |
+/// | `MessageCopy` | |
+/// | `MessageSink` | |
+/// | `MessageSource` | `message`, `interval` (in seconds), `n` (optional) |
+/// | `NullSink` | |
+/// | `NullSource` | |
+/// | `SignalSource` | `waveform` (`cos`, `sin`, or `square`), `frequency`, `sample_rate`, `amplitude` (default `1`), `offset` (default `0`), `phase` (default `0`); `type` is `f32` or `c32` |
+/// | `Throttle` | `rate` |
+pub fn register(registry: &mut BlockRegistry) {
+    registry.register("Copy", |a| typed!(a, copy));
+    #[cfg(not(target_arch = "wasm32"))]
+    registry.register("FileSink", |a| typed!(a, file_sink));
+    #[cfg(not(target_arch = "wasm32"))]
+    registry.register("FileSource", |a| typed!(a, file_source));
+    registry.register("Fir", fir);
+    registry.register("Head", |a| typed!(a, head));
+    registry.register("MessageCopy", |_| Ok(MessageCopy::new().into()));
+    registry.register("MessageSink", |_| Ok(MessageSink::new().into()));
+    registry.register("MessageSource", |a| {
+        let message = a.get("message").cloned().unwrap_or(Pmt::Null);
+        let interval = Duration::from_secs_f64(arg(a, "interval")?);
+        let n = if a.contains_key("n") {
+            Some(arg(a, "n")?)
+        } else {
+            None
+        };
+        Ok(MessageSource::new(message, interval, n).into())
+    });
+    registry.register("NullSink", |a| typed!(a, null_sink));
+    registry.register("NullSource", |a| typed!(a, null_source));
+    registry.register("SignalSource", signal_source);
+    registry.register("Throttle", |a| typed!(a, throttle));
+}
+
+fn copy<T: core::marker::Copy + Send + 'static>(_args: &BlockArgs) -> Result<Block, Error> {
+    Ok(Copy::<T>::new().into())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn file_sink<T: Send + 'static>(args: &BlockArgs) -> Result<Block, Error> {
+    Ok(FileSink::<T>::new(arg::<String>(args, "file")?).into())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn file_source<T: Send + 'static>(args: &BlockArgs) -> Result<Block, Error> {
+    Ok(FileSource::<T>::new(arg::<String>(args, "file")?, arg_or(args, "repeat", false)?).into())
+}
+
+fn fir(args: &BlockArgs) -> Result<Block, Error> {
+    let taps: Vec<f32> = arg(args, "taps")?;
+    let decim = arg_or(args, "decim", 1usize)?;
+    match (arg_or(args, "type", "f32".to_string())?.as_str(), decim) {
+        ("f32", 1) => Ok(FirBuilder::new::<f32, f32, _>(taps).into()),
+        ("f32", d) => Ok(FirBuilder::decimating_with_taps::<f32, f32, _>(d, taps).into()),
+        ("c32", 1) => Ok(FirBuilder::new::<Complex32, Complex32, _>(taps).into()),
+        ("c32", d) => {
+            Ok(FirBuilder::decimating_with_taps::<Complex32, Complex32, _>(d, taps).into())
+        }
+        (t, _) => Err(Error::ValidationError(format!(
+            "unsupported item type '{t}'"
+        ))),
+    }
+}
+
+fn head<T: core::marker::Copy + Send + 'static>(args: &BlockArgs) -> Result<Block, Error> {
+    Ok(Head::<T>::new(arg(args, "n")?).into())
+}
+
+fn null_sink<T: Send + 'static>(_args: &BlockArgs) -> Result<Block, Error> {
+    Ok(NullSink::<T>::new().into())
+}
+
+fn null_source<T: Send + 'static>(_args: &BlockArgs) -> Result<Block, Error> {
+    Ok(NullSource::<T>::new().into())
+}
+
+fn signal_source(args: &BlockArgs) -> Result<Block, Error> {
+    let waveform = arg_or(args, "waveform", "cos".to_string())?;
+    let frequency = arg::<f64>(args, "frequency")? as f32;
+    let sample_rate = arg::<f64>(args, "sample_rate")? as f32;
+    let amplitude = arg_or(args, "amplitude", 1.0f64)? as f32;
+    let offset = arg_or(args, "offset", 0.0f64)? as f32;
+    let phase = arg_or(args, "phase", 0.0f64)? as f32;
+
+    macro_rules! build {
+        ($t:ty, $a:expr, $o:expr) => {{
+            let b = match waveform.as_str() {
+                "cos" => SignalSourceBuilder::<$t>::cos(frequency, sample_rate),
+                "sin" => SignalSourceBuilder::<$t>::sin(frequency, sample_rate),
+                "square" => SignalSourceBuilder::<$t>::square(frequency, sample_rate),
+                w => {
+                    return Err(Error::ValidationError(format!(
+                        "unsupported waveform '{w}'"
+                    )))
+                }
+            };
+            Ok(b.amplitude($a).offset($o).initial_phase(phase).build())
+        }};
+    }
+
+    match arg_or(args, "type", "f32".to_string())?.as_str() {
+        "f32" => build!(f32, amplitude, offset),
+        "c32" => build!(
+            Complex32,
+            Complex32::new(amplitude, 0.0),
+            Complex32::new(offset, 0.0)
+        ),
+        t => Err(Error::ValidationError(format!(
+            "unsupported item type '{t}'"
+        ))),
+    }
+}
+
+fn throttle<T: core::marker::Copy + Send + 'static>(args: &BlockArgs) -> Result<Block, Error> {
+    Ok(Throttle::<T>::new(arg(args, "rate")?).into())
+}
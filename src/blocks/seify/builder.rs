@@ -8,6 +8,7 @@ use crate::blocks::seify::Sink;
 use crate::blocks::seify::Source;
 use crate::runtime::Block;
 use crate::runtime::Error;
+use crate::runtime::TimeSpec;
 use crate::runtime::TypedBlock;
 
 pub enum BuilderType {
@@ -37,7 +38,7 @@ pub struct Builder<D: DeviceTrait + Clone> {
     channels: Vec<usize>,
     config: Config,
     dev: Option<Device<D>>,
-    start_time: Option<i64>,
+    start_time: Option<TimeSpec>,
     builder_type: BuilderType,
 }
 
@@ -106,8 +107,14 @@ impl<D: DeviceTrait + Clone> Builder<D> {
         self
     }
     /// Start Time
-    pub fn start_time(mut self, s: i64) -> Self {
-        self.start_time = Some(s);
+    ///
+    /// Time of the device clock in nanoseconds, at which streaming starts.
+    pub fn start_time(self, ns: i64) -> Self {
+        self.start_at(TimeSpec::from_nanos(ns))
+    }
+    /// Start at the given time of the device clock
+    pub fn start_at(mut self, t: TimeSpec) -> Self {
+        self.start_time = Some(t);
         self
     }
     /// Build Typed Seify Source
@@ -1,13 +1,17 @@
 use seify::Device;
 use seify::DeviceTrait;
 use seify::Direction;
-use std::collections::HashMap;
 
 use crate::runtime::Error;
+use crate::runtime::IntoPmt;
 use crate::runtime::Pmt;
 
 /// Seify Config
-#[derive(Debug, Default, Clone)]
+///
+/// Converts to and from a [`Pmt::MapStrPmt`] with the field names as keys. Unset
+/// fields map to [`Pmt::Null`] and may be missing when converting back. A [`Pmt::Any`]
+/// with a `Config`, as created by earlier versions of [`Config::to_pmt`], is accepted as well.
+#[derive(Debug, Default, Clone, IntoPmt)]
 pub struct Config {
     /// Antenna
     pub antenna: Option<String>,
@@ -29,28 +33,14 @@ impl Config {
 
     /// Generate a [`Pmt`] that can be used as a "cmd" port message
     pub fn to_pmt(&self) -> Pmt {
-        Pmt::Any(Box::new(self.clone()))
+        self.clone().into_pmt()
     }
 
     /// Generate a [`Pmt`] that can be serialized
+    ///
+    /// Same as [`Config::to_pmt`], which is serializable as well.
     pub fn to_serializable_pmt(&self) -> Pmt {
-        let mut m = HashMap::new();
-        if let Some(antenna) = &self.antenna {
-            m.insert("antenna".to_string(), Pmt::String(antenna.clone()));
-        }
-        if let Some(bandwidth) = &self.bandwidth {
-            m.insert("bandwidth".to_string(), Pmt::F64(*bandwidth));
-        }
-        if let Some(freq) = &self.freq {
-            m.insert("freq".to_string(), Pmt::F64(*freq));
-        }
-        if let Some(gain) = &self.gain {
-            m.insert("gain".to_string(), Pmt::F64(*gain));
-        }
-        if let Some(sample_rate) = &self.sample_rate {
-            m.insert("sample_rate".to_string(), Pmt::F64(*sample_rate));
-        }
-        Pmt::MapStrPmt(m)
+        self.to_pmt()
     }
 
     /// Apply config to a device
@@ -114,8 +104,10 @@ impl TryFrom<Pmt> for Config {
                 let mut cfg = Config::default();
                 for (n, v) in m.drain() {
                     match (n.as_str(), v) {
+                        // unset fields
+                        (_, Pmt::Null) => {}
                         ("antenna", Pmt::String(p)) => {
-                            cfg.antenna = Some(p.to_owned());
+                            cfg.antenna = Some(p);
                         }
                         ("bandwidth", p) => {
                             cfg.bandwidth = Some(p.try_into()?);
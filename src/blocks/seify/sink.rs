@@ -20,6 +20,7 @@ use crate::runtime::Result;
 use crate::runtime::StreamIo;
 use crate::runtime::StreamIoBuilder;
 use crate::runtime::Tag;
+use crate::runtime::TimeSpec;
 use crate::runtime::TypedBlock;
 use crate::runtime::WorkIo;
 
@@ -41,18 +42,23 @@ use super::builder::BuilderType;
 ///     - `"config"`: `u32`, `u64`, `usize` (channel id) returns the `Config` for the specified channel as a `Pmt::MapStrPmt`
 /// * Message outputs:
 ///     - `"terminate_out"`: `Pmt::Ok` when stream has finished
+///
+/// # Tags
+///
+/// Samples tagged with a [`tx_time`](Tag::TX_TIME) tag are transmitted at the given time.
+/// Samples following them are sent right after.
 pub struct Sink<D: DeviceTrait + Clone> {
     channels: Vec<usize>,
     dev: Device<D>,
     streamer: Option<D::TxStreamer>,
-    start_time: Option<i64>,
+    start_time: Option<TimeSpec>,
 }
 
 impl<D: DeviceTrait + Clone> Sink<D> {
     pub(super) fn new(
         dev: Device<D>,
         channels: Vec<usize>,
-        start_time: Option<i64>,
+        start_time: Option<TimeSpec>,
     ) -> TypedBlock<Self> {
         assert!(!channels.is_empty());
 
@@ -69,11 +75,19 @@ impl<D: DeviceTrait + Clone> Sink<D> {
             BlockMetaBuilder::new("Sink").blocking().build(),
             siob.build(),
             MessageIoBuilder::new()
-                .add_input("freq", Self::freq_handler)
-                .add_input("gain", Self::gain_handler)
-                .add_input("sample_rate", Self::sample_rate_handler)
-                .add_input("cmd", Self::cmd_handler)
-                .add_input("config", Self::get_config_handler)
+                .add_input_with_schema("freq", Self::freq_handler_schema(), Self::freq_handler)
+                .add_input_with_schema("gain", Self::gain_handler_schema(), Self::gain_handler)
+                .add_input_with_schema(
+                    "sample_rate",
+                    Self::sample_rate_handler_schema(),
+                    Self::sample_rate_handler,
+                )
+                .add_input_with_schema("cmd", Self::cmd_handler_schema(), Self::cmd_handler)
+                .add_input_with_schema(
+                    "config",
+                    Self::get_config_handler_schema(),
+                    Self::get_config_handler,
+                )
                 .add_output("terminate_out")
                 .build(),
             Self {
@@ -85,7 +99,11 @@ impl<D: DeviceTrait + Clone> Sink<D> {
         )
     }
 
-    #[message_handler]
+    #[message_handler(
+        accepts(MapStrPmt, Any),
+        returns(Ok),
+        description = "Config to apply to all channels at once"
+    )]
     fn cmd_handler(
         &mut self,
         _io: &mut WorkIo,
@@ -98,7 +116,11 @@ impl<D: DeviceTrait + Clone> Sink<D> {
         Ok(Pmt::Ok)
     }
 
-    #[message_handler]
+    #[message_handler(
+        accepts(F64, F32, U64, U32, Null),
+        returns(Ok, F64),
+        description = "Center frequency in Hz, or Null to query"
+    )]
     fn freq_handler(
         &mut self,
         _io: &mut WorkIo,
@@ -118,7 +140,11 @@ impl<D: DeviceTrait + Clone> Sink<D> {
         Ok(Pmt::Ok)
     }
 
-    #[message_handler]
+    #[message_handler(
+        accepts(F64, F32, U64, U32, Null),
+        returns(Ok, F64),
+        description = "Gain in dB, or Null to query"
+    )]
     fn gain_handler(
         &mut self,
         _io: &mut WorkIo,
@@ -138,7 +164,11 @@ impl<D: DeviceTrait + Clone> Sink<D> {
         Ok(Pmt::Ok)
     }
 
-    #[message_handler]
+    #[message_handler(
+        accepts(F64, F32, U64, U32, Null),
+        returns(Ok, F64),
+        description = "Sample rate in Hz, or Null to query"
+    )]
     fn sample_rate_handler(
         &mut self,
         _io: &mut WorkIo,
@@ -158,7 +188,11 @@ impl<D: DeviceTrait + Clone> Sink<D> {
         Ok(Pmt::Ok)
     }
 
-    #[message_handler]
+    #[message_handler(
+        accepts(Null, Ok, U32, U64, Usize),
+        returns(MapStrPmt),
+        description = "Config of the given channel"
+    )]
     fn get_config_handler(
         &mut self,
         _io: &mut WorkIo,
@@ -200,7 +234,25 @@ impl<D: DeviceTrait + Clone> Kernel for Sink<D> {
         let nitems_per_input_stream = bufs.iter().map(|b| b.len());
         let n = nitems_per_input_stream.clone().min().unwrap_or(0);
         let consumed = if n > 0 {
-            let t = sio.input(0).tags().iter().find_map(|x| match x {
+            let tags = sio.input(0).tags();
+            let at_ns = tags
+                .iter()
+                .filter(|x| x.index == 0)
+                .find_map(|x| x.tag.time(Tag::TX_TIME))
+                .map(|t| {
+                    t.checked_as_nanos().ok_or(Error::RuntimeError(format!(
+                        "Seify: tx_time {t} out of range"
+                    )))
+                })
+                .transpose()?;
+            // timed writes start at the sample with the tx_time tag
+            let n_untimed = tags
+                .iter()
+                .filter(|x| x.index > 0 && x.tag.time(Tag::TX_TIME).is_some())
+                .map(|x| x.index)
+                .fold(n, usize::min);
+
+            let t = tags.iter().find_map(|x| match x {
                 ItemTag {
                     index,
                     tag: Tag::NamedUsize(n, len),
@@ -218,7 +270,7 @@ impl<D: DeviceTrait + Clone> Kernel for Sink<D> {
                 if n >= len {
                     // send burst
                     let bufs: Vec<&[Complex32]> = bufs.iter().map(|b| &b[0..len]).collect();
-                    let ret = streamer.write(&bufs, None, true, 2_000_000)?;
+                    let ret = streamer.write(&bufs, at_ns, true, 2_000_000)?;
                     debug_assert_eq!(ret, len);
                     ret
                 } else {
@@ -227,7 +279,8 @@ impl<D: DeviceTrait + Clone> Kernel for Sink<D> {
                 }
             } else {
                 // send in non-burst mode
-                let ret = streamer.write(&bufs, None, false, 2_000_000)?;
+                let bufs: Vec<&[Complex32]> = bufs.iter().map(|b| &b[0..n_untimed]).collect();
+                let ret = streamer.write(&bufs, at_ns, false, 2_000_000)?;
                 if ret != n {
                     io.call_again = true;
                 }
@@ -269,11 +322,19 @@ impl<D: DeviceTrait + Clone> Kernel for Sink<D> {
         _mio: &mut MessageIo<Self>,
         _meta: &mut BlockMeta,
     ) -> Result<()> {
+        let start_ns = self
+            .start_time
+            .map(|t| {
+                t.checked_as_nanos().ok_or(Error::RuntimeError(
+                    "Seify: start time out of range".to_string(),
+                ))
+            })
+            .transpose()?;
         self.streamer = Some(self.dev.tx_streamer(&self.channels)?);
         self.streamer
             .as_mut()
             .ok_or(Error::RuntimeError("Seify: no streamer".to_string()))?
-            .activate_at(self.start_time)?;
+            .activate_at(start_ns)?;
 
         Ok(())
     }
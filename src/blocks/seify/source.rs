@@ -11,6 +11,8 @@ use crate::runtime::Pmt;
 use crate::runtime::Result;
 use crate::runtime::StreamIo;
 use crate::runtime::StreamIoBuilder;
+use crate::runtime::Tag;
+use crate::runtime::TimeSpec;
 use crate::runtime::TypedBlock;
 use crate::runtime::WorkIo;
 use anyhow::Context;
@@ -20,6 +22,8 @@ use seify::Direction::Rx;
 use seify::GenericDevice;
 use seify::RxStreamer;
 use std::time::Duration;
+use web_time::SystemTime;
+use web_time::UNIX_EPOCH;
 
 /// Seify Source block
 ///
@@ -37,11 +41,21 @@ use std::time::Duration;
 ///     - `"terminate"`: `Pmt::Ok` to terminate the block
 ///     - `"config"`: `u32`, `u64`, `usize` (channel id) returns the `Config` for the specified channel as a `Pmt::MapStrPmt`
 /// * Message outputs: None
+///
+/// # Tags
+///
+/// The first sample and the first sample after an overflow or a sample rate change are tagged
+/// with an [`rx_time`](Tag::RX_TIME) tag. Seify does not provide hardware timestamps, so the
+/// time is derived from the start time, if set, and the number of received samples. Without start
+/// time or after an overflow, it is estimated from the host clock.
 pub struct Source<D: DeviceTrait + Clone> {
     channels: Vec<usize>,
     dev: Device<D>,
     streamer: Option<D::RxStreamer>,
-    start_time: Option<i64>,
+    start_time: Option<TimeSpec>,
+    sample_rate: f64,
+    next_time: Option<TimeSpec>,
+    tag_time: bool,
     overflows: u64,
 }
 
@@ -49,7 +63,7 @@ impl<D: DeviceTrait + Clone> Source<D> {
     pub(super) fn new(
         dev: Device<D>,
         channels: Vec<usize>,
-        start_time: Option<i64>,
+        start_time: Option<TimeSpec>,
     ) -> TypedBlock<Self> {
         assert!(!channels.is_empty());
 
@@ -67,19 +81,38 @@ impl<D: DeviceTrait + Clone> Source<D> {
             BlockMetaBuilder::new("Source").blocking().build(),
             siob.build(),
             MessageIoBuilder::new()
-                .add_input("freq", Self::freq_handler)
-                .add_input("gain", Self::gain_handler)
-                .add_input("sample_rate", Self::sample_rate_handler)
-                .add_input("cmd", Self::cmd_handler)
-                .add_input("terminate", Self::terminate_handler)
-                .add_input("config", Self::get_config_handler)
-                .add_input("overflows", Self::overflows_handler)
+                .add_input_with_schema("freq", Self::freq_handler_schema(), Self::freq_handler)
+                .add_input_with_schema("gain", Self::gain_handler_schema(), Self::gain_handler)
+                .add_input_with_schema(
+                    "sample_rate",
+                    Self::sample_rate_handler_schema(),
+                    Self::sample_rate_handler,
+                )
+                .add_input_with_schema("cmd", Self::cmd_handler_schema(), Self::cmd_handler)
+                .add_input_with_schema(
+                    "terminate",
+                    Self::terminate_handler_schema(),
+                    Self::terminate_handler,
+                )
+                .add_input_with_schema(
+                    "config",
+                    Self::get_config_handler_schema(),
+                    Self::get_config_handler,
+                )
+                .add_input_with_schema(
+                    "overflows",
+                    Self::overflows_handler_schema(),
+                    Self::overflows_handler,
+                )
                 .build(),
             Source {
                 channels,
                 dev,
                 streamer: None,
                 start_time,
+                sample_rate: 0.0,
+                next_time: None,
+                tag_time: true,
                 overflows: 0,
             },
         )
@@ -90,7 +123,7 @@ impl<D: DeviceTrait + Clone> Source<D> {
         self.overflows
     }
 
-    #[message_handler]
+    #[message_handler(accepts(Ok), returns(Ok), description = "Terminate the block")]
     fn terminate_handler(
         &mut self,
         io: &mut WorkIo,
@@ -109,7 +142,11 @@ impl<D: DeviceTrait + Clone> Source<D> {
         Ok(Pmt::Ok)
     }
 
-    #[message_handler]
+    #[message_handler(
+        accepts(MapStrPmt, Any),
+        returns(Ok),
+        description = "Config to apply to all channels at once"
+    )]
     fn cmd_handler(
         &mut self,
         _io: &mut WorkIo,
@@ -119,10 +156,17 @@ impl<D: DeviceTrait + Clone> Source<D> {
     ) -> Result<Pmt> {
         let c: Config = p.try_into()?;
         c.apply(&self.dev, &self.channels, Rx)?;
+        if c.sample_rate.is_some() {
+            self.update_sample_rate()?;
+        }
         Ok(Pmt::Ok)
     }
 
-    #[message_handler]
+    #[message_handler(
+        accepts(F64, F32, U64, U32, Null),
+        returns(Ok, F64),
+        description = "Center frequency in Hz, or Null to query"
+    )]
     fn freq_handler(
         &mut self,
         _io: &mut WorkIo,
@@ -143,7 +187,11 @@ impl<D: DeviceTrait + Clone> Source<D> {
         Ok(Pmt::Ok)
     }
 
-    #[message_handler]
+    #[message_handler(
+        accepts(F64, F32, U64, U32, Null),
+        returns(Ok, F64),
+        description = "Gain in dB, or Null to query"
+    )]
     fn gain_handler(
         &mut self,
         _io: &mut WorkIo,
@@ -164,7 +212,11 @@ impl<D: DeviceTrait + Clone> Source<D> {
         Ok(Pmt::Ok)
     }
 
-    #[message_handler]
+    #[message_handler(
+        accepts(F64, F32, U64, U32, Null),
+        returns(Ok, F64),
+        description = "Sample rate in Hz, or Null to query"
+    )]
     fn sample_rate_handler(
         &mut self,
         _io: &mut WorkIo,
@@ -182,10 +234,22 @@ impl<D: DeviceTrait + Clone> Source<D> {
                 _ => return Ok(Pmt::InvalidValue),
             };
         }
+        self.update_sample_rate()?;
         Ok(Pmt::Ok)
     }
 
-    #[message_handler]
+    // sample times are only consistent with the new rate from the next sample on
+    fn update_sample_rate(&mut self) -> Result<()> {
+        self.sample_rate = self.dev.sample_rate(Rx, self.channels[0])?;
+        self.tag_time = true;
+        Ok(())
+    }
+
+    #[message_handler(
+        accepts(Null, Ok, U32, U64, Usize),
+        returns(MapStrPmt),
+        description = "Config of the given channel"
+    )]
     fn get_config_handler(
         &mut self,
         _io: &mut WorkIo,
@@ -206,7 +270,7 @@ impl<D: DeviceTrait + Clone> Source<D> {
         Ok(Config::from(&self.dev, Rx, id)?.to_serializable_pmt())
     }
 
-    #[message_handler]
+    #[message_handler(returns(U64), description = "Number of overflows")]
     fn overflows_handler(
         &mut self,
         _io: &mut WorkIo,
@@ -241,12 +305,31 @@ impl<D: DeviceTrait + Clone> Kernel for Source<D> {
 
         match streamer.read(&mut bufs, 1_000_000) {
             Ok(len) => {
-                for i in 0..outs.len() {
+                if len > 0 {
+                    let duration = TimeSpec::from_ticks(len as i64, self.sample_rate);
+                    let time = match self.next_time {
+                        Some(t) => t,
+                        None => {
+                            let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+                            TimeSpec::from(now) - duration
+                        }
+                    };
+                    if self.tag_time {
+                        for i in 0..sio.outputs().len() {
+                            sio.output(i).add_tag(0, Tag::rx_time(time));
+                        }
+                        self.tag_time = false;
+                    }
+                    self.next_time = Some(time + duration);
+                }
+                for i in 0..sio.outputs().len() {
                     sio.output(i).produce(len);
                 }
             }
             Err(seify::Error::Overflow) => {
                 self.overflows += 1;
+                self.next_time = None;
+                self.tag_time = true;
                 warn!("Seify Source Overflow");
             }
             Err(e) => {
@@ -265,11 +348,18 @@ impl<D: DeviceTrait + Clone> Kernel for Source<D> {
         _mio: &mut MessageIo<Self>,
         _meta: &mut BlockMeta,
     ) -> Result<()> {
+        self.sample_rate = self.dev.sample_rate(Rx, self.channels[0])?;
+        self.next_time = self.start_time;
+        self.tag_time = true;
+        let start_ns = self
+            .start_time
+            .map(|t| t.checked_as_nanos().context("start time out of range"))
+            .transpose()?;
         self.streamer = Some(self.dev.rx_streamer(&self.channels)?);
         self.streamer
             .as_mut()
             .context("no stream")?
-            .activate_at(self.start_time)?;
+            .activate_at(start_ns)?;
 
         Ok(())
     }
@@ -63,6 +63,10 @@ pub mod macros {
     pub use async_trait::async_trait as async_trait_orig;
 
     pub use futuresdr_macros::async_trait_external as async_trait;
-    pub use futuresdr_macros::connect;
+    pub use futuresdr_macros::connect_external as connect;
     pub use futuresdr_macros::message_handler_external as message_handler;
+    pub use futuresdr_macros::message_handlers_external as message_handlers;
+    pub use futuresdr_macros::BlockExternal as Block;
+    pub use futuresdr_macros::FromPmtExternal as FromPmt;
+    pub use futuresdr_macros::IntoPmtExternal as IntoPmt;
 }
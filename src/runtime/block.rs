@@ -9,6 +9,9 @@ use std::any::Any;
 use std::fmt;
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Poll;
 
 use crate::runtime::BlockDescription;
 use crate::runtime::BlockMessage;
@@ -16,10 +19,12 @@ use crate::runtime::BlockMeta;
 use crate::runtime::BlockPortCtx;
 use crate::runtime::Error;
 use crate::runtime::FlowgraphMessage;
+use crate::runtime::LatencyHistogram;
 use crate::runtime::MessageIo;
 use crate::runtime::MessageOutput;
 use crate::runtime::Pmt;
 use crate::runtime::PortId;
+use crate::runtime::Priority;
 use crate::runtime::Result;
 use crate::runtime::StreamInput;
 use crate::runtime::StreamIo;
@@ -70,6 +75,11 @@ impl fmt::Debug for WorkIo {
 /// Central trait to implement a block
 #[async_trait]
 pub trait Kernel: Send {
+    /// Whether [`work_sync`](Kernel::work_sync) always returns `Some`
+    ///
+    /// Schedulers can use this to run the block without going through the executor, e.g.,
+    /// by calling it back-to-back with other synchronous blocks.
+    const SYNC: bool = false;
     /// Processes stream data
     async fn work(
         &mut self,
@@ -80,6 +90,20 @@ pub trait Kernel: Send {
     ) -> Result<()> {
         Ok(())
     }
+    /// Processes stream data synchronously
+    ///
+    /// Fast path for kernels that do not have to `.await` in their work function. If it
+    /// returns `Some`, the runtime uses the result and does not call [`work`](Kernel::work),
+    /// avoiding the allocation of a boxed future for each call.
+    fn work_sync(
+        &mut self,
+        _io: &mut WorkIo,
+        _s: &mut StreamIo,
+        _m: &mut MessageIo<Self>,
+        _b: &mut BlockMeta,
+    ) -> Option<Result<()>> {
+        None
+    }
     /// Initialize kernel
     async fn init(
         &mut self,
@@ -100,6 +124,87 @@ pub trait Kernel: Send {
     }
 }
 
+/// Synchronous Kernel
+///
+/// Variant of [`Kernel`] for blocks that never `.await`, where `work`, `init`, and `deinit`
+/// are plain functions. Every `SyncKernel` is a [`Kernel`], using the synchronous
+/// [`work_sync`](Kernel::work_sync) fast path.
+pub trait SyncKernel: Send {
+    /// Processes stream data
+    fn work(
+        &mut self,
+        _io: &mut WorkIo,
+        _s: &mut StreamIo,
+        _m: &mut MessageIo<Self>,
+        _b: &mut BlockMeta,
+    ) -> Result<()> {
+        Ok(())
+    }
+    /// Initialize kernel
+    fn init(
+        &mut self,
+        _s: &mut StreamIo,
+        _m: &mut MessageIo<Self>,
+        _b: &mut BlockMeta,
+    ) -> Result<()> {
+        Ok(())
+    }
+    /// De-initialize kernel
+    fn deinit(
+        &mut self,
+        _s: &mut StreamIo,
+        _m: &mut MessageIo<Self>,
+        _b: &mut BlockMeta,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<T: SyncKernel> Kernel for T {
+    const SYNC: bool = true;
+
+    fn work_sync(
+        &mut self,
+        io: &mut WorkIo,
+        s: &mut StreamIo,
+        m: &mut MessageIo<Self>,
+        b: &mut BlockMeta,
+    ) -> Option<Result<()>> {
+        Some(SyncKernel::work(self, io, s, m, b))
+    }
+    async fn init(
+        &mut self,
+        s: &mut StreamIo,
+        m: &mut MessageIo<Self>,
+        b: &mut BlockMeta,
+    ) -> Result<()> {
+        SyncKernel::init(self, s, m, b)
+    }
+    async fn deinit(
+        &mut self,
+        s: &mut StreamIo,
+        m: &mut MessageIo<Self>,
+        b: &mut BlockMeta,
+    ) -> Result<()> {
+        SyncKernel::deinit(self, s, m, b)
+    }
+}
+
+/// Call the work function of a kernel, preferring [`Kernel::work_sync`]
+pub(crate) async fn kernel_work<T: Kernel>(
+    kernel: &mut T,
+    io: &mut WorkIo,
+    sio: &mut StreamIo,
+    mio: &mut MessageIo<T>,
+    meta: &mut BlockMeta,
+) -> Result<()> {
+    match kernel.work_sync(io, sio, mio, meta) {
+        Some(r) => r,
+        None => kernel.work(io, sio, mio, meta).await,
+    }
+}
+
 #[async_trait]
 /// Block interface, implemented for [TypedBlock]s
 pub trait BlockT: Send + Any {
@@ -127,6 +232,16 @@ pub trait BlockT: Send + Any {
     ///
     /// Blocking blocks will be spawned in a separate thread.
     fn is_blocking(&self) -> bool;
+    /// Priority class of the block.
+    fn priority(&self) -> Priority;
+    /// Set priority class (see [`BlockMeta::set_priority`])
+    fn set_priority(&mut self, priority: Priority);
+    /// Check whether the kernel is synchronous (see [`Kernel::SYNC`]).
+    fn is_sync(&self) -> bool;
+    /// Get the block as [`Fusible`], if it can be run as part of a fused chain
+    fn as_fusible(&mut self) -> Option<&mut dyn Fusible> {
+        None
+    }
 
     // ##### STREAM IO
     #[allow(clippy::type_complexity)]
@@ -135,6 +250,13 @@ pub trait BlockT: Send + Any {
         &mut self,
         f: Box<dyn FnMut(&mut [StreamInput], &mut [StreamOutput]) + Send + 'static>,
     );
+    /// Enable latency instrumentation (see [`Tag::Latency`](crate::runtime::Tag::Latency))
+    ///
+    /// Returns the latency histogram, if the block is a sink. Blocks that do not support
+    /// instrumentation are not measured.
+    fn instrument_latency(&mut self, _interval: usize) -> Option<Arc<Mutex<LatencyHistogram>>> {
+        None
+    }
     /// Get stream input ports
     fn stream_inputs(&self) -> &Vec<StreamInput>;
     /// Get stream input port
@@ -149,6 +271,8 @@ pub trait BlockT: Send + Any {
     fn stream_output_name_to_id(&self, name: &str) -> Option<usize>;
 
     // ##### MESSAGE IO
+    /// Get message input port names
+    fn message_input_names(&self) -> Vec<String>;
     /// Map message input port name to id
     fn message_input_name_to_id(&self, name: &str) -> Option<usize>;
     /// Get message output ports
@@ -157,6 +281,30 @@ pub trait BlockT: Send + Any {
     fn message_output_name_to_id(&self, name: &str) -> Option<usize>;
 }
 
+/// Block that can be run as part of a fused chain (see [`Topology::fuse`](crate::runtime::Topology::fuse))
+///
+/// Instead of running in its own task, the block is driven step by step by the task of the chain.
+#[async_trait]
+pub trait Fusible: Send {
+    /// Handle a message of the setup phase, returns `true` once the block is initialized
+    async fn setup(
+        &mut self,
+        msg: BlockMessage,
+        main_inbox: &mut Sender<FlowgraphMessage>,
+    ) -> Result<bool, Error>;
+    /// Handle a message of the main loop
+    async fn handle(
+        &mut self,
+        block_id: usize,
+        io: &mut WorkIo,
+        msg: BlockMessage,
+    ) -> Result<(), Error>;
+    /// Call the synchronous work function once and commit the stream io
+    fn work(&mut self, io: &mut WorkIo) -> Result<(), Error>;
+    /// Notify connected blocks that the block is finished and de-initialize it
+    async fn shutdown(&mut self) -> Result<(), Error>;
+}
+
 /// Typed Block
 pub struct TypedBlock<T> {
     /// Block metadata
@@ -217,11 +365,63 @@ impl<T: Kernel + Send + 'static> TypedBlock<T> {
         f.await.map_err(|e| Error::HandlerError(e.to_string()))
     }
 
-    async fn run_impl(
+    /// Handle a message of the setup phase, returns `true` once the block is initialized
+    async fn setup_message(
+        &mut self,
+        msg: BlockMessage,
+        main_inbox: &mut Sender<FlowgraphMessage>,
+    ) -> Result<bool, Error> {
+        let TypedBlock {
+            meta,
+            sio,
+            mio,
+            kernel,
+        } = self;
+
+        match msg {
+            BlockMessage::Initialize => {
+                if let Err(e) = kernel.init(sio, mio, meta).await {
+                    error!(
+                        "{}: Error during initialization. Terminating.",
+                        meta.instance_name().unwrap()
+                    );
+                    return Err(Error::RuntimeError(e.to_string()));
+                } else {
+                    main_inbox
+                        .send(FlowgraphMessage::Initialized)
+                        .await
+                        .map_err(|e| Error::RuntimeError(e.to_string()))?;
+                }
+                return Ok(true);
+            }
+            BlockMessage::StreamOutputInit { src_port, writer } => {
+                sio.output(src_port).init(writer);
+            }
+            BlockMessage::StreamInputInit { dst_port, reader } => {
+                sio.input(dst_port).set_reader(reader);
+            }
+            BlockMessage::MessageOutputConnect {
+                src_port,
+                dst_port,
+                dst_inbox,
+            } => {
+                mio.output_mut(src_port).connect(dst_port, dst_inbox);
+            }
+            t => warn!(
+                "{} unhandled message during init {:?}",
+                meta.instance_name().unwrap(),
+                t
+            ),
+        }
+        Ok(false)
+    }
+
+    /// Handle a message of the main loop
+    async fn handle_message(
         &mut self,
         block_id: usize,
-        mut main_inbox: Sender<FlowgraphMessage>,
-        mut inbox: Receiver<BlockMessage>,
+        work_io: &mut WorkIo,
+        msg: BlockMessage,
     ) -> Result<(), Error> {
         let TypedBlock {
             meta,
@@ -230,6 +430,117 @@ impl<T: Kernel + Send + 'static> TypedBlock<T> {
             kernel,
         } = self;
 
+        match msg {
+            BlockMessage::Notify => {}
+            BlockMessage::BlockDescription { tx } => {
+                let stream_inputs: Vec<String> =
+                    sio.inputs().iter().map(|x| x.name().to_string()).collect();
+                let stream_outputs: Vec<String> =
+                    sio.outputs().iter().map(|x| x.name().to_string()).collect();
+                let message_inputs: Vec<String> = mio.input_names();
+                let message_input_schemas = mio.input_schemas();
+                let message_outputs: Vec<String> =
+                    mio.outputs().iter().map(|x| x.name().to_string()).collect();
+
+                let description = BlockDescription {
+                    id: block_id,
+                    type_name: meta.type_name().to_string(),
+                    instance_name: meta.instance_name().unwrap().to_string(),
+                    stream_inputs,
+                    stream_outputs,
+                    message_inputs,
+                    message_outputs,
+                    message_input_schemas,
+                    blocking: meta.is_blocking(),
+                };
+                tx.send(description).unwrap();
+            }
+            BlockMessage::StreamInputDone { input_id } => {
+                sio.input(input_id).finish();
+            }
+            BlockMessage::StreamOutputDone { .. } => {
+                work_io.finished = true;
+            }
+            BlockMessage::Call { port_id, data } => {
+                match Self::call_handler(work_io, mio, meta, kernel, port_id, data).await {
+                    Err(Error::InvalidMessagePort(_, port_id)) => {
+                        error!(
+                            "{}: BlockMessage::Call -> Invalid Handler {port_id:?}.",
+                            meta.instance_name().unwrap(),
+                        );
+                    }
+                    Err(e @ Error::HandlerError(..)) => {
+                        error!(
+                            "{}: BlockMessage::Call -> {e}. Terminating.",
+                            meta.instance_name().unwrap(),
+                        );
+                        return Err(e);
+                    }
+                    _ => {}
+                }
+            }
+            BlockMessage::Callback { port_id, data, tx } => {
+                match Self::call_handler(work_io, mio, meta, kernel, port_id.clone(), data).await {
+                    Err(e @ Error::HandlerError(..)) => {
+                        error!(
+                            "{}: BlockMessage::Callback -> {e}. Terminating.",
+                            meta.instance_name().unwrap(),
+                        );
+                        let _ = tx.send(Err(Error::InvalidMessagePort(
+                            BlockPortCtx::Id(block_id),
+                            port_id,
+                        )));
+                        return Err(e);
+                    }
+                    res => {
+                        let _ = tx.send(res);
+                    }
+                }
+            }
+            BlockMessage::MessageOutputConnect {
+                src_port,
+                dst_port,
+                dst_inbox,
+            } => {
+                mio.output_mut(src_port).connect(dst_port, dst_inbox);
+            }
+            BlockMessage::Terminate => work_io.finished = true,
+            t => warn!("block unhandled message in main loop {:?}", t),
+        }
+        Ok(())
+    }
+
+    /// Notify connected blocks that the block is finished and de-initialize the kernel
+    async fn shutdown_impl(&mut self) -> Result<(), Error> {
+        let TypedBlock {
+            meta,
+            sio,
+            mio,
+            kernel,
+        } = self;
+
+        debug!("{} terminating ", meta.instance_name().unwrap());
+        join_all(sio.inputs_mut().iter_mut().map(|i| i.notify_finished())).await;
+        join_all(sio.outputs_mut().iter_mut().map(|o| o.notify_finished())).await;
+        join_all(mio.outputs_mut().iter_mut().map(|o| o.notify_finished())).await;
+
+        if let Err(e) = kernel.deinit(sio, mio, meta).await {
+            error!(
+                "{}: Error in deinit (). Terminating. ({:?})",
+                meta.instance_name().unwrap(),
+                e
+            );
+            return Err(Error::RuntimeError(e.to_string()));
+        }
+        Ok(())
+    }
+
+    async fn run_impl(
+        &mut self,
+        block_id: usize,
+        mut main_inbox: Sender<FlowgraphMessage>,
+        mut inbox: Receiver<BlockMessage>,
+    ) -> Result<(), Error> {
         // init work io
         let mut work_io = WorkIo {
             call_again: false,
@@ -239,44 +550,12 @@ impl<T: Kernel + Send + 'static> TypedBlock<T> {
 
         // setup phase
         loop {
-            match inbox
+            let msg = inbox
                 .next()
                 .await
-                .ok_or_else(|| Error::RuntimeError("no msg".to_string()))?
-            {
-                BlockMessage::Initialize => {
-                    if let Err(e) = kernel.init(sio, mio, meta).await {
-                        error!(
-                            "{}: Error during initialization. Terminating.",
-                            meta.instance_name().unwrap()
-                        );
-                        return Err(Error::RuntimeError(e.to_string()));
-                    } else {
-                        main_inbox
-                            .send(FlowgraphMessage::Initialized)
-                            .await
-                            .map_err(|e| Error::RuntimeError(e.to_string()))?;
-                    }
-                    break;
-                }
-                BlockMessage::StreamOutputInit { src_port, writer } => {
-                    sio.output(src_port).init(writer);
-                }
-                BlockMessage::StreamInputInit { dst_port, reader } => {
-                    sio.input(dst_port).set_reader(reader);
-                }
-                BlockMessage::MessageOutputConnect {
-                    src_port,
-                    dst_port,
-                    dst_inbox,
-                } => {
-                    mio.output_mut(src_port).connect(dst_port, dst_inbox);
-                }
-                t => warn!(
-                    "{} unhandled message during init {:?}",
-                    meta.instance_name().unwrap(),
-                    t
-                ),
+                .ok_or_else(|| Error::RuntimeError("no msg".to_string()))?;
+            if self.setup_message(msg, &mut main_inbox).await? {
+                break;
             }
         }
 
@@ -286,111 +565,16 @@ impl<T: Kernel + Send + 'static> TypedBlock<T> {
         // main loop
         loop {
             // ================== non blocking
-            loop {
-                match inbox.next().now_or_never() {
-                    Some(Some(BlockMessage::Notify)) => {}
-                    Some(Some(BlockMessage::BlockDescription { tx })) => {
-                        let stream_inputs: Vec<String> =
-                            sio.inputs().iter().map(|x| x.name().to_string()).collect();
-                        let stream_outputs: Vec<String> =
-                            sio.outputs().iter().map(|x| x.name().to_string()).collect();
-                        let message_inputs: Vec<String> = mio.input_names();
-                        let message_outputs: Vec<String> =
-                            mio.outputs().iter().map(|x| x.name().to_string()).collect();
-
-                        let description = BlockDescription {
-                            id: block_id,
-                            type_name: meta.type_name().to_string(),
-                            instance_name: meta.instance_name().unwrap().to_string(),
-                            stream_inputs,
-                            stream_outputs,
-                            message_inputs,
-                            message_outputs,
-                            blocking: meta.is_blocking(),
-                        };
-                        tx.send(description).unwrap();
-                    }
-                    Some(Some(BlockMessage::StreamInputDone { input_id })) => {
-                        sio.input(input_id).finish();
-                    }
-                    Some(Some(BlockMessage::StreamOutputDone { .. })) => {
-                        work_io.finished = true;
-                    }
-                    Some(Some(BlockMessage::Call { port_id, data })) => {
-                        match Self::call_handler(&mut work_io, mio, meta, kernel, port_id, data)
-                            .await
-                        {
-                            Err(Error::InvalidMessagePort(_, port_id)) => {
-                                error!(
-                                    "{}: BlockMessage::Call -> Invalid Handler {port_id:?}.",
-                                    meta.instance_name().unwrap(),
-                                );
-                            }
-                            Err(e @ Error::HandlerError(..)) => {
-                                error!(
-                                    "{}: BlockMessage::Call -> {e}. Terminating.",
-                                    meta.instance_name().unwrap(),
-                                );
-                                return Err(e);
-                            }
-                            _ => {}
-                        }
-                    }
-                    Some(Some(BlockMessage::Callback { port_id, data, tx })) => {
-                        match Self::call_handler(
-                            &mut work_io,
-                            mio,
-                            meta,
-                            kernel,
-                            port_id.clone(),
-                            data,
-                        )
-                        .await
-                        {
-                            Err(e @ Error::HandlerError(..)) => {
-                                error!(
-                                    "{}: BlockMessage::Callback -> {e}. Terminating.",
-                                    meta.instance_name().unwrap(),
-                                );
-                                let _ = tx.send(Err(Error::InvalidMessagePort(
-                                    BlockPortCtx::Id(block_id),
-                                    port_id,
-                                )));
-                                return Err(e);
-                            }
-                            res => {
-                                let _ = tx.send(res);
-                            }
-                        }
-                    }
-                    Some(Some(BlockMessage::Terminate)) => work_io.finished = true,
-                    Some(Some(t)) => warn!("block unhandled message in main loop {:?}", t),
-                    _ => break,
-                };
+            while let Some(Some(msg)) = inbox.next().now_or_never() {
+                self.handle_message(block_id, &mut work_io, msg).await?;
                 // received at least one message
                 work_io.call_again = true;
             }
 
             // ================== shutdown
             if work_io.finished {
-                debug!("{} terminating ", meta.instance_name().unwrap());
-                join_all(sio.inputs_mut().iter_mut().map(|i| i.notify_finished())).await;
-                join_all(sio.outputs_mut().iter_mut().map(|o| o.notify_finished())).await;
-                join_all(mio.outputs_mut().iter_mut().map(|o| o.notify_finished())).await;
-
-                match kernel.deinit(sio, mio, meta).await {
-                    Ok(_) => {
-                        break;
-                    }
-                    Err(e) => {
-                        error!(
-                            "{}: Error in deinit (). Terminating. ({:?})",
-                            meta.instance_name().unwrap(),
-                            e
-                        );
-                        return Err(Error::RuntimeError(e.to_string()));
-                    }
-                };
+                self.shutdown_impl().await?;
+                break;
             }
 
             // ================== blocking
@@ -415,7 +599,13 @@ impl<T: Kernel + Send + 'static> TypedBlock<T> {
 
             // ================== work
             work_io.call_again = false;
-            if let Err(e) = kernel.work(&mut work_io, sio, mio, meta).await {
+            let TypedBlock {
+                meta,
+                sio,
+                mio,
+                kernel,
+            } = self;
+            if let Err(e) = kernel_work(kernel, &mut work_io, sio, mio, meta).await {
                 error!(
                     "{}: Error in work(). Terminating. ({:?})",
                     meta.instance_name().unwrap(),
@@ -432,6 +622,57 @@ impl<T: Kernel + Send + 'static> TypedBlock<T> {
     }
 }
 
+#[async_trait]
+impl<T: Kernel + Send + 'static> Fusible for TypedBlock<T> {
+    async fn setup(
+        &mut self,
+        msg: BlockMessage,
+        main_inbox: &mut Sender<FlowgraphMessage>,
+    ) -> Result<bool, Error> {
+        self.setup_message(msg, main_inbox).await
+    }
+
+    async fn handle(
+        &mut self,
+        block_id: usize,
+        io: &mut WorkIo,
+        msg: BlockMessage,
+    ) -> Result<(), Error> {
+        self.handle_message(block_id, io, msg).await
+    }
+
+    fn work(&mut self, io: &mut WorkIo) -> Result<(), Error> {
+        let TypedBlock {
+            meta,
+            sio,
+            mio,
+            kernel,
+        } = self;
+        match kernel.work_sync(io, sio, mio, meta) {
+            Some(Ok(())) => {
+                sio.commit();
+                Ok(())
+            }
+            Some(Err(e)) => {
+                error!(
+                    "{}: Error in work(). Terminating. ({:?})",
+                    meta.instance_name().unwrap(),
+                    e
+                );
+                Err(Error::RuntimeError(e.to_string()))
+            }
+            None => Err(Error::RuntimeError(format!(
+                "{}: kernel cannot be fused, work() is not synchronous",
+                meta.instance_name().unwrap()
+            ))),
+        }
+    }
+
+    async fn shutdown(&mut self) -> Result<(), Error> {
+        self.shutdown_impl().await
+    }
+}
+
 #[async_trait]
 impl<T: Kernel + Send + 'static> BlockT for TypedBlock<T> {
     // ##### Block
@@ -455,6 +696,22 @@ impl<T: Kernel + Send + 'static> BlockT for TypedBlock<T> {
     fn is_blocking(&self) -> bool {
         self.meta.is_blocking()
     }
+    fn priority(&self) -> Priority {
+        self.meta.priority()
+    }
+    fn set_priority(&mut self, priority: Priority) {
+        self.meta.set_priority(priority)
+    }
+    fn is_sync(&self) -> bool {
+        T::SYNC
+    }
+    fn as_fusible(&mut self) -> Option<&mut dyn Fusible> {
+        if T::SYNC {
+            Some(self)
+        } else {
+            None
+        }
+    }
 
     // ##### KERNEL
     async fn run(
@@ -473,6 +730,9 @@ impl<T: Kernel + Send + 'static> BlockT for TypedBlock<T> {
     ) {
         self.sio.set_tag_propagation(f)
     }
+    fn instrument_latency(&mut self, interval: usize) -> Option<Arc<Mutex<LatencyHistogram>>> {
+        self.sio.instrument_latency(interval)
+    }
     fn stream_inputs(&self) -> &Vec<StreamInput> {
         self.sio.inputs()
     }
@@ -493,6 +753,9 @@ impl<T: Kernel + Send + 'static> BlockT for TypedBlock<T> {
     }
 
     // ##### MESSAGE IO
+    fn message_input_names(&self) -> Vec<String> {
+        self.mio.input_names()
+    }
     fn message_input_name_to_id(&self, name: &str) -> Option<usize> {
         self.mio.input_name_to_id(name)
     }
@@ -561,6 +824,18 @@ impl Block {
     pub fn is_blocking(&self) -> bool {
         self.0.is_blocking()
     }
+    /// Priority class of the block (see [`BlockMeta::priority`])
+    pub fn priority(&self) -> Priority {
+        self.0.priority()
+    }
+    /// Set priority class (see [`BlockMeta::set_priority`])
+    pub fn set_priority(&mut self, priority: Priority) {
+        self.0.set_priority(priority)
+    }
+    /// Is block synchronous (see [`Kernel::SYNC`])
+    pub fn is_sync(&self) -> bool {
+        self.0.is_sync()
+    }
 
     pub(crate) async fn run(
         mut self,
@@ -568,7 +843,149 @@ impl Block {
         mut main_inbox: Sender<FlowgraphMessage>,
         inbox: Receiver<BlockMessage>,
     ) {
-        match self.0.run(block_id, main_inbox.clone(), inbox).await {
+        let result = self.0.run(block_id, main_inbox.clone(), inbox).await;
+        self.report(block_id, &mut main_inbox, result).await;
+    }
+
+    /// Run a fused chain of blocks in one task
+    ///
+    /// The inboxes of the blocks are merged into one stream, i.e., the task is woken up by any
+    /// message for the chain. Blocks that have to be called are called back-to-back in stream
+    /// order, handling new messages before each block.
+    pub(crate) async fn run_fused(
+        chain: Vec<(usize, Block, Receiver<BlockMessage>)>,
+        mut main_inbox: Sender<FlowgraphMessage>,
+    ) {
+        struct Member {
+            id: usize,
+            block: Option<Block>,
+            io: WorkIo,
+            initialized: bool,
+        }
+
+        impl Member {
+            fn ready(&self) -> bool {
+                self.block.is_some() && self.initialized && (self.io.call_again || self.io.finished)
+            }
+
+            async fn dispatch(
+                &mut self,
+                msg: BlockMessage,
+                main_inbox: &mut Sender<FlowgraphMessage>,
+            ) {
+                let Some(b) = self.block.as_mut().and_then(|b| b.0.as_fusible()) else {
+                    return;
+                };
+                let result = if !self.initialized {
+                    match b.setup(msg, main_inbox).await {
+                        Ok(initialized) => {
+                            self.initialized = initialized;
+                            Ok(())
+                        }
+                        Err(e) => Err(e),
+                    }
+                } else {
+                    // received at least one message
+                    self.io.call_again = true;
+                    match msg {
+                        BlockMessage::Notify => Ok(()),
+                        msg => b.handle(self.id, &mut self.io, msg).await,
+                    }
+                };
+                if let Err(e) = result {
+                    self.fail(e, main_inbox).await;
+                }
+            }
+
+            async fn step(&mut self, main_inbox: &mut Sender<FlowgraphMessage>) {
+                if !self.ready() {
+                    return;
+                }
+                let b = self.block.as_mut().unwrap().0.as_fusible().unwrap();
+                if self.io.finished {
+                    let result = b.shutdown().await;
+                    let block = self.block.take().unwrap();
+                    block.report(self.id, main_inbox, result).await;
+                } else {
+                    self.io.call_again = false;
+                    if let Err(e) = b.work(&mut self.io) {
+                        self.fail(e, main_inbox).await;
+                    }
+                }
+            }
+
+            async fn fail(&mut self, e: Error, main_inbox: &mut Sender<FlowgraphMessage>) {
+                if let Some(block) = self.block.take() {
+                    block.report(self.id, main_inbox, Err(e)).await;
+                }
+            }
+        }
+
+        let mut members = Vec::new();
+        let mut inboxes = Vec::new();
+        for (i, (id, block, inbox)) in chain.into_iter().enumerate() {
+            members.push(Member {
+                id,
+                block: Some(block),
+                io: WorkIo {
+                    call_again: false,
+                    finished: false,
+                    block_on: None,
+                },
+                initialized: false,
+            });
+            inboxes.push(inbox.map(move |m| (i, m)));
+        }
+        let mut inbox = futures::stream::select_all(inboxes);
+
+        while members.iter().any(|m| m.block.is_some()) {
+            // ================== blocking
+            if !members.iter().any(Member::ready) {
+                let next = futures::future::poll_fn(|cx| {
+                    for m in members.iter_mut() {
+                        if let Some(f) = m.io.block_on.as_mut() {
+                            if f.as_mut().poll(cx).is_ready() {
+                                m.io.block_on = None;
+                                m.io.call_again = true;
+                                return Poll::Ready(None);
+                            }
+                        }
+                    }
+                    inbox.poll_next_unpin(cx).map(Some)
+                })
+                .await;
+                match next {
+                    Some(Some((i, msg))) => members[i].dispatch(msg, &mut main_inbox).await,
+                    Some(None) => {
+                        for m in members.iter_mut() {
+                            m.fail(Error::RuntimeError("no msg".to_string()), &mut main_inbox)
+                                .await;
+                        }
+                    }
+                    None => {}
+                }
+            }
+
+            // ================== work
+            for i in 0..members.len() {
+                while let Some(Some((j, msg))) = inbox.next().now_or_never() {
+                    members[j].dispatch(msg, &mut main_inbox).await;
+                }
+                members[i].step(&mut main_inbox).await;
+            }
+
+            futures_lite::future::yield_now().await;
+        }
+    }
+
+    /// Hand the block back to the flowgraph, after it terminated
+    async fn report(
+        self,
+        block_id: usize,
+        main_inbox: &mut Sender<FlowgraphMessage>,
+        result: Result<(), Error>,
+    ) {
+        match result {
             Ok(_) => {
                 let _ = main_inbox
                     .send(FlowgraphMessage::BlockDone {
@@ -602,6 +1019,12 @@ impl Block {
     ) {
         self.0.set_tag_propagation(f);
     }
+    pub(crate) fn instrument_latency(
+        &mut self,
+        interval: usize,
+    ) -> Option<Arc<Mutex<LatencyHistogram>>> {
+        self.0.instrument_latency(interval)
+    }
     /// Get stream input ports
     pub fn stream_inputs(&self) -> &Vec<StreamInput> {
         self.0.stream_inputs()
@@ -628,6 +1051,10 @@ impl Block {
     }
 
     // ##### MESSAGE IO
+    /// Get message input port names
+    pub fn message_input_names(&self) -> Vec<String> {
+        self.0.message_input_names()
+    }
     /// Map message input port name to id
     pub fn message_input_name_to_id(&self, name: &str) -> Option<usize> {
         self.0.message_input_name_to_id(name)
@@ -1,16 +1,48 @@
+use std::str::FromStr;
+
+/// Priority class of a block
+///
+/// Schedulers serve blocks of higher classes first. Classes are ordered, i.e.,
+/// `Background < Normal < Realtime`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Only run, if no other blocks are ready
+    Background,
+    /// Default priority
+    #[default]
+    Normal,
+    /// Latency-critical blocks, e.g., feeding a hardware sink
+    Realtime,
+}
+
+impl FromStr for Priority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "background" => Ok(Priority::Background),
+            "normal" => Ok(Priority::Normal),
+            "realtime" => Ok(Priority::Realtime),
+            _ => Err(format!("unknown priority {s:?}")),
+        }
+    }
+}
+
 /// Block metadata
 pub struct BlockMeta {
     type_name: String,
     instance_name: Option<String>,
     blocking: bool,
+    priority: Priority,
 }
 
 impl BlockMeta {
-    fn new(type_name: String, blocking: bool) -> BlockMeta {
+    fn new(type_name: String, blocking: bool, priority: Priority) -> BlockMeta {
         BlockMeta {
             type_name,
             instance_name: None,
             blocking,
+            priority,
         }
     }
     /// Name of block type
@@ -31,12 +63,21 @@ impl BlockMeta {
     pub fn is_blocking(&self) -> bool {
         self.blocking
     }
+    /// Priority class of the block
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+    /// Set priority class of the block
+    pub fn set_priority(&mut self, priority: Priority) {
+        self.priority = priority;
+    }
 }
 
 /// Block metadata buidler
 pub struct BlockMetaBuilder {
     name: String,
     blocking: bool,
+    priority: Priority,
 }
 
 impl BlockMetaBuilder {
@@ -45,6 +86,7 @@ impl BlockMetaBuilder {
         BlockMetaBuilder {
             name: name.into(),
             blocking: false,
+            priority: Priority::Normal,
         }
     }
     /// Mark block as blocking
@@ -55,8 +97,14 @@ impl BlockMetaBuilder {
         self.blocking = true;
         self
     }
+    /// Set priority class of the block
+    #[must_use]
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
     /// Build block metadata
     pub fn build(self) -> BlockMeta {
-        BlockMeta::new(self.name, self.blocking)
+        BlockMeta::new(self.name, self.blocking, self.priority)
     }
 }
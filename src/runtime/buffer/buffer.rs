@@ -16,6 +16,15 @@ pub trait BufferBuilder: Send + Sync + Any {
         writer_inbox: Sender<BlockMessage>,
         writer_output_id: usize,
     ) -> BufferWriter;
+
+    /// Short description of the buffer, shown in the flowgraph description
+    ///
+    /// Defaults to the name of the type.
+    fn description(&self) -> String {
+        let name = std::any::type_name::<Self>();
+        let name = name.split('<').next().unwrap_or(name);
+        name.rsplit("::").next().unwrap_or(name).to_string()
+    }
 }
 
 /// CPU buffer writer
@@ -30,7 +39,9 @@ pub trait BufferWriterHost: Send + Any + Debug {
     /// Cast to any
     fn as_any(&mut self) -> &mut dyn Any;
     /// Produce samples
-    fn produce(&mut self, amount: usize, tags: Vec<ItemTag>);
+    ///
+    /// The tags are drained, the vector and its allocation stay with the caller.
+    fn produce(&mut self, amount: usize, tags: &mut Vec<ItemTag>);
     /// Get buffer
     fn bytes(&mut self) -> (*mut u8, usize);
     /// Notify readers that we are finished
@@ -89,7 +100,7 @@ impl BufferWriter {
         }
     }
     /// Produce samples
-    pub fn produce(&mut self, amount: usize, tags: Vec<ItemTag>) {
+    pub fn produce(&mut self, amount: usize, tags: &mut Vec<ItemTag>) {
         match self {
             BufferWriter::Host(w) => w.produce(amount, tags),
             _ => unimplemented!(),
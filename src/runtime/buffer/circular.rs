@@ -81,6 +81,12 @@ impl Default for Circular {
     }
 }
 
+impl fmt::Display for Circular {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Circular ({} bytes)", self.min_bytes)
+    }
+}
+
 impl BufferBuilder for Circular {
     fn build(
         &self,
@@ -95,6 +101,10 @@ impl BufferBuilder for Circular {
             writer_output_id,
         )))
     }
+
+    fn description(&self) -> String {
+        self.to_string()
+    }
 }
 
 /// Circular writer
@@ -171,10 +181,14 @@ impl BufferWriterHost for Writer {
         self
     }
 
-    fn produce(&mut self, items: usize, mut tags: Vec<ItemTag>) {
-        for t in tags.iter_mut() {
-            t.index *= self.item_size;
-        }
+    fn produce(&mut self, items: usize, tags: &mut Vec<ItemTag>) {
+        let tags = tags
+            .drain(..)
+            .map(|mut t| {
+                t.index *= self.item_size;
+                t
+            })
+            .collect();
         self.writer.produce(items * self.item_size, tags);
     }
 
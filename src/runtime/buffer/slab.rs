@@ -2,6 +2,7 @@ use futures::channel::mpsc::Sender;
 use futures::prelude::*;
 use std::any::Any;
 use std::collections::VecDeque;
+use std::fmt;
 use std::sync::Arc;
 use std::sync::Mutex;
 
@@ -68,6 +69,16 @@ impl Default for Slab {
     }
 }
 
+impl fmt::Display for Slab {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Slab ({} bytes, {} buffers, {} reserved items)",
+            self.min_bytes, self.n_buffer, self.reserved_items
+        )
+    }
+}
+
 impl BufferBuilder for Slab {
     fn build(
         &self,
@@ -84,6 +95,10 @@ impl BufferBuilder for Slab {
             writer_output_id,
         )
     }
+
+    fn description(&self) -> String {
+        self.to_string()
+    }
 }
 
 #[derive(Debug)]
@@ -222,7 +237,7 @@ impl BufferWriterHost for Writer {
         }
     }
 
-    fn produce(&mut self, amount: usize, mut tags: Vec<ItemTag>) {
+    fn produce(&mut self, amount: usize, tags: &mut Vec<ItemTag>) {
         debug_assert!(amount > 0);
 
         let c = self.current.as_mut().unwrap();
@@ -230,7 +245,7 @@ impl BufferWriterHost for Writer {
         for t in tags.iter_mut() {
             t.index += c.offset;
         }
-        c.tags.append(&mut tags);
+        c.tags.append(tags);
         c.offset += amount;
         if c.offset == c.capacity {
             let c = self.current.take().unwrap();
@@ -143,7 +143,8 @@ impl BufferWriterHost for WriterH2D {
         }
     }
 
-    fn produce(&mut self, amount: usize, _tags: Vec<ItemTag>) {
+    fn produce(&mut self, amount: usize, tags: &mut Vec<ItemTag>) {
+        tags.clear();
         debug!("H2D writer called produce {}", amount);
         let buffer = self.buffer.as_mut().unwrap();
         let capacity = buffer.buffer.buffer.len() / self.item_size;
@@ -1,26 +1,72 @@
 //! Configuration Management
 #[cfg(not(target_arch = "wasm32"))]
 use config::File;
+use config::Map;
 #[cfg(not(target_arch = "wasm32"))]
 use config::Source;
-use config::Value;
+pub use config::Value;
+use config::ValueKind;
+use futures::channel::mpsc::unbounded;
+use futures::channel::mpsc::UnboundedReceiver;
+use futures::channel::mpsc::UnboundedSender;
 use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Mutex;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::Once;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
 use tracing::level_filters::LevelFilter;
 
+use crate::runtime::logging;
+use crate::runtime::Error;
+use crate::runtime::Pmt;
+
 /// Get global configuration
 pub fn config() -> Config {
     CONFIG.lock().unwrap().clone()
 }
 
 /// Set config value
-pub fn set<V: Into<config::Value>>(name: impl Into<String>, value: V) {
+///
+/// The value is sent to its subscribers (see [`subscribe`]). If the value is invalid, an
+/// [`Error::InvalidConfig`] is returned and the config is not changed.
+pub fn set<V: Into<config::Value>>(name: impl Into<String>, value: V) -> Result<(), Error> {
+    let name = name.into();
+    let value = value.into();
+    CONFIG
+        .lock()
+        .unwrap()
+        .set_value(name.clone(), value.clone())?;
+    notify(&name, &value);
+    Ok(())
+}
+
+/// Add or replace a named buffer profile
+pub fn set_buffer_profile(name: impl Into<String>, profile: BufferProfile) {
     let mut c = CONFIG.lock().unwrap();
-    c.set_value(name, value);
+    c.buffer_profiles.insert(name.into(), profile);
+}
+
+/// Use buffer profile for stream connections matching the given key
+///
+/// See [`Topology::buffer_override`](crate::runtime::Topology::buffer_override) for the format
+/// of the key. The profile has to exist.
+pub fn set_buffer_override(
+    key: impl Into<String>,
+    profile: impl Into<String>,
+) -> Result<(), Error> {
+    let profile = profile.into();
+    let mut c = CONFIG.lock().unwrap();
+    if !c.buffer_profiles.contains_key(&profile) {
+        return Err(Error::InvalidBufferProfile(profile));
+    }
+    c.buffer_overrides.insert(key.into(), profile);
+    Ok(())
 }
 
 /// Get value from config
@@ -44,69 +90,298 @@ pub fn get_or_default<T: FromStr>(name: &str, default: T) -> T {
     get(name).unwrap_or(default)
 }
 
+/// Deserialize a config section into a typed struct
+///
+/// Values of the section can be overridden through environment variables, separating nested
+/// keys with `__` (e.g., `FUTURESDR_MY_APP__GAIN=30`). A missing section is deserialized like an
+/// empty table, i.e., it succeeds if all fields have serde defaults. Errors name the key of the
+/// invalid value.
+///
+/// ```toml
+/// [my_app]
+/// gain = 30.0
+/// frequency = 100e6
+///
+/// [my_app.source]
+/// args = "driver=rtlsdr"
+/// ```
+pub fn section<T: DeserializeOwned>(name: &str) -> Result<T, Error> {
+    let value = get_value(name).unwrap_or_else(|| Value::new(None, ValueKind::Table(Map::new())));
+    serde_path_to_error::deserialize(value).map_err(|e| {
+        let key = match e.path().to_string().as_str() {
+            "." => name.to_string(),
+            path => format!("{name}.{path}"),
+        };
+        Error::InvalidConfig(key, e.into_inner().to_string())
+    })
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 static CONFIG: Lazy<Mutex<Config>> = Lazy::new(|| {
-    let mut settings = ::config::Config::builder();
+    let values = match load() {
+        Ok(v) => v,
+        Err(e) => {
+            warn!("failed to load config: {}, using defaults", e);
+            return Mutex::new(Config::default());
+        }
+    };
+
+    // start from default config
+    let mut c = Config::default();
+    let applied = values
+        .iter()
+        .try_for_each(|(k, v)| c.apply(k.clone(), v.clone()))
+        .and_then(|_| c.validate());
+    if let Err(e) = applied {
+        // keep LOADED empty, so that a reload applies all values once the files are fixed
+        warn!("{}, using default config", e);
+        return Mutex::new(Config::default());
+    }
+
+    *LOADED.lock().unwrap() = values;
+    Mutex::new(c)
+});
+
+// values of the config files and the environment, as applied on the last (re)load
+#[cfg(not(target_arch = "wasm32"))]
+static LOADED: Lazy<Mutex<HashMap<String, Value>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+static SUBSCRIBERS: Lazy<Mutex<HashMap<String, Vec<UnboundedSender<Pmt>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// user and project config files
+#[cfg(not(target_arch = "wasm32"))]
+fn config_files() -> Vec<PathBuf> {
+    let mut files = Vec::new();
 
     // user config
     if let Some(mut path) = dirs::config_dir() {
         path.push("futuresdr");
         path.push("config.toml");
-
-        settings = settings.add_source(File::from(path.clone()).required(false));
+        files.push(path);
     }
 
     // project config
-    settings =
-        settings.add_source(File::new("config.toml", config::FileFormat::Toml).required(false));
+    files.push(PathBuf::from("config.toml"));
+
+    files
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn load() -> Result<HashMap<String, Value>, config::ConfigError> {
+    let mut settings = ::config::Config::builder();
+
+    for path in config_files() {
+        settings = settings.add_source(File::from(path).required(false));
+    }
 
     // env config
-    settings = settings.add_source(config::Environment::with_prefix("futuresdr"));
+    settings = settings.add_source(
+        config::Environment::with_prefix("futuresdr")
+            .prefix_separator("_")
+            .separator("__"),
+    );
 
-    // start from default config
-    let mut c = Config::default();
+    settings.build()?.collect()
+}
 
-    if let Ok(config) = settings.build().unwrap().collect() {
-        for (k, v) in config.iter() {
-            match k.as_str() {
-                "queue_size" => {
-                    c.queue_size = config_parse::<usize>(v);
-                }
-                "buffer_size" => {
-                    c.buffer_size = config_parse::<usize>(v);
-                }
-                "stack_size" => {
-                    c.stack_size = config_parse::<usize>(v);
-                }
-                "slab_reserved" => {
-                    c.slab_reserved = config_parse::<usize>(v);
-                }
-                "log_level" => {
-                    c.log_level = config_parse::<LevelFilter>(v);
-                }
-                "ctrlport_enable" => {
-                    c.ctrlport_enable = config_parse::<bool>(v);
-                }
-                "ctrlport_bind" => {
-                    c.ctrlport_bind = Some(config_parse::<SocketAddr>(v));
-                }
-                "frontend_path" => {
-                    c.frontend_path = Some(config_parse::<PathBuf>(v));
-                }
-                _ => {
-                    c.misc.insert(k.clone(), v.clone());
-                }
-            }
+/// Reload the user and project config files
+///
+/// Values that changed since the last (re)load are applied and sent to their subscribers (see
+/// [`subscribe`]). Keys that were removed from the files keep their current value. If a file
+/// cannot be parsed or the resulting config is invalid, nothing is applied.
+///
+/// Returns the changed keys.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn reload() -> Vec<String> {
+    let values = match load() {
+        Ok(v) => v,
+        Err(e) => {
+            warn!("failed to reload config: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let changed: Vec<(String, Value)> = {
+        let mut c = CONFIG.lock().unwrap();
+        let mut loaded = LOADED.lock().unwrap();
+
+        let changed: Vec<(String, Value)> = values
+            .iter()
+            .filter(|(k, v)| loaded.get(*k) != Some(*v))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+
+        let mut new = c.clone();
+        let applied = changed
+            .iter()
+            .try_for_each(|(k, v)| new.apply(k.clone(), v.clone()))
+            .and_then(|_| new.validate());
+        if let Err(e) = applied {
+            warn!("{}, keeping the current config", e);
+            return Vec::new();
         }
+
+        *c = new;
+        *loaded = values;
+        changed
+    };
+
+    for (k, v) in changed.iter() {
+        notify(k, v);
     }
-    assert!(c.validate(), "invalid config");
+    changed.into_iter().map(|(k, _)| k).collect()
+}
 
-    Mutex::new(c)
-});
+/// Watch the user and project config files and [`reload`] them on changes
+///
+/// Starts a thread that checks the modification times of the files once per second. Only the
+/// first call has an effect. The [`Runtime`](crate::runtime::Runtime) calls this on startup, if
+/// `config_watch` is enabled.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn watch() {
+    static WATCH: Once = Once::new();
+
+    WATCH.call_once(|| {
+        let modified = || {
+            config_files()
+                .iter()
+                .map(|p| std::fs::metadata(p).and_then(|m| m.modified()).ok())
+                .collect::<Vec<_>>()
+        };
+
+        std::thread::Builder::new()
+            .name("config-watch".to_string())
+            .spawn(move || {
+                let mut last = modified();
+                loop {
+                    std::thread::sleep(Duration::from_secs(1));
+                    let current = modified();
+                    if current != last {
+                        last = current;
+                        let keys = reload();
+                        if !keys.is_empty() {
+                            info!("config reloaded, changed keys {:?}", keys);
+                        }
+                    }
+                }
+            })
+            .expect("failed to spawn config watcher");
+    });
+}
+
+/// Subscribe to changes of a config value
+///
+/// Every time the value is changed, through [`set`] or a [`reload`] of the config files, the new
+/// value is sent as [`Pmt`] on the returned channel. Tables are converted to
+/// [`Pmt::MapStrPmt`], arrays to [`Pmt::VecPmt`].
+///
+/// To forward updates to a message port of a block, use
+/// [`Flowgraph::connect_config`](crate::runtime::Flowgraph::connect_config).
+pub fn subscribe(name: impl Into<String>) -> UnboundedReceiver<Pmt> {
+    let (tx, rx) = unbounded();
+    SUBSCRIBERS
+        .lock()
+        .unwrap()
+        .entry(name.into())
+        .or_default()
+        .push(tx);
+    rx
+}
+
+// apply side effects of a changed value and send it to subscribers
+fn notify(name: &str, value: &Value) {
+    if name == "log_level" {
+        logging::set_level(config().log_level);
+    }
+
+    let mut subscribers = SUBSCRIBERS.lock().unwrap();
+    if let Some(s) = subscribers.get_mut(name) {
+        let Some(p) = value_to_pmt(value) else {
+            warn!(
+                "config value {} does not fit into a Pmt, not sent to subscribers",
+                name
+            );
+            return;
+        };
+        s.retain(|tx| tx.unbounded_send(p.clone()).is_ok());
+    }
+}
+
+// `None` if an integer does not fit into the Pmt
+fn value_to_pmt(v: &Value) -> Option<Pmt> {
+    Some(match &v.kind {
+        ValueKind::Nil => Pmt::Null,
+        ValueKind::Boolean(b) => Pmt::Bool(*b),
+        ValueKind::I64(i) => Pmt::Isize(isize::try_from(*i).ok()?),
+        ValueKind::I128(i) => match isize::try_from(*i) {
+            Ok(i) => Pmt::Isize(i),
+            Err(_) => Pmt::U64(u64::try_from(*i).ok()?),
+        },
+        ValueKind::U64(u) => Pmt::U64(*u),
+        ValueKind::U128(u) => Pmt::U64(u64::try_from(*u).ok()?),
+        ValueKind::Float(f) => Pmt::F64(*f),
+        ValueKind::String(s) => Pmt::String(s.clone()),
+        ValueKind::Table(t) => Pmt::MapStrPmt(
+            t.iter()
+                .map(|(k, v)| Some((k.clone(), value_to_pmt(v)?)))
+                .collect::<Option<_>>()?,
+        ),
+        ValueKind::Array(a) => Pmt::VecPmt(a.iter().map(value_to_pmt).collect::<Option<_>>()?),
+    })
+}
 
 #[cfg(target_arch = "wasm32")]
 static CONFIG: Lazy<Mutex<Config>> = Lazy::new(|| Mutex::new(Config::default()));
 
+/// Stream buffer implementation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BufferKind {
+    /// Double-mapped circular buffer (falls back to [`BufferKind::Slab`] on WASM)
+    Circular,
+    /// Slab buffer
+    Slab,
+}
+
+impl FromStr for BufferKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "circular" => Ok(BufferKind::Circular),
+            "slab" => Ok(BufferKind::Slab),
+            _ => Err(format!("unknown buffer kind {s:?}")),
+        }
+    }
+}
+
+/// Named stream buffer configuration
+///
+/// Profiles are defined in the `buffer_profiles` table of the config and selected for stream
+/// connections through `buffer_overrides`. Unset values fall back to the global `buffer_size`
+/// and `slab_reserved` settings.
+///
+/// ```toml
+/// [buffer_profiles.hot]
+/// kind = "slab"
+/// min_bytes = 65536
+/// n_buffer = 4
+///
+/// [buffer_overrides]
+/// "src.out -> fir.in" = "hot"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BufferProfile {
+    /// Buffer implementation
+    pub kind: BufferKind,
+    /// Minimum buffer size in bytes
+    pub min_bytes: Option<usize>,
+    /// Number of buffers (only used by [`BufferKind::Slab`])
+    pub n_buffer: Option<usize>,
+    /// Reserved items (only used by [`BufferKind::Slab`])
+    pub reserved_items: Option<usize>,
+}
+
 /// Configuration
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -124,55 +399,123 @@ pub struct Config {
     pub ctrlport_enable: bool,
     /// Control port socket address
     pub ctrlport_bind: Option<SocketAddr>,
+    /// Allow starting flowgraphs through the control port (`POST /api/fg/`)
+    pub ctrlport_start_flowgraphs: bool,
     /// Frontend path for Webserver
     pub frontend_path: Option<PathBuf>,
+    /// Interval (in items) of latency probes, inserted by sources (0 to disable)
+    pub latency_interval: usize,
+    /// Stream buffer size in bytes within fused block chains
+    pub fused_buffer_size: usize,
+    /// Named stream buffer profiles
+    pub buffer_profiles: HashMap<String, BufferProfile>,
+    /// Stream buffer overrides, mapping connections to buffer profiles
+    pub buffer_overrides: HashMap<String, String>,
+    /// Worker threads of blocks by instance or type name, used by the `CpuPinScheduler` and the
+    /// `FlowScheduler`
+    pub cpu_pins: HashMap<String, Vec<usize>>,
+    /// Watch the user and project config files and reload them on changes
+    pub config_watch: bool,
+    /// Directory with block plugins, loaded into the registry of the runtime (requires the
+    /// `plugins` feature)
+    pub plugin_dir: Option<PathBuf>,
     misc: HashMap<String, Value>,
 }
 
 impl Config {
-    fn validate(&self) -> bool {
+    fn validate(&self) -> Result<(), Error> {
         #[cfg(not(target_arch = "wasm32"))]
         if self.ctrlport_enable && self.ctrlport_bind.is_none() {
-            println!("ctrlport enabled but socket not set");
-            return false;
+            return Err(Error::InvalidConfig(
+                "ctrlport_bind".to_string(),
+                "ctrlport enabled but socket not set".to_string(),
+            ));
+        }
+        for (k, v) in self.buffer_overrides.iter() {
+            if !self.buffer_profiles.contains_key(v) {
+                return Err(Error::InvalidConfig(
+                    format!("buffer_overrides.{k}"),
+                    format!("unknown profile {v:?}"),
+                ));
+            }
         }
-        true
+        Ok(())
     }
 
-    fn set_value<V: Into<config::Value>>(&mut self, name: impl Into<String>, value: V) {
-        let name = name.into();
-        let value = value.into();
+    /// Get buffer profile for a stream connection
+    ///
+    /// The `keys` are tried in order. The profile of the first key that has an entry in
+    /// `buffer_overrides` is returned.
+    pub fn buffer_override<S: AsRef<str>>(&self, keys: &[S]) -> Option<&BufferProfile> {
+        keys.iter()
+            .find_map(|k| self.buffer_overrides.get(k.as_ref()))
+            .and_then(|p| self.buffer_profiles.get(p))
+    }
+
+    // apply the value, keeping the config unchanged if the result is invalid
+    fn set_value(&mut self, name: String, value: Value) -> Result<(), Error> {
+        let mut new = self.clone();
+        new.apply(name, value)?;
+        new.validate()?;
+        *self = new;
+        Ok(())
+    }
 
+    fn apply(&mut self, name: String, value: Value) -> Result<(), Error> {
         match name.as_str() {
             "queue_size" => {
-                self.queue_size = config_parse::<usize>(&value);
+                self.queue_size = config_parse::<usize>(&name, &value)?;
             }
             "buffer_size" => {
-                self.buffer_size = config_parse::<usize>(&value);
+                self.buffer_size = config_parse::<usize>(&name, &value)?;
             }
             "stack_size" => {
-                self.stack_size = config_parse::<usize>(&value);
+                self.stack_size = config_parse::<usize>(&name, &value)?;
             }
             "slab_reserved" => {
-                self.slab_reserved = config_parse::<usize>(&value);
+                self.slab_reserved = config_parse::<usize>(&name, &value)?;
             }
             "log_level" => {
-                self.log_level = config_parse::<LevelFilter>(&value);
+                self.log_level = config_parse::<LevelFilter>(&name, &value)?;
             }
             "ctrlport_enable" => {
-                self.ctrlport_enable = config_parse::<bool>(&value);
+                self.ctrlport_enable = config_parse::<bool>(&name, &value)?;
             }
             "ctrlport_bind" => {
-                self.ctrlport_bind = Some(config_parse::<SocketAddr>(&value));
+                self.ctrlport_bind = Some(config_parse::<SocketAddr>(&name, &value)?);
+            }
+            "ctrlport_start_flowgraphs" => {
+                self.ctrlport_start_flowgraphs = config_parse::<bool>(&name, &value)?;
             }
             "frontend_path" => {
-                self.frontend_path = Some(config_parse::<PathBuf>(&value));
+                self.frontend_path = Some(config_parse::<PathBuf>(&name, &value)?);
+            }
+            "latency_interval" => {
+                self.latency_interval = config_parse::<usize>(&name, &value)?;
+            }
+            "fused_buffer_size" => {
+                self.fused_buffer_size = config_parse::<usize>(&name, &value)?;
+            }
+            "buffer_profiles" => {
+                self.buffer_profiles = parse_buffer_profiles(&value)?;
+            }
+            "buffer_overrides" => {
+                self.buffer_overrides = parse_buffer_overrides(&value)?;
+            }
+            "cpu_pins" => {
+                self.cpu_pins = parse_cpu_pins(&value)?;
+            }
+            "config_watch" => {
+                self.config_watch = config_parse::<bool>(&name, &value)?;
+            }
+            "plugin_dir" => {
+                self.plugin_dir = Some(config_parse::<PathBuf>(&name, &value)?);
             }
             _ => {
                 self.misc.insert(name, value);
             }
         }
-        assert!(self.validate());
+        Ok(())
     }
 }
 
@@ -187,7 +530,15 @@ impl Default for Config {
             log_level: LevelFilter::DEBUG,
             ctrlport_enable: true,
             ctrlport_bind: "127.0.0.1:1337".parse::<SocketAddr>().ok(),
+            ctrlport_start_flowgraphs: false,
             frontend_path: None,
+            latency_interval: 0,
+            fused_buffer_size: 16384,
+            buffer_profiles: HashMap::new(),
+            buffer_overrides: HashMap::new(),
+            cpu_pins: HashMap::new(),
+            config_watch: false,
+            plugin_dir: None,
             misc: HashMap::new(),
         }
     }
@@ -202,20 +553,96 @@ impl Default for Config {
             log_level: LevelFilter::INFO,
             ctrlport_enable: true,
             ctrlport_bind: "127.0.0.1:1337".parse::<SocketAddr>().ok(),
+            ctrlport_start_flowgraphs: false,
             frontend_path: None,
+            latency_interval: 0,
+            fused_buffer_size: 16384,
+            buffer_profiles: HashMap::new(),
+            buffer_overrides: HashMap::new(),
+            cpu_pins: HashMap::new(),
+            config_watch: false,
+            plugin_dir: None,
             misc: HashMap::new(),
         }
     }
 }
 
-// #[cfg(not(target_arch = "wasm32"))]
-fn config_parse<T: FromStr>(v: &Value) -> T {
-    if let Ok(v) = v.clone().into_string() {
-        if let Ok(v) = v.parse::<T>() {
-            return v;
-        }
-    }
+fn config_parse<T: FromStr>(name: &str, v: &Value) -> Result<T, Error> {
+    v.clone()
+        .into_string()
+        .ok()
+        .and_then(|s| s.parse::<T>().ok())
+        .ok_or_else(|| Error::InvalidConfig(name.to_string(), format!("invalid value {v}")))
+}
+
+fn parse_buffer_profiles(v: &Value) -> Result<HashMap<String, BufferProfile>, Error> {
+    let profiles = v
+        .clone()
+        .into_table()
+        .map_err(|e| Error::InvalidConfig("buffer_profiles".to_string(), e.to_string()))?;
+
+    profiles
+        .into_iter()
+        .map(|(name, v)| {
+            let key = format!("buffer_profiles.{name}");
+            let table = v
+                .into_table()
+                .map_err(|e| Error::InvalidConfig(key.clone(), e.to_string()))?;
+            let mut profile = BufferProfile {
+                kind: BufferKind::Circular,
+                min_bytes: None,
+                n_buffer: None,
+                reserved_items: None,
+            };
+            for (k, v) in table.iter() {
+                let key = format!("{key}.{k}");
+                match k.as_str() {
+                    "kind" => profile.kind = config_parse::<BufferKind>(&key, v)?,
+                    "min_bytes" => profile.min_bytes = Some(config_parse::<usize>(&key, v)?),
+                    "n_buffer" => profile.n_buffer = Some(config_parse::<usize>(&key, v)?),
+                    "reserved_items" => {
+                        profile.reserved_items = Some(config_parse::<usize>(&key, v)?)
+                    }
+                    _ => return Err(Error::InvalidConfig(key, "unknown key".to_string())),
+                }
+            }
+            Ok((name, profile))
+        })
+        .collect()
+}
+
+fn parse_buffer_overrides(v: &Value) -> Result<HashMap<String, String>, Error> {
+    let overrides = v
+        .clone()
+        .into_table()
+        .map_err(|e| Error::InvalidConfig("buffer_overrides".to_string(), e.to_string()))?;
+
+    overrides
+        .into_iter()
+        .map(|(k, v)| {
+            let p = config_parse::<String>(&format!("buffer_overrides.{k}"), &v)?;
+            Ok((k, p))
+        })
+        .collect()
+}
+
+fn parse_cpu_pins(v: &Value) -> Result<HashMap<String, Vec<usize>>, Error> {
+    let pins = v
+        .clone()
+        .into_table()
+        .map_err(|e| Error::InvalidConfig("cpu_pins".to_string(), e.to_string()))?;
 
-    println!("invalid config value {v:?}");
-    panic!();
+    pins.into_iter()
+        .map(|(k, v)| {
+            let key = format!("cpu_pins.{k}");
+            let workers = match v.clone().into_array() {
+                Ok(a) => a
+                    .iter()
+                    .map(|v| config_parse::<usize>(&key, v))
+                    .collect::<Result<Vec<_>, _>>()?,
+                Err(_) => vec![config_parse::<usize>(&key, &v)?],
+            };
+            Ok((k, workers))
+        })
+        .collect()
 }
@@ -1,15 +1,32 @@
 //! Remote Control through REST API
+use axum::extract::ws::Message;
+use axum::extract::ws::WebSocket;
+use axum::extract::ws::WebSocketUpgrade;
 use axum::extract::Path;
+use axum::extract::Query;
 use axum::extract::State;
+use axum::http::header;
 use axum::http::StatusCode;
 use axum::http::Uri;
 use axum::response::Redirect;
+use axum::response::Response;
 use axum::routing::any;
 use axum::routing::get;
 use axum::routing::get_service;
+use axum::routing::post;
 use axum::Json;
 use axum::Router;
 use futures::channel::oneshot;
+use futures::future;
+use futures::future::select;
+use futures::future::Either;
+use futures::stream;
+use futures::FutureExt;
+use futures::Stream;
+use futures::StreamExt;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
 use std::path;
 use std::thread::JoinHandle;
 use tokio::net::TcpListener;
@@ -19,6 +36,7 @@ use tower_http::services::ServeDir;
 use crate::runtime::config;
 use crate::runtime::BlockDescription;
 use crate::runtime::FlowgraphDescription;
+use crate::runtime::LatencyHistogram;
 use crate::runtime::Pmt;
 use crate::runtime::PortId;
 use crate::runtime::RuntimeHandle;
@@ -37,6 +55,74 @@ async fn flowgraphs(State(rt): State<RuntimeHandle>) -> Json<Vec<usize>> {
     Json::from(rt.get_flowgraphs())
 }
 
+async fn start_flowgraph(
+    State(rt): State<RuntimeHandle>,
+    description: String,
+) -> Result<Json<usize>, (StatusCode, String)> {
+    if !config::config().ctrlport_start_flowgraphs {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "starting flowgraphs is disabled (ctrlport_start_flowgraphs)".to_string(),
+        ));
+    }
+    match rt.start_str(&description).await {
+        Ok((id, _)) => Ok(Json::from(id)),
+        Err(e) => Err((StatusCode::BAD_REQUEST, e.to_string())),
+    }
+}
+
+async fn terminate_flowgraph(
+    Path(fg): Path<usize>,
+    State(rt): State<RuntimeHandle>,
+) -> Result<(), StatusCode> {
+    let fg = rt.get_flowgraph(fg);
+    if let Some(mut fg) = fg {
+        if fg.terminate().await.is_ok() {
+            return Ok(());
+        }
+    }
+    Err(StatusCode::BAD_REQUEST)
+}
+
+async fn flowgraph_events(
+    Path(fg): Path<usize>,
+    State(rt): State<RuntimeHandle>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, StatusCode> {
+    let mut fg = rt.get_flowgraph(fg).ok_or(StatusCode::BAD_REQUEST)?;
+    let events = fg.subscribe().await.or(Err(StatusCode::BAD_REQUEST))?;
+
+    Ok(ws.on_upgrade(|socket| forward(events, socket)))
+}
+
+async fn block_tap(
+    Path((fg, blk, port)): Path<(usize, usize, String)>,
+    State(rt): State<RuntimeHandle>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, StatusCode> {
+    let mut fg = rt.get_flowgraph(fg).ok_or(StatusCode::BAD_REQUEST)?;
+    let port = match port.parse::<usize>() {
+        Ok(i) => PortId::Index(i),
+        Err(_) => PortId::Name(port),
+    };
+    let messages = fg.tap(blk, port).await.or(Err(StatusCode::BAD_REQUEST))?;
+
+    Ok(ws.on_upgrade(|socket| forward(messages, socket)))
+}
+
+/// Send items as JSON over the WebSocket, closing it when the stream ends
+async fn forward<T: Serialize>(mut items: impl Stream<Item = T> + Unpin, mut socket: WebSocket) {
+    while let Some(i) = items.next().await {
+        let Ok(json) = serde_json::to_string(&i) else {
+            continue;
+        };
+        if socket.send(Message::Text(json.into())).await.is_err() {
+            return;
+        }
+    }
+    let _ = socket.send(Message::Close(None)).await;
+}
+
 async fn flowgraph_description(
     Path(fg): Path<usize>,
     State(rt): State<RuntimeHandle>,
@@ -64,6 +150,70 @@ async fn block_description(
     Err(StatusCode::BAD_REQUEST)
 }
 
+async fn flowgraph_latency(
+    Path(fg): Path<usize>,
+    State(rt): State<RuntimeHandle>,
+) -> Result<Json<HashMap<usize, LatencyHistogram>>, StatusCode> {
+    let fg = rt.get_flowgraph(fg);
+    if let Some(mut fg) = fg {
+        if let Ok(l) = fg.latency().await {
+            return Ok(Json::from(l));
+        }
+    }
+    Err(StatusCode::BAD_REQUEST)
+}
+
+#[derive(Deserialize)]
+struct DiagramQuery {
+    /// Annotate blocks with latency statistics
+    #[serde(default)]
+    stats: bool,
+}
+
+/// Render a flowgraph, optionally with live latency statistics
+async fn diagram(
+    rt: &RuntimeHandle,
+    fg: usize,
+    stats: bool,
+    render: impl Fn(&FlowgraphDescription, &HashMap<usize, LatencyHistogram>) -> String,
+) -> Result<String, StatusCode> {
+    let mut fg = rt.get_flowgraph(fg).ok_or(StatusCode::BAD_REQUEST)?;
+    let d = fg.description().await.or(Err(StatusCode::BAD_REQUEST))?;
+    let latency = if stats {
+        fg.latency().await.or(Err(StatusCode::BAD_REQUEST))?
+    } else {
+        HashMap::new()
+    };
+    Ok(render(&d, &latency))
+}
+
+async fn flowgraph_mermaid(
+    Path(fg): Path<usize>,
+    Query(q): Query<DiagramQuery>,
+    State(rt): State<RuntimeHandle>,
+) -> Result<([(header::HeaderName, &'static str); 1], String), StatusCode> {
+    let g = diagram(
+        &rt,
+        fg,
+        q.stats,
+        FlowgraphDescription::to_mermaid_with_stats,
+    )
+    .await?;
+    Ok(([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], g))
+}
+
+async fn flowgraph_dot(
+    Path(fg): Path<usize>,
+    Query(q): Query<DiagramQuery>,
+    State(rt): State<RuntimeHandle>,
+) -> Result<([(header::HeaderName, &'static str); 1], String), StatusCode> {
+    let g = diagram(&rt, fg, q.stats, FlowgraphDescription::to_dot_with_stats).await?;
+    Ok((
+        [(header::CONTENT_TYPE, "text/vnd.graphviz; charset=utf-8")],
+        g,
+    ))
+}
+
 async fn handler_id(
     Path((fg, blk, handler)): Path<(usize, usize, String)>,
     State(rt): State<RuntimeHandle>,
@@ -101,6 +251,22 @@ async fn handler_id_post(
     Err(StatusCode::BAD_REQUEST)
 }
 
+async fn serve(app: Router) {
+    let c = config::config();
+    if c.ctrlport_enable {
+        let addr = c.ctrlport_bind.unwrap();
+        if let Ok(listener) = TcpListener::bind(&addr).await {
+            debug!("Listening on {}", addr);
+            axum::serve(listener, app.into_make_service())
+                .await
+                .unwrap();
+        } else {
+            warn!("CtrlPort address {} already in use", addr);
+        }
+    }
+    future::pending::<()>().await;
+}
+
 pub struct ControlPort {
     thread: Option<(oneshot::Sender<()>, JoinHandle<()>)>,
     handle: RuntimeHandle,
@@ -117,7 +283,9 @@ impl ControlPort {
     }
 
     fn start(&mut self, custom_routes: Option<Router>) {
-        if !config::config().ctrlport_enable {
+        // with a watched config, the control port can be enabled later on
+        let c = config::config();
+        if !c.ctrlport_enable && !c.config_watch {
             return;
         }
 
@@ -126,13 +294,19 @@ impl ControlPort {
         }
 
         let mut app = Router::new()
-            .route("/api/fg/", get(flowgraphs))
+            .route("/api/fg/", get(flowgraphs).post(start_flowgraph))
             .route("/api/fg/{fg}/", get(flowgraph_description))
+            .route("/api/fg/{fg}/terminate/", post(terminate_flowgraph))
+            .route("/api/fg/{fg}/events/", get(flowgraph_events))
+            .route("/api/fg/{fg}/latency/", get(flowgraph_latency))
+            .route("/api/fg/{fg}/mermaid/", get(flowgraph_mermaid))
+            .route("/api/fg/{fg}/dot/", get(flowgraph_dot))
             .route("/api/fg/{fg}/block/{blk}/", get(block_description))
             .route(
                 "/api/fg/{fg}/block/{blk}/call/{handler}/",
                 get(handler_id).post(handler_id_post),
             )
+            .route("/api/fg/{fg}/block/{blk}/tap/{port}/", get(block_tap))
             .route(
                 "/api/block/{*foo}",
                 any(|uri: Uri| async move {
@@ -168,14 +342,17 @@ impl ControlPort {
                 .unwrap();
 
             runtime.spawn(async move {
-                let addr = config::config().ctrlport_bind.unwrap();
-                if let Ok(listener) = TcpListener::bind(&addr).await {
-                    debug!("Listening on {}", addr);
-                    axum::serve(listener, app.into_make_service())
-                        .await
-                        .unwrap();
-                } else {
-                    warn!("CtrlPort address {} already in use", addr);
+                let mut updates = stream::select(
+                    config::subscribe("ctrlport_enable"),
+                    config::subscribe("ctrlport_bind"),
+                );
+                // restart the server when its settings change
+                loop {
+                    let server = serve(app.clone()).boxed();
+                    if let Either::Right((None, _)) = select(server, updates.next()).await {
+                        break;
+                    }
+                    debug!("restarting control port");
                 }
             });
 
@@ -0,0 +1,597 @@
+use crate::runtime::BlockArgs;
+use crate::runtime::BlockRegistry;
+use crate::runtime::Error;
+use crate::runtime::Flowgraph;
+use crate::runtime::Pmt;
+use crate::runtime::PortId;
+use crate::runtime::PortKind;
+
+#[derive(Clone, Copy, Debug)]
+struct Pos {
+    line: usize,
+    column: usize,
+}
+
+impl Pos {
+    fn error(&self, msg: impl Into<String>) -> Error {
+        Error::DslError(self.line, self.column, msg.into())
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Port {
+    Name(String),
+    Wildcard(String),
+    Range(String, usize, usize),
+}
+
+impl Port {
+    fn resolve(&self, fg: &Flowgraph, block: usize, kind: PortKind) -> Result<Vec<PortId>, Error> {
+        match self {
+            Port::Name(n) => Ok(vec![PortId::Name(n.clone())]),
+            Port::Wildcard(p) => fg.numbered_ports(block, p, kind),
+            Port::Range(p, start, end) => Ok((*start..*end)
+                .map(|i| PortId::Name(format!("{p}{i}")))
+                .collect()),
+        }
+    }
+
+    fn describe(&self, block: &str) -> String {
+        match self {
+            Port::Name(n) => format!("{block}.{n}"),
+            Port::Wildcard(p) => format!("{block}.{p}*"),
+            Port::Range(p, start, end) => format!("{block}.{p}[{start}..{end}]"),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Endpoint {
+    pos: Pos,
+    block: String,
+    input: Port,
+    output: Port,
+}
+
+#[derive(Debug)]
+enum Op {
+    Stream(Option<String>),
+    Message,
+}
+
+#[derive(Debug)]
+enum Statement {
+    Block {
+        pos: Pos,
+        name: String,
+        type_name: String,
+        args: BlockArgs,
+    },
+    Connection {
+        endpoints: Vec<Endpoint>,
+        ops: Vec<(Pos, Op)>,
+    },
+}
+
+/// Parse the description and apply it to the flowgraph
+///
+/// Blocks are declared first, so that connections can refer to blocks, which are declared further
+/// down. If a statement fails, the blocks and connections of the description are removed again,
+/// leaving the flowgraph unchanged.
+pub(crate) fn connect_str(
+    fg: &mut Flowgraph,
+    registry: &BlockRegistry,
+    s: &str,
+) -> Result<(), Error> {
+    let statements = Parser::new(s).parse()?;
+
+    let checkpoint = fg.topology.as_ref().unwrap().checkpoint();
+    let result = apply(fg, registry, &statements);
+    if result.is_err() {
+        fg.topology.as_mut().unwrap().rollback(checkpoint);
+    }
+    result
+}
+
+fn apply(
+    fg: &mut Flowgraph,
+    registry: &BlockRegistry,
+    statements: &[Statement],
+) -> Result<(), Error> {
+    for s in statements.iter() {
+        if let Statement::Block {
+            pos,
+            name,
+            type_name,
+            args,
+        } = s
+        {
+            let mut block = registry
+                .create(type_name, args)
+                .map_err(|e| pos.error(e.to_string()))?;
+            block.set_instance_name(name);
+            fg.add_block(block).map_err(|e| pos.error(e.to_string()))?;
+        }
+    }
+
+    for s in statements.iter() {
+        if let Statement::Connection { endpoints, ops } = s {
+            let ids = endpoints
+                .iter()
+                .map(|e| {
+                    fg.topology
+                        .as_ref()
+                        .unwrap()
+                        .block_id(&e.block)
+                        .ok_or_else(|| e.pos.error(format!("Block '{}' does not exist", e.block)))
+                })
+                .collect::<Result<Vec<usize>, Error>>()?;
+
+            for (i, (pos, op)) in ops.iter().enumerate() {
+                let (src, dst) = (&endpoints[i], &endpoints[i + 1]);
+                connect(fg, op, src, ids[i], dst, ids[i + 1])
+                    .map_err(|e| pos.error(e.to_string()))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn connect(
+    fg: &mut Flowgraph,
+    op: &Op,
+    src: &Endpoint,
+    src_id: usize,
+    dst: &Endpoint,
+    dst_id: usize,
+) -> Result<(), Error> {
+    let (src_kind, dst_kind) = match op {
+        Op::Stream(_) => (PortKind::StreamOutput, PortKind::StreamInput),
+        Op::Message => (PortKind::MessageOutput, PortKind::MessageInput),
+    };
+    let src_ports = src.output.resolve(fg, src_id, src_kind)?;
+    let dst_ports = dst.input.resolve(fg, dst_id, dst_kind)?;
+
+    let pairs: Vec<(PortId, PortId)> = if src_ports.len() == dst_ports.len() {
+        src_ports.into_iter().zip(dst_ports).collect()
+    } else if src_ports.len() == 1 {
+        dst_ports
+            .into_iter()
+            .map(|d| (src_ports[0].clone(), d))
+            .collect()
+    } else if dst_ports.len() == 1 {
+        src_ports
+            .into_iter()
+            .map(|s| (s, dst_ports[0].clone()))
+            .collect()
+    } else {
+        return Err(Error::PortCountMismatch(
+            src.output.describe(&src.block),
+            src_ports.len(),
+            dst.input.describe(&dst.block),
+            dst_ports.len(),
+        ));
+    };
+
+    for (s, d) in pairs {
+        match op {
+            Op::Stream(Some(profile)) => {
+                fg.connect_stream_with_profile(src_id, s, dst_id, d, profile)?
+            }
+            Op::Stream(None) => fg.connect_stream(src_id, s, dst_id, d)?,
+            Op::Message => fg.connect_message(src_id, s, dst_id, d)?,
+        }
+    }
+    Ok(())
+}
+
+#[derive(Clone, Copy)]
+struct State {
+    index: usize,
+    line: usize,
+    column: usize,
+}
+
+struct Parser {
+    chars: Vec<char>,
+    state: State,
+}
+
+impl Parser {
+    fn new(s: &str) -> Self {
+        Parser {
+            chars: s.chars().collect(),
+            state: State {
+                index: 0,
+                line: 1,
+                column: 1,
+            },
+        }
+    }
+
+    fn pos(&self) -> Pos {
+        Pos {
+            line: self.state.line,
+            column: self.state.column,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.state.index).copied()
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<char> {
+        self.chars.get(self.state.index + n).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.state.index += 1;
+        if c == '\n' {
+            self.state.line += 1;
+            self.state.column = 1;
+        } else {
+            self.state.column += 1;
+        }
+        Some(c)
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), Error> {
+        match self.peek() {
+            Some(x) if x == c => {
+                self.bump();
+                Ok(())
+            }
+            Some(x) => Err(self.pos().error(format!("Expected '{c}', found '{x}'"))),
+            None => Err(self
+                .pos()
+                .error(format!("Expected '{c}', found end of input"))),
+        }
+    }
+
+    /// Skip whitespace and comments, optionally including line breaks
+    fn skip(&mut self, newlines: bool) {
+        loop {
+            match self.peek() {
+                Some(' ' | '\t' | '\r') => {
+                    self.bump();
+                }
+                Some('\n') if newlines => {
+                    self.bump();
+                }
+                Some('#') => self.skip_comment(),
+                Some('/') if self.peek_nth(1) == Some('/') => self.skip_comment(),
+                _ => break,
+            }
+        }
+    }
+
+    fn skip_comment(&mut self) {
+        while !matches!(self.peek(), None | Some('\n')) {
+            self.bump();
+        }
+    }
+
+    fn parse(mut self) -> Result<Vec<Statement>, Error> {
+        let mut statements = Vec::new();
+        loop {
+            self.skip(true);
+            match self.peek() {
+                None => break,
+                Some(';') => {
+                    self.bump();
+                    continue;
+                }
+                _ => (),
+            }
+
+            statements.push(self.statement()?);
+
+            self.skip(false);
+            match self.peek() {
+                None => break,
+                Some(';' | '\n') => {
+                    self.bump();
+                }
+                Some(c) => {
+                    return Err(self.pos().error(format!(
+                        "Expected connection, ';', or line break, found '{c}'"
+                    )))
+                }
+            }
+        }
+        Ok(statements)
+    }
+
+    fn statement(&mut self) -> Result<Statement, Error> {
+        let start = self.state;
+        let pos = self.pos();
+        let name = self.name()?;
+        self.skip(false);
+        if self.peek() != Some('=') {
+            self.state = start;
+            return self.connection();
+        }
+
+        self.bump();
+        self.skip(false);
+        let type_name = self.name()?;
+        self.skip(false);
+        let args = if self.peek() == Some('(') {
+            self.args()?
+        } else {
+            BlockArgs::new()
+        };
+        Ok(Statement::Block {
+            pos,
+            name,
+            type_name,
+            args,
+        })
+    }
+
+    fn connection(&mut self) -> Result<Statement, Error> {
+        let mut endpoints = vec![self.endpoint()?];
+        let mut ops = Vec::new();
+        loop {
+            self.skip(false);
+            let pos = self.pos();
+            let op = match self.peek() {
+                Some('>') => {
+                    self.bump();
+                    Op::Stream(None)
+                }
+                Some('|') => {
+                    self.bump();
+                    Op::Message
+                }
+                Some('[') => {
+                    self.bump();
+                    self.skip(false);
+                    if self.peek() != Some('"') {
+                        return Err(self.pos().error("Expected buffer profile name"));
+                    }
+                    let profile = self.string()?;
+                    self.skip(false);
+                    self.expect(']')?;
+                    Op::Stream(Some(profile))
+                }
+                _ => break,
+            };
+            // connections can continue on the next line
+            self.skip(true);
+            ops.push((pos, op));
+            endpoints.push(self.endpoint()?);
+        }
+        Ok(Statement::Connection { endpoints, ops })
+    }
+
+    fn endpoint(&mut self) -> Result<Endpoint, Error> {
+        let pos = self.pos();
+        let mut items = Vec::new();
+        loop {
+            let item_pos = self.pos();
+            let name = self.name()?;
+            let port = if self.peek() == Some('*') {
+                self.bump();
+                Port::Wildcard(name)
+            } else if self.is_range() {
+                let (start, end) = self.range()?;
+                Port::Range(name, start, end)
+            } else {
+                Port::Name(name)
+            };
+            items.push((item_pos, port));
+
+            if self.peek() == Some('.') && items.len() < 3 {
+                self.bump();
+            } else {
+                break;
+            }
+        }
+
+        let block_index = if items.len() == 3 { 1 } else { 0 };
+        let block = match &items[block_index] {
+            (_, Port::Name(n)) => n.clone(),
+            (p, _) => return Err(p.error("Expected block name without index or wildcard")),
+        };
+
+        let (input, output) = match items.len() {
+            1 => (Port::Name("in".into()), Port::Name("out".into())),
+            2 => (items[1].1.clone(), items[1].1.clone()),
+            _ => (items[0].1.clone(), items[2].1.clone()),
+        };
+
+        Ok(Endpoint {
+            pos,
+            block,
+            input,
+            output,
+        })
+    }
+
+    /// An index range like `[0..4]`, as opposed to a buffer profile like `["hot"]`
+    fn is_range(&self) -> bool {
+        if self.peek() != Some('[') {
+            return false;
+        }
+        let mut i = 1;
+        while matches!(self.peek_nth(i), Some(' ' | '\t')) {
+            i += 1;
+        }
+        matches!(self.peek_nth(i), Some(c) if c.is_ascii_digit())
+    }
+
+    fn range(&mut self) -> Result<(usize, usize), Error> {
+        self.expect('[')?;
+        self.skip(false);
+        let start = self.number()?;
+        self.skip(false);
+        self.expect('.')?;
+        self.expect('.')?;
+        let inclusive = if self.peek() == Some('=') {
+            self.bump();
+            true
+        } else {
+            false
+        };
+        self.skip(false);
+        let pos = self.pos();
+        let end = self.number()? + usize::from(inclusive);
+        self.skip(false);
+        self.expect(']')?;
+        if end <= start {
+            return Err(pos.error("Empty port range"));
+        }
+        Ok((start, end))
+    }
+
+    fn number(&mut self) -> Result<usize, Error> {
+        let pos = self.pos();
+        let mut s = String::new();
+        while let Some(c) = self.peek().filter(char::is_ascii_digit) {
+            s.push(c);
+            self.bump();
+        }
+        s.parse().map_err(|_| pos.error("Expected number"))
+    }
+
+    /// A block, block type, or port name, which is either quoted or consists of alphanumeric
+    /// characters, `_`, and `-`
+    fn name(&mut self) -> Result<String, Error> {
+        if self.peek() == Some('"') {
+            return self.string();
+        }
+
+        let mut s = String::new();
+        while let Some(c) = self
+            .peek()
+            .filter(|c| c.is_alphanumeric() || *c == '_' || *c == '-')
+        {
+            s.push(c);
+            self.bump();
+        }
+
+        if s.is_empty() {
+            match self.peek() {
+                Some(c) => Err(self.pos().error(format!("Expected name, found '{c}'"))),
+                None => Err(self.pos().error("Expected name, found end of input")),
+            }
+        } else {
+            Ok(s)
+        }
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        let pos = self.pos();
+        self.expect('"')?;
+        let mut s = String::new();
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(s),
+                Some('\\') => match self.bump() {
+                    Some('n') => s.push('\n'),
+                    Some('t') => s.push('\t'),
+                    Some(c) => s.push(c),
+                    None => break,
+                },
+                Some(c) => s.push(c),
+                None => break,
+            }
+        }
+        Err(pos.error("Unterminated string"))
+    }
+
+    fn args(&mut self) -> Result<BlockArgs, Error> {
+        let mut args = BlockArgs::new();
+        self.expect('(')?;
+        loop {
+            self.skip(true);
+            if self.peek() == Some(')') {
+                self.bump();
+                break;
+            }
+
+            let pos = self.pos();
+            let key = self.name()?;
+            self.skip(false);
+            self.expect('=')?;
+            self.skip(false);
+            let value = self.value()?;
+            if args.insert(key.clone(), value).is_some() {
+                return Err(pos.error(format!("Duplicate argument '{key}'")));
+            }
+
+            self.skip(true);
+            match self.peek() {
+                Some(',') => {
+                    self.bump();
+                }
+                Some(')') => {
+                    self.bump();
+                    break;
+                }
+                Some(c) => {
+                    return Err(self
+                        .pos()
+                        .error(format!("Expected ',' or ')', found '{c}'")))
+                }
+                None => return Err(self.pos().error("Expected ')', found end of input")),
+            }
+        }
+        Ok(args)
+    }
+
+    /// An argument value
+    ///
+    /// Strings, booleans, and numbers map to the corresponding [`Pmt`]. Everything else is parsed
+    /// with [`Pmt`]'s `FromStr` implementation, e.g., `{ "U32": 5 }`.
+    fn value(&mut self) -> Result<Pmt, Error> {
+        let pos = self.pos();
+        if self.peek() == Some('"') {
+            return Ok(Pmt::String(self.string()?));
+        }
+
+        let mut s = String::new();
+        let mut depth = 0usize;
+        let mut quoted = false;
+        while let Some(c) = self.peek() {
+            match c {
+                '"' => quoted = !quoted,
+                '\\' if quoted => {
+                    s.push(c);
+                    self.bump();
+                }
+                '(' | '[' | '{' if !quoted => depth += 1,
+                ')' | ']' | '}' if !quoted && depth > 0 => depth -= 1,
+                ',' | ')' | '\n' | ';' if !quoted && depth == 0 => break,
+                _ => (),
+            }
+            if let Some(c) = self.bump() {
+                s.push(c);
+            }
+        }
+
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(pos.error("Expected value"));
+        }
+        match s {
+            "true" => return Ok(Pmt::Bool(true)),
+            "false" => return Ok(Pmt::Bool(false)),
+            _ => (),
+        }
+        if let Ok(v) = s.parse::<usize>() {
+            return Ok(Pmt::Usize(v));
+        }
+        if let Ok(v) = s.parse::<isize>() {
+            return Ok(Pmt::Isize(v));
+        }
+        if let Ok(v) = s.parse::<f64>() {
+            return Ok(Pmt::F64(v));
+        }
+        s.parse::<Pmt>()
+            .map_err(|_| pos.error(format!("Invalid value '{s}'")))
+    }
+}
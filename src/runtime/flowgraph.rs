@@ -1,7 +1,10 @@
+use futures::channel::mpsc::channel;
+use futures::channel::mpsc::Receiver;
 use futures::channel::mpsc::Sender;
 use futures::channel::oneshot;
 use futures::SinkExt;
 use std::cmp::PartialEq;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::hash::Hash;
 
@@ -11,15 +14,21 @@ use crate::runtime::buffer::circular::Circular;
 use crate::runtime::buffer::slab::Slab;
 use crate::runtime::buffer::BufferBuilder;
 use crate::runtime::buffer::BufferWriter;
+use crate::runtime::config;
 use crate::runtime::Block;
 use crate::runtime::BlockDescription;
 use crate::runtime::BlockMessage;
+use crate::runtime::BlockPortCtx;
+use crate::runtime::BlockRegistry;
 use crate::runtime::Error;
 use crate::runtime::FlowgraphDescription;
+use crate::runtime::FlowgraphEvent;
 use crate::runtime::FlowgraphMessage;
 use crate::runtime::Kernel;
+use crate::runtime::LatencyHistogram;
 use crate::runtime::Pmt;
 use crate::runtime::PortId;
+use crate::runtime::PortKind;
 use crate::runtime::Topology;
 
 /// The main component of any FutureSDR program.
@@ -43,7 +52,15 @@ impl Flowgraph {
         self.topology.as_mut().unwrap().add_block(block.into())
     }
 
+    /// Get Id of a block, given its instance name
+    pub fn block_id(&self, name: &str) -> Option<usize> {
+        self.topology.as_ref().unwrap().block_id(name)
+    }
+
     /// Make stream connection
+    ///
+    /// Uses the buffer profile, configured for this connection in the `buffer_overrides` of the
+    /// [config](crate::runtime::config), or the default buffer, if there is none.
     pub fn connect_stream(
         &mut self,
         src_block: usize,
@@ -51,12 +68,42 @@ impl Flowgraph {
         dst_block: usize,
         dst_port: impl Into<PortId>,
     ) -> Result<(), Error> {
-        self.topology.as_mut().unwrap().connect_stream(
+        let src_port = src_port.into();
+        let dst_port = dst_port.into();
+        let topology = self.topology.as_mut().unwrap();
+        match topology.buffer_override(src_block, src_port.clone(), dst_block, dst_port.clone())? {
+            Some(profile) => topology
+                .connect_stream_with_profile(src_block, src_port, dst_block, dst_port, &profile),
+            None => topology.connect_stream(
+                src_block,
+                src_port,
+                dst_block,
+                dst_port,
+                DefaultBuffer::new(),
+            ),
+        }
+    }
+
+    /// Make stream connection, using the named buffer profile from the config
+    pub fn connect_stream_with_profile(
+        &mut self,
+        src_block: usize,
+        src_port: impl Into<PortId>,
+        dst_block: usize,
+        dst_port: impl Into<PortId>,
+        profile: &str,
+    ) -> Result<(), Error> {
+        let p = config::config()
+            .buffer_profiles
+            .get(profile)
+            .cloned()
+            .ok_or_else(|| Error::InvalidBufferProfile(profile.to_string()))?;
+        self.topology.as_mut().unwrap().connect_stream_with_profile(
             src_block,
             src_port.into(),
             dst_block,
             dst_port.into(),
-            DefaultBuffer::new(),
+            &p,
         )
     }
 
@@ -94,6 +141,73 @@ impl Flowgraph {
         )
     }
 
+    /// Connect config value to message input port
+    ///
+    /// While the flowgraph is running, every change of the value, through
+    /// [`config::set`](crate::runtime::config::set) or a reload of the config files, is sent to the
+    /// port as [`Pmt`]. This allows, for example, adjusting the gain of a headless receiver by
+    /// editing its `config.toml`, if `config_watch` is enabled.
+    pub fn connect_config(
+        &mut self,
+        key: impl Into<String>,
+        dst_block: usize,
+        dst_port: impl Into<PortId>,
+    ) -> Result<(), Error> {
+        self.topology
+            .as_mut()
+            .unwrap()
+            .connect_config(key.into(), dst_block, dst_port.into())
+    }
+
+    /// Add and connect blocks, described in the syntax of the [`connect`](crate::macros::connect)
+    /// macro
+    ///
+    /// Blocks are referred to by their instance name. Statements are separated by `;` or line
+    /// breaks and `#` or `//` start comments. Additionally, blocks can be declared with their type
+    /// name in the registry and arguments, which makes it possible to load a whole flowgraph from
+    /// a text file.
+    ///
+    /// ```text
+    /// src = NullSource(type = "c32")
+    /// head = Head(type = "c32", n = 1000000)
+    /// snk = NullSink(type = "c32")
+    ///
+    /// src > head ["hot"] snk
+    /// msg.out | existing.in   # connect blocks that were added before
+    /// ```
+    ///
+    /// Errors point to the line and column of the statement that caused them.
+    pub fn connect_str(&mut self, blocks: &BlockRegistry, s: &str) -> Result<(), Error> {
+        crate::runtime::dsl::connect_str(self, blocks, s)
+    }
+
+    /// Get the numbered ports `<prefix>0`, `<prefix>1`, ... of a block
+    ///
+    /// Numbering starts at 0 or, if there is no such port, at 1 and ends before the first missing
+    /// number. Used by the [`connect`](crate::macros::connect) macro for wildcard ports, like
+    /// `src.out* > snk.in*`.
+    pub fn numbered_ports(
+        &self,
+        block: usize,
+        prefix: &str,
+        kind: PortKind,
+    ) -> Result<Vec<PortId>, Error> {
+        self.topology
+            .as_ref()
+            .unwrap()
+            .numbered_ports(block, prefix, kind)
+    }
+
+    /// Optimize flowgraph before running it
+    ///
+    /// Fuses linear chains of synchronous blocks, running each chain as one task with small
+    /// intermediate buffers (see [`Topology::fuse`]). Call this after all connections are made.
+    ///
+    /// Returns the fused chains as lists of block ids.
+    pub fn optimize(&mut self) -> Vec<Vec<usize>> {
+        self.topology.as_mut().unwrap().fuse()
+    }
+
     /// Try to get kernel from given block
     pub fn kernel<T: Kernel + 'static>(&self, id: usize) -> Option<&T> {
         self.topology
@@ -202,6 +316,68 @@ impl FlowgraphHandle {
         Ok(d)
     }
 
+    /// Get [`LatencyHistograms`](LatencyHistogram) of all sinks, indexed by block id
+    ///
+    /// Only available, if latency measurement is enabled through the `latency_interval` config.
+    pub async fn latency(&mut self) -> Result<HashMap<usize, LatencyHistogram>, Error> {
+        let (tx, rx) = oneshot::channel::<HashMap<usize, LatencyHistogram>>();
+        self.inbox
+            .send(FlowgraphMessage::Latency { tx })
+            .await
+            .or(Err(Error::FlowgraphTerminated))?;
+        let l = rx.await.or(Err(Error::FlowgraphTerminated))?;
+        Ok(l)
+    }
+
+    /// Subscribe to [`FlowgraphEvents`](FlowgraphEvent)
+    ///
+    /// The stream ends after [`FlowgraphEvent::Terminated`]. Events are dropped, if the subscriber
+    /// does not keep up and its queue is full.
+    pub async fn subscribe(&mut self) -> Result<Receiver<FlowgraphEvent>, Error> {
+        let (tx, rx) = channel::<FlowgraphEvent>(config::config().queue_size);
+        self.inbox
+            .send(FlowgraphMessage::Subscribe { tx })
+            .await
+            .or(Err(Error::FlowgraphTerminated))?;
+        Ok(rx)
+    }
+
+    /// Tap a message output of a block
+    ///
+    /// Returns a stream of the messages that the block posts to the output, in addition to the
+    /// connected message inputs. The block is not slowed down by the tap; messages are dropped, if
+    /// the receiver does not keep up. The stream ends with [`Pmt::Finished`], when the block
+    /// terminates.
+    pub async fn tap(
+        &mut self,
+        block_id: usize,
+        port_id: impl Into<PortId>,
+    ) -> Result<Receiver<Pmt>, Error> {
+        let port_id = port_id.into();
+        let outputs = self.block_description(block_id).await?.message_outputs;
+        let port = match &port_id {
+            PortId::Index(i) if *i < outputs.len() => Some(*i),
+            PortId::Index(_) => None,
+            PortId::Name(n) => outputs.iter().position(|o| o == n),
+        }
+        .ok_or(Error::InvalidMessagePort(
+            BlockPortCtx::Id(block_id),
+            port_id,
+        ))?;
+
+        let (tx, rx) = channel::<Pmt>(config::config().queue_size);
+        self.inbox
+            .send(FlowgraphMessage::Tap { block_id, port, tx })
+            .await
+            .or(Err(Error::FlowgraphTerminated))?;
+        Ok(rx)
+    }
+
+    /// Check if the [`Flowgraph`] is terminated
+    pub fn is_terminated(&self) -> bool {
+        self.inbox.is_closed()
+    }
+
     /// Send a terminate message to the [`Flowgraph`]
     ///
     /// Does not wait until the [`Flowgraph`] is actually terminated.
@@ -260,4 +436,13 @@ impl BufferBuilder for DefaultBuffer {
     ) -> BufferWriter {
         Slab::new().build(item_size, writer_inbox, writer_output_id)
     }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn description(&self) -> String {
+        Circular::new().to_string()
+    }
+    #[cfg(target_arch = "wasm32")]
+    fn description(&self) -> String {
+        Slab::new().to_string()
+    }
 }
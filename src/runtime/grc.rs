@@ -0,0 +1,1227 @@
+//! Import of GNU Radio Companion flowgraphs
+use futuredsp::firdes;
+use futuredsp::windows;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::runtime::BlockArgs;
+use crate::runtime::BlockRegistry;
+use crate::runtime::Error;
+use crate::runtime::Flowgraph;
+use crate::runtime::Pmt;
+
+#[derive(Deserialize)]
+struct File {
+    #[serde(default)]
+    blocks: Vec<FileBlock>,
+    #[serde(default)]
+    connections: Vec<Vec<serde_yaml::Value>>,
+}
+
+#[derive(Deserialize)]
+struct FileBlock {
+    name: String,
+    id: String,
+    #[serde(default)]
+    parameters: HashMap<String, serde_yaml::Value>,
+    #[serde(default)]
+    states: HashMap<String, serde_yaml::Value>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    Enabled,
+    Disabled,
+    Bypassed,
+}
+
+impl FileBlock {
+    fn state(&self) -> State {
+        match self.states.get("state").map(to_string).as_deref() {
+            Some("disabled") | Some("False") => State::Disabled,
+            Some("bypassed") => State::Bypassed,
+            _ => State::Enabled,
+        }
+    }
+
+    /// Variables and GUI widgets that only provide a value
+    fn is_variable(&self) -> bool {
+        self.id == "parameter"
+            || (self.id.starts_with("variable") && self.parameters.contains_key("value"))
+    }
+
+    /// Blocks without function in the flowgraph
+    fn is_decoration(&self) -> bool {
+        matches!(
+            self.id.as_str(),
+            "import" | "note" | "snippet" | "epy_module" | "virtual_sink" | "virtual_source"
+        ) || (self.id.starts_with("variable") && !self.is_variable())
+    }
+}
+
+/// Block of an imported flowgraph, i.e., a type name and arguments for the [`BlockRegistry`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct GrcBlock {
+    /// Instance name
+    pub name: String,
+    /// Type name in the [`BlockRegistry`]
+    pub type_name: String,
+    /// Arguments of the constructor
+    pub args: BlockArgs,
+}
+
+/// Connection of an imported flowgraph
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GrcConnection {
+    /// Instance name of the source block
+    pub src: String,
+    /// Output port
+    pub src_port: String,
+    /// Instance name of the destination block
+    pub dst: String,
+    /// Input port
+    pub dst_port: String,
+    /// Message connection, stream connection otherwise
+    pub message: bool,
+}
+
+/// Blocks and connections that could not be imported
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GrcReport {
+    /// Blocks without FutureSDR equivalent (name, GRC block id, and reason)
+    pub unmapped: Vec<(String, String, String)>,
+    /// Connections that were dropped, since a block or port was not mapped
+    pub dropped: Vec<String>,
+    /// Blocks that were imported with limitations, e.g., Qt GUI sinks that are replaced by a
+    /// `NullSink` (name and note)
+    pub notes: Vec<(String, String)>,
+    /// Disabled blocks, which are skipped
+    pub disabled: Vec<String>,
+}
+
+impl GrcReport {
+    /// Check if all enabled blocks and their connections were imported
+    pub fn is_complete(&self) -> bool {
+        self.unmapped.is_empty() && self.dropped.is_empty()
+    }
+}
+
+impl fmt::Display for GrcReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, id, reason) in self.unmapped.iter() {
+            writeln!(f, "unmapped  {name} ({id}): {reason}")?;
+        }
+        for c in self.dropped.iter() {
+            writeln!(f, "dropped   {c}")?;
+        }
+        for (name, note) in self.notes.iter() {
+            writeln!(f, "note      {name}: {note}")?;
+        }
+        for name in self.disabled.iter() {
+            writeln!(f, "disabled  {name}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Flowgraph, imported from a GNU Radio Companion `.grc` file
+///
+/// GRC blocks are mapped onto blocks of the [`BlockRegistry`] (see
+/// [`blocks::register`](crate::blocks::register)), translating their parameters. Variables are
+/// evaluated, supporting arithmetic expressions like `samp_rate * 10`. Qt GUI sinks are replaced
+/// by `NullSink`s. Blocks without FutureSDR equivalent and their connections are skipped and
+/// listed in the [`GrcReport`].
+///
+/// | GRC block | FutureSDR block |
+/// |---|---|
+/// | `analog_sig_source_x` | `SignalSource` |
+/// | `blocks_copy` | `Copy` |
+/// | `blocks_file_sink` | `FileSink` |
+/// | `blocks_file_source` | `FileSource` |
+/// | `blocks_head` | `Head` |
+/// | `blocks_message_debug` | `MessageSink` |
+/// | `blocks_message_strobe` | `MessageSource` |
+/// | `blocks_null_sink` | `NullSink` |
+/// | `blocks_null_source` | `NullSource` |
+/// | `blocks_throttle`, `blocks_throttle2` | `Throttle` |
+/// | `low_pass_filter` | `Fir` |
+/// | `qtgui_*_sink_*` | `NullSink` |
+///
+/// ```no_run
+/// use futuresdr::blocks;
+/// use futuresdr::runtime::BlockRegistry;
+/// use futuresdr::runtime::Grc;
+/// use futuresdr::runtime::Runtime;
+///
+/// let grc = Grc::from_file("examples/debug/oscillators.grc")?;
+/// print!("{}", grc.report());
+///
+/// let mut registry = BlockRegistry::new();
+/// blocks::register(&mut registry);
+/// Runtime::new().run(grc.to_flowgraph(&registry)?)?;
+/// # Ok::<(), futuresdr::runtime::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct Grc {
+    blocks: Vec<GrcBlock>,
+    connections: Vec<GrcConnection>,
+    report: GrcReport,
+}
+
+impl Grc {
+    /// Import a flowgraph from the contents of a `.grc` file
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        let file: File = serde_yaml::from_str(s).map_err(|e| Error::GrcError(e.to_string()))?;
+        let vars = variables(&file.blocks)?;
+
+        let mut blocks = Vec::new();
+        let mut mapped = HashMap::new();
+        let mut report = GrcReport::default();
+
+        for b in file.blocks.iter() {
+            if b.is_variable() || b.is_decoration() {
+                continue;
+            }
+            let result = match b.state() {
+                State::Disabled => {
+                    report.disabled.push(b.name.clone());
+                    continue;
+                }
+                State::Bypassed => Err("bypassed blocks are not supported".to_string()),
+                State::Enabled => map(
+                    b,
+                    &Params {
+                        block: b,
+                        vars: &vars,
+                    },
+                ),
+            };
+            match result {
+                Ok(m) => {
+                    if let Some(n) = m.note.clone() {
+                        report.notes.push((b.name.clone(), n));
+                    }
+                    blocks.extend(m.blocks.iter().cloned());
+                    mapped.insert(b.name.as_str(), m);
+                }
+                Err(e) => report.unmapped.push((b.name.clone(), b.id.clone(), e)),
+            }
+        }
+
+        let mut connections = Vec::new();
+        for c in file.connections.iter() {
+            let [src, src_port, dst, dst_port] = c.as_slice() else {
+                return Err(Error::GrcError(format!("invalid connection {c:?}")));
+            };
+            let (src, src_port, dst, dst_port) = (
+                to_string(src),
+                to_string(src_port),
+                to_string(dst),
+                to_string(dst_port),
+            );
+            let unmapped = |b: &str| report.unmapped.iter().any(|u| u.0 == b);
+            if !(mapped.contains_key(src.as_str()) || unmapped(&src))
+                || !(mapped.contains_key(dst.as_str()) || unmapped(&dst))
+            {
+                // connection of a disabled block
+                continue;
+            }
+
+            let endpoints = mapped
+                .get(src.as_str())
+                .and_then(|m| m.port(&src_port, true))
+                .zip(
+                    mapped
+                        .get(dst.as_str())
+                        .and_then(|m| m.port(&dst_port, false)),
+                );
+            match endpoints {
+                Some(((s, sp), (d, dp))) => connections.push(GrcConnection {
+                    src: s,
+                    src_port: sp,
+                    dst: d,
+                    dst_port: dp,
+                    message: src_port.parse::<usize>().is_err(),
+                }),
+                None => report
+                    .dropped
+                    .push(format!("{src}.{src_port} -> {dst}.{dst_port}")),
+            }
+        }
+
+        Ok(Self {
+            blocks,
+            connections,
+            report,
+        })
+    }
+
+    /// Import a flowgraph from a `.grc` file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let s = std::fs::read_to_string(path).map_err(|e| Error::GrcError(e.to_string()))?;
+        Self::parse(&s)
+    }
+
+    /// Imported blocks
+    pub fn blocks(&self) -> &[GrcBlock] {
+        &self.blocks
+    }
+
+    /// Imported connections
+    pub fn connections(&self) -> &[GrcConnection] {
+        &self.connections
+    }
+
+    /// Blocks and connections that could not be imported
+    pub fn report(&self) -> &GrcReport {
+        &self.report
+    }
+
+    /// Create a [`Flowgraph`], instantiating the blocks through the registry
+    pub fn to_flowgraph(&self, registry: &BlockRegistry) -> Result<Flowgraph, Error> {
+        let mut fg = Flowgraph::new();
+        for b in self.blocks.iter() {
+            let mut block = registry.create(&b.type_name, &b.args)?;
+            block.set_instance_name(&b.name);
+            fg.add_block(block)?;
+        }
+
+        for c in self.connections.iter() {
+            let id = |name: &str| {
+                fg.block_id(name)
+                    .ok_or_else(|| Error::GrcError(format!("block '{name}' does not exist")))
+            };
+            let (src, dst) = (id(&c.src)?, id(&c.dst)?);
+            if c.message {
+                fg.connect_message(src, c.src_port.as_str(), dst, c.dst_port.as_str())?;
+            } else {
+                fg.connect_stream(src, c.src_port.as_str(), dst, c.dst_port.as_str())?;
+            }
+        }
+        Ok(fg)
+    }
+
+    /// Generate a Rust program that sets up the flowgraph with the
+    /// [`connect`](crate::macros::connect) macro
+    ///
+    /// Fails, if a block type has no Rust equivalent.
+    pub fn to_rust(&self) -> Result<String, Error> {
+        let mut imports: Vec<&str> = self
+            .blocks
+            .iter()
+            .map(|b| match b.type_name.as_str() {
+                "Fir" => "FirBuilder",
+                "SignalSource" => "SignalSourceBuilder",
+                t => t,
+            })
+            .collect();
+        imports.sort_unstable();
+        imports.dedup();
+        let complex = self
+            .blocks
+            .iter()
+            .any(|b| b.args.get("type") == Some(&Pmt::String("c32".to_string())));
+        let messages = self.blocks.iter().any(|b| b.type_name == "MessageSource");
+
+        let mut s = String::from("use anyhow::Result;\n");
+        for i in imports {
+            let _ = writeln!(s, "use futuresdr::blocks::{i};");
+        }
+        s.push_str("use futuresdr::macros::connect;\n");
+        if complex {
+            s.push_str("use futuresdr::num_complex::Complex32;\n");
+        }
+        s.push_str("use futuresdr::runtime::Flowgraph;\n");
+        if messages {
+            s.push_str("use futuresdr::runtime::Pmt;\n");
+        }
+        s.push_str("use futuresdr::runtime::Runtime;\n");
+        if messages {
+            s.push_str("use std::time::Duration;\n");
+        }
+
+        s.push_str("\nfn main() -> Result<()> {\n    let mut fg = Flowgraph::new();\n\n");
+        for b in self.blocks.iter() {
+            let _ = writeln!(s, "    let {} = {};", b.name, rust_block(b)?);
+        }
+
+        s.push_str("\n    connect!(fg,\n");
+        for c in self.connections.iter() {
+            let op = if c.message { "|" } else { ">" };
+            let _ = writeln!(
+                s,
+                "        {}.{} {op} {}.{};",
+                c.src, c.src_port, c.dst, c.dst_port
+            );
+        }
+        for b in self.blocks.iter() {
+            if !self
+                .connections
+                .iter()
+                .any(|c| c.src == b.name || c.dst == b.name)
+            {
+                let _ = writeln!(s, "        {};", b.name);
+            }
+        }
+        s.push_str("    );\n\n    Runtime::new().run(fg)?;\n    Ok(())\n}\n");
+        Ok(s)
+    }
+}
+
+/// FutureSDR blocks and ports of a GRC block
+struct Mapped {
+    blocks: Vec<GrcBlock>,
+    /// Block and port of each GRC stream input
+    inputs: Vec<(String, String)>,
+    /// Block and port of each GRC stream output
+    outputs: Vec<(String, String)>,
+    /// Block and port of GRC message ports
+    messages: HashMap<&'static str, (String, String)>,
+    note: Option<String>,
+}
+
+impl Mapped {
+    fn new<const N: usize>(name: &str, type_name: &str, args: [(&str, Pmt); N]) -> Self {
+        Self {
+            blocks: vec![GrcBlock {
+                name: name.to_string(),
+                type_name: type_name.to_string(),
+                args: args.into_iter().map(|(k, v)| (k.to_string(), v)).collect(),
+            }],
+            inputs: vec![(name.to_string(), "in".to_string())],
+            outputs: vec![(name.to_string(), "out".to_string())],
+            messages: HashMap::new(),
+            note: None,
+        }
+    }
+
+    /// One `NullSink` per input
+    fn null_sinks(name: &str, item_type: &str, n: usize) -> Self {
+        let names: Vec<String> = match n {
+            1 => vec![name.to_string()],
+            n => (0..n).map(|i| format!("{name}_in{i}")).collect(),
+        };
+        Self {
+            blocks: names
+                .iter()
+                .map(|n| GrcBlock {
+                    name: n.clone(),
+                    type_name: "NullSink".to_string(),
+                    args: BlockArgs::from([("type".to_string(), Pmt::String(item_type.into()))]),
+                })
+                .collect(),
+            inputs: names.into_iter().map(|n| (n, "in".to_string())).collect(),
+            outputs: Vec::new(),
+            messages: HashMap::new(),
+            note: None,
+        }
+    }
+
+    fn message(mut self, grc_port: &'static str, port: &str) -> Self {
+        let name = self.blocks[0].name.clone();
+        self.messages.insert(grc_port, (name, port.to_string()));
+        self
+    }
+
+    fn note(mut self, note: impl Into<String>) -> Self {
+        self.note = Some(note.into());
+        self
+    }
+
+    fn port(&self, port: &str, output: bool) -> Option<(String, String)> {
+        match port.parse::<usize>() {
+            Ok(i) if output => self.outputs.get(i).cloned(),
+            Ok(i) => self.inputs.get(i).cloned(),
+            Err(_) => self.messages.get(port).cloned(),
+        }
+    }
+}
+
+/// Map a GRC block onto FutureSDR blocks
+fn map(b: &FileBlock, p: &Params) -> Result<Mapped, String> {
+    let name = b.name.as_str();
+    let string = |s: &str| Pmt::String(s.to_string());
+
+    let mapped = match b.id.as_str() {
+        "analog_sig_source_x" => {
+            let waveform = match p.raw("waveform").rsplit('.').next() {
+                Some("GR_COS_WAVE") => "cos",
+                Some("GR_SIN_WAVE") => "sin",
+                Some("GR_SQR_WAVE") => "square",
+                _ => return Err(format!("waveform {} is not supported", p.raw("waveform"))),
+            };
+            Mapped::new(
+                name,
+                "SignalSource",
+                [
+                    ("type", string(p.float_type()?)),
+                    ("waveform", string(waveform)),
+                    ("frequency", Pmt::F64(p.num("freq")?)),
+                    ("sample_rate", Pmt::F64(p.num("samp_rate")?)),
+                    ("amplitude", Pmt::F64(p.num_or("amp", 1.0)?)),
+                    ("offset", Pmt::F64(p.num_or("offset", 0.0)?)),
+                    ("phase", Pmt::F64(p.num_or("phase", 0.0)?)),
+                ],
+            )
+        }
+        "blocks_copy" => Mapped::new(name, "Copy", [("type", string(p.item_type()?))]),
+        "blocks_file_sink" => {
+            let m = Mapped::new(
+                name,
+                "FileSink",
+                [
+                    ("type", string(p.item_type()?)),
+                    ("file", string(&p.raw("file"))),
+                ],
+            );
+            if p.bool_or("append", false)? {
+                m.note("append is not supported, the file is overwritten")
+            } else {
+                m
+            }
+        }
+        "blocks_file_source" => {
+            let m = Mapped::new(
+                name,
+                "FileSource",
+                [
+                    ("type", string(p.item_type()?)),
+                    ("file", string(&p.raw("file"))),
+                    ("repeat", Pmt::Bool(p.bool_or("repeat", false)?)),
+                ],
+            );
+            if p.num_or("offset", 0.0)? != 0.0 || p.num_or("length", 0.0)? != 0.0 {
+                m.note("offset and length are ignored")
+            } else {
+                m
+            }
+        }
+        "blocks_head" => Mapped::new(
+            name,
+            "Head",
+            [
+                ("type", string(p.item_type()?)),
+                ("n", Pmt::Usize(p.int("num_items")?)),
+            ],
+        ),
+        "blocks_message_debug" => Mapped::new(name, "MessageSink", [])
+            .message("print", "in")
+            .message("store", "in")
+            .message("print_pdu", "in"),
+        "blocks_message_strobe" => {
+            let msg = p.raw("msg");
+            let message = pmt(&msg).ok_or_else(|| format!("message {msg} is not supported"))?;
+            Mapped::new(
+                name,
+                "MessageSource",
+                [
+                    ("message", message),
+                    ("interval", Pmt::F64(p.num("period")? / 1000.0)),
+                ],
+            )
+            .message("strobe", "out")
+        }
+        "blocks_null_sink" => Mapped::null_sinks(name, p.item_type()?, p.int_or("num_inputs", 1)?),
+        "blocks_null_source" => {
+            if p.int_or("num_outputs", 1)? != 1 {
+                return Err("multiple outputs are not supported".to_string());
+            }
+            Mapped::new(name, "NullSource", [("type", string(p.item_type()?))])
+        }
+        "blocks_throttle" | "blocks_throttle2" => Mapped::new(
+            name,
+            "Throttle",
+            [
+                ("type", string(p.item_type()?)),
+                ("rate", Pmt::F64(p.num("samples_per_second")?)),
+            ],
+        ),
+        "low_pass_filter" => {
+            let item_type = match p.raw("type").as_str() {
+                "fir_filter_ccf" => "c32",
+                "fir_filter_fff" => "f32",
+                t => return Err(format!("filter type {t} is not supported")),
+            };
+            if p.int_or("interp", 1)? != 1 {
+                return Err("interpolation is not supported".to_string());
+            }
+            let taps = lowpass(
+                p.num_or("gain", 1.0)?,
+                p.num("samp_rate")?,
+                p.num("cutoff_freq")?,
+                p.num("width")?,
+                &p.raw("win"),
+                p.num_or("beta", 6.76)?,
+            )?;
+            Mapped::new(
+                name,
+                "Fir",
+                [
+                    ("type", string(item_type)),
+                    ("taps", Pmt::VecF32(taps)),
+                    ("decim", Pmt::Usize(p.int_or("decim", 1)?)),
+                ],
+            )
+        }
+        id if id.starts_with("qtgui_") && id.contains("_sink") => {
+            let item_type = match p.raw("type").as_str() {
+                "complex" => "c32",
+                "float" => "f32",
+                t => return Err(format!("item type {t} is not supported")),
+            };
+            Mapped::null_sinks(name, item_type, p.int_or("nconnections", 1)?)
+                .note("Qt GUI sink is replaced by NullSink")
+        }
+        _ => return Err("no FutureSDR equivalent".to_string()),
+    };
+    Ok(mapped)
+}
+
+/// Parameters of a GRC block
+struct Params<'a> {
+    block: &'a FileBlock,
+    vars: &'a HashMap<String, f64>,
+}
+
+impl Params<'_> {
+    fn raw(&self, name: &str) -> String {
+        self.block
+            .parameters
+            .get(name)
+            .map(to_string)
+            .unwrap_or_default()
+    }
+
+    fn num(&self, name: &str) -> Result<f64, String> {
+        let raw = self.raw(name);
+        eval(&raw, self.vars)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("cannot evaluate {name} '{raw}'"))
+    }
+
+    fn num_or(&self, name: &str, default: f64) -> Result<f64, String> {
+        if self.raw(name).is_empty() {
+            Ok(default)
+        } else {
+            self.num(name)
+        }
+    }
+
+    fn int(&self, name: &str) -> Result<usize, String> {
+        let v = self.num(name)?;
+        if v >= 0.0 && v.fract() == 0.0 {
+            Ok(v as usize)
+        } else {
+            Err(format!("{name} is not a positive integer ({v})"))
+        }
+    }
+
+    fn int_or(&self, name: &str, default: usize) -> Result<usize, String> {
+        if self.raw(name).is_empty() {
+            Ok(default)
+        } else {
+            self.int(name)
+        }
+    }
+
+    fn bool_or(&self, name: &str, default: bool) -> Result<bool, String> {
+        if self.raw(name).is_empty() {
+            Ok(default)
+        } else {
+            Ok(self.num(name)? != 0.0)
+        }
+    }
+
+    /// Item type in the notation of the [`BlockRegistry`]
+    fn item_type(&self) -> Result<&'static str, String> {
+        let vlen = self.int_or("vlen", 1)?;
+        if vlen != 1 {
+            return Err(format!("vector length {vlen} is not supported"));
+        }
+        match self.raw("type").as_str() {
+            "complex" => Ok("c32"),
+            "float" => Ok("f32"),
+            "int" => Ok("i32"),
+            "short" => Ok("i16"),
+            "byte" => Ok("u8"),
+            t => Err(format!("item type {t} is not supported")),
+        }
+    }
+
+    /// Item type of blocks that only support `float` and `complex`
+    fn float_type(&self) -> Result<&'static str, String> {
+        match self.item_type()? {
+            t @ ("c32" | "f32") => Ok(t),
+            _ => Err(format!("item type {} is not supported", self.raw("type"))),
+        }
+    }
+}
+
+fn to_string(v: &serde_yaml::Value) -> String {
+    match v {
+        serde_yaml::Value::Null => String::new(),
+        serde_yaml::Value::Bool(true) => "True".to_string(),
+        serde_yaml::Value::Bool(false) => "False".to_string(),
+        serde_yaml::Value::Number(n) => n.to_string(),
+        serde_yaml::Value::String(s) => s.clone(),
+        v => serde_yaml::to_string(v)
+            .unwrap_or_default()
+            .trim()
+            .to_string(),
+    }
+}
+
+/// Evaluate variables, which might refer to each other
+fn variables(blocks: &[FileBlock]) -> Result<HashMap<String, f64>, Error> {
+    let mut vars = HashMap::new();
+    let mut pending: Vec<&FileBlock> = blocks
+        .iter()
+        .filter(|b| b.is_variable() && b.state() == State::Enabled)
+        .collect();
+    loop {
+        let n = pending.len();
+        let mut error = None;
+        pending.retain(|b| {
+            let value = b.parameters.get("value").map(to_string).unwrap_or_default();
+            match eval(&value, &vars) {
+                Ok(Some(v)) => {
+                    vars.insert(b.name.clone(), v);
+                    false
+                }
+                Ok(None) => true,
+                Err(e) => {
+                    error.get_or_insert(e);
+                    true
+                }
+            }
+        });
+        if let Some(e) = error {
+            return Err(e);
+        }
+        if pending.len() == n {
+            return Ok(vars);
+        }
+    }
+}
+
+/// Message of a message strobe
+fn pmt(s: &str) -> Option<Pmt> {
+    let s = s.trim();
+    match s {
+        "pmt.PMT_NIL" => Some(Pmt::Null),
+        "pmt.PMT_T" => Some(Pmt::Bool(true)),
+        "pmt.PMT_F" => Some(Pmt::Bool(false)),
+        _ => {
+            let arg = s.strip_prefix("pmt.intern(")?.strip_suffix(')')?.trim();
+            let arg = arg
+                .strip_prefix('"')
+                .and_then(|a| a.strip_suffix('"'))
+                .or_else(|| arg.strip_prefix('\'').and_then(|a| a.strip_suffix('\'')))?;
+            Some(Pmt::String(arg.to_string()))
+        }
+    }
+}
+
+/// Maximum number of taps of a filter, designed from the parameters of a GRC block
+const MAX_TAPS: usize = 1 << 16;
+
+/// Low-pass taps, designed like GNU Radio's `firdes.low_pass`
+fn lowpass(
+    gain: f64,
+    samp_rate: f64,
+    cutoff: f64,
+    width: f64,
+    window: &str,
+    beta: f64,
+) -> Result<Vec<f32>, String> {
+    if cutoff <= 0.0 || width <= 0.0 || cutoff >= samp_rate / 2.0 {
+        return Err(format!(
+            "invalid filter parameters (cutoff {cutoff}, width {width}, sample rate {samp_rate})"
+        ));
+    }
+    let window = window.rsplit('.').next().unwrap_or_default();
+    let attenuation = match window {
+        "WIN_HAMMING" => 53.0,
+        "WIN_HANN" | "WIN_HANNING" => 44.0,
+        "WIN_BLACKMAN" => 74.0,
+        "WIN_RECTANGULAR" | "WIN_RECT" => 21.0,
+        "WIN_KAISER" => beta / 0.1102 + 8.7,
+        w => return Err(format!("window {w} is not supported")),
+    };
+    let ntaps = attenuation * samp_rate / (22.0 * width);
+    if !ntaps.is_finite() || ntaps >= MAX_TAPS as f64 {
+        return Err(format!(
+            "invalid filter parameters (cutoff {cutoff}, width {width}, sample rate {samp_rate})"
+        ));
+    }
+    let ntaps = ntaps as usize | 1;
+    let window = match window {
+        "WIN_HAMMING" => windows::hamming(ntaps, false),
+        "WIN_HANN" | "WIN_HANNING" => windows::hann(ntaps, false),
+        "WIN_BLACKMAN" => windows::blackman(ntaps, false),
+        "WIN_KAISER" => windows::kaiser(ntaps, beta),
+        _ => windows::rect(ntaps),
+    };
+    let taps: Vec<f64> = firdes::lowpass(cutoff / samp_rate, &window);
+    let sum: f64 = taps.iter().sum();
+    Ok(taps.iter().map(|t| (t * gain / sum) as f32).collect())
+}
+
+/// Rust expression that creates the block
+fn rust_block(b: &GrcBlock) -> Result<String, Error> {
+    let item_type = match b.args.get("type") {
+        Some(Pmt::String(t)) if t == "c32" => "Complex32",
+        Some(Pmt::String(t)) => t.as_str(),
+        _ => "f32",
+    };
+    let num = |name: &str, default: f64| match b.args.get(name) {
+        Some(p) => f64::try_from(p).unwrap_or(default),
+        None => default,
+    };
+    let string = |name: &str| match b.args.get(name) {
+        Some(Pmt::String(s)) => format!("{s:?}"),
+        _ => "\"\"".to_string(),
+    };
+
+    Ok(match b.type_name.as_str() {
+        "Copy" | "NullSink" | "NullSource" => {
+            format!("{}::<{item_type}>::new()", b.type_name)
+        }
+        "FileSink" => format!("FileSink::<{item_type}>::new({})", string("file")),
+        "FileSource" => format!(
+            "FileSource::<{item_type}>::new({}, {})",
+            string("file"),
+            b.args.get("repeat") == Some(&Pmt::Bool(true))
+        ),
+        "Fir" => {
+            let taps = match b.args.get("taps") {
+                Some(Pmt::VecF32(t)) => t.iter().map(|t| format!("{t:?}")).collect(),
+                _ => Vec::new(),
+            };
+            let taps = format!("vec![{}]", taps.join(", "));
+            match num("decim", 1.0) as usize {
+                1 => format!("FirBuilder::new::<{item_type}, {item_type}, _>({taps})"),
+                d => format!(
+                    "FirBuilder::decimating_with_taps::<{item_type}, {item_type}, _>({d}, {taps})"
+                ),
+            }
+        }
+        "Head" => format!("Head::<{item_type}>::new({})", num("n", 0.0) as u64),
+        "MessageSink" => "MessageSink::new()".to_string(),
+        "MessageSource" => {
+            let message = match b.args.get("message") {
+                Some(Pmt::Bool(v)) => format!("Pmt::Bool({v})"),
+                Some(Pmt::String(s)) => format!("Pmt::String({s:?}.to_string())"),
+                _ => "Pmt::Null".to_string(),
+            };
+            format!(
+                "MessageSource::new({message}, Duration::from_secs_f64({:?}), None)",
+                num("interval", 1.0)
+            )
+        }
+        "SignalSource" => {
+            let waveform = match b.args.get("waveform") {
+                Some(Pmt::String(w)) => w.as_str(),
+                _ => "cos",
+            };
+            let value = |v: f64| match item_type {
+                "Complex32" => format!("Complex32::new({:?}, 0.0)", v as f32),
+                _ => format!("{:?}", v as f32),
+            };
+            let mut s = format!(
+                "SignalSourceBuilder::<{item_type}>::{waveform}({:?}, {:?})",
+                num("frequency", 0.0) as f32,
+                num("sample_rate", 1.0) as f32
+            );
+            if num("amplitude", 1.0) != 1.0 {
+                s.push_str(&format!(".amplitude({})", value(num("amplitude", 1.0))));
+            }
+            if num("offset", 0.0) != 0.0 {
+                s.push_str(&format!(".offset({})", value(num("offset", 0.0))));
+            }
+            if num("phase", 0.0) != 0.0 {
+                s.push_str(&format!(".initial_phase({:?})", num("phase", 0.0) as f32));
+            }
+            s.push_str(".build()");
+            s
+        }
+        "Throttle" => format!("Throttle::<{item_type}>::new({:?})", num("rate", 0.0)),
+        t => {
+            return Err(Error::GrcError(format!(
+                "cannot generate Rust code for block type {t}"
+            )))
+        }
+    })
+}
+
+/// Maximum nesting depth of expressions
+const MAX_DEPTH: usize = 64;
+
+/// Evaluate an arithmetic Python expression
+///
+/// Returns `None`, if the expression is invalid or refers to unknown variables, and an error, if
+/// it is nested too deeply.
+fn eval(s: &str, vars: &HashMap<String, f64>) -> Result<Option<f64>, Error> {
+    let mut e = Expr {
+        chars: s.chars().collect(),
+        index: 0,
+        depth: 0,
+        too_deep: false,
+        vars,
+    };
+    let v = e.sum();
+    e.skip_whitespace();
+    if e.too_deep {
+        return Err(Error::GrcError(format!(
+            "expression nested deeper than {MAX_DEPTH} levels"
+        )));
+    }
+    Ok(v.filter(|_| e.index == e.chars.len()))
+}
+
+struct Expr<'a> {
+    chars: Vec<char>,
+    index: usize,
+    depth: usize,
+    too_deep: bool,
+    vars: &'a HashMap<String, f64>,
+}
+
+impl Expr<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).copied()
+    }
+
+    // whitespace separates tokens, e.g., `1 2` is not `12`
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(c) if c.is_whitespace()) {
+            self.index += 1;
+        }
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        self.skip_whitespace();
+        let n = s.chars().count();
+        if self.chars[self.index..]
+            .iter()
+            .take(n)
+            .copied()
+            .eq(s.chars())
+        {
+            self.index += n;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn sum(&mut self) -> Option<f64> {
+        let mut v = self.product()?;
+        loop {
+            if self.eat("+") {
+                v += self.product()?;
+            } else if self.eat("-") {
+                v -= self.product()?;
+            } else {
+                return Some(v);
+            }
+        }
+    }
+
+    fn product(&mut self) -> Option<f64> {
+        let mut v = self.unary()?;
+        loop {
+            if self.eat("*") {
+                v *= self.unary()?;
+            } else if self.eat("//") {
+                v = (v / self.unary()?).floor();
+            } else if self.eat("/") {
+                v /= self.unary()?;
+            } else if self.eat("%") {
+                v = v.rem_euclid(self.unary()?);
+            } else {
+                return Some(v);
+            }
+        }
+    }
+
+    // all recursion passes through here, so the depth is limited here
+    fn unary(&mut self) -> Option<f64> {
+        if self.depth == MAX_DEPTH {
+            self.too_deep = true;
+            return None;
+        }
+        self.depth += 1;
+        let v = if self.eat("-") {
+            self.unary().map(|v| -v)
+        } else if self.eat("+") {
+            self.unary()
+        } else {
+            self.power()
+        };
+        self.depth -= 1;
+        v
+    }
+
+    fn power(&mut self) -> Option<f64> {
+        let v = self.atom()?;
+        if self.eat("**") {
+            Some(v.powf(self.unary()?))
+        } else {
+            Some(v)
+        }
+    }
+
+    fn atom(&mut self) -> Option<f64> {
+        self.skip_whitespace();
+        let c = self.peek()?;
+        if self.eat("(") {
+            let v = self.sum()?;
+            return self.eat(")").then_some(v);
+        }
+
+        if c.is_ascii_digit() || c == '.' {
+            let start = self.index;
+            while let Some(c) = self.peek() {
+                let exponent =
+                    matches!(c, '+' | '-') && matches!(self.chars[self.index - 1], 'e' | 'E');
+                if c.is_ascii_alphanumeric() || c == '.' || c == '_' || exponent {
+                    self.index += 1;
+                } else {
+                    break;
+                }
+            }
+            let s: String = self.chars[start..self.index]
+                .iter()
+                .filter(|c| **c != '_')
+                .collect();
+            return s.parse().ok();
+        }
+
+        if c.is_alphabetic() || c == '_' {
+            let start = self.index;
+            while matches!(self.peek(), Some(c) if c.is_alphanumeric() || c == '_' || c == '.') {
+                self.index += 1;
+            }
+            let name: String = self.chars[start..self.index].iter().collect();
+            if self.eat("(") {
+                let v = self.sum()?;
+                if !self.eat(")") {
+                    return None;
+                }
+                return match name.as_str() {
+                    "int" => Some(v.trunc()),
+                    "float" => Some(v),
+                    "abs" => Some(v.abs()),
+                    "round" => Some(v.round()),
+                    _ => None,
+                };
+            }
+            return match name.as_str() {
+                "True" => Some(1.0),
+                "False" => Some(0.0),
+                "pi" | "math.pi" | "numpy.pi" | "np.pi" => Some(std::f64::consts::PI),
+                "math.e" | "numpy.e" | "np.e" => Some(std::f64::consts::E),
+                n => self.vars.get(n).copied(),
+            };
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const GRC: &str = r#"
+options:
+  parameters:
+    id: test
+blocks:
+- name: samp_rate
+  id: variable
+  parameters:
+    value: '48000'
+- name: rate
+  id: variable
+  parameters:
+    value: samp_rate * 10
+  states:
+    state: enabled
+- name: src
+  id: analog_sig_source_x
+  parameters:
+    amp: '0.5'
+    freq: samp_rate / 4
+    offset: '0'
+    phase: '0'
+    samp_rate: samp_rate
+    type: complex
+    waveform: analog.GR_COS_WAVE
+  states:
+    state: true
+- name: throttle
+  id: blocks_throttle
+  parameters:
+    samples_per_second: rate
+    type: complex
+    vlen: '1'
+- name: sub
+  id: blocks_sub_xx
+  parameters:
+    type: complex
+- name: head
+  id: blocks_head
+  parameters:
+    num_items: '1024'
+    type: float
+  states:
+    state: disabled
+- name: gui
+  id: qtgui_time_sink_x
+  parameters:
+    nconnections: '2'
+    type: complex
+- name: strobe
+  id: blocks_message_strobe
+  parameters:
+    msg: pmt.intern("TEST")
+    period: '500'
+- name: debug
+  id: blocks_message_debug
+connections:
+- [src, '0', throttle, '0']
+- [throttle, '0', gui, '0']
+- [throttle, '0', sub, '0']
+- [sub, '0', gui, '1']
+- [head, '0', gui, '1']
+- [strobe, strobe, debug, print]
+"#;
+
+    #[test]
+    fn expressions() {
+        let vars = HashMap::from([("samp_rate".to_string(), 48000.0)]);
+        assert_eq!(eval("samp_rate * 10", &vars).unwrap(), Some(480000.0));
+        assert_eq!(eval("-(1 + 2) * 2 ** 3", &vars).unwrap(), Some(-24.0));
+        assert_eq!(eval("int(samp_rate / 7)", &vars).unwrap(), Some(6857.0));
+        assert_eq!(eval("7 // 2 + 7 % 2", &vars).unwrap(), Some(4.0));
+        assert_eq!(eval("1.5e3 + 1e-3", &vars).unwrap(), Some(1500.001));
+        assert_eq!(eval("True", &vars).unwrap(), Some(1.0));
+        assert_eq!(eval("foo * 2", &vars).unwrap(), None);
+        assert_eq!(eval("(1 + 2", &vars).unwrap(), None);
+        assert_eq!(eval("/tmp/foo", &vars).unwrap(), None);
+        assert_eq!(eval(" ( 1+2 )\t* 3 ", &vars).unwrap(), Some(9.0));
+        assert_eq!(eval("1 2", &vars).unwrap(), None);
+        assert_eq!(eval("samp_rate 2", &vars).unwrap(), None);
+        assert_eq!(eval("2 * * 3", &vars).unwrap(), None);
+
+        let nested = format!("{}1{}", "(".repeat(100), ")".repeat(100));
+        assert!(matches!(eval(&nested, &vars), Err(Error::GrcError(_))));
+        assert!(matches!(
+            eval(&"-".repeat(100), &vars),
+            Err(Error::GrcError(_))
+        ));
+        let nested = format!("{}1{}", "(".repeat(10), ")".repeat(10));
+        assert_eq!(eval(&nested, &vars).unwrap(), Some(1.0));
+    }
+
+    #[test]
+    fn import() {
+        let grc = Grc::parse(GRC).unwrap();
+
+        let names: Vec<&str> = grc.blocks().iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["src", "throttle", "gui_in0", "gui_in1", "strobe", "debug"]
+        );
+        let src = &grc.blocks()[0];
+        assert_eq!(src.type_name, "SignalSource");
+        assert_eq!(src.args["type"], Pmt::String("c32".to_string()));
+        assert_eq!(src.args["frequency"], Pmt::F64(12000.0));
+        assert_eq!(src.args["amplitude"], Pmt::F64(0.5));
+        assert_eq!(grc.blocks()[1].args["rate"], Pmt::F64(480000.0));
+        assert_eq!(grc.blocks()[4].args["interval"], Pmt::F64(0.5));
+        assert_eq!(
+            grc.blocks()[4].args["message"],
+            Pmt::String("TEST".to_string())
+        );
+
+        assert_eq!(grc.connections().len(), 3);
+        assert_eq!(
+            grc.connections()[1],
+            GrcConnection {
+                src: "throttle".to_string(),
+                src_port: "out".to_string(),
+                dst: "gui_in0".to_string(),
+                dst_port: "in".to_string(),
+                message: false,
+            }
+        );
+        assert!(grc.connections()[2].message);
+
+        let report = grc.report();
+        assert!(!report.is_complete());
+        assert_eq!(
+            report.unmapped,
+            [(
+                "sub".to_string(),
+                "blocks_sub_xx".to_string(),
+                "no FutureSDR equivalent".to_string()
+            )]
+        );
+        assert_eq!(report.dropped, ["throttle.0 -> sub.0", "sub.0 -> gui.1"]);
+        assert_eq!(report.disabled, ["head"]);
+        assert_eq!(report.notes.len(), 1);
+    }
+
+    #[test]
+    fn rust() {
+        let code = Grc::parse(GRC).unwrap().to_rust().unwrap();
+        assert!(code.contains("use futuresdr::blocks::SignalSourceBuilder;\n"));
+        assert!(code.contains("use futuresdr::num_complex::Complex32;\n"));
+        assert!(code.contains(
+            "let src = SignalSourceBuilder::<Complex32>::cos(12000.0, 48000.0).amplitude(Complex32::new(0.5, 0.0)).build();"
+        ));
+        assert!(code.contains("let throttle = Throttle::<Complex32>::new(480000.0);"));
+        assert!(code.contains(
+            "let strobe = MessageSource::new(Pmt::String(\"TEST\".to_string()), Duration::from_secs_f64(0.5), None);"
+        ));
+        assert!(code.contains("        throttle.out > gui_in0.in;\n"));
+        assert!(code.contains("        strobe.out | debug.in;\n"));
+        assert!(code.contains("        gui_in1;\n"));
+    }
+
+    #[test]
+    fn rust_unknown_block() {
+        let b = GrcBlock {
+            name: "foo".to_string(),
+            type_name: "Foo".to_string(),
+            args: BlockArgs::new(),
+        };
+        assert!(matches!(rust_block(&b), Err(Error::GrcError(_))));
+    }
+
+    #[test]
+    fn lowpass_filter() {
+        let taps = lowpass(2.0, 48000.0, 4000.0, 1000.0, "window.WIN_HAMMING", 6.76).unwrap();
+        assert_eq!(taps.len(), 115);
+        assert!((taps.iter().sum::<f32>() - 2.0).abs() < 1e-4);
+        assert!(lowpass(1.0, 48000.0, 30000.0, 1000.0, "window.WIN_HAMMING", 6.76).is_err());
+        assert!(lowpass(1.0, 48000.0, 4000.0, 1000.0, "window.WIN_FOO", 6.76).is_err());
+        assert!(lowpass(1.0, 48000.0, 4000.0, 1e-9, "window.WIN_HAMMING", 6.76).is_err());
+        assert!(lowpass(1.0, 1e300, 4000.0, 1000.0, "window.WIN_HAMMING", 6.76).is_err());
+    }
+
+    #[test]
+    fn examples() {
+        let grc = Grc::parse(include_str!("../../examples/debug/oscillators.grc")).unwrap();
+        assert_eq!(grc.report().disabled.len(), 3);
+        assert_eq!(grc.report().unmapped.len(), 1);
+
+        let grc = Grc::parse(include_str!("../../examples/ssb/ssb-decoder.grc")).unwrap();
+        assert!(grc.blocks().iter().any(|b| b.type_name == "FileSource"));
+    }
+}
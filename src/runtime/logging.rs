@@ -1,9 +1,22 @@
+use once_cell::sync::OnceCell;
+use tracing::level_filters::LevelFilter;
 use tracing_subscriber::filter::EnvFilter;
 use tracing_subscriber::fmt;
 use tracing_subscriber::prelude::*;
+use tracing_subscriber::reload;
+use tracing_subscriber::Registry;
 
 use crate::runtime::config;
 
+static FILTER: OnceCell<reload::Handle<EnvFilter, Registry>> = OnceCell::new();
+
+fn filter(level: LevelFilter) -> EnvFilter {
+    EnvFilter::builder()
+        .with_default_directive(level.into())
+        .with_env_var("FUTURESDR_LOG")
+        .from_env_lossy()
+}
+
 pub fn init() {
     let format = fmt::layer()
         .with_level(true)
@@ -12,15 +25,22 @@ pub fn init() {
         .with_thread_names(true)
         .compact();
 
-    let level = config::config().log_level;
-    let filter = EnvFilter::builder()
-        .with_default_directive(level.into())
-        .with_env_var("FUTURESDR_LOG")
-        .from_env_lossy();
+    let (filter, handle) = reload::Layer::new(filter(config::config().log_level));
 
     let subscriber = tracing_subscriber::registry().with(filter).with(format);
 
     if tracing::subscriber::set_global_default(subscriber).is_err() {
         debug!("logger already initialized");
+    } else {
+        let _ = FILTER.set(handle);
+    }
+}
+
+/// Change the log level of the FutureSDR logger, if it is initialized
+pub fn set_level(level: LevelFilter) {
+    if let Some(handle) = FILTER.get() {
+        if handle.reload(filter(level)).is_err() {
+            warn!("failed to set log level");
+        }
     }
 }
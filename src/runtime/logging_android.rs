@@ -5,3 +5,5 @@ use android_logger::Config;
 pub fn init() {
     android_logger::init_once(Config::default().with_max_level(LevelFilter::Debug));
 }
+
+pub fn set_level(_level: tracing::level_filters::LevelFilter) {}
@@ -1,3 +1,5 @@
 pub fn init() {
     let _ = tracing_wasm::try_set_as_global_default();
 }
+
+pub fn set_level(_level: tracing::level_filters::LevelFilter) {}
@@ -6,6 +6,7 @@ use std::sync::Arc;
 
 use crate::runtime::BlockMessage;
 use crate::runtime::BlockMeta;
+use crate::runtime::HandlerSchema;
 use crate::runtime::Pmt;
 use crate::runtime::PortId;
 use crate::runtime::Result;
@@ -20,6 +21,7 @@ type HandlerFuture<'a> = Pin<Box<dyn Future<Output = Result<Pmt>> + 'a>>;
 pub struct MessageInput<T: ?Sized> {
     name: String,
     finished: bool,
+    schema: Option<HandlerSchema>,
     #[allow(clippy::type_complexity)]
     handler: Arc<
         dyn for<'a> Fn(
@@ -54,6 +56,7 @@ impl<T: Send + ?Sized> MessageInput<T> {
         MessageInput {
             name: name.to_string(),
             finished: false,
+            schema: None,
             handler,
         }
     }
@@ -81,6 +84,16 @@ impl<T: Send + ?Sized> MessageInput<T> {
         &self.name
     }
 
+    /// Get schema of the handler
+    pub fn schema(&self) -> Option<&HandlerSchema> {
+        self.schema.as_ref()
+    }
+
+    /// Set schema of the handler
+    pub fn set_schema(&mut self, schema: HandlerSchema) {
+        self.schema = Some(schema);
+    }
+
     /// Mark port as finished
     pub fn finish(&mut self) {
         self.finished = true;
@@ -131,14 +144,23 @@ impl MessageOutput {
     }
 
     /// Post data to connected downstream message port
+    ///
+    /// Connections, whose receiver is gone (e.g., a tap that was closed), are removed.
     pub async fn post(&mut self, p: Pmt) {
+        let mut disconnected = false;
         for (port_id, sender) in self.handlers.iter_mut() {
-            let _ = sender
+            if let Err(e) = sender
                 .send(BlockMessage::Call {
                     port_id: PortId::Index(*port_id),
                     data: p.clone(),
                 })
-                .await;
+                .await
+            {
+                disconnected |= e.is_disconnected();
+            }
+        }
+        if disconnected {
+            self.handlers.retain(|(_, s)| !s.is_closed());
         }
     }
 }
@@ -183,6 +205,11 @@ impl<T: Send + ?Sized> MessageIo<T> {
         self.inputs.iter().map(|x| x.name().to_string()).collect()
     }
 
+    /// Get input port schemas, in the order of the ports
+    pub fn input_schemas(&self) -> Vec<Option<HandlerSchema>> {
+        self.inputs.iter().map(|x| x.schema().cloned()).collect()
+    }
+
     /// Get all outputs
     pub fn outputs(&self) -> &Vec<MessageOutput> {
         &self.outputs
@@ -256,6 +283,35 @@ impl<T: Send> MessageIoBuilder<T> {
         self
     }
 
+    /// Add input port with a [`HandlerSchema`]
+    ///
+    /// The schema is included in the [`BlockDescription`](crate::runtime::BlockDescription),
+    /// allowing clients to validate input and generate UIs. With the
+    /// [`message_handler`](crate::message_handler) macro, it can be declared as attribute of the
+    /// handler, e.g., `#[message_handler(accepts(F64), range(0.0, 1.0))]`, which generates a
+    /// `<handler>_schema()` function.
+    #[must_use]
+    pub fn add_input_with_schema(
+        mut self,
+        name: &str,
+        schema: HandlerSchema,
+        c: impl for<'a> Fn(
+                &'a mut T,
+                &'a mut WorkIo,
+                &'a mut MessageIo<T>,
+                &'a mut BlockMeta,
+                Pmt,
+            ) -> HandlerFuture<'a>
+            + Send
+            + Sync
+            + 'static,
+    ) -> MessageIoBuilder<T> {
+        let mut input = MessageInput::new(name, Arc::new(c));
+        input.set_schema(schema);
+        self.inputs.push(input);
+        self
+    }
+
     /// Add output port
     #[must_use]
     pub fn add_output(mut self, name: &str) -> MessageIoBuilder<T> {
@@ -274,3 +330,43 @@ impl<T: Send> Default for MessageIoBuilder<T> {
         Self::new()
     }
 }
+
+/// Message inputs of blocks without a `#[message_handlers]` impl block
+///
+/// Used by `#[derive(Block)]`. The `message_handlers` macro generates an inherent
+/// `__message_inputs` function, which takes precedence over this default.
+#[doc(hidden)]
+pub trait DefaultMessageInputs: Sized {
+    /// Add no message inputs
+    fn __message_inputs(builder: MessageIoBuilder<Self>) -> MessageIoBuilder<Self> {
+        builder
+    }
+}
+
+impl<T> DefaultMessageInputs for T {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::channel::mpsc::channel;
+
+    #[test]
+    fn post_prunes_closed_connections() {
+        let mut out = MessageOutput::new("out");
+        let (tx, mut rx) = channel(4);
+        let (tap_tx, tap_rx) = channel(4);
+        out.connect(0, tx);
+        out.connect(0, tap_tx);
+
+        drop(tap_rx);
+        crate::async_io::block_on(out.post(Pmt::U32(1)));
+        assert_eq!(out.handlers.len(), 1);
+        assert!(matches!(
+            rx.try_next(),
+            Ok(Some(BlockMessage::Call {
+                data: Pmt::U32(1),
+                ..
+            }))
+        ));
+    }
+}
@@ -4,6 +4,7 @@ use futures::channel::mpsc::Sender;
 use std::any::Any;
 use std::fmt::Debug;
 
+use crate::runtime::block::kernel_work;
 use crate::runtime::buffer::BufferReaderHost;
 use crate::runtime::buffer::BufferWriterHost;
 use crate::runtime::config::config;
@@ -196,16 +197,15 @@ impl<K: Kernel + 'static> Mocker<K> {
         };
 
         loop {
-            self.block
-                .kernel
-                .work(
-                    &mut io,
-                    &mut self.block.sio,
-                    &mut self.block.mio,
-                    &mut self.block.meta,
-                )
-                .await
-                .unwrap();
+            kernel_work(
+                &mut self.block.kernel,
+                &mut io,
+                &mut self.block.sio,
+                &mut self.block.mio,
+                &mut self.block.meta,
+            )
+            .await
+            .unwrap();
             self.block.sio.commit();
 
             for (n, r) in self.message_sinks.iter_mut().enumerate() {
@@ -306,12 +306,12 @@ impl<T: Clone + Debug + Send + 'static> BufferWriterHost for MockWriter<T> {
         self
     }
 
-    fn produce(&mut self, amount: usize, tags: Vec<ItemTag>) {
+    fn produce(&mut self, amount: usize, tags: &mut Vec<ItemTag>) {
         let curr_len = self.data.len();
         unsafe {
             self.data.set_len(curr_len + amount);
         }
-        self.tags.extend(tags.into_iter().map(|mut t| {
+        self.tags.extend(tags.drain(..).map(|mut t| {
             t.index += curr_len;
             t
         }));
@@ -3,6 +3,7 @@
 use futures::channel::mpsc;
 use futures::channel::oneshot;
 use futuresdr_types::PmtConversionError;
+use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
@@ -29,10 +30,16 @@ mod logging;
 #[path = "logging_wasm.rs"]
 mod logging;
 
+mod dsl;
 mod flowgraph;
+#[cfg(feature = "grc")]
+mod grc;
 pub mod message_io;
 #[cfg(not(target_arch = "wasm32"))]
 mod mocker;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod plugin;
+pub mod registry;
 #[allow(clippy::module_inception)]
 mod runtime;
 pub mod scheduler;
@@ -42,35 +49,65 @@ mod topology;
 
 pub use block::Block;
 pub use block::BlockT;
+pub use block::Fusible;
 pub use block::Kernel;
+pub use block::SyncKernel;
 pub use block::TypedBlock;
 pub use block::WorkIo;
 pub use block_meta::BlockMeta;
 pub use block_meta::BlockMetaBuilder;
+pub use block_meta::Priority;
 pub use flowgraph::Flowgraph;
 pub use flowgraph::FlowgraphHandle;
+#[cfg(feature = "grc")]
+pub use grc::Grc;
+#[cfg(feature = "grc")]
+pub use grc::GrcBlock;
+#[cfg(feature = "grc")]
+pub use grc::GrcConnection;
+#[cfg(feature = "grc")]
+pub use grc::GrcReport;
+#[doc(hidden)]
+pub use message_io::DefaultMessageInputs;
 pub use message_io::MessageInput;
 pub use message_io::MessageIo;
 pub use message_io::MessageIoBuilder;
 pub use message_io::MessageOutput;
 #[cfg(not(target_arch = "wasm32"))]
 pub use mocker::Mocker;
+pub use registry::BlockArgs;
+pub use registry::BlockConstructor;
+pub use registry::BlockRegistry;
 pub use runtime::Runtime;
 pub use runtime::RuntimeHandle;
+pub use stream_io::StreamIn;
 pub use stream_io::StreamInput;
 pub use stream_io::StreamIo;
 pub use stream_io::StreamIoBuilder;
+pub use stream_io::StreamOut;
 pub use stream_io::StreamOutput;
+pub use stream_io::TypedStreamInput;
+pub use stream_io::TypedStreamOutput;
 pub use tag::copy_tag_propagation;
 pub use tag::ItemTag;
 pub use tag::Tag;
+pub use topology::PortKind;
 pub use topology::Topology;
 
 pub use futuresdr_types::BlockDescription;
 pub use futuresdr_types::FlowgraphDescription;
+pub use futuresdr_types::FlowgraphEvent;
+pub use futuresdr_types::FromPmt;
+pub use futuresdr_types::FromPmtError;
+pub use futuresdr_types::GrPmt;
+pub use futuresdr_types::HandlerSchema;
+pub use futuresdr_types::IntoPmt;
+pub use futuresdr_types::LatencyHistogram;
 pub use futuresdr_types::Pmt;
+pub use futuresdr_types::PmtCodecError;
 pub use futuresdr_types::PmtKind;
 pub use futuresdr_types::PortId;
+pub use futuresdr_types::TimeSpec;
 
 use buffer::BufferReader;
 use buffer::BufferWriter;
@@ -85,11 +122,16 @@ pub type Result<T, E = anyhow::Error> = anyhow::Result<T, E>;
 /// This function does not have to be called. Once a [`Runtime`] is started,
 /// this function is called automatically.
 ///
-/// At the moment, this only enables logging. Calling it manually, allows using
-/// FutureSDR logging before a [`Runtime`] is started.
+/// At the moment, this enables logging and, if `config_watch` is set, starts watching the
+/// config files (see [`config::watch`]). Calling it manually, allows using FutureSDR logging
+/// before a [`Runtime`] is started.
 ///
 pub fn init() {
     logging::init();
+    #[cfg(not(target_arch = "wasm32"))]
+    if config::config().config_watch {
+        config::watch();
+    }
 }
 
 /// Flowgraph inbox message type
@@ -147,6 +189,25 @@ pub enum FlowgraphMessage {
         /// Back channel for result
         tx: oneshot::Sender<Result<BlockDescription, Error>>,
     },
+    /// Get [`LatencyHistograms`](LatencyHistogram) of sinks
+    Latency {
+        /// Back channel for result
+        tx: oneshot::Sender<HashMap<usize, LatencyHistogram>>,
+    },
+    /// Subscribe to [`FlowgraphEvents`](FlowgraphEvent)
+    Subscribe {
+        /// Channel for events
+        tx: mpsc::Sender<FlowgraphEvent>,
+    },
+    /// Forward messages, posted to a message output of a block
+    Tap {
+        /// Block Id
+        block_id: usize,
+        /// Message output Id
+        port: usize,
+        /// Channel for messages
+        tx: mpsc::Sender<Pmt>,
+    },
 }
 
 /// Block inbox message type
@@ -260,6 +321,27 @@ pub enum Error {
     /// Duplicate block name
     #[error("A Block with an instance name of '{0}' already exists")]
     DuplicateBlockName(String),
+    /// Buffer profile does not exist
+    #[error("Buffer profile '{0}' does not exist")]
+    InvalidBufferProfile(String),
+    /// Port counts of a multi-port connection do not match
+    #[error("Cannot connect {1} ports of '{0}' to {3} ports of '{2}'")]
+    PortCountMismatch(String, usize, String, usize),
+    /// Block type is not in the [`BlockRegistry`]
+    #[error("Block type '{0}' is not registered")]
+    UnknownBlockType(String),
+    /// Error in a textual flowgraph description, with line and column
+    #[error("Error at line {0}, column {1}: {2}")]
+    DslError(usize, usize, String),
+    /// GNU Radio Companion flowgraph cannot be read
+    #[error("Invalid GRC file: {0}")]
+    GrcError(String),
+    /// Config value cannot be deserialized
+    #[error("Invalid config value '{0}': {1}")]
+    InvalidConfig(String, String),
+    /// Plugin cannot be loaded
+    #[error("Cannot load plugin '{0}': {1}")]
+    PluginError(String, String),
     /// Error returned from a Receiver when the corresponding Sender is dropped
     #[error(transparent)]
     ChannelCanceled(#[from] oneshot::Canceled),
@@ -0,0 +1,229 @@
+//! Out-of-tree block libraries
+//!
+//! A plugin is a shared library that exports a [`PluginDeclaration`] with
+//! [`export_plugin!`](crate::export_plugin). Its registration function adds block constructors to
+//! a [`BlockRegistry`], so that flowgraphs from a textual description, a GRC file, or the control
+//! port can instantiate the blocks by their type name without relinking the host binary.
+//!
+//! ```ignore
+//! // Cargo.toml: [lib] crate-type = ["cdylib"]
+//! use futuresdr::blocks::Apply;
+//! use futuresdr::runtime::registry::arg;
+//! use futuresdr::runtime::BlockRegistry;
+//!
+//! fn register(registry: &mut BlockRegistry) {
+//!     registry.register("Scale", |args| {
+//!         let factor = arg::<f64>(args, "factor")? as f32;
+//!         Ok(Apply::new(move |x: &f32| x * factor).into())
+//!     });
+//! }
+//!
+//! futuresdr::export_plugin!(register);
+//! ```
+//!
+//! With the `plugins` feature, the [`Runtime`](crate::runtime::Runtime) loads all plugins of the
+//! directory that is set as `plugin_dir` in the config into its registry. Plugins can also be
+//! loaded explicitly with [`load`] and [`load_dir`].
+//!
+//! Blocks are Rust types that cross the library boundary, so a plugin is only compatible with a
+//! host that uses the same version of FutureSDR, compiled with the same version of rustc, the same
+//! features, and the same profile. This is checked before the registration function is called.
+//! Host and plugins have to use the system allocator. Each plugin links its own copy of FutureSDR,
+//! i.e., log messages of plugin blocks are only shown if the plugin sets up logging itself.
+//! Plugins are never unloaded.
+use crate::runtime::BlockRegistry;
+
+/// Version of the plugin interface, i.e., of the layout of [`PluginDeclaration`]
+pub const ABI_VERSION: u32 = 2;
+
+/// Version of rustc that compiled FutureSDR
+pub const RUSTC_VERSION: &str = env!("FUTURESDR_RUSTC_VERSION");
+
+/// Hash of the features and the profile that FutureSDR was compiled with
+pub const BUILD_FINGERPRINT: &str = env!("FUTURESDR_BUILD_FINGERPRINT");
+
+/// Version of FutureSDR
+pub const FUTURESDR_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Name of the symbol of the [`PluginDeclaration`]
+pub const PLUGIN_SYMBOL: &str = "FUTURESDR_PLUGIN";
+
+/// Entry point of a plugin, created with [`export_plugin!`](crate::export_plugin)
+#[repr(C)]
+#[derive(Debug)]
+pub struct PluginDeclaration {
+    /// [`ABI_VERSION`] of the plugin
+    pub abi_version: u32,
+    /// [`RUSTC_VERSION`] of the plugin
+    pub rustc_version: &'static str,
+    /// [`FUTURESDR_VERSION`] of the plugin
+    pub futuresdr_version: &'static str,
+    /// [`BUILD_FINGERPRINT`] of the plugin
+    pub build_fingerprint: &'static str,
+    /// Crate name of the plugin
+    pub name: &'static str,
+    /// Crate version of the plugin
+    pub version: &'static str,
+    /// Add the blocks of the plugin to a registry
+    pub register: fn(&mut BlockRegistry),
+}
+
+impl PluginDeclaration {
+    /// Check that the plugin was built like the host
+    ///
+    /// Compares the ABI version, the rustc version, the FutureSDR version, and the build
+    /// fingerprint (features and profile) with the ones of the host.
+    pub fn check(&self) -> Result<(), String> {
+        if self.abi_version != ABI_VERSION {
+            return Err(format!(
+                "plugin ABI version {} does not match {}",
+                self.abi_version, ABI_VERSION
+            ));
+        }
+        if self.rustc_version != RUSTC_VERSION {
+            return Err(format!(
+                "plugin was compiled with {}, not {}",
+                self.rustc_version, RUSTC_VERSION
+            ));
+        }
+        if self.futuresdr_version != FUTURESDR_VERSION {
+            return Err(format!(
+                "plugin uses FutureSDR {}, not {}",
+                self.futuresdr_version, FUTURESDR_VERSION
+            ));
+        }
+        if self.build_fingerprint != BUILD_FINGERPRINT {
+            return Err(format!(
+                "plugin build fingerprint {} does not match {}, features or profile differ",
+                self.build_fingerprint, BUILD_FINGERPRINT
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Export a registration function `fn(&mut BlockRegistry)` as FutureSDR plugin
+///
+/// The crate has to be built as `cdylib`.
+#[macro_export]
+macro_rules! export_plugin {
+    ($register:path) => {
+        #[doc(hidden)]
+        #[no_mangle]
+        pub static FUTURESDR_PLUGIN: $crate::runtime::plugin::PluginDeclaration =
+            $crate::runtime::plugin::PluginDeclaration {
+                abi_version: $crate::runtime::plugin::ABI_VERSION,
+                rustc_version: $crate::runtime::plugin::RUSTC_VERSION,
+                futuresdr_version: $crate::runtime::plugin::FUTURESDR_VERSION,
+                build_fingerprint: $crate::runtime::plugin::BUILD_FINGERPRINT,
+                name: env!("CARGO_PKG_NAME"),
+                version: env!("CARGO_PKG_VERSION"),
+                register: $register,
+            };
+    };
+}
+
+#[cfg(feature = "plugins")]
+pub use loader::load;
+#[cfg(feature = "plugins")]
+pub use loader::load_dir;
+#[cfg(feature = "plugins")]
+pub use loader::Plugin;
+
+#[cfg(feature = "plugins")]
+mod loader {
+    use libloading::Library;
+    use std::path::Path;
+    use std::path::PathBuf;
+
+    use super::PluginDeclaration;
+    use super::PLUGIN_SYMBOL;
+    use crate::runtime::BlockRegistry;
+    use crate::runtime::Error;
+
+    /// Plugin that was loaded into a [`BlockRegistry`]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Plugin {
+        /// Path of the shared library
+        pub path: PathBuf,
+        /// Crate name of the plugin
+        pub name: String,
+        /// Crate version of the plugin
+        pub version: String,
+    }
+
+    /// Load a plugin and add its blocks to the registry
+    ///
+    /// Blocks that are already registered with the same type name are replaced.
+    ///
+    /// # Safety
+    ///
+    /// Loading a library runs its initialization code. The library has to export a
+    /// [`PluginDeclaration`] through [`export_plugin!`](crate::export_plugin). Only its ABI,
+    /// rustc, and FutureSDR versions and its build fingerprint are checked, see
+    /// [`PluginDeclaration::check`].
+    pub unsafe fn load(
+        path: impl AsRef<Path>,
+        registry: &mut BlockRegistry,
+    ) -> Result<Plugin, Error> {
+        let path = path.as_ref();
+        let err = |e: String| Error::PluginError(path.display().to_string(), e);
+
+        let library = Library::new(path).map_err(|e| err(e.to_string()))?;
+        let declaration = library
+            .get::<*const PluginDeclaration>(PLUGIN_SYMBOL.as_bytes())
+            .map_err(|e| err(e.to_string()))?;
+        let declaration = &**declaration;
+
+        declaration.check().map_err(err)?;
+
+        (declaration.register)(registry);
+        let plugin = Plugin {
+            path: path.to_path_buf(),
+            name: declaration.name.to_string(),
+            version: declaration.version.to_string(),
+        };
+        debug!(
+            "loaded plugin {} {} from {:?}",
+            plugin.name, plugin.version, path
+        );
+
+        // Constructors and blocks refer to code of the library.
+        std::mem::forget(library);
+        Ok(plugin)
+    }
+
+    /// Load all plugins of a directory into the registry
+    ///
+    /// Shared libraries are loaded in alphabetical order of their file names. Libraries that
+    /// cannot be loaded are skipped with a warning.
+    ///
+    /// # Safety
+    ///
+    /// See [`load`]. All shared libraries of the directory have to be FutureSDR plugins.
+    pub unsafe fn load_dir(
+        dir: impl AsRef<Path>,
+        registry: &mut BlockRegistry,
+    ) -> Result<Vec<Plugin>, Error> {
+        let dir = dir.as_ref();
+        let mut paths = std::fs::read_dir(dir)
+            .map_err(|e| Error::PluginError(dir.display().to_string(), e.to_string()))?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| {
+                p.is_file()
+                    && p.extension().and_then(|e| e.to_str())
+                        == Some(std::env::consts::DLL_EXTENSION)
+            })
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        let mut plugins = Vec::new();
+        for path in paths {
+            match load(&path, registry) {
+                Ok(p) => plugins.push(p),
+                Err(e) => warn!("{e}"),
+            }
+        }
+        Ok(plugins)
+    }
+}
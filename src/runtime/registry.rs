@@ -0,0 +1,96 @@
+//! Block Registry
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::runtime::Block;
+use crate::runtime::Error;
+use crate::runtime::Pmt;
+
+/// Arguments passed to a [`BlockConstructor`]
+pub type BlockArgs = HashMap<String, Pmt>;
+
+/// Function that creates a [`Block`] from its arguments
+pub type BlockConstructor = Arc<dyn Fn(&BlockArgs) -> Result<Block, Error> + Send + Sync>;
+
+/// Block constructors, indexed by type name
+///
+/// Allows instantiating blocks that are not known at compile time, for example, for flowgraphs
+/// that are read from a text file (see [`Flowgraph::connect_str`](crate::runtime::Flowgraph::connect_str)).
+/// [`blocks::register`](crate::blocks::register) adds constructors for a set of core blocks.
+#[derive(Clone, Default)]
+pub struct BlockRegistry {
+    constructors: HashMap<String, BlockConstructor>,
+}
+
+impl BlockRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a constructor for a block type
+    ///
+    /// Replaces a previously registered constructor with the same type name.
+    pub fn register<F>(&mut self, type_name: impl Into<String>, constructor: F)
+    where
+        F: Fn(&BlockArgs) -> Result<Block, Error> + Send + Sync + 'static,
+    {
+        self.constructors
+            .insert(type_name.into(), Arc::new(constructor));
+    }
+
+    /// Remove the constructor of a block type
+    ///
+    /// Returns `true`, if the type was registered.
+    pub fn remove(&mut self, type_name: &str) -> bool {
+        self.constructors.remove(type_name).is_some()
+    }
+
+    /// Check if a block type is registered
+    pub fn contains(&self, type_name: &str) -> bool {
+        self.constructors.contains_key(type_name)
+    }
+
+    /// Names of all registered block types
+    pub fn type_names(&self) -> impl Iterator<Item = &str> {
+        self.constructors.keys().map(|s| s.as_str())
+    }
+
+    /// Create a block of the given type
+    pub fn create(&self, type_name: &str, args: &BlockArgs) -> Result<Block, Error> {
+        let constructor = self
+            .constructors
+            .get(type_name)
+            .ok_or_else(|| Error::UnknownBlockType(type_name.to_string()))?;
+        constructor(args)
+    }
+}
+
+impl fmt::Debug for BlockRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names: Vec<&str> = self.type_names().collect();
+        names.sort_unstable();
+        f.debug_struct("BlockRegistry")
+            .field("types", &names)
+            .finish()
+    }
+}
+
+/// Get a required argument, converted to `T`
+pub fn arg<T: TryFrom<Pmt>>(args: &BlockArgs, name: &str) -> Result<T, Error> {
+    let p = args
+        .get(name)
+        .ok_or_else(|| Error::ValidationError(format!("missing argument '{name}'")))?;
+    T::try_from(p.clone())
+        .map_err(|_| Error::ValidationError(format!("invalid value for argument '{name}': {p}")))
+}
+
+/// Get an optional argument, converted to `T`, falling back to `default` if it is not set
+pub fn arg_or<T: TryFrom<Pmt>>(args: &BlockArgs, name: &str, default: T) -> Result<T, Error> {
+    if args.contains_key(name) {
+        arg(args, name)
+    } else {
+        Ok(default)
+    }
+}
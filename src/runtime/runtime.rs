@@ -8,7 +8,9 @@ use futures::channel::mpsc::Sender;
 use futures::channel::oneshot;
 use futures::prelude::*;
 use futures::FutureExt;
+use once_cell::sync::OnceCell;
 use slab::Slab;
+use std::collections::HashMap;
 use std::fmt;
 use std::pin::Pin;
 use std::sync::Arc;
@@ -16,6 +18,7 @@ use std::sync::Mutex;
 use std::task;
 use std::task::Poll;
 
+use crate::blocks;
 use crate::runtime;
 use crate::runtime::config;
 use crate::runtime::scheduler::Scheduler;
@@ -26,13 +29,17 @@ use crate::runtime::scheduler::Task;
 use crate::runtime::scheduler::WasmScheduler;
 use crate::runtime::BlockDescription;
 use crate::runtime::BlockMessage;
+use crate::runtime::BlockRegistry;
 use crate::runtime::ControlPort;
 use crate::runtime::Error;
 use crate::runtime::Flowgraph;
 use crate::runtime::FlowgraphDescription;
+use crate::runtime::FlowgraphEvent;
 use crate::runtime::FlowgraphHandle;
 use crate::runtime::FlowgraphMessage;
+use crate::runtime::LatencyHistogram;
 use crate::runtime::Pmt;
+use crate::runtime::PortId;
 
 pub struct TaskHandle<'a, T> {
     task: Option<Task<T>>,
@@ -68,6 +75,7 @@ impl<T> std::future::Future for TaskHandle<'_, T> {
 pub struct Runtime<'a, S> {
     scheduler: S,
     flowgraphs: Arc<Mutex<Slab<FlowgraphHandle>>>,
+    registry: Arc<Mutex<BlockRegistry>>,
     _control_port: ControlPort,
     _p: std::marker::PhantomData<&'a ()>,
 }
@@ -84,13 +92,16 @@ impl Runtime<'_, SmolScheduler> {
         runtime::init();
         let scheduler = SmolScheduler::default();
         let flowgraphs = Arc::new(Mutex::new(Slab::new()));
+        let registry = core_registry();
         let handle = RuntimeHandle {
             flowgraphs: flowgraphs.clone(),
+            registry: registry.clone(),
             scheduler: Arc::new(scheduler.clone()),
         };
         Runtime {
             scheduler,
             flowgraphs,
+            registry,
             _control_port: ControlPort::new(handle, routes),
             _p: std::marker::PhantomData,
         }
@@ -119,6 +130,7 @@ impl Runtime<'_, WasmScheduler> {
         Runtime {
             scheduler: WasmScheduler,
             flowgraphs,
+            registry: core_registry(),
             _control_port: ControlPort::new(),
             _p: std::marker::PhantomData,
         }
@@ -145,13 +157,16 @@ impl<'a, S: Scheduler + Sync> Runtime<'a, S> {
         runtime::init();
 
         let flowgraphs = Arc::new(Mutex::new(Slab::new()));
+        let registry = core_registry();
         let handle = RuntimeHandle {
             flowgraphs: flowgraphs.clone(),
+            registry: registry.clone(),
             scheduler: Arc::new(scheduler.clone()),
         };
         Runtime {
             scheduler,
             flowgraphs,
+            registry,
             _control_port: ControlPort::new(handle, routes),
             _p: std::marker::PhantomData,
         }
@@ -262,11 +277,37 @@ impl<'a, S: Scheduler + Sync> Runtime<'a, S> {
     pub fn handle(&self) -> RuntimeHandle {
         RuntimeHandle {
             flowgraphs: self.flowgraphs.clone(),
+            registry: self.registry.clone(),
             scheduler: Arc::new(self.scheduler.clone()),
         }
     }
 }
 
+/// Registry with the core blocks, used to start flowgraphs from their textual description
+///
+/// Blocks that access the file system are left out, since flowgraphs can be started through the
+/// control port. With the `plugins` feature, the plugins of the configured `plugin_dir` are added.
+/// They are loaded once, when the first runtime is created; each runtime gets its own copy.
+fn core_registry() -> Arc<Mutex<BlockRegistry>> {
+    static CORE_REGISTRY: OnceCell<BlockRegistry> = OnceCell::new();
+
+    let registry = CORE_REGISTRY.get_or_init(|| {
+        let mut registry = BlockRegistry::new();
+        blocks::register(&mut registry);
+        registry.remove("FileSink");
+        registry.remove("FileSource");
+        #[cfg(all(feature = "plugins", not(target_arch = "wasm32")))]
+        if let Some(dir) = config::config().plugin_dir {
+            // The plugin directory is part of the trusted configuration.
+            if let Err(e) = unsafe { runtime::plugin::load_dir(dir, &mut registry) } {
+                warn!("{e}");
+            }
+        }
+        registry
+    });
+    Arc::new(Mutex::new(registry.clone()))
+}
+
 #[async_trait]
 trait Spawn {
     async fn start(&self, fg: Flowgraph) -> Result<FlowgraphHandle, Error>;
@@ -304,12 +345,14 @@ impl<S: Scheduler + Sync + 'static> Spawn for S {
 pub struct RuntimeHandle {
     scheduler: Arc<dyn Spawn + Send + Sync + 'static>,
     flowgraphs: Arc<Mutex<Slab<FlowgraphHandle>>>,
+    registry: Arc<Mutex<BlockRegistry>>,
 }
 
 impl fmt::Debug for RuntimeHandle {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RuntimeHandle")
             .field("flowgraphs", &self.flowgraphs)
+            .field("registry", &self.registry)
             .finish()
     }
 }
@@ -329,6 +372,27 @@ impl RuntimeHandle {
         Ok(handle)
     }
 
+    /// Build a [`Flowgraph`] from its textual description and start it on the runtime
+    ///
+    /// Blocks are created with the block registry of the runtime, which contains the core blocks
+    /// (see [`blocks::register`]) except `FileSink` and `FileSource`, the plugins of the
+    /// configured `plugin_dir`, and can be extended with [`register_blocks`](Self::register_blocks).
+    /// The syntax is described in [`Flowgraph::connect_str`]. Returns the id of the flowgraph,
+    /// used by the web handlers, and its handle.
+    pub async fn start_str(&self, s: &str) -> Result<(usize, FlowgraphHandle), Error> {
+        let mut fg = Flowgraph::new();
+        fg.connect_str(&self.registry.lock().unwrap(), s)?;
+        let handle = self.scheduler.start(fg).await?;
+
+        let id = self.add_flowgraph(handle.clone());
+        Ok((id, handle))
+    }
+
+    /// Add block types to the registry that is used for [`start_str`](Self::start_str)
+    pub fn register_blocks(&self, f: impl FnOnce(&mut BlockRegistry)) {
+        f(&mut self.registry.lock().unwrap());
+    }
+
     /// Add a [`FlowgraphHandle`] to make it available to web handlers
     fn add_flowgraph(&self, handle: FlowgraphHandle) -> usize {
         let mut v = self.flowgraphs.lock().unwrap();
@@ -341,16 +405,27 @@ impl RuntimeHandle {
     }
 
     /// Get list of flowgraph IDs
+    ///
+    /// Terminated flowgraphs are not included.
     pub fn get_flowgraphs(&self) -> Vec<usize> {
         self.flowgraphs
             .lock()
             .unwrap()
             .iter()
+            .filter(|x| !x.1.is_terminated())
             .map(|x| x.0)
             .collect()
     }
 }
 
+/// Publish an event to all subscribers, dropping the ones that disconnected
+fn publish(subscribers: &mut Vec<Sender<FlowgraphEvent>>, event: FlowgraphEvent) {
+    subscribers.retain_mut(|s| match s.try_send(event.clone()) {
+        Ok(()) => true,
+        Err(e) => !e.is_disconnected(),
+    });
+}
+
 pub(crate) async fn run_flowgraph<S: Scheduler>(
     mut fg: Flowgraph,
     scheduler: S,
@@ -367,6 +442,26 @@ pub(crate) async fn run_flowgraph<S: Scheduler>(
         return Err(e);
     }
 
+    let latency_interval = config::config().latency_interval;
+    let mut latency = HashMap::new();
+    if latency_interval > 0 {
+        for (id, block) in topology.blocks.iter_mut() {
+            if let Some(h) = block
+                .as_mut()
+                .and_then(|b| b.instrument_latency(latency_interval))
+            {
+                latency.insert(id, h);
+            }
+        }
+    }
+
+    // subscribe before the blocks are started to not miss updates
+    let config_subscriptions: Vec<_> = topology
+        .config_edges
+        .iter()
+        .map(|(key, dst, dst_port)| (config::subscribe(key.clone()), *dst, *dst_port))
+        .collect();
+
     let mut inboxes = scheduler.run_topology(&mut topology, &main_channel);
 
     debug!("connect stream io");
@@ -477,6 +572,27 @@ pub(crate) async fn run_flowgraph<S: Scheduler>(
             .expect("main inbox exceeded capacity during startup");
     }
 
+    debug!("forward config updates");
+    // tasks are cancelled when they are dropped on return
+    let _config_tasks: Vec<Task<()>> = config_subscriptions
+        .into_iter()
+        .filter_map(|(mut rx, dst, dst_port)| {
+            let mut inbox = inboxes[dst].clone()?;
+            Some(scheduler.spawn(async move {
+                while let Some(data) = rx.next().await {
+                    let port_id = PortId::Index(dst_port);
+                    if inbox
+                        .send(BlockMessage::Call { port_id, data })
+                        .await
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            }))
+        })
+        .collect();
+
     initialized
         .send(Ok(()))
         .expect("failed to signal flowgraph startup complete.");
@@ -488,6 +604,7 @@ pub(crate) async fn run_flowgraph<S: Scheduler>(
     }
 
     let mut terminated = false;
+    let mut subscribers = Vec::new();
 
     // main loop
     loop {
@@ -503,6 +620,14 @@ pub(crate) async fn run_flowgraph<S: Scheduler>(
                 data,
                 tx,
             } => {
+                publish(
+                    &mut subscribers,
+                    FlowgraphEvent::HandlerCalled {
+                        block_id,
+                        handler: port_id.to_string(),
+                        data: data.clone(),
+                    },
+                );
                 if let Some(inbox) = inboxes[block_id].as_mut() {
                     if inbox
                         .send(BlockMessage::Call { port_id, data })
@@ -523,6 +648,14 @@ pub(crate) async fn run_flowgraph<S: Scheduler>(
                 data,
                 tx,
             } => {
+                publish(
+                    &mut subscribers,
+                    FlowgraphEvent::HandlerCalled {
+                        block_id,
+                        handler: port_id.to_string(),
+                        data: data.clone(),
+                    },
+                );
                 let (block_tx, block_rx) = oneshot::channel::<Result<Pmt, Error>>();
                 if let Some(Some(inbox)) = inboxes.get_mut(block_id) {
                     if inbox
@@ -549,12 +682,14 @@ pub(crate) async fn run_flowgraph<S: Scheduler>(
                 *topology.blocks.get_mut(block_id).unwrap() = Some(block);
                 inboxes[block_id] = None;
                 active_blocks -= 1;
+                publish(&mut subscribers, FlowgraphEvent::BlockDone { block_id });
             }
             FlowgraphMessage::BlockError { block_id, block } => {
                 *topology.blocks.get_mut(block_id).unwrap() = Some(block);
                 inboxes[block_id] = None;
                 block_error = true;
                 active_blocks -= 1;
+                publish(&mut subscribers, FlowgraphEvent::BlockError { block_id });
                 let _ = main_channel.send(FlowgraphMessage::Terminate).await;
             }
             FlowgraphMessage::BlockDescription { block_id, tx } => {
@@ -598,18 +733,67 @@ pub(crate) async fn run_flowgraph<S: Scheduler>(
                     .flat_map(|x| x.1.iter().map(|y| (x.0 .0, x.0 .1, y.0, y.1)))
                     .collect();
                 let message_edges = topology.message_edges.clone();
+                let stream_buffers = topology
+                    .stream_edges
+                    .keys()
+                    .map(|(src, src_port, buffer)| (*src, *src_port, buffer.description()))
+                    .collect();
 
                 if tx
                     .send(FlowgraphDescription {
                         blocks,
                         stream_edges,
                         message_edges,
+                        stream_buffers,
                     })
                     .is_err()
                 {
                     error!("Failed to send flowgraph description. Receiver may have disconnected.");
                 }
             }
+            FlowgraphMessage::Latency { tx } => {
+                let l: HashMap<usize, LatencyHistogram> = latency
+                    .iter()
+                    .map(|(id, h)| (*id, h.lock().unwrap().clone()))
+                    .collect();
+                if tx.send(l).is_err() {
+                    error!("Failed to send latency histograms. Receiver may have disconnected.");
+                }
+            }
+            FlowgraphMessage::Subscribe { tx } => subscribers.push(tx),
+            FlowgraphMessage::Tap {
+                block_id,
+                port,
+                mut tx,
+            } => {
+                if let Some(Some(inbox)) = inboxes.get_mut(block_id) {
+                    let (tap_tx, mut tap_rx) = channel::<BlockMessage>(config::config().queue_size);
+                    if inbox
+                        .send(BlockMessage::MessageOutputConnect {
+                            src_port: port,
+                            dst_port: 0,
+                            dst_inbox: tap_tx,
+                        })
+                        .await
+                        .is_ok()
+                    {
+                        // drain the tap, so that the block never waits for a slow receiver
+                        scheduler
+                            .spawn(async move {
+                                while let Some(m) = tap_rx.next().await {
+                                    if let BlockMessage::Call { data, .. } = m {
+                                        if let Err(e) = tx.try_send(data) {
+                                            if e.is_disconnected() {
+                                                break;
+                                            }
+                                        }
+                                    }
+                                }
+                            })
+                            .detach();
+                    }
+                }
+            }
             FlowgraphMessage::Terminate => {
                 if !terminated {
                     for (_, opt) in inboxes.iter_mut() {
@@ -628,6 +812,8 @@ pub(crate) async fn run_flowgraph<S: Scheduler>(
         }
     }
 
+    publish(&mut subscribers, FlowgraphEvent::Terminated);
+
     fg.topology = Some(topology);
     if block_error {
         return Err(Error::RuntimeError("A block raised an error".to_string()));
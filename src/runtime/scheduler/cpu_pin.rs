@@ -1,8 +1,11 @@
 use super::flow::FlowExecutor;
 use crate::runtime::config;
+use crate::runtime::scheduler::take_fused;
+use crate::runtime::scheduler::BlockPinning;
 use crate::runtime::scheduler::Scheduler;
 use crate::runtime::BlockMessage;
 use crate::runtime::FlowgraphMessage;
+use crate::runtime::Priority;
 use crate::runtime::Topology;
 use async_io::block_on;
 use async_lock::Barrier;
@@ -21,7 +24,8 @@ type CpuPins = HashMap<usize, usize>;
 
 /// CPU pin scheduler
 ///
-/// Pins blocks to worker threads fixed to CPUs according to a hashmap.
+/// Pins blocks to worker threads fixed to CPUs according to a hashmap of block ids and a
+/// [`BlockPinning`] by block names.
 #[derive(Clone, Debug)]
 pub struct CpuPinScheduler {
     inner: Arc<CpuPinSchedulerInner>,
@@ -31,6 +35,7 @@ struct CpuPinSchedulerInner {
     executor: Arc<FlowExecutor>,
     workers: Vec<(thread::JoinHandle<()>, oneshot::Sender<()>)>,
     cpu_pins: CpuPins,
+    pinning: BlockPinning,
 }
 
 impl fmt::Debug for CpuPinSchedulerInner {
@@ -54,7 +59,19 @@ impl Drop for CpuPinSchedulerInner {
 
 impl CpuPinScheduler {
     /// Create CPU pin scheduler
+    ///
+    /// Blocks that are not in `cpu_pins` are pinned according to the `cpu_pins` of the
+    /// [config](crate::runtime::config).
     pub fn new(cpu_pins: CpuPins) -> CpuPinScheduler {
+        Self::with_pins(cpu_pins, BlockPinning::from_config())
+    }
+
+    /// Create CPU pin scheduler, pinning blocks by name
+    pub fn with_pinning(pinning: BlockPinning) -> CpuPinScheduler {
+        Self::with_pins(HashMap::new(), pinning)
+    }
+
+    fn with_pins(cpu_pins: CpuPins, pinning: BlockPinning) -> CpuPinScheduler {
         let executor = Arc::new(FlowExecutor::new());
         let mut workers = Vec::new();
 
@@ -97,6 +114,7 @@ impl CpuPinScheduler {
                 executor,
                 workers,
                 cpu_pins,
+                pinning,
             }),
         }
     }
@@ -115,12 +133,30 @@ impl Scheduler for CpuPinScheduler {
         }
         let queue_size = config::config().queue_size;
 
-        let _n_blocks = topology.blocks.len();
-        let _n_cores = self.inner.workers.len();
+        let mut cpu_pins = self
+            .inner
+            .pinning
+            .resolve(topology, self.inner.workers.len());
+        cpu_pins.extend(self.inner.cpu_pins.iter());
+
+        // spawn fused block chains
+        for (id, priority, chain) in take_fused(topology, main_channel, &mut inboxes, queue_size) {
+            if let Some(&c) = cpu_pins.get(&id) {
+                self.inner
+                    .executor
+                    .spawn_executor(chain, c, priority)
+                    .detach();
+            } else {
+                self.inner.executor.spawn_priority(chain, priority).detach();
+            }
+        }
 
         // spawn block executors
         for (id, block_o) in topology.blocks.iter_mut() {
-            let block = block_o.take().unwrap();
+            let Some(block) = block_o.take() else {
+                continue;
+            };
+            let priority = block.priority();
             // println!("{}: {}", id, block.instance_name().unwrap());
 
             let (sender, receiver) = channel::<BlockMessage>(queue_size);
@@ -130,26 +166,27 @@ impl Scheduler for CpuPinScheduler {
                 let main = main_channel.clone();
                 debug!("spawing block on executor");
 
-                if let Some(&c) = self.inner.cpu_pins.get(&id) {
+                if let Some(&c) = cpu_pins.get(&id) {
                     self.inner
                         .executor
                         .spawn_executor(
                             blocking::unblock(move || block_on(block.run(id, main, receiver))),
                             c,
+                            Priority::Normal,
                         )
                         .detach();
                 } else {
                     panic!("foo");
                 }
-            } else if let Some(&c) = self.inner.cpu_pins.get(&id) {
+            } else if let Some(&c) = cpu_pins.get(&id) {
                 self.inner
                     .executor
-                    .spawn_executor(block.run(id, main_channel.clone(), receiver), c)
+                    .spawn_executor(block.run(id, main_channel.clone(), receiver), c, priority)
                     .detach();
             } else {
                 self.inner
                     .executor
-                    .spawn(block.run(id, main_channel.clone(), receiver))
+                    .spawn_priority(block.run(id, main_channel.clone(), receiver), priority)
                     .detach();
             }
         }
@@ -6,15 +6,18 @@ use concurrent_queue::ConcurrentQueue;
 use futures::channel::mpsc::channel;
 use futures::channel::mpsc::Sender;
 use futures::channel::oneshot;
+use futures::future::BoxFuture;
 use futures_lite::future::Future;
 use futures_lite::future::FutureExt;
 use futures_lite::future::{self};
 use slab::Slab;
 // use std::cmp;
+use std::collections::HashMap;
 use std::fmt;
 use std::panic::RefUnwindSafe;
 use std::panic::UnwindSafe;
 use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
@@ -23,24 +26,69 @@ use std::sync::RwLock;
 use std::task::Poll;
 use std::task::Waker;
 use std::thread;
+use std::time::Duration;
+use std::time::Instant;
 
 use crate::runtime::config;
+use crate::runtime::scheduler::take_fused;
+use crate::runtime::scheduler::BlockPinning;
 use crate::runtime::scheduler::Scheduler;
 use crate::runtime::BlockMessage;
 use crate::runtime::FlowgraphMessage;
+use crate::runtime::Priority;
 use crate::runtime::Topology;
 
 /// Flow scheduler
 ///
-/// Groups blocks and puts them fixed in local queues of worker threads.
+/// Groups blocks and puts them fixed in local queues of worker threads. Blocks are assigned to
+/// workers according to a [`BlockPinning`] and, if they are not pinned, according to their id.
+///
+/// In [adaptive](FlowScheduler::adaptive) mode, the scheduler measures the CPU time of the blocks
+/// and periodically migrates unpinned blocks between workers to balance their load.
 #[derive(Clone, Debug)]
 pub struct FlowScheduler {
     inner: Arc<FlowSchedulerInner>,
+    adaptive: Option<Duration>,
 }
 
 struct FlowSchedulerInner {
     executor: Arc<FlowExecutor>,
     workers: Vec<(thread::JoinHandle<()>, oneshot::Sender<()>)>,
+    pinning: BlockPinning,
+    migrations: Arc<Mutex<Vec<Migration>>>,
+    tasks: Mutex<Vec<Arc<MigratableTask>>>,
+}
+
+/// Migration of a block between workers, decided by an adaptive [`FlowScheduler`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Migration {
+    /// Block id (first block of fused chains)
+    pub block: usize,
+    /// Previous worker
+    pub from: usize,
+    /// New worker
+    pub to: usize,
+    /// Share of the interval, the block was busy
+    pub block_load: f64,
+    /// Share of the interval, each worker was busy before the migration
+    pub worker_loads: Vec<f64>,
+}
+
+/// Load and migrations of a block, measured by an adaptive [`FlowScheduler`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockStats {
+    /// Block id (first block of fused chains)
+    pub block: usize,
+    /// Current worker
+    pub worker: usize,
+    /// Whether the block is pinned to its worker
+    pub pinned: bool,
+    /// Total time, the block was busy
+    pub busy: Duration,
+    /// Share of the last interval, the block was busy
+    pub load: f64,
+    /// Number of times, the block was moved to another worker
+    pub migrations: usize,
 }
 
 impl fmt::Debug for FlowSchedulerInner {
@@ -64,16 +112,31 @@ impl Drop for FlowSchedulerInner {
 
 impl FlowScheduler {
     /// Create Flow scheduler
+    ///
+    /// Uses the `cpu_pins` of the [config](crate::runtime::config) to assign blocks to workers.
     pub fn new() -> FlowScheduler {
+        Self::with_pinning(BlockPinning::from_config())
+    }
+
+    /// Create Flow scheduler, assigning blocks to workers by name
+    pub fn with_pinning(pinning: BlockPinning) -> FlowScheduler {
+        let n_workers = core_affinity::get_core_ids().unwrap().len();
+        Self::with_workers(n_workers, pinning)
+    }
+
+    /// Create Flow scheduler with the given number of workers, assigning blocks to workers by name
+    ///
+    /// Workers are pinned to the CPUs in a round-robin fashion.
+    pub fn with_workers(n_workers: usize, pinning: BlockPinning) -> FlowScheduler {
         let executor = Arc::new(FlowExecutor::new());
         let mut workers = Vec::new();
 
         let core_ids = core_affinity::get_core_ids().unwrap();
         debug!("flowsched: core ids {}", core_ids.len());
 
-        let barrier = Arc::new(Barrier::new(core_ids.len() + 1));
+        let barrier = Arc::new(Barrier::new(n_workers + 1));
 
-        for id in core_ids {
+        for id in core_ids.into_iter().cycle().take(n_workers) {
             let b = barrier.clone();
             let e = executor.clone();
             let (sender, receiver) = oneshot::channel::<()>();
@@ -103,8 +166,158 @@ impl FlowScheduler {
         async_io::block_on(barrier.wait());
 
         FlowScheduler {
-            inner: Arc::new(FlowSchedulerInner { executor, workers }),
+            inner: Arc::new(FlowSchedulerInner {
+                executor,
+                workers,
+                pinning,
+                migrations: Arc::new(Mutex::new(Vec::new())),
+                tasks: Mutex::new(Vec::new()),
+            }),
+            adaptive: None,
+        }
+    }
+
+    /// Rebalance blocks between workers
+    ///
+    /// Every `interval`, the busiest unpinned block that reduces the load difference between the
+    /// most and the least loaded worker is moved, preferring blocks whose stream neighbors run on
+    /// the target worker.
+    #[must_use]
+    pub fn adaptive(mut self, interval: Duration) -> Self {
+        self.adaptive = Some(interval);
+        self
+    }
+
+    /// Migrations decided in adaptive mode
+    pub fn migrations(&self) -> Vec<Migration> {
+        self.inner.migrations.lock().unwrap().clone()
+    }
+
+    /// Per-block statistics of the last flowgraph run in adaptive mode
+    pub fn block_stats(&self) -> Vec<BlockStats> {
+        let interval = self.adaptive.map(|i| i.as_nanos() as f64).unwrap_or(1.0);
+        self.inner
+            .tasks
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|t| BlockStats {
+                block: t.id,
+                worker: t.worker(),
+                pinned: t.pinned,
+                busy: Duration::from_nanos(t.busy_ns.load(Ordering::Relaxed)),
+                load: t.load_ns.load(Ordering::Relaxed) as f64 / interval,
+                migrations: t.migrations.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+
+    /// Number of task slots in the local queues of the workers
+    ///
+    /// Slots of migrated blocks are reused, i.e., this does not grow with the number of
+    /// migrations.
+    pub fn local_slots(&self) -> usize {
+        self.inner.executor.local_slots()
+    }
+
+    fn run_adaptive(
+        &self,
+        topology: &mut Topology,
+        main_channel: &Sender<FlowgraphMessage>,
+        inboxes: &mut Slab<Option<Sender<BlockMessage>>>,
+        pins: HashMap<usize, usize>,
+        interval: Duration,
+    ) {
+        let queue_size = config::config().queue_size;
+        let n_workers = self.inner.workers.len();
+        let executor = &self.inner.executor;
+
+        // stream neighbors of tasks, fused chains are represented by their first block
+        let mut owner = HashMap::new();
+        for chain in topology.fused.iter() {
+            for b in chain.iter() {
+                owner.insert(*b, chain[0]);
+            }
+        }
+        let key = |b: &usize| owner.get(b).copied().unwrap_or(*b);
+        let mut neighbors: HashMap<usize, Vec<usize>> = HashMap::new();
+        for ((src, _, _), dsts) in topology.stream_edges.iter() {
+            for (dst, _) in dsts.iter() {
+                let (src, dst) = (key(src), key(dst));
+                if src != dst {
+                    neighbors.entry(src).or_default().push(dst);
+                    neighbors.entry(dst).or_default().push(src);
+                }
+            }
+        }
+
+        let mut units: Vec<(usize, Priority, BoxFuture<'static, ()>)> =
+            take_fused(topology, main_channel, inboxes, queue_size)
+                .into_iter()
+                .map(|(id, priority, chain)| (id, priority, chain.boxed()))
+                .collect();
+
+        for (id, block_o) in topology.blocks.iter_mut() {
+            let Some(block) = block_o.take() else {
+                continue;
+            };
+
+            let (sender, receiver) = channel::<BlockMessage>(queue_size);
+            inboxes[id] = Some(sender);
+
+            if block.is_blocking() {
+                let main = main_channel.clone();
+                executor
+                    .spawn(blocking::unblock(move || {
+                        block_on(block.run(id, main, receiver))
+                    }))
+                    .detach();
+            } else {
+                let priority = block.priority();
+                let run = block.run(id, main_channel.clone(), receiver).boxed();
+                units.push((id, priority, run));
+            }
         }
+        units.sort_by_key(|u| u.0);
+
+        // start with contiguous groups of blocks, which keeps producers and consumers of
+        // linear flowgraphs together
+        let n_free = units.iter().filter(|u| !pins.contains_key(&u.0)).count();
+        let mut tasks = Vec::new();
+        let mut i = 0;
+        for (id, priority, run) in units {
+            let (worker, pinned) = match pins.get(&id) {
+                Some(w) => (*w, true),
+                None => {
+                    i += 1;
+                    ((i - 1) * n_workers / n_free, false)
+                }
+            };
+            let route = (worker, executor.local_slot(worker, priority));
+            let task = Arc::new(MigratableTask {
+                id,
+                priority,
+                pinned,
+                route: spin::Mutex::new(route),
+                busy_ns: AtomicU64::new(0),
+                load_ns: AtomicU64::new(0),
+                migrations: AtomicUsize::new(0),
+                done: AtomicBool::new(false),
+            });
+            executor.spawn_migratable(run, task.clone()).detach();
+            tasks.push(task);
+        }
+        *self.inner.tasks.lock().unwrap() = tasks.clone();
+
+        let rebalancer = Rebalancer {
+            executor: executor.clone(),
+            tasks,
+            neighbors,
+            n_workers,
+            interval,
+            migrations: self.inner.migrations.clone(),
+        };
+        executor.spawn(rebalancer.run()).detach();
     }
 
     // fn map_block(block: usize, n_blocks: usize, n_cores: usize) -> usize {
@@ -148,12 +361,41 @@ impl Scheduler for FlowScheduler {
         }
         let queue_size = config::config().queue_size;
 
-        let _n_blocks = topology.blocks.len();
-        let _n_cores = self.inner.workers.len();
+        let pins = self
+            .inner
+            .pinning
+            .resolve(topology, self.inner.workers.len());
+
+        if let Some(interval) = self.adaptive {
+            self.run_adaptive(topology, main_channel, &mut inboxes, pins, interval);
+            return inboxes;
+        }
+
+        let n_workers = self.inner.workers.len();
+        let map_block = |id| {
+            pins.get(&id)
+                .copied()
+                .or(FlowScheduler::map_block(id, 0, 0).filter(|w| *w < n_workers))
+        };
+
+        // spawn fused block chains
+        for (id, priority, chain) in take_fused(topology, main_channel, &mut inboxes, queue_size) {
+            if let Some(c) = map_block(id) {
+                self.inner
+                    .executor
+                    .spawn_executor(chain, c, priority)
+                    .detach();
+            } else {
+                self.inner.executor.spawn_priority(chain, priority).detach();
+            }
+        }
 
         // spawn block executors
         for (id, block_o) in topology.blocks.iter_mut() {
-            let block = block_o.take().unwrap();
+            let Some(block) = block_o.take() else {
+                continue;
+            };
+            let priority = block.priority();
             // println!("{}: {}", id, block.instance_name().unwrap());
 
             let (sender, receiver) = channel::<BlockMessage>(queue_size);
@@ -163,26 +405,27 @@ impl Scheduler for FlowScheduler {
                 let main = main_channel.clone();
                 debug!("spawing block on executor");
 
-                if let Some(c) = FlowScheduler::map_block(id, 0, 0) {
+                if let Some(c) = map_block(id) {
                     self.inner
                         .executor
                         .spawn_executor(
                             blocking::unblock(move || block_on(block.run(id, main, receiver))),
                             c,
+                            Priority::Normal,
                         )
                         .detach();
                 } else {
                     panic!("foo");
                 }
-            } else if let Some(c) = FlowScheduler::map_block(id, 0, 0) {
+            } else if let Some(c) = map_block(id) {
                 self.inner
                     .executor
-                    .spawn_executor(block.run(id, main_channel.clone(), receiver), c)
+                    .spawn_executor(block.run(id, main_channel.clone(), receiver), c, priority)
                     .detach();
             } else {
                 self.inner
                     .executor
-                    .spawn(block.run(id, main_channel.clone(), receiver))
+                    .spawn_priority(block.run(id, main_channel.clone(), receiver), priority)
                     .detach();
             }
         }
@@ -213,6 +456,125 @@ impl Default for FlowScheduler {
     }
 }
 
+/// Task of an adaptive [`FlowScheduler`] that can be moved between workers
+struct MigratableTask {
+    id: usize,
+    priority: Priority,
+    pinned: bool,
+    /// Executor and slot in its local queue
+    route: spin::Mutex<(usize, usize)>,
+    /// Time spent polling the task
+    busy_ns: AtomicU64,
+    /// Time spent polling the task in the last interval
+    load_ns: AtomicU64,
+    migrations: AtomicUsize,
+    done: AtomicBool,
+}
+
+impl MigratableTask {
+    fn worker(&self) -> usize {
+        self.route.lock().0
+    }
+}
+
+/// Periodically moves tasks from the most to the least loaded worker
+struct Rebalancer {
+    executor: Arc<FlowExecutor>,
+    tasks: Vec<Arc<MigratableTask>>,
+    neighbors: HashMap<usize, Vec<usize>>,
+    n_workers: usize,
+    interval: Duration,
+    migrations: Arc<Mutex<Vec<Migration>>>,
+}
+
+impl Rebalancer {
+    async fn run(self) {
+        let mut last = vec![0; self.tasks.len()];
+        while self.tasks.iter().any(|t| !t.done.load(Ordering::SeqCst)) {
+            async_io::Timer::after(self.interval).await;
+            let busy: Vec<u64> = self
+                .tasks
+                .iter()
+                .zip(last.iter_mut())
+                .map(|(t, l)| {
+                    let b = t.busy_ns.load(Ordering::Relaxed);
+                    let d = b - *l;
+                    *l = b;
+                    t.load_ns.store(d, Ordering::Relaxed);
+                    d
+                })
+                .collect();
+            self.rebalance(&busy);
+        }
+    }
+
+    fn rebalance(&self, busy: &[u64]) {
+        let workers: HashMap<usize, usize> =
+            self.tasks.iter().map(|t| (t.id, t.worker())).collect();
+        let mut load = vec![0; self.n_workers];
+        for (t, b) in self.tasks.iter().zip(busy) {
+            load[workers[&t.id]] += b;
+        }
+
+        let (hi, lo) = match (
+            (0..self.n_workers).max_by_key(|w| load[*w]),
+            (0..self.n_workers).min_by_key(|w| load[*w]),
+        ) {
+            (Some(hi), Some(lo)) => (hi, lo),
+            _ => return,
+        };
+        let diff = load[hi] - load[lo];
+        let interval = self.interval.as_nanos() as u64;
+        // ignore small imbalances to avoid moving blocks back and forth
+        if diff < interval / 10 {
+            return;
+        }
+
+        // moving a block with load `b` reduces the difference, if `0 < b < diff`
+        let candidate = self
+            .tasks
+            .iter()
+            .zip(busy.iter().copied())
+            .filter(|(t, b)| {
+                !t.pinned
+                    && !t.done.load(Ordering::SeqCst)
+                    && workers[&t.id] == hi
+                    && *b > 0
+                    && *b < diff
+            })
+            .max_by_key(|(t, b)| {
+                let colocated: i64 = self
+                    .neighbors
+                    .get(&t.id)
+                    .map(|n| {
+                        n.iter()
+                            .map(|n| match workers.get(n) {
+                                Some(w) if *w == lo => 1,
+                                Some(w) if *w == hi => -1,
+                                _ => 0,
+                            })
+                            .sum()
+                    })
+                    .unwrap_or(0);
+                (colocated, std::cmp::Reverse(diff.abs_diff(2 * b)))
+            });
+
+        if let Some((task, b)) = candidate {
+            let migration = Migration {
+                block: task.id,
+                from: hi,
+                to: lo,
+                block_load: b as f64 / interval as f64,
+                worker_loads: load.iter().map(|l| *l as f64 / interval as f64).collect(),
+            };
+            debug!("flow scheduler: {:?}", migration);
+            self.executor.migrate(task, lo);
+            task.migrations.fetch_add(1, Ordering::Relaxed);
+            self.migrations.lock().unwrap().push(migration);
+        }
+    }
+}
+
 /// An async executor.
 ///
 /// # Examples
@@ -277,6 +639,17 @@ impl FlowExecutor {
     pub fn spawn<T: Send + 'static>(
         &self,
         future: impl Future<Output = T> + Send + 'static,
+    ) -> Task<T> {
+        self.spawn_priority(future, Priority::Normal)
+    }
+
+    /// Spawns a task onto the global queue of the given priority class.
+    ///
+    /// Workers serve ready tasks of higher priority classes first.
+    pub fn spawn_priority<T: Send + 'static>(
+        &self,
+        future: impl Future<Output = T> + Send + 'static,
+        priority: Priority,
     ) -> Task<T> {
         let mut active = self.state().active.lock().unwrap();
 
@@ -290,17 +663,22 @@ impl FlowExecutor {
         };
 
         // Create the task and register it in the set of active tasks.
-        let (runnable, task) = unsafe { async_task::spawn_unchecked(future, self.schedule()) };
+        let (runnable, task) =
+            unsafe { async_task::spawn_unchecked(future, self.schedule(priority)) };
         entry.insert(runnable.waker());
 
         runnable.schedule();
         task
     }
 
+    /// Spawns a task onto the local queue of the given executor.
+    ///
+    /// Ready tasks of higher priority classes are run first.
     pub fn spawn_executor<T: Send + 'static>(
         &self,
         future: impl Future<Output = T> + Send + 'static,
         executor: usize,
+        priority: Priority,
     ) -> Task<T> {
         let mut active = self.state().active.lock().unwrap();
 
@@ -313,13 +691,7 @@ impl FlowExecutor {
             future.await
         };
 
-        // Creates a slot for the task in the local queue of the executor
-        let queues = self.state().local_queues.write().unwrap();
-        let mut inner = queues[executor].lock();
-        let n = inner.1.len();
-        inner.1.push(None);
-        drop(inner);
-        drop(queues);
+        let n = self.local_slot(executor, priority);
 
         // Create the task and register it in the set of active tasks.
         let (runnable, task) =
@@ -330,6 +702,96 @@ impl FlowExecutor {
         task
     }
 
+    /// Spawns a task that can be moved between local queues (see [`FlowExecutor::migrate`]).
+    ///
+    /// The time spent polling the task is accumulated in the [`MigratableTask`].
+    fn spawn_migratable<T: Send + 'static>(
+        &self,
+        future: impl Future<Output = T> + Send + 'static,
+        task: Arc<MigratableTask>,
+    ) -> Task<T> {
+        let mut active = self.state().active.lock().unwrap();
+
+        // Remove the task from the set of active tasks when the future finishes.
+        let entry = active.vacant_entry();
+        let key = entry.key();
+        let state = self.state().clone();
+        let t = task.clone();
+        let mut future = Box::pin(future);
+        let future = async move {
+            let _guard = CallOnDrop(move || drop(state.active.lock().unwrap().remove(key)));
+            let output = future::poll_fn(|cx| {
+                let start = Instant::now();
+                let ret = future.as_mut().poll(cx);
+                t.busy_ns
+                    .fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+                ret
+            })
+            .await;
+            t.done.store(true, Ordering::SeqCst);
+            output
+        };
+
+        // Create the task and register it in the set of active tasks.
+        let (runnable, task) =
+            unsafe { async_task::spawn_unchecked(future, self.schedule_migratable(task)) };
+        entry.insert(runnable.waker());
+
+        runnable.schedule();
+        task
+    }
+
+    /// Moves a task to the local queue of another executor.
+    ///
+    /// The slot in the previous local queue is freed for reuse. If the task is already
+    /// scheduled, it is moved along.
+    fn migrate(&self, task: &MigratableTask, executor: usize) {
+        let mut route = task.route.lock();
+        let (old_executor, old_n) = *route;
+        if old_executor == executor {
+            return;
+        }
+
+        let runnable = {
+            let queues = self.state().local_queues.read().unwrap();
+            let mut inner = queues[old_executor].lock();
+            inner.2.push(old_n);
+            inner.1[old_n].1.take()
+        };
+
+        let n = self.local_slot(executor, task.priority);
+        *route = (executor, n);
+        if let Some(runnable) = runnable {
+            self.state().local_queues.read().unwrap()[executor].lock().1[n].1 = Some(runnable);
+            self.state().notify_executor(executor);
+        }
+    }
+
+    /// Creates a slot for a task in the local queue of the executor, reusing freed slots
+    fn local_slot(&self, executor: usize, priority: Priority) -> usize {
+        let queues = self.state().local_queues.read().unwrap();
+        let mut inner = queues[executor].lock();
+        if let Some(n) = inner.2.pop() {
+            inner.1[n].0 = priority;
+            n
+        } else {
+            let n = inner.1.len();
+            inner.1.push((priority, None));
+            n
+        }
+    }
+
+    /// Number of task slots in the local queues of all executors
+    fn local_slots(&self) -> usize {
+        self.state()
+            .local_queues
+            .read()
+            .unwrap()
+            .iter()
+            .map(|q| q.lock().1.len())
+            .sum()
+    }
+
     /// Runs the executor until the given future completes.
     ///
     /// # Examples
@@ -362,12 +824,12 @@ impl FlowExecutor {
     }
 
     /// Returns a function that schedules a runnable task when it gets woken up.
-    fn schedule(&self) -> impl Fn(Runnable) + Send + Sync + 'static {
+    fn schedule(&self, priority: Priority) -> impl Fn(Runnable) + Send + Sync + 'static {
         let state = self.state().clone();
 
         // TODO(stjepang): If possible, push into the current local queue and notify the ticker.
         move |runnable| {
-            state.queue.push(runnable).unwrap();
+            state.queues[priority as usize].push(runnable).unwrap();
             state.notify();
         }
     }
@@ -383,8 +845,28 @@ impl FlowExecutor {
 
         move |runnable| {
             {
-                local.lock().1[n_task] = Some(runnable);
+                local.lock().1[n_task].1 = Some(runnable);
+            }
+            state.notify_executor(executor);
+        }
+    }
+
+    /// Returns a function that schedules a runnable task on its current executor.
+    fn schedule_migratable(
+        &self,
+        task: Arc<MigratableTask>,
+    ) -> impl Fn(Runnable) + Send + Sync + 'static {
+        let state = self.state().clone();
+
+        move |runnable| {
+            // hold the route, so that the slot is not freed by a concurrent migration
+            let route = task.route.lock();
+            let (executor, n_task) = *route;
+            let local = state.local_queues.read().unwrap()[executor].clone();
+            {
+                local.lock().1[n_task].1 = Some(runnable);
             }
+            drop(route);
             state.notify_executor(executor);
         }
     }
@@ -408,7 +890,9 @@ impl Drop for FlowExecutor {
 
             drop(active);
 
-            while state.queue.pop().is_ok() {}
+            for q in state.queues.iter() {
+                while q.pop().is_ok() {}
+            }
 
             for q in state.local_queues.write().unwrap().iter() {
                 let runnables = &mut q.lock().1;
@@ -418,14 +902,17 @@ impl Drop for FlowExecutor {
     }
 }
 
+/// Local queue of a runner: round-robin offset, task slots with their priority class, and freed
+/// slots.
+type LocalQueue = spin::Mutex<(usize, Vec<(Priority, Option<Runnable>)>, Vec<usize>)>;
+
 /// The state of a executor.
 struct State {
-    /// The global queue.
-    queue: ConcurrentQueue<Runnable>,
+    /// The global queues, one per priority class.
+    queues: [ConcurrentQueue<Runnable>; 3],
 
     /// Local queues created by runners.
-    #[allow(clippy::type_complexity)]
-    local_queues: RwLock<Vec<Arc<spin::Mutex<(usize, Vec<Option<Runnable>>)>>>>,
+    local_queues: RwLock<Vec<Arc<LocalQueue>>>,
 
     /// Set to `true` when a sleeping ticker is notified or no tickers are sleeping.
     notified: AtomicBool,
@@ -441,7 +928,7 @@ impl State {
     /// Creates state for a new executor.
     fn new() -> State {
         State {
-            queue: ConcurrentQueue::unbounded(),
+            queues: std::array::from_fn(|_| ConcurrentQueue::unbounded()),
             local_queues: RwLock::new(Vec::new()),
             notified: AtomicBool::new(true),
             sleepers: spin::Mutex::new(Sleepers {
@@ -726,13 +1213,13 @@ struct Runner<'a> {
     /// Inner ticker.
     ticker: Ticker<'a>,
     /// The local queue.
-    local: Arc<spin::Mutex<(usize, Vec<Option<Runnable>>)>>,
+    local: Arc<LocalQueue>,
 }
 
 impl Runner<'_> {
     /// Creates a runner and registers it in the executor state.
     fn new(state: &State) -> Runner<'_> {
-        let local = Arc::new(spin::Mutex::new((0, Vec::new())));
+        let local = Arc::new(spin::Mutex::new((0, Vec::new(), Vec::new())));
 
         let mut s = state.local_queues.write().unwrap();
 
@@ -751,23 +1238,23 @@ impl Runner<'_> {
         let runnable = self
             .ticker
             .runnable_with(|| {
-                // Try the local queue.
+                // Serve higher priority classes first, trying the local queue before
+                // stealing from the global queue of the class.
                 let mut item = self.local.lock();
-                let mut offset = item.0;
-                let q = &mut item.1;
+                let (offset, q, _) = &mut *item;
                 let l = q.len();
-                for (n, runnable) in q.iter().cycle().skip(offset).take(l).enumerate() {
-                    if runnable.is_some() {
-                        offset = (offset + n) % l;
-                        let ret = q[offset].take();
-                        item.0 = (offset + 1) % l;
-                        return ret;
+                for priority in [Priority::Realtime, Priority::Normal, Priority::Background] {
+                    for n in 0..l {
+                        let i = (*offset + n) % l;
+                        if q[i].0 == priority && q[i].1.is_some() {
+                            *offset = (i + 1) % l;
+                            return q[i].1.take();
+                        }
                     }
-                }
 
-                // Try stealing one task from global queue
-                if let Ok(r) = self.state.queue.pop() {
-                    return Some(r);
+                    if let Ok(r) = self.state.queues[priority as usize].pop() {
+                        return Some(r);
+                    }
                 }
 
                 None
@@ -833,3 +1320,101 @@ impl<F: Fn()> Drop for CallOnDrop<F> {
 //         assert_eq!(a, vec![0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2]);
 //     }
 // }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rebalancer(neighbors: HashMap<usize, Vec<usize>>) -> Rebalancer {
+        let executor = Arc::new(FlowExecutor::new());
+        // create local queues of two workers
+        let _ = Runner::new(executor.state());
+        let _ = Runner::new(executor.state());
+
+        let tasks = [0, 0, 0, 1]
+            .iter()
+            .enumerate()
+            .map(|(id, worker)| {
+                Arc::new(MigratableTask {
+                    id,
+                    priority: Priority::Normal,
+                    pinned: false,
+                    route: spin::Mutex::new((
+                        *worker,
+                        executor.local_slot(*worker, Priority::Normal),
+                    )),
+                    busy_ns: AtomicU64::new(0),
+                    load_ns: AtomicU64::new(0),
+                    migrations: AtomicUsize::new(0),
+                    done: AtomicBool::new(false),
+                })
+            })
+            .collect();
+
+        Rebalancer {
+            executor,
+            tasks,
+            neighbors,
+            n_workers: 2,
+            interval: Duration::from_millis(10),
+            migrations: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    #[test]
+    fn rebalance() {
+        let ms = 1_000_000;
+
+        // move the block that balances the load best
+        let r = rebalancer(HashMap::new());
+        r.rebalance(&[6 * ms, 3 * ms, ms, 0]);
+        let m = r.migrations.lock().unwrap().clone();
+        assert_eq!(m.len(), 1);
+        assert_eq!((m[0].block, m[0].from, m[0].to), (0, 0, 1));
+        assert_eq!(m[0].worker_loads, vec![1.0, 0.0]);
+        assert_eq!(r.tasks[0].worker(), 1);
+        assert_eq!(r.tasks[0].migrations.load(Ordering::Relaxed), 1);
+
+        // slots of migrated tasks are reused
+        for i in 0..10 {
+            r.executor.migrate(&r.tasks[0], i % 2);
+        }
+        assert_eq!(r.executor.local_slots(), 5);
+
+        // prefer blocks with neighbors on the target worker
+        let r = rebalancer(HashMap::from([(2, vec![3]), (3, vec![2])]));
+        r.rebalance(&[6 * ms, 3 * ms, ms, 0]);
+        assert_eq!(r.migrations.lock().unwrap()[0].block, 2);
+
+        // ignore small imbalances
+        let r = rebalancer(HashMap::new());
+        r.rebalance(&[ms / 2, 0, 0, 0]);
+        assert!(r.migrations.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn priority_order() {
+        let executor = FlowExecutor::new();
+        let runner = Runner::new(executor.state());
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        let record = |name: &'static str| {
+            let order = order.clone();
+            async move { order.lock().unwrap().push(name) }
+        };
+        let tasks = vec![
+            executor.spawn_priority(record("background"), Priority::Background),
+            executor.spawn_priority(record("normal"), Priority::Normal),
+            executor.spawn_executor(record("normal local"), 0, Priority::Normal),
+            executor.spawn_priority(record("realtime"), Priority::Realtime),
+        ];
+
+        for _ in 0..tasks.len() {
+            block_on(runner.runnable()).run();
+        }
+        assert_eq!(
+            *order.lock().unwrap(),
+            vec!["realtime", "normal local", "normal", "background"]
+        );
+    }
+}
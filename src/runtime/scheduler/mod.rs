@@ -7,7 +7,16 @@ pub use crate::runtime::scheduler::cpu_pin::CpuPinScheduler;
 #[cfg(feature = "flow_scheduler")]
 mod flow;
 #[cfg(feature = "flow_scheduler")]
+pub use crate::runtime::scheduler::flow::BlockStats;
+#[cfg(feature = "flow_scheduler")]
 pub use crate::runtime::scheduler::flow::FlowScheduler;
+#[cfg(feature = "flow_scheduler")]
+pub use crate::runtime::scheduler::flow::Migration;
+
+#[cfg(feature = "flow_scheduler")]
+mod pinning;
+#[cfg(feature = "flow_scheduler")]
+pub use crate::runtime::scheduler::pinning::BlockPinning;
 
 #[cfg(not(target_arch = "wasm32"))]
 mod smol;
@@ -17,6 +26,8 @@ pub use crate::runtime::scheduler::smol::SmolScheduler;
 #[cfg(feature = "tpb_scheduler")]
 mod tpb;
 #[cfg(feature = "tpb_scheduler")]
+pub use crate::runtime::scheduler::tpb::ThreadPriority;
+#[cfg(feature = "tpb_scheduler")]
 pub use crate::runtime::scheduler::tpb::TpbScheduler;
 
 #[allow(clippy::module_inception)]
@@ -32,3 +43,45 @@ pub use wasm::WasmScheduler;
 pub use async_task::Task;
 #[cfg(target_arch = "wasm32")]
 pub use wasm::Task;
+
+use futures::channel::mpsc::channel;
+use futures::channel::mpsc::Sender;
+use futures::future::Future;
+use slab::Slab;
+
+use crate::runtime::Block;
+use crate::runtime::BlockMessage;
+use crate::runtime::FlowgraphMessage;
+use crate::runtime::Priority;
+use crate::runtime::Topology;
+
+/// Take the blocks of fused chains from the topology
+///
+/// Creates the inboxes of the blocks and returns, for each chain, the id of its first block, the
+/// highest priority of its blocks, and a future that runs all blocks of the chain in one task
+/// (see [`Block::run_fused`]).
+pub(crate) fn take_fused(
+    topology: &mut Topology,
+    main_channel: &Sender<FlowgraphMessage>,
+    inboxes: &mut Slab<Option<Sender<BlockMessage>>>,
+    queue_size: usize,
+) -> Vec<(usize, Priority, impl Future<Output = ()>)> {
+    let mut chains = Vec::new();
+    for chain in topology.fused.iter() {
+        let mut blocks = Vec::new();
+        let mut priority = Priority::Background;
+        for id in chain.iter().copied() {
+            let block = topology.blocks[id].take().unwrap();
+            priority = priority.max(block.priority());
+            let (sender, receiver) = channel::<BlockMessage>(queue_size);
+            inboxes[id] = Some(sender);
+            blocks.push((id, block, receiver));
+        }
+        chains.push((
+            chain[0],
+            priority,
+            Block::run_fused(blocks, main_channel.clone()),
+        ));
+    }
+    chains
+}
@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use crate::runtime::config;
+use crate::runtime::Flowgraph;
+use crate::runtime::Topology;
+
+/// Assignment of blocks to worker threads by instance or type name
+///
+/// Used by the [`CpuPinScheduler`](super::CpuPinScheduler) and the
+/// [`FlowScheduler`](super::FlowScheduler). Each name maps to a set of workers. Blocks are matched
+/// by instance name first and by type name second. If multiple blocks match the same entry, they
+/// are distributed round-robin over its workers.
+///
+/// The defaults are read from the `cpu_pins` table of the [config](crate::runtime::config).
+///
+/// ```toml
+/// [cpu_pins]
+/// "my_fir" = 1
+/// "Apply" = [2, 3]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockPinning {
+    pins: HashMap<String, Vec<usize>>,
+}
+
+impl BlockPinning {
+    /// Create empty pinning
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create pinning from the `cpu_pins` of the config
+    pub fn from_config() -> Self {
+        Self {
+            pins: config::config().cpu_pins,
+        }
+    }
+
+    /// Pin blocks with the given instance or type name to a worker
+    #[must_use]
+    pub fn pin(self, name: impl Into<String>, worker: usize) -> Self {
+        self.pin_set(name, [worker])
+    }
+
+    /// Distribute blocks with the given instance or type name over a set of workers
+    #[must_use]
+    pub fn pin_set(
+        mut self,
+        name: impl Into<String>,
+        workers: impl IntoIterator<Item = usize>,
+    ) -> Self {
+        self.pins.insert(name.into(), workers.into_iter().collect());
+        self
+    }
+
+    /// Check if no pins are set
+    pub fn is_empty(&self) -> bool {
+        self.pins.is_empty()
+    }
+
+    /// Resolve the worker of each block of a flowgraph
+    ///
+    /// Returns a map from block id to worker index.
+    pub fn assignment(&self, fg: &Flowgraph, n_workers: usize) -> HashMap<usize, usize> {
+        fg.topology
+            .as_ref()
+            .map(|t| self.resolve(t, n_workers))
+            .unwrap_or_default()
+    }
+
+    /// Resolve the worker of each block, warning about names without matching blocks and
+    /// workers that do not exist.
+    pub(crate) fn resolve(&self, topology: &Topology, n_workers: usize) -> HashMap<usize, usize> {
+        let mut pins = HashMap::new();
+        let mut used = HashSet::new();
+        let mut next = HashMap::new();
+
+        for (id, block) in topology.blocks.iter() {
+            let Some(block) = block else {
+                continue;
+            };
+            let entry = block
+                .instance_name()
+                .and_then(|n| self.pins.get_key_value(n))
+                .or_else(|| self.pins.get_key_value(block.type_name()));
+            let Some((name, workers)) = entry else {
+                continue;
+            };
+            used.insert(name.as_str());
+            if workers.is_empty() {
+                continue;
+            }
+
+            let n = next.entry(name.as_str()).or_insert(0);
+            let worker = workers[*n % workers.len()];
+            *n += 1;
+
+            if worker < n_workers {
+                debug!("pinning block {} ({:?}) to worker {}", id, name, worker);
+                pins.insert(id, worker);
+            } else {
+                warn!(
+                    "cannot pin block {} ({:?}) to worker {}, only {} workers",
+                    id, name, worker, n_workers
+                );
+            }
+        }
+
+        for name in self.pins.keys() {
+            if !used.contains(name.as_str()) {
+                warn!("cpu pin {:?} does not match any block", name);
+            }
+        }
+
+        pins
+    }
+}
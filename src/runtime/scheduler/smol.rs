@@ -4,6 +4,8 @@ use futures::channel::mpsc::channel;
 use futures::channel::mpsc::Sender;
 use futures::channel::oneshot;
 use futures::future::Future;
+use futures_lite::future;
+use futures_lite::future::FutureExt;
 use once_cell::sync::Lazy;
 use slab::Slab;
 use std::fmt;
@@ -12,16 +14,24 @@ use std::sync::Mutex;
 use std::thread;
 
 use crate::runtime::config;
+use crate::runtime::scheduler::take_fused;
 use crate::runtime::scheduler::Scheduler;
 use crate::runtime::BlockMessage;
 use crate::runtime::FlowgraphMessage;
+use crate::runtime::Priority;
 use crate::runtime::Topology;
 
-static SMOL: Lazy<Mutex<Slab<Arc<Executor<'_>>>>> = Lazy::new(|| Mutex::new(Slab::new()));
+// one executor per priority class, indexed by `Priority as usize`
+type Executors = [Executor<'static>; 3];
+
+static SMOL: Lazy<Mutex<Slab<Arc<Executors>>>> = Lazy::new(|| Mutex::new(Slab::new()));
 
 /// Smol Scheduler
 ///
 /// Default scheduler of the smol async runtime
+///
+/// Tasks of each [`Priority`] class are spawned on a separate executor. Worker threads always
+/// run ready tasks of higher classes first.
 #[derive(Clone, Debug)]
 pub struct SmolScheduler {
     inner: Arc<SmolSchedulerInner>,
@@ -61,7 +71,7 @@ impl SmolScheduler {
     /// - `pin_executors`: pin worker threads to CPUs?
     pub fn new(n_executors: usize, pin_executors: bool) -> SmolScheduler {
         let mut slab = SMOL.lock().unwrap();
-        let executor = Arc::new(Executor::new());
+        let executor = Arc::new([Executor::new(), Executor::new(), Executor::new()]);
         let mut workers = Vec::new();
 
         let core_ids = if let Some(core_ids) = core_affinity::get_core_ids() {
@@ -85,7 +95,7 @@ impl SmolScheduler {
                         core_affinity::set_for_current(c);
                     }
                     let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                        async_io::block_on(e.run(receiver))
+                        async_io::block_on(Self::run_worker(&e, receiver))
                     }));
                     if result.is_err() {
                         eprintln!("smol worker panicked {result:?}");
@@ -103,6 +113,41 @@ impl SmolScheduler {
             inner: Arc::new(SmolSchedulerInner { id, workers }),
         }
     }
+
+    async fn run_worker(executors: &Executors, shutdown: oneshot::Receiver<()>) {
+        let run_forever = async {
+            let mut n = 0usize;
+            loop {
+                // `any` short-circuits, i.e., only runs a task if no higher class is ready
+                if executors.iter().rev().any(|e| e.try_tick()) {
+                    n = n.wrapping_add(1);
+                    if n % 64 == 0 {
+                        future::yield_now().await;
+                    }
+                    continue;
+                }
+                executors[2]
+                    .tick()
+                    .or(executors[1].tick())
+                    .or(executors[0].tick())
+                    .await;
+            }
+        };
+
+        async {
+            let _ = shutdown.await;
+        }
+        .or(run_forever)
+        .await
+    }
+
+    fn spawn_priority<T: Send + 'static>(
+        &self,
+        priority: Priority,
+        future: impl Future<Output = T> + Send + 'static,
+    ) -> Task<T> {
+        SMOL.lock().unwrap().get(self.inner.id).unwrap()[priority as usize].spawn(future)
+    }
 }
 
 impl Scheduler for SmolScheduler {
@@ -118,9 +163,16 @@ impl Scheduler for SmolScheduler {
         }
         let queue_size = config::config().queue_size;
 
+        // spawn fused block chains
+        for (_, priority, chain) in take_fused(topology, main_channel, &mut inboxes, queue_size) {
+            self.spawn_priority(priority, chain).detach();
+        }
+
         // spawn block executors
         for (id, block_o) in topology.blocks.iter_mut() {
-            let block = block_o.take().unwrap();
+            let Some(block) = block_o.take() else {
+                continue;
+            };
 
             let (sender, receiver) = channel::<BlockMessage>(queue_size);
             inboxes[id] = Some(sender);
@@ -129,8 +181,11 @@ impl Scheduler for SmolScheduler {
                 self.spawn_blocking(block.run(id, main_channel.clone(), receiver))
                     .detach();
             } else {
-                self.spawn(block.run(id, main_channel.clone(), receiver))
-                    .detach();
+                self.spawn_priority(
+                    block.priority(),
+                    block.run(id, main_channel.clone(), receiver),
+                )
+                .detach();
             }
         }
 
@@ -141,22 +196,17 @@ impl Scheduler for SmolScheduler {
         &self,
         future: impl Future<Output = T> + Send + 'static,
     ) -> Task<T> {
-        SMOL.lock()
-            .unwrap()
-            .get(self.inner.id)
-            .unwrap()
-            .spawn(future)
+        self.spawn_priority(Priority::Normal, future)
     }
 
     fn spawn_blocking<T: Send + 'static>(
         &self,
         future: impl Future<Output = T> + Send + 'static,
     ) -> Task<T> {
-        SMOL.lock()
-            .unwrap()
-            .get(self.inner.id)
-            .unwrap()
-            .spawn(blocking::unblock(|| async_io::block_on(future)))
+        self.spawn_priority(
+            Priority::Normal,
+            blocking::unblock(|| async_io::block_on(future)),
+        )
     }
 }
 
@@ -183,4 +233,40 @@ mod test {
         let r = async_io::block_on(t);
         assert_eq!(r, 2);
     }
+
+    #[test]
+    fn priority_order() {
+        let s = SmolScheduler::new(1, false);
+        let (started_tx, started_rx) = std::sync::mpsc::channel();
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+
+        // keep the only worker busy, while tasks of all classes become ready
+        let blocker = s.spawn(async move {
+            started_tx.send(()).unwrap();
+            release_rx.recv().unwrap();
+        });
+        started_rx.recv().unwrap();
+
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let tasks: Vec<_> = [Priority::Background, Priority::Normal, Priority::Realtime]
+            .into_iter()
+            .map(|p| {
+                let order = order.clone();
+                s.spawn_priority(p, async move { order.lock().unwrap().push(p) })
+            })
+            .collect();
+
+        release_tx.send(()).unwrap();
+        async_io::block_on(async {
+            blocker.await;
+            for t in tasks {
+                t.await;
+            }
+        });
+
+        assert_eq!(
+            *order.lock().unwrap(),
+            vec![Priority::Realtime, Priority::Normal, Priority::Background]
+        );
+    }
 }
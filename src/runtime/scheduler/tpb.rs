@@ -12,19 +12,39 @@ use std::sync::Mutex;
 use std::thread;
 
 use crate::runtime::config;
+use crate::runtime::scheduler::take_fused;
 use crate::runtime::scheduler::Scheduler;
 use crate::runtime::BlockMessage;
 use crate::runtime::FlowgraphMessage;
+use crate::runtime::Priority;
 use crate::runtime::Topology;
 
 static TPB: Lazy<Mutex<Slab<Arc<Executor<'_>>>>> = Lazy::new(|| Mutex::new(Slab::new()));
 
+/// OS scheduling of the threads that run blocks of a [`Priority`] class
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThreadPriority {
+    /// Default scheduling policy and niceness
+    Default,
+    /// Real-time `SCHED_FIFO` scheduling policy with the given priority (1-99)
+    Fifo(i32),
+    /// Default scheduling policy with the given niceness (-20-19)
+    Nice(i32),
+}
+
 /// Thread-per-Block scheduler
 ///
 /// This is mainly for comparision to GNU Radio. Do not use.
+///
+/// Threads of blocks are configured according to the [`ThreadPriority`] of the block's
+/// [`Priority`] class, set with [`TpbScheduler::thread_priority`]. By default, all classes use
+/// the default scheduling of the process. If the process lacks the permission for a configured
+/// priority, blocks run with default scheduling.
 #[derive(Clone, Debug)]
 pub struct TpbScheduler {
     inner: Arc<TpbSchedulerInner>,
+    // indexed by `Priority as usize`
+    thread_priorities: [ThreadPriority; 3],
 }
 
 struct TpbSchedulerInner {
@@ -81,8 +101,79 @@ impl TpbScheduler {
 
         TpbScheduler {
             inner: Arc::new(TpbSchedulerInner { id, workers }),
+            thread_priorities: [ThreadPriority::Default; 3],
         }
     }
+
+    /// Set OS scheduling of the threads that run blocks of the given priority class
+    #[must_use]
+    pub fn thread_priority(mut self, class: Priority, priority: ThreadPriority) -> Self {
+        self.thread_priorities[class as usize] = priority;
+        self
+    }
+
+    fn spawn_prioritized(
+        &self,
+        id: usize,
+        class: Priority,
+        future: impl Future<Output = ()> + Send + 'static,
+    ) {
+        let priority = self.thread_priorities[class as usize];
+        if priority == ThreadPriority::Default {
+            self.spawn_blocking(future).detach();
+            return;
+        }
+
+        // use a dedicated thread, since the setting would stick to threads of the blocking pool
+        thread::Builder::new()
+            .stack_size(config::config().stack_size)
+            .name(format!("tpb-{id}"))
+            .spawn(move || {
+                set_thread_priority(priority);
+                async_io::block_on(future)
+            })
+            .expect("failed to spawn block thread");
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn set_thread_priority(priority: ThreadPriority) {
+    match priority {
+        ThreadPriority::Default => {}
+        ThreadPriority::Fifo(p) => {
+            let param = libc::sched_param { sched_priority: p };
+            let ret = unsafe {
+                libc::pthread_setschedparam(libc::pthread_self(), libc::SCHED_FIFO, &param)
+            };
+            if ret != 0 {
+                warn!(
+                    "cannot set SCHED_FIFO priority {} ({}), using default scheduling",
+                    p,
+                    std::io::Error::from_raw_os_error(ret)
+                );
+            }
+        }
+        ThreadPriority::Nice(n) => {
+            let ret = unsafe {
+                let tid = libc::syscall(libc::SYS_gettid) as libc::id_t;
+                libc::setpriority(libc::PRIO_PROCESS, tid, n)
+            };
+            if ret != 0 {
+                warn!(
+                    "cannot set niceness {} ({}), using default niceness",
+                    n,
+                    std::io::Error::last_os_error()
+                );
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_thread_priority(priority: ThreadPriority) {
+    if priority != ThreadPriority::Default {
+        warn!("thread priorities are not supported on this platform, using default scheduling");
+    }
 }
 
 impl Scheduler for TpbScheduler {
@@ -100,15 +191,25 @@ impl Scheduler for TpbScheduler {
 
         assert!(topology.blocks.len() < 490); // default upper-limit of thread pool size of unblock crate is 500
 
+        // spawn fused block chains
+        for (id, priority, chain) in take_fused(topology, main_channel, &mut inboxes, queue_size) {
+            self.spawn_prioritized(id, priority, chain);
+        }
+
         // spawn block executors
         for (id, block_o) in topology.blocks.iter_mut() {
-            let block = block_o.take().unwrap();
+            let Some(block) = block_o.take() else {
+                continue;
+            };
 
             let (sender, receiver) = channel::<BlockMessage>(queue_size);
             inboxes[id] = Some(sender);
 
-            self.spawn_blocking(block.run(id, main_channel.clone(), receiver))
-                .detach();
+            self.spawn_prioritized(
+                id,
+                block.priority(),
+                block.run(id, main_channel.clone(), receiver),
+            );
         }
 
         inboxes
@@ -10,6 +10,7 @@ use slab::Slab;
 use std::pin::Pin;
 
 use crate::runtime::config;
+use crate::runtime::scheduler::take_fused;
 use crate::runtime::scheduler::Scheduler;
 use crate::runtime::BlockMessage;
 use crate::runtime::FlowgraphMessage;
@@ -39,9 +40,16 @@ impl Scheduler for WasmScheduler {
         }
         let queue_size = config::config().queue_size;
 
+        // spawn fused block chains
+        for (_, _, chain) in take_fused(topology, main_channel, &mut inboxes, queue_size) {
+            self.spawn(chain);
+        }
+
         // spawn block executors
         for (id, block_o) in topology.blocks.iter_mut() {
-            let block = block_o.take().unwrap();
+            let Some(block) = block_o.take() else {
+                continue;
+            };
 
             let (sender, receiver) = channel::<BlockMessage>(queue_size);
             inboxes[id] = Some(sender);
@@ -3,14 +3,21 @@ use futures::channel::mpsc::Sender;
 use std::any::Any;
 use std::any::TypeId;
 use std::fmt;
+use std::marker::PhantomData;
 use std::mem;
+use std::ops::Deref;
+use std::ops::DerefMut;
 use std::slice;
+use std::sync::Arc;
+use std::sync::Mutex;
+use web_time::Instant;
 
 use crate::runtime::buffer::BufferReader;
 use crate::runtime::buffer::BufferWriter;
 use crate::runtime::tag::default_tag_propagation;
 use crate::runtime::BlockMessage;
 use crate::runtime::ItemTag;
+use crate::runtime::LatencyHistogram;
 use crate::runtime::Tag;
 
 #[derive(Debug)]
@@ -34,6 +41,8 @@ pub struct StreamInput {
     reader: Option<BufferReader>,
     current: Option<CurrentInput>,
     tags: Vec<ItemTag>,
+    // tag vector of the last `CurrentInput`, kept to reuse its allocation
+    spare_tags: Vec<ItemTag>,
 }
 
 impl StreamInput {
@@ -47,6 +56,7 @@ impl StreamInput {
             reader: None,
             current: None,
             tags: Vec::new(),
+            spare_tags: Vec::new(),
         }
     }
 
@@ -103,11 +113,13 @@ impl StreamInput {
             let (ptr, len, tags) = self.reader.as_mut().unwrap().bytes();
             self.tags = tags;
             self.tags.sort_by_key(|x| x.index);
+            let mut current_tags = mem::take(&mut self.spare_tags);
+            current_tags.clone_from(&self.tags);
             self.current = Some(CurrentInput {
                 ptr,
                 len,
                 index: 0,
-                tags: self.tags.clone(),
+                tags: current_tags,
             });
         }
     }
@@ -154,12 +166,12 @@ impl StreamInput {
     }
 
     fn commit(&mut self) {
-        if let Some(ref c) = self.current {
+        if let Some(c) = self.current.take() {
             let amount = c.index / self.item_size;
             if amount != 0 {
                 self.reader.as_mut().unwrap().consume(amount);
             }
-            self.current = None;
+            self.spare_tags = c.tags;
         }
     }
 
@@ -319,11 +331,21 @@ impl StreamOutput {
             return;
         }
 
-        let mut tmp = self.tags.clone();
-        tmp.retain(|x| x.index < self.offset);
-        self.tags.retain(|x| x.index >= self.offset);
-
-        self.writer.as_mut().unwrap().produce(self.offset, tmp);
+        // the buffer drains the tag vector, so its allocation is reused in the next call;
+        // it is only split if some tags are not yet produced
+        let offset = self.offset;
+        if self.tags.iter().all(|x| x.index < offset) {
+            self.writer
+                .as_mut()
+                .unwrap()
+                .produce(offset, &mut self.tags);
+        } else {
+            let (mut tags, pending): (Vec<_>, Vec<_>) = mem::take(&mut self.tags)
+                .into_iter()
+                .partition(|x| x.index < offset);
+            self.writer.as_mut().unwrap().produce(offset, &mut tags);
+            self.tags = pending;
+        }
         self.offset = 0;
     }
 
@@ -355,12 +377,78 @@ impl StreamOutput {
     }
 }
 
+/// Latency instrumentation of a block
+///
+/// Sources insert [`Tag::Latency`] tags every `interval` items, intermediate blocks forward them
+/// independent of their tag propagation, and sinks record the latency in a histogram.
+struct LatencyProbe {
+    interval: usize,
+    items: usize,
+    pending: Vec<Instant>,
+    histogram: Option<Arc<Mutex<LatencyHistogram>>>,
+}
+
+impl LatencyProbe {
+    fn process(&mut self, inputs: &mut [StreamInput], outputs: &mut [StreamOutput]) {
+        for i in inputs.iter() {
+            let (n, tags) = i.consumed();
+            self.pending.extend(
+                tags.iter()
+                    .filter(|t| t.index < n)
+                    .filter_map(|t| match t.tag {
+                        Tag::Latency(t) => Some(t),
+                        _ => None,
+                    }),
+            );
+        }
+
+        if let Some(ref h) = self.histogram {
+            if !self.pending.is_empty() {
+                let now = Instant::now();
+                let mut h = h.lock().unwrap();
+                for t in self.pending.drain(..) {
+                    h.record(now - t);
+                }
+            }
+            return;
+        }
+
+        // drop probes that were copied by the block's tag propagation
+        for o in outputs.iter_mut() {
+            o.tags.retain(|t| !matches!(t.tag, Tag::Latency(_)));
+        }
+
+        if inputs.is_empty() {
+            let produced = outputs[0].produced();
+            let mut next = (self.items + self.interval - 1) / self.interval * self.interval;
+            if next < self.items + produced {
+                let now = Instant::now();
+                while next < self.items + produced {
+                    for o in outputs.iter_mut() {
+                        o.add_tag_abs(next - self.items, Tag::Latency(now));
+                    }
+                    next += self.interval;
+                }
+            }
+            self.items += produced;
+        } else if !self.pending.is_empty() && outputs.iter().any(|o| o.produced() > 0) {
+            for o in outputs.iter_mut().filter(|o| o.produced() > 0) {
+                for t in self.pending.iter() {
+                    o.add_tag_abs(0, Tag::Latency(*t));
+                }
+            }
+            self.pending.clear();
+        }
+    }
+}
+
 /// Stream IO
 pub struct StreamIo {
     inputs: Vec<StreamInput>,
     outputs: Vec<StreamOutput>,
     #[allow(clippy::type_complexity)]
     tag_propagation: Box<dyn FnMut(&mut [StreamInput], &mut [StreamOutput]) + Send + 'static>,
+    latency: Option<LatencyProbe>,
 }
 
 impl fmt::Debug for StreamIo {
@@ -383,6 +471,7 @@ impl StreamIo {
             inputs,
             outputs,
             tag_propagation,
+            latency: None,
         }
     }
 
@@ -467,6 +556,9 @@ impl StreamIo {
     /// Commit all consume/produce calls after `work()` call
     pub fn commit(&mut self) {
         (self.tag_propagation)(&mut self.inputs, &mut self.outputs);
+        if let Some(ref mut l) = self.latency {
+            l.process(&mut self.inputs, &mut self.outputs);
+        }
         for i in self.inputs_mut() {
             i.commit();
         }
@@ -483,6 +575,30 @@ impl StreamIo {
     ) {
         self.tag_propagation = f;
     }
+
+    /// Enable latency instrumentation, inserting probes every `interval` items in sources
+    ///
+    /// Returns the latency histogram, if the block is a sink.
+    pub(crate) fn instrument_latency(
+        &mut self,
+        interval: usize,
+    ) -> Option<Arc<Mutex<LatencyHistogram>>> {
+        if interval == 0 || (self.inputs.is_empty() && self.outputs.is_empty()) {
+            return None;
+        }
+        let histogram = if self.outputs.is_empty() {
+            Some(Arc::new(Mutex::new(LatencyHistogram::new())))
+        } else {
+            None
+        };
+        self.latency = Some(LatencyProbe {
+            interval,
+            items: 0,
+            pending: Vec::new(),
+            histogram: histogram.clone(),
+        });
+        histogram
+    }
 }
 
 /// Stream IO builder
@@ -539,6 +655,152 @@ impl Default for StreamIoBuilder {
     }
 }
 
+/// Typed stream input of a block, declared through `#[derive(Block)]`
+///
+/// The field only carries the item type. In `work()`, the port is accessed through the generated
+/// method of the same name, which returns a [`TypedStreamInput`].
+pub struct StreamIn<T>(PhantomData<fn() -> T>);
+
+impl<T> StreamIn<T> {
+    /// Create typed stream input
+    pub const fn new() -> Self {
+        StreamIn(PhantomData)
+    }
+}
+
+impl<T> Default for StreamIn<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for StreamIn<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for StreamIn<T> {}
+
+impl<T> fmt::Debug for StreamIn<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "StreamIn<{}>", std::any::type_name::<T>())
+    }
+}
+
+/// Typed stream output of a block, declared through `#[derive(Block)]`
+///
+/// The field only carries the item type. In `work()`, the port is accessed through the generated
+/// method of the same name, which returns a [`TypedStreamOutput`].
+pub struct StreamOut<T>(PhantomData<fn() -> T>);
+
+impl<T> StreamOut<T> {
+    /// Create typed stream output
+    pub const fn new() -> Self {
+        StreamOut(PhantomData)
+    }
+}
+
+impl<T> Default for StreamOut<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for StreamOut<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for StreamOut<T> {}
+
+impl<T> fmt::Debug for StreamOut<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "StreamOut<{}>", std::any::type_name::<T>())
+    }
+}
+
+/// [`StreamInput`] with known item type
+///
+/// Dereferences to the [`StreamInput`] for consuming items, tags, etc.
+pub struct TypedStreamInput<'a, T> {
+    port: &'a mut StreamInput,
+    _type: PhantomData<T>,
+}
+
+impl<'a, T: Any> TypedStreamInput<'a, T> {
+    /// Wrap stream input
+    ///
+    /// Panics, if the items of the input are not of type `T`.
+    pub fn new(port: &'a mut StreamInput) -> Self {
+        assert_eq!((*port).type_id(), TypeId::of::<T>());
+        TypedStreamInput {
+            port,
+            _type: PhantomData,
+        }
+    }
+
+    /// Get buffer content as slice
+    pub fn slice(&mut self) -> &'static [T] {
+        self.port.slice_unchecked()
+    }
+}
+
+impl<T> Deref for TypedStreamInput<'_, T> {
+    type Target = StreamInput;
+
+    fn deref(&self) -> &StreamInput {
+        self.port
+    }
+}
+
+impl<T> DerefMut for TypedStreamInput<'_, T> {
+    fn deref_mut(&mut self) -> &mut StreamInput {
+        self.port
+    }
+}
+
+/// [`StreamOutput`] with known item type
+///
+/// Dereferences to the [`StreamOutput`] for producing items, adding tags, etc.
+pub struct TypedStreamOutput<'a, T> {
+    port: &'a mut StreamOutput,
+    _type: PhantomData<T>,
+}
+
+impl<'a, T: Any> TypedStreamOutput<'a, T> {
+    /// Wrap stream output
+    ///
+    /// Panics, if the items of the output are not of type `T`.
+    pub fn new(port: &'a mut StreamOutput) -> Self {
+        assert_eq!((*port).type_id(), TypeId::of::<T>());
+        TypedStreamOutput {
+            port,
+            _type: PhantomData,
+        }
+    }
+
+    /// Get buffer content as mutable slice
+    pub fn slice(&mut self) -> &'static mut [T] {
+        self.port.slice_unchecked()
+    }
+}
+
+impl<T> Deref for TypedStreamOutput<'_, T> {
+    type Target = StreamOutput;
+
+    fn deref(&self) -> &StreamOutput {
+        self.port
+    }
+}
+
+impl<T> DerefMut for TypedStreamOutput<'_, T> {
+    fn deref_mut(&mut self) -> &mut StreamOutput {
+        self.port
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
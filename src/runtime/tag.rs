@@ -1,10 +1,12 @@
 use dyn_clone::DynClone;
 use std::any::Any;
 use std::fmt;
+use web_time::Instant;
 
 use crate::runtime::Pmt;
 use crate::runtime::StreamInput;
 use crate::runtime::StreamOutput;
+use crate::runtime::TimeSpec;
 
 pub trait TagAny: Any + DynClone + Send + 'static {
     fn as_any(&self) -> &dyn Any;
@@ -55,6 +57,43 @@ pub enum Tag {
     NamedF32(String, f32),
     /// Arbitrary data with a name
     NamedAny(String, Box<dyn TagAny>),
+    /// A [`Pmt`] with a name
+    ///
+    /// Used for the standard time tags, see [`Tag::RX_TIME`] and [`Tag::TX_TIME`].
+    NamedPmt(String, Pmt),
+    /// Sampling time of a latency probe
+    ///
+    /// Inserted by sources and forwarded by the runtime, if latency measurement is enabled
+    /// through the `latency_interval` config.
+    Latency(Instant),
+}
+
+impl Tag {
+    /// Key of the tag with the [`TimeSpec`], at which the tagged sample was received
+    ///
+    /// Sources, like the Seify source or a `FileSource` with a start time, tag the first sample
+    /// and every sample after a discontinuity. Later sample times follow from the sample rate.
+    pub const RX_TIME: &'static str = "rx_time";
+    /// Key of the tag with the [`TimeSpec`], at which the tagged sample should be transmitted
+    pub const TX_TIME: &'static str = "tx_time";
+
+    /// Create an `rx_time` tag.
+    pub fn rx_time(t: TimeSpec) -> Self {
+        Tag::NamedPmt(Self::RX_TIME.to_string(), Pmt::Time(t))
+    }
+
+    /// Create a `tx_time` tag.
+    pub fn tx_time(t: TimeSpec) -> Self {
+        Tag::NamedPmt(Self::TX_TIME.to_string(), Pmt::Time(t))
+    }
+
+    /// Get the time, if this is a [`Tag::NamedPmt`] with the given key and a [`Pmt::Time`].
+    pub fn time(&self, key: &str) -> Option<TimeSpec> {
+        match self {
+            Tag::NamedPmt(k, Pmt::Time(t)) if k == key => Some(*t),
+            _ => None,
+        }
+    }
 }
 
 /// Item tag
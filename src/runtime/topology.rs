@@ -3,12 +3,18 @@ use slab::Slab;
 use std::any::Any;
 use std::any::TypeId;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::fmt::Debug;
 use std::hash::Hash;
 use std::hash::Hasher;
 
+use crate::runtime::buffer;
 use crate::runtime::buffer::BufferBuilder;
 use crate::runtime::buffer::BufferWriter;
+use crate::runtime::config;
+use crate::runtime::config::BufferKind;
+use crate::runtime::config::BufferProfile;
+use crate::runtime::flowgraph::DefaultBuffer;
 use crate::runtime::Block;
 use crate::runtime::BlockMessage;
 use crate::runtime::ConnectCtx;
@@ -61,6 +67,26 @@ impl BufferBuilderEntry {
             .builder()
             .build(self.item_size, writer_inbox, writer_output_id)
     }
+
+    pub(crate) fn description(&self) -> String {
+        self.builder.builder().description()
+    }
+}
+
+fn profile_builder(profile: &BufferProfile) -> Box<dyn BufferBuilderKey> {
+    let c = config::config();
+    let min_bytes = profile.min_bytes.unwrap_or(c.buffer_size);
+    match profile.kind {
+        #[cfg(not(target_arch = "wasm32"))]
+        BufferKind::Circular => Box::new(buffer::circular::Circular::with_size(min_bytes)),
+        #[cfg(target_arch = "wasm32")]
+        BufferKind::Circular => Box::new(buffer::slab::Slab::with_size(min_bytes)),
+        BufferKind::Slab => Box::new(buffer::slab::Slab::with_config(
+            min_bytes,
+            profile.n_buffer.unwrap_or(2),
+            profile.reserved_items.unwrap_or(c.slab_reserved),
+        )),
+    }
 }
 
 impl PartialEq for BufferBuilderEntry {
@@ -85,6 +111,10 @@ pub struct Topology {
     pub(crate) stream_edges: HashMap<(usize, usize, BufferBuilderEntry), Vec<(usize, usize)>>,
     // src blk, src port, dst blk, dst port
     pub(crate) message_edges: Vec<(usize, usize, usize, usize)>,
+    // chains of blocks that are run in one task, in stream order
+    pub(crate) fused: Vec<Vec<usize>>,
+    // config key, dst blk, dst port
+    pub(crate) config_edges: Vec<(String, usize, usize)>,
 }
 
 impl Topology {
@@ -94,6 +124,8 @@ impl Topology {
             blocks: Slab::new(),
             stream_edges: HashMap::new(),
             message_edges: Vec::new(),
+            fused: Vec::new(),
+            config_edges: Vec::new(),
         }
     }
 
@@ -150,6 +182,10 @@ impl Topology {
 
         // delete associated message edges
         self.message_edges.retain(|x| x.0 != id && x.2 != id);
+        self.config_edges.retain(|x| x.1 != id);
+
+        // split up fused chains
+        self.fused.retain(|c| !c.contains(&id));
     }
 
     /// Connect stream ports
@@ -161,6 +197,89 @@ impl Topology {
         dst_port: PortId,
         buffer_builder: B,
     ) -> Result<(), Error> {
+        let (src_port_id, dst_port_id, item_size) =
+            self.stream_ports(src_block, src_port, dst_block, dst_port)?;
+        let buffer_entry = BufferBuilderEntry {
+            item_size,
+            builder: Box::new(buffer_builder),
+        };
+        self.add_stream_edge(src_block, src_port_id, dst_block, dst_port_id, buffer_entry);
+        Ok(())
+    }
+
+    /// Connect stream ports, using a buffer created from a [`BufferProfile`]
+    pub fn connect_stream_with_profile(
+        &mut self,
+        src_block: usize,
+        src_port: PortId,
+        dst_block: usize,
+        dst_port: PortId,
+        profile: &BufferProfile,
+    ) -> Result<(), Error> {
+        let (src_port_id, dst_port_id, item_size) =
+            self.stream_ports(src_block, src_port, dst_block, dst_port)?;
+        let buffer_entry = BufferBuilderEntry {
+            item_size,
+            builder: profile_builder(profile),
+        };
+        self.add_stream_edge(src_block, src_port_id, dst_block, dst_port_id, buffer_entry);
+        Ok(())
+    }
+
+    /// Get the [`BufferProfile`] configured for a stream connection
+    ///
+    /// Checks the `buffer_overrides` of the [config](crate::runtime::config) for the keys
+    ///
+    /// 1. `"<src instance>.<src port> -> <dst instance>.<dst port>"`
+    /// 2. `"<src type>.<src port> -> <dst type>.<dst port>"`
+    /// 3. `"<src instance>.<src port>"`
+    /// 4. `"<src type>.<src port>"`
+    ///
+    /// and returns the profile of the first one that matches. Keys with instance names are only
+    /// checked for blocks that have an instance name.
+    pub fn buffer_override(
+        &self,
+        src_block: usize,
+        src_port: PortId,
+        dst_block: usize,
+        dst_port: PortId,
+    ) -> Result<Option<BufferProfile>, Error> {
+        let (src_port_id, dst_port_id, _) =
+            self.stream_ports(src_block, src_port, dst_block, dst_port)?;
+        let src = self
+            .block_ref(src_block)
+            .ok_or(Error::InvalidBlock(src_block))?;
+        let dst = self
+            .block_ref(dst_block)
+            .ok_or(Error::InvalidBlock(dst_block))?;
+
+        let src_port = src.stream_output(src_port_id).name();
+        let dst_port = dst.stream_input(dst_port_id).name();
+        let src_name = src.instance_name();
+        let dst_name = dst.instance_name();
+        let src_type = src.type_name();
+        let dst_type = dst.type_name();
+
+        // instance name keys only apply to named blocks
+        let mut keys = Vec::with_capacity(4);
+        if let (Some(src_name), Some(dst_name)) = (src_name, dst_name) {
+            keys.push(format!("{src_name}.{src_port} -> {dst_name}.{dst_port}"));
+        }
+        keys.push(format!("{src_type}.{src_port} -> {dst_type}.{dst_port}"));
+        if let Some(src_name) = src_name {
+            keys.push(format!("{src_name}.{src_port}"));
+        }
+        keys.push(format!("{src_type}.{src_port}"));
+        Ok(config::config().buffer_override(&keys).cloned())
+    }
+
+    fn stream_ports(
+        &self,
+        src_block: usize,
+        src_port: PortId,
+        dst_block: usize,
+        dst_port: PortId,
+    ) -> Result<(usize, usize, usize), Error> {
         let src = self
             .blocks
             .get(src_block)
@@ -208,17 +327,23 @@ impl Topology {
             ))));
         }
 
-        let buffer_entry = BufferBuilderEntry {
-            item_size: sp.item_size(),
-            builder: Box::new(buffer_builder),
-        };
+        Ok((src_port_id, dst_port_id, sp.item_size()))
+    }
+
+    fn add_stream_edge(
+        &mut self,
+        src_block: usize,
+        src_port_id: usize,
+        dst_block: usize,
+        dst_port_id: usize,
+        buffer_entry: BufferBuilderEntry,
+    ) {
         let id = (src_block, src_port_id, buffer_entry);
         if let Some(v) = self.stream_edges.get_mut(&id) {
             v.push((dst_block, dst_port_id));
         } else {
             self.stream_edges.insert(id, vec![(dst_block, dst_port_id)]);
         }
-        Ok(())
     }
 
     /// Connect message ports
@@ -273,6 +398,130 @@ impl Topology {
         Ok(())
     }
 
+    /// Connect config value to message input port
+    ///
+    /// While the flowgraph is running, changes of the value are sent to the port (see
+    /// [`config::subscribe`]).
+    pub fn connect_config(
+        &mut self,
+        key: String,
+        dst_block: usize,
+        dst_port: PortId,
+    ) -> Result<(), Error> {
+        let dst = self
+            .blocks
+            .get(dst_block)
+            .ok_or(Error::InvalidBlock(dst_block))?
+            .as_ref()
+            .ok_or(Error::InvalidBlock(dst_block))?;
+
+        let dst_port_id = match dst_port {
+            PortId::Name(ref s) => dst
+                .message_input_name_to_id(s)
+                .ok_or(Error::InvalidMessagePort(dst.into(), dst_port.clone()))?,
+            PortId::Index(i) => {
+                if i < dst.message_input_names().len() {
+                    i
+                } else {
+                    return Err(Error::InvalidMessagePort(dst.into(), dst_port));
+                }
+            }
+        };
+
+        self.config_edges.push((key, dst_block, dst_port_id));
+
+        Ok(())
+    }
+
+    /// Fuse linear chains of synchronous blocks
+    ///
+    /// A block can be fused, if it is synchronous (see [`Kernel::SYNC`](crate::runtime::Kernel::SYNC)),
+    /// not blocking, and has exactly one stream input and one stream output. Consecutive blocks
+    /// that can be fused and that are connected through a default buffer without further readers
+    /// form a chain. All blocks of a chain are run in one task, which is woken up by messages to
+    /// any of its blocks and calls their work functions back-to-back. The buffers within the
+    /// chain are replaced with small buffers of `fused_buffer_size` bytes (see
+    /// [config](crate::runtime::config)).
+    ///
+    /// The blocks stay separate blocks of the topology, i.e., their message handlers and
+    /// descriptions are not affected.
+    ///
+    /// Returns the newly fused chains as lists of block ids in stream order.
+    pub fn fuse(&mut self) -> Vec<Vec<usize>> {
+        let fusible: HashSet<usize> = self
+            .blocks
+            .iter_mut()
+            .filter_map(|(id, b)| {
+                let b = b.as_mut()?;
+                (!b.is_blocking()
+                    && b.stream_inputs().len() == 1
+                    && b.stream_outputs().len() == 1
+                    && b.0.as_fusible().is_some())
+                .then_some(id)
+            })
+            .collect();
+
+        let mut n_edges = HashMap::new();
+        for (src, _, _) in self.stream_edges.keys() {
+            *n_edges.entry(*src).or_insert(0) += 1;
+        }
+
+        let mut next = HashMap::new();
+        let mut prev = HashSet::new();
+        for ((src, _, entry), v) in self.stream_edges.iter() {
+            if let [(dst, _)] = v[..] {
+                if *src != dst
+                    && n_edges[src] == 1
+                    && entry.builder.as_any().is::<DefaultBuffer>()
+                    && fusible.contains(src)
+                    && fusible.contains(&dst)
+                {
+                    next.insert(*src, dst);
+                    prev.insert(dst);
+                }
+            }
+        }
+
+        let mut chains = Vec::new();
+        for start in next.keys().filter(|b| !prev.contains(*b)) {
+            let mut chain = vec![*start];
+            while let Some(n) = next.get(chain.last().unwrap()) {
+                chain.push(*n);
+            }
+            chains.push(chain);
+        }
+        chains.sort();
+
+        let fused: HashSet<usize> = chains
+            .iter()
+            .flat_map(|c| c[..c.len() - 1].iter().copied())
+            .collect();
+        let profile = BufferProfile {
+            kind: BufferKind::Circular,
+            min_bytes: Some(config::config().fused_buffer_size),
+            n_buffer: None,
+            reserved_items: None,
+        };
+        self.stream_edges = self
+            .stream_edges
+            .drain()
+            .map(|((src, port, entry), v)| {
+                if fused.contains(&src) {
+                    let entry = BufferBuilderEntry {
+                        item_size: entry.item_size,
+                        builder: profile_builder(&profile),
+                    };
+                    ((src, port, entry), v)
+                } else {
+                    ((src, port, entry), v)
+                }
+            })
+            .collect();
+
+        self.fused.extend(chains.iter().cloned());
+        chains
+    }
+
     /// Validate [Flowgraph](crate::runtime::Flowgraph) topology.
     ///
     /// Make sure that all stream ports are connected. Check if connections are valid, e.g., every
@@ -371,6 +620,105 @@ impl Topology {
     pub fn block_mut(&mut self, id: usize) -> Option<&mut Block> {
         self.blocks.get_mut(id).and_then(|v| v.as_mut())
     }
+
+    /// Get the numbered ports `<prefix>0`, `<prefix>1`, ... of a block
+    ///
+    /// Numbering starts at 0 or, if there is no such port, at 1 and ends before the first missing
+    /// number.
+    pub fn numbered_ports(
+        &self,
+        block: usize,
+        prefix: &str,
+        kind: PortKind,
+    ) -> Result<Vec<PortId>, Error> {
+        let b = self.block_ref(block).ok_or(Error::InvalidBlock(block))?;
+        let exists = |name: &str| match kind {
+            PortKind::StreamInput => b.stream_input_name_to_id(name).is_some(),
+            PortKind::StreamOutput => b.stream_output_name_to_id(name).is_some(),
+            PortKind::MessageInput => b.message_input_name_to_id(name).is_some(),
+            PortKind::MessageOutput => b.message_output_name_to_id(name).is_some(),
+        };
+
+        let start = if exists(&format!("{prefix}0")) { 0 } else { 1 };
+        let ports: Vec<PortId> = (start..)
+            .map(|i| format!("{prefix}{i}"))
+            .take_while(|n| exists(n))
+            .map(PortId::Name)
+            .collect();
+
+        if ports.is_empty() {
+            let port = PortId::Name(format!("{prefix}*"));
+            return Err(match kind {
+                PortKind::StreamInput | PortKind::StreamOutput => {
+                    Error::InvalidStreamPort(b.into(), port)
+                }
+                PortKind::MessageInput | PortKind::MessageOutput => {
+                    Error::InvalidMessagePort(b.into(), port)
+                }
+            });
+        }
+        Ok(ports)
+    }
+
+    /// Record the blocks and edges, to undo later changes with [`Topology::rollback`]
+    pub(crate) fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            blocks: self.blocks.iter().map(|(i, _)| i).collect(),
+            stream_edges: self
+                .stream_edges
+                .iter()
+                .flat_map(|((src, src_port, _), v)| {
+                    v.iter()
+                        .map(move |(dst, dst_port)| (*src, *src_port, *dst, *dst_port))
+                })
+                .collect(),
+            message_edges: self.message_edges.len(),
+        }
+    }
+
+    /// Remove blocks and edges that were added after the checkpoint
+    pub(crate) fn rollback(&mut self, checkpoint: Checkpoint) {
+        let added: Vec<usize> = self
+            .blocks
+            .iter()
+            .map(|(i, _)| i)
+            .filter(|i| !checkpoint.blocks.contains(i))
+            .collect();
+        for id in added {
+            self.delete_block(id);
+        }
+
+        for ((src, src_port, _), v) in self.stream_edges.iter_mut() {
+            v.retain(|(dst, dst_port)| {
+                checkpoint
+                    .stream_edges
+                    .contains(&(*src, *src_port, *dst, *dst_port))
+            });
+        }
+        self.stream_edges.retain(|_, v| !v.is_empty());
+        self.message_edges.truncate(checkpoint.message_edges);
+    }
+}
+
+/// Blocks and edges of a [`Topology`] at some point in time
+pub(crate) struct Checkpoint {
+    blocks: HashSet<usize>,
+    // src blk, src port, dst blk, dst port
+    stream_edges: HashSet<(usize, usize, usize, usize)>,
+    message_edges: usize,
+}
+
+/// Kind of block port
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortKind {
+    /// Stream input
+    StreamInput,
+    /// Stream output
+    StreamOutput,
+    /// Message input
+    MessageInput,
+    /// Message output
+    MessageOutput,
 }
 
 impl Default for Topology {
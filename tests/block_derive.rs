@@ -0,0 +1,193 @@
+use anyhow::Result;
+use futuresdr::async_io::block_on;
+use futuresdr::blocks::Head;
+use futuresdr::blocks::NullSink;
+use futuresdr::blocks::NullSource;
+use futuresdr::blocks::VectorSink;
+use futuresdr::blocks::VectorSinkBuilder;
+use futuresdr::macros::connect;
+use futuresdr::macros::message_handler;
+use futuresdr::macros::message_handlers;
+use futuresdr::macros::Block;
+use futuresdr::runtime::Block;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::Runtime;
+use futuresdr::runtime::StreamIn;
+use futuresdr::runtime::StreamIo;
+use futuresdr::runtime::StreamOut;
+use futuresdr::runtime::SyncKernel;
+use futuresdr::runtime::TypedBlock;
+use futuresdr::runtime::WorkIo;
+
+#[derive(Block)]
+#[block(type_name = "Offset", message_outputs(info))]
+struct AddOffset<T>
+where
+    T: Copy + Send + Sync + std::ops::Add<Output = T> + TryFrom<Pmt> + 'static,
+{
+    #[input(name = "in")]
+    input: StreamIn<T>,
+    #[output]
+    out: StreamOut<T>,
+    offset: T,
+}
+
+#[message_handlers]
+impl<T> AddOffset<T>
+where
+    T: Copy + Send + Sync + std::ops::Add<Output = T> + TryFrom<Pmt> + 'static,
+{
+    fn new(offset: T) -> TypedBlock<Self> {
+        AddOffset {
+            input: StreamIn::new(),
+            out: StreamOut::new(),
+            offset,
+        }
+        .into()
+    }
+
+    #[message_handler(name = "offset")]
+    async fn set_offset(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match T::try_from(p) {
+            Ok(o) => {
+                self.offset = o;
+                Ok(Pmt::Ok)
+            }
+            Err(_) => Ok(Pmt::InvalidValue),
+        }
+    }
+}
+
+impl<T> SyncKernel for AddOffset<T>
+where
+    T: Copy + Send + Sync + std::ops::Add<Output = T> + TryFrom<Pmt> + 'static,
+{
+    fn work(
+        &mut self,
+        io: &mut WorkIo,
+        sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> futuresdr::runtime::Result<()> {
+        let i = self.input(sio).slice();
+        let o = self.out(sio).slice();
+
+        let m = std::cmp::min(i.len(), o.len());
+        for (v, r) in i.iter().zip(o.iter_mut()).take(m) {
+            *r = *v + self.offset;
+        }
+        self.input(sio).consume(m);
+        self.out(sio).produce(m);
+
+        if self.input(sio).finished() && m == i.len() {
+            io.finished = true;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Block)]
+struct Tune {
+    #[output]
+    r#type: StreamOut<f32>,
+}
+
+#[message_handlers]
+impl Tune {
+    #[message_handler(name = "freq-hz")]
+    async fn freq(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+        _p: Pmt,
+    ) -> Result<Pmt> {
+        Ok(Pmt::Ok)
+    }
+}
+
+impl Kernel for Tune {}
+
+#[test]
+fn derive_block() -> Result<()> {
+    assert_eq!(AddOffset::<f64>::INPUT, "in");
+    assert_eq!(AddOffset::<f64>::OUT, "out");
+    assert_eq!(AddOffset::<f64>::OFFSET, "offset");
+    assert_eq!(AddOffset::<f64>::INFO, "info");
+
+    let b = Block::from(AddOffset::new(1.0f64));
+    assert_eq!(b.type_name(), "Offset");
+    assert!(b.is_sync());
+    assert!(!b.is_blocking());
+    assert_eq!(b.stream_input_name_to_id("in"), Some(0));
+    assert_eq!(b.stream_output_name_to_id("out"), Some(0));
+    assert_eq!(b.message_input_name_to_id("offset"), Some(0));
+    assert_eq!(b.message_output_name_to_id("info"), Some(0));
+    Ok(())
+}
+
+#[test]
+fn derive_block_flowgraph() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let src = NullSource::<f64>::new();
+    let head = Head::<f64>::new(100_000);
+    let add = AddOffset::new(1.0f64);
+    let snk = VectorSinkBuilder::<f64>::new().build();
+
+    connect!(fg, src > head > add > snk);
+    fg = Runtime::new().run(fg)?;
+
+    let snk = fg.kernel::<VectorSink<f64>>(snk).unwrap();
+    assert_eq!(snk.items().len(), 100_000);
+    assert!(snk.items().iter().all(|x| *x == 1.0));
+    Ok(())
+}
+
+#[test]
+fn derive_block_handler() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let src = NullSource::<f64>::new();
+    let add = AddOffset::new(1.0f64);
+    let snk = NullSink::<f64>::new();
+
+    connect!(fg, src > add > snk);
+
+    let rt = Runtime::new();
+    let (task, mut handle) = rt.start_sync(fg);
+    block_on(async move {
+        let r = handle
+            .callback(add, AddOffset::<f64>::OFFSET, Pmt::F64(2.0))
+            .await?;
+        assert_eq!(r, Pmt::Ok);
+        let r = handle.callback(add, "offset", Pmt::Null).await?;
+        assert_eq!(r, Pmt::InvalidValue);
+
+        handle.terminate_and_wait().await?;
+        task.await?;
+        Ok(())
+    })
+}
+
+#[test]
+fn derive_block_const_names() {
+    assert_eq!(Tune::TYPE, "type");
+    assert_eq!(Tune::FREQ_HZ, "freq-hz");
+
+    let b = Block::from(Tune {
+        r#type: StreamOut::new(),
+    });
+    assert_eq!(b.stream_output_name_to_id("type"), Some(0));
+    assert_eq!(b.message_input_name_to_id("freq-hz"), Some(0));
+}
@@ -0,0 +1,110 @@
+use anyhow::Result;
+use futuresdr::blocks::Head;
+use futuresdr::blocks::NullSink;
+use futuresdr::blocks::NullSource;
+use futuresdr::macros::connect;
+use futuresdr::runtime::config;
+use futuresdr::runtime::config::BufferKind;
+use futuresdr::runtime::config::BufferProfile;
+use futuresdr::runtime::BlockT;
+use futuresdr::runtime::Error;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::Runtime;
+
+fn hot_profile() {
+    config::set_buffer_profile(
+        "hot",
+        BufferProfile {
+            kind: BufferKind::Slab,
+            min_bytes: Some(4096),
+            n_buffer: Some(4),
+            reserved_items: Some(0),
+        },
+    );
+}
+
+#[test]
+fn buffer_override_instance() -> Result<()> {
+    hot_profile();
+    config::set_buffer_override("override_src.out -> override_head.in", "hot")?;
+
+    let mut fg = Flowgraph::new();
+    let mut src = NullSource::<f32>::new();
+    src.set_instance_name("override_src");
+    let mut head = Head::<f32>::new(u64::MAX);
+    head.set_instance_name("override_head");
+    let snk = NullSink::<f32>::new();
+    connect!(fg, src > head > snk);
+
+    let rt = Runtime::new();
+    let (_task, mut handle) = rt.start_sync(fg);
+    let desc = rt.block_on(async move {
+        let desc = handle.description().await?;
+        handle.terminate_and_wait().await?;
+        Ok::<_, Error>(desc)
+    })?;
+
+    let src_buffer = desc
+        .stream_buffers
+        .iter()
+        .find(|(blk, port, _)| *blk == src && *port == 0)
+        .unwrap();
+    assert_eq!(
+        src_buffer.2,
+        "Slab (4096 bytes, 4 buffers, 0 reserved items)"
+    );
+    let head_buffer = desc
+        .stream_buffers
+        .iter()
+        .find(|(blk, port, _)| *blk == head && *port == 0)
+        .unwrap();
+    assert!(!head_buffer.2.starts_with("Slab"));
+    Ok(())
+}
+
+#[test]
+fn buffer_profile_connect() -> Result<()> {
+    hot_profile();
+
+    let mut fg = Flowgraph::new();
+    let src = NullSource::<u8>::new();
+    let head = Head::<u8>::new(u64::MAX);
+    let snk = NullSink::<u8>::new();
+    connect!(fg, src > head ["hot"] snk);
+
+    let rt = Runtime::new();
+    let (_task, mut handle) = rt.start_sync(fg);
+    let desc = rt.block_on(async move {
+        let desc = handle.description().await?;
+        handle.terminate_and_wait().await?;
+        Ok::<_, Error>(desc)
+    })?;
+
+    let head_buffer = desc
+        .stream_buffers
+        .iter()
+        .find(|(blk, port, _)| *blk == head && *port == 0)
+        .unwrap();
+    assert!(head_buffer.2.starts_with("Slab"));
+    Ok(())
+}
+
+#[test]
+fn buffer_profile_unknown() -> Result<()> {
+    let mut fg = Flowgraph::new();
+    let src = fg.add_block(NullSource::<u8>::new())?;
+    let snk = fg.add_block(NullSink::<u8>::new())?;
+
+    assert_eq!(
+        fg.connect_stream_with_profile(src, "out", snk, "in", "does-not-exist"),
+        Err(Error::InvalidBufferProfile("does-not-exist".to_string()))
+    );
+    assert_eq!(
+        config::set_buffer_override("src.out -> snk.in", "does-not-exist"),
+        Err(Error::InvalidBufferProfile("does-not-exist".to_string()))
+    );
+    assert!(config::config()
+        .buffer_override(&["src.out -> snk.in"])
+        .is_none());
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use anyhow::Result;
+use futuresdr::async_io::block_on;
+use futuresdr::blocks::MessagePipe;
+use futuresdr::futures::channel::mpsc;
+use futuresdr::futures::StreamExt;
+use futuresdr::runtime::config;
+use futuresdr::runtime::config::Value;
+use futuresdr::runtime::Error;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::Runtime;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[test]
+fn subscribe() {
+    let mut rx = config::subscribe("test_subscribe");
+    assert!(rx.try_recv().is_err());
+
+    config::set("test_subscribe", 2.5).unwrap();
+    config::set("test_subscribe", vec![1, 2]).unwrap();
+    config::set("test_other", true).unwrap();
+    // does not fit into a Pmt and is not sent
+    config::set("test_subscribe", u128::MAX).unwrap();
+
+    assert_eq!(rx.try_recv().unwrap(), Pmt::F64(2.5));
+    assert_eq!(
+        rx.try_recv().unwrap(),
+        Pmt::VecPmt(vec![Pmt::Isize(1), Pmt::Isize(2)])
+    );
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+fn connect_config() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let (tx, mut rx) = mpsc::channel(10);
+    let pipe = fg.add_block(MessagePipe::new(tx))?;
+    fg.connect_config("test_freq", pipe, "in")?;
+    assert!(fg.connect_config("test_freq", pipe, "foo").is_err());
+    assert!(matches!(
+        fg.connect_config("test_freq", pipe, 1),
+        Err(Error::InvalidMessagePort(_, _))
+    ));
+
+    let rt = Runtime::new();
+    let (task, mut handle) = rt.start_sync(fg);
+    block_on(async move {
+        config::set("test_freq", 100_000_000u64)?;
+        assert_eq!(rx.next().await, Some(Pmt::U64(100_000_000)));
+        config::set("test_freq", "2.4e9")?;
+        assert_eq!(rx.next().await, Some(Pmt::String("2.4e9".to_string())));
+
+        handle.terminate_and_wait().await?;
+        task.await?;
+        Ok(())
+    })
+}
+
+#[test]
+fn invalid_value() {
+    config::set("latency_interval", 0).unwrap();
+    assert!(matches!(
+        config::set("latency_interval", "many"),
+        Err(Error::InvalidConfig(k, _)) if k == "latency_interval"
+    ));
+    assert_eq!(config::config().latency_interval, 0);
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Source {
+    args: String,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct App {
+    gain: f64,
+    #[serde(default)]
+    channels: Vec<u32>,
+    source: Source,
+}
+
+#[test]
+fn section() {
+    let source = HashMap::from([("args".to_string(), "driver=dummy")]);
+    config::set(
+        "test_app",
+        HashMap::from([
+            ("gain".to_string(), Value::from(30.5)),
+            ("channels".to_string(), vec![1, 2].into()),
+            ("source".to_string(), source.into()),
+        ]),
+    .unwrap();
+
+    let app = config::section::<App>("test_app").unwrap();
+    assert_eq!(
+        app,
+        App {
+            gain: 30.5,
+            channels: vec![1, 2],
+            source: Source {
+                args: "driver=dummy".to_string()
+            },
+        }
+    );
+
+    config::set(
+        "test_invalid",
+        HashMap::from([
+            ("gain".to_string(), Value::from(1.0)),
+            ("channels".to_string(), vec!["a"].into()),
+        ]),
+    .unwrap();
+    match config::section::<App>("test_invalid") {
+        Err(Error::InvalidConfig(key, _)) => assert_eq!(key, "test_invalid.channels[0]"),
+        x => panic!("unexpected result {x:?}"),
+    }
+
+    #[derive(Debug, Default, Deserialize)]
+    #[serde(default)]
+    struct Defaults {
+        gain: f64,
+    }
+    assert_eq!(
+        config::section::<Defaults>("test_missing").unwrap().gain,
+        0.0
+    );
+    assert!(matches!(
+        config::section::<App>("test_missing"),
+        Err(Error::InvalidConfig(key, _)) if key == "test_missing"
+    ));
+}
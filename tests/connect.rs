@@ -0,0 +1,117 @@
+use anyhow::Result;
+use futuresdr::blocks::Combine;
+use futuresdr::blocks::Head;
+use futuresdr::blocks::MessageSink;
+use futuresdr::blocks::MessageSource;
+use futuresdr::blocks::NullSink;
+use futuresdr::blocks::StreamDeinterleaver;
+use futuresdr::blocks::StreamDuplicator;
+use futuresdr::blocks::VectorSink;
+use futuresdr::blocks::VectorSinkBuilder;
+use futuresdr::blocks::VectorSource;
+use futuresdr::macros::connect;
+use futuresdr::runtime::Error;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::Runtime;
+use std::time::Duration;
+
+#[test]
+fn connect_port_range() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let src = VectorSource::<u32>::new((0..1000).collect());
+    let dup = StreamDuplicator::<u32>::new(4);
+    let snks: Vec<_> = (0..4)
+        .map(|_| VectorSinkBuilder::<u32>::new().build())
+        .collect();
+
+    connect!(fg, src > dup; dup.out[0..4] > snks[..]);
+    assert_eq!(snks.len(), 4);
+
+    fg = Runtime::new().run(fg)?;
+    for snk in snks {
+        let snk = fg.kernel::<VectorSink<u32>>(snk).unwrap();
+        assert_eq!(snk.items(), &(0..1000).collect::<Vec<_>>());
+    }
+    Ok(())
+}
+
+#[test]
+fn connect_wildcard() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let src = VectorSource::<u32>::new((0..1000).collect());
+    let deint = StreamDeinterleaver::<u32>::new(2);
+    let add = Combine::new(|a: &u32, b: &u32| a + b);
+    let snk = VectorSinkBuilder::<u32>::new().build();
+
+    connect!(fg, src > deint; deint.out* > add.in*; add > snk);
+
+    fg = Runtime::new().run(fg)?;
+    let snk = fg.kernel::<VectorSink<u32>>(snk).unwrap();
+    assert_eq!(
+        snk.items(),
+        &(0..500).map(|i| 4 * i + 1).collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+#[test]
+fn connect_static_ranges() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let src = VectorSource::<u32>::new((0..1000).collect());
+    let dup = StreamDuplicator::<u32>::new(3);
+    let add = Combine::new(|a: &u32, b: &u32| a + b);
+    let snk = VectorSinkBuilder::<u32>::new().build();
+    let null = NullSink::<u32>::new();
+
+    connect!(fg, src > dup; dup.out[0..=1] > add.in[0..2]; dup.out2 > null; add > snk);
+
+    fg = Runtime::new().run(fg)?;
+    let snk = fg.kernel::<VectorSink<u32>>(snk).unwrap();
+    assert_eq!(snk.items(), &(0..1000).map(|i| 2 * i).collect::<Vec<_>>());
+    Ok(())
+}
+
+#[test]
+fn connect_count_mismatch() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let src = Head::<u32>::new(10);
+    let dup = StreamDuplicator::<u32>::new(4);
+    let snks = [NullSink::<u32>::new(), NullSink::new(), NullSink::new()];
+
+    let result = (|| -> Result<(), Error> {
+        connect!(fg, src > dup; dup.out* > snks[..]);
+        Ok(())
+    })();
+    assert_eq!(
+        result,
+        Err(Error::PortCountMismatch(
+            "dup.out*".to_string(),
+            4,
+            "snks[..].in".to_string(),
+            3
+        ))
+    );
+    Ok(())
+}
+
+#[test]
+fn connect_message_fan_in() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let srcs: Vec<_> = (0..3)
+        .map(|_| MessageSource::new(Pmt::Null, Duration::from_millis(10), Some(5)))
+        .collect();
+    let snk = MessageSink::new();
+
+    connect!(fg, srcs[..] | snk.in);
+
+    fg = Runtime::new().run(fg)?;
+    let snk = fg.kernel::<MessageSink>(snk).unwrap();
+    assert_eq!(snk.received(), 15);
+    Ok(())
+}
@@ -0,0 +1,160 @@
+use anyhow::Result;
+use futuresdr::blocks;
+use futuresdr::blocks::Head;
+use futuresdr::blocks::MessageSink;
+use futuresdr::blocks::NullSource;
+use futuresdr::blocks::StreamDuplicator;
+use futuresdr::blocks::VectorSink;
+use futuresdr::blocks::VectorSinkBuilder;
+use futuresdr::blocks::VectorSource;
+use futuresdr::runtime::registry::arg;
+use futuresdr::runtime::Block;
+use futuresdr::runtime::BlockRegistry;
+use futuresdr::runtime::Error;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::Runtime;
+
+fn registry() -> BlockRegistry {
+    let mut r = BlockRegistry::new();
+    blocks::register(&mut r);
+    r.register("VectorSink", |_| {
+        Ok(VectorSinkBuilder::<u32>::new().build().into())
+    });
+    r.register("Counter", |a| {
+        let n: usize = arg(a, "n")?;
+        Ok(VectorSource::<u32>::new((0..n as u32).collect()).into())
+    });
+    r
+}
+
+#[test]
+fn connect_existing_blocks() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let mut src = Block::from(NullSource::<u32>::new());
+    src.set_instance_name("src");
+    fg.add_block(src)?;
+    let mut head = Block::from(Head::<u32>::new(1234));
+    head.set_instance_name("head");
+    fg.add_block(head)?;
+    let mut snk = Block::from(VectorSinkBuilder::<u32>::new().build());
+    snk.set_instance_name("snk");
+    let snk = fg.add_block(snk)?;
+
+    fg.connect_str(&BlockRegistry::new(), "src > head; head.out > snk.in")?;
+
+    fg = Runtime::new().run(fg)?;
+    let snk = fg.kernel::<VectorSink<u32>>(snk).unwrap();
+    assert_eq!(snk.items().len(), 1234);
+    Ok(())
+}
+
+#[test]
+fn declare_blocks() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let mut dup = Block::from(StreamDuplicator::<u32>::new(2));
+    dup.set_instance_name("dup");
+    fg.add_block(dup)?;
+
+    fg.connect_str(
+        &registry(),
+        r#"
+        # a flowgraph in a text file
+        src = Counter(n = 100)
+        copy = Copy(type = "u32")
+        snk0 = VectorSink
+        snk1 = VectorSink()
+
+        src > copy >
+            dup                  // continued on the next line
+        dup.out0 > "snk0"; "dup".out[1..2] > snk1
+        "#,
+    )?;
+
+    let ids: Vec<usize> = ["snk0", "snk1"]
+        .iter()
+        .map(|n| fg.block_id(n).unwrap())
+        .collect();
+
+    fg = Runtime::new().run(fg)?;
+    let snk0 = fg.kernel::<VectorSink<u32>>(ids[0]).unwrap();
+    assert_eq!(snk0.items(), &(0..100).collect::<Vec<u32>>());
+    let snk1 = fg.kernel::<VectorSink<u32>>(ids[1]).unwrap();
+    assert_eq!(snk1.items(), &(0..100).collect::<Vec<u32>>());
+    Ok(())
+}
+
+#[test]
+fn message_connection() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    fg.connect_str(
+        &registry(),
+        "src = MessageSource(message = { \"U32\": 42 }, interval = 0.001, n = 5)
+         snk = MessageSink; src | snk",
+    )?;
+    let snk = fg.block_id("snk").unwrap();
+
+    fg = Runtime::new().run(fg)?;
+    let snk = fg.kernel::<MessageSink>(snk).unwrap();
+    assert_eq!(snk.received(), 5);
+    Ok(())
+}
+
+#[test]
+fn errors() {
+    fn err(s: &str) -> Error {
+        let mut fg = Flowgraph::new();
+        fg.connect_str(&registry(), s).unwrap_err()
+    }
+
+    assert_eq!(
+        err("src = NullSource\nsrc > snk"),
+        Error::DslError(2, 7, "Block 'snk' does not exist".to_string())
+    );
+    assert_eq!(
+        err("a = Foo(x = 1)"),
+        Error::DslError(1, 1, "Block type 'Foo' is not registered".to_string())
+    );
+    assert_eq!(
+        err("a = Head(n = 1)\n\n  a.out > ; "),
+        Error::DslError(3, 11, "Expected name, found ';'".to_string())
+    );
+    assert_eq!(
+        err("a = Head(n = 1,, )"),
+        Error::DslError(1, 16, "Expected name, found ','".to_string())
+    );
+    assert!(matches!(
+        err("a = NullSource\nb = NullSink(type = \"u8\")\na > b"),
+        Error::DslError(3, 3, msg) if msg.starts_with("Connect error")
+    ));
+}
+
+#[test]
+fn rollback_on_error() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let mut src = Block::from(VectorSource::<u32>::new((0..100).collect()));
+    src.set_instance_name("src");
+    fg.add_block(src)?;
+    let mut snk = Block::from(VectorSinkBuilder::<u32>::new().build());
+    snk.set_instance_name("snk");
+    let snk = fg.add_block(snk)?;
+
+    // fails after declaring a block and connecting existing ones
+    assert!(fg
+        .connect_str(
+            &registry(),
+            "copy = Copy(type = \"u32\")\nsrc > snk\nsrc > missing"
+        )
+        .is_err());
+    assert!(fg.block_id("copy").is_none());
+
+    fg.connect_str(&registry(), "copy = Copy(type = \"u32\")\nsrc > copy > snk")?;
+
+    fg = Runtime::new().run(fg)?;
+    let snk = fg.kernel::<VectorSink<u32>>(snk).unwrap();
+    assert_eq!(snk.items(), &(0..100).collect::<Vec<u32>>());
+    Ok(())
+}
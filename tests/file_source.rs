@@ -0,0 +1,58 @@
+use anyhow::Result;
+use futuresdr::blocks::FileSource;
+use futuresdr::runtime::Mocker;
+use futuresdr::runtime::Tag;
+use futuresdr::runtime::TimeSpec;
+
+#[test]
+fn rx_time_tags() -> Result<()> {
+    let path = std::env::temp_dir().join(format!("fsdr-file-source-{}.bin", std::process::id()));
+    let data: Vec<u32> = (0..16).collect();
+    std::fs::write(
+        &path,
+        data.iter()
+            .flat_map(|x| x.to_ne_bytes())
+            .collect::<Vec<u8>>(),
+    )?;
+
+    let start = TimeSpec::new(1_700_000_000, 0.25);
+    let src = FileSource::<u32>::with_start_time(path.to_str().unwrap(), true, start);
+
+    let mut mocker = Mocker::new(src);
+    mocker.init();
+    mocker.init_output::<u32>(0, 40);
+    mocker.run();
+    let (output, tags) = mocker.output::<u32>(0);
+    std::fs::remove_file(&path)?;
+
+    assert_eq!(output.len(), 40);
+    assert_eq!(&output[16..32], &data[..]);
+
+    // first sample and first sample of each repetition of the file
+    let times: Vec<(usize, TimeSpec)> = tags
+        .iter()
+        .filter_map(|t| t.tag.time(Tag::RX_TIME).map(|time| (t.index, time)))
+        .collect();
+    assert_eq!(times, vec![(0, start), (16, start), (32, start)]);
+    Ok(())
+}
+
+#[test]
+fn no_tags_without_start_time() -> Result<()> {
+    let path = std::env::temp_dir().join(format!(
+        "fsdr-file-source-untagged-{}.bin",
+        std::process::id()
+    ));
+    std::fs::write(&path, [0u8; 64])?;
+
+    let mut mocker = Mocker::new(FileSource::<u32>::new(path.to_str().unwrap(), false));
+    mocker.init();
+    mocker.init_output::<u32>(0, 32);
+    mocker.run();
+    let (output, tags) = mocker.output::<u32>(0);
+    std::fs::remove_file(&path)?;
+
+    assert_eq!(output.len(), 16);
+    assert!(tags.is_empty());
+    Ok(())
+}
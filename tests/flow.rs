@@ -1,15 +1,19 @@
 use anyhow::Result;
+use futuresdr::blocks::Apply;
 use futuresdr::blocks::Copy;
 use futuresdr::blocks::Head;
 use futuresdr::blocks::NullSink;
 use futuresdr::blocks::NullSource;
 use futuresdr::blocks::VectorSink;
 use futuresdr::blocks::VectorSinkBuilder;
+use futuresdr::macros::connect;
+use futuresdr::runtime::scheduler::BlockPinning;
+use futuresdr::runtime::scheduler::CpuPinScheduler;
 use futuresdr::runtime::scheduler::FlowScheduler;
 use futuresdr::runtime::BlockT;
 use futuresdr::runtime::Flowgraph;
 use futuresdr::runtime::Runtime;
-use futuresdr_macros::connect;
+use std::time::Duration;
 
 #[test]
 fn flowgraph_flow() -> Result<()> {
@@ -58,3 +62,78 @@ fn flowgraph_instance_name() -> Result<()> {
     assert_eq!(desc.blocks.first().unwrap().instance_name, name);
     Ok(())
 }
+
+#[test]
+fn flowgraph_pinning() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let mut src = NullSource::<f32>::new();
+    src.set_instance_name("src");
+    let head = Head::<f32>::new(100_000);
+    let copy0 = Copy::<f32>::new();
+    let copy1 = Copy::<f32>::new();
+    let snk = VectorSinkBuilder::<f32>::new().build();
+    connect!(fg, src > head > copy0 > copy1 > snk);
+
+    let pinning = BlockPinning::new()
+        .pin("src", 0)
+        .pin_set("Copy", [0, 1])
+        .pin("unknown", 0);
+    let pins = pinning.assignment(&fg, 2);
+    assert_eq!(pins.len(), 3);
+    assert_eq!(pins[&src], 0);
+    assert_eq!(pins[&copy0], 0);
+    assert_eq!(pins[&copy1], 1);
+
+    // workers that do not exist are ignored
+    assert!(BlockPinning::new()
+        .pin("src", 1)
+        .assignment(&fg, 1)
+        .is_empty());
+
+    let pinning = BlockPinning::new().pin("src", 0).pin("Copy", 0);
+    fg = Runtime::with_scheduler(CpuPinScheduler::with_pinning(pinning)).run(fg)?;
+    let snk = fg.kernel::<VectorSink<f32>>(snk).unwrap();
+    assert_eq!(snk.items().len(), 100_000);
+
+    Ok(())
+}
+
+#[test]
+fn flowgraph_adaptive() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let src = NullSource::<f32>::new();
+    let head = Head::<f32>::new(2_000_000);
+    let heavy = Apply::new(|x: &f32| (0..50).fold(*x, |a, i| (a + i as f32).sin()));
+    let copy = Copy::<f32>::new();
+    let snk = VectorSinkBuilder::<f32>::new().build();
+    connect!(fg, src > head > heavy > copy > snk);
+
+    // the heavy block starts on the first worker, together with the source and head
+    let scheduler =
+        FlowScheduler::with_workers(2, BlockPinning::new()).adaptive(Duration::from_millis(5));
+    fg = Runtime::with_scheduler(scheduler.clone()).run(fg)?;
+
+    let snk = fg.kernel::<VectorSink<f32>>(snk).unwrap();
+    assert_eq!(snk.items().len(), 2_000_000);
+
+    let migrations = scheduler.migrations();
+    assert!(!migrations.is_empty());
+    for m in migrations.iter() {
+        assert_ne!(m.from, m.to);
+        assert!(m.worker_loads[m.from] > m.worker_loads[m.to]);
+    }
+
+    let stats = scheduler.block_stats();
+    assert_eq!(stats.len(), 5);
+    assert_eq!(
+        stats.iter().map(|s| s.migrations).sum::<usize>(),
+        migrations.len()
+    );
+    assert!(stats.iter().any(|s| s.busy > Duration::ZERO));
+
+    // slots of migrated blocks are reused
+    assert!(scheduler.local_slots() <= 2 * stats.len());
+    Ok(())
+}
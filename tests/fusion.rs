@@ -0,0 +1,178 @@
+use anyhow::Result;
+use futuresdr::async_io::block_on;
+use futuresdr::blocks::Apply;
+use futuresdr::blocks::Copy;
+use futuresdr::blocks::Head;
+use futuresdr::blocks::NullSink;
+use futuresdr::blocks::NullSource;
+use futuresdr::blocks::VectorSink;
+use futuresdr::blocks::VectorSinkBuilder;
+use futuresdr::futures::future::poll_fn;
+use futuresdr::macros::connect;
+use futuresdr::macros::message_handler;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::Runtime;
+use futuresdr::runtime::StreamIo;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::SyncKernel;
+use futuresdr::runtime::TypedBlock;
+use futuresdr::runtime::WorkIo;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Poll;
+use std::task::Waker;
+
+/// Copies samples and records the waker of the task that calls its message handler
+struct Probe {
+    waker: Arc<Mutex<Option<Waker>>>,
+}
+
+impl Probe {
+    fn new(waker: Arc<Mutex<Option<Waker>>>) -> TypedBlock<Self> {
+        TypedBlock::new(
+            BlockMetaBuilder::new("Probe").build(),
+            StreamIoBuilder::new()
+                .add_input::<f32>("in")
+                .add_output::<f32>("out")
+                .build(),
+            MessageIoBuilder::new()
+                .add_input("waker", Self::waker)
+                .build(),
+            Probe { waker },
+        )
+    }
+
+    #[message_handler]
+    async fn waker(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+        _p: Pmt,
+    ) -> Result<Pmt> {
+        let waker = poll_fn(|cx| Poll::Ready(cx.waker().clone())).await;
+        *self.waker.lock().unwrap() = Some(waker);
+        Ok(Pmt::Ok)
+    }
+}
+
+impl SyncKernel for Probe {
+    fn work(
+        &mut self,
+        io: &mut WorkIo,
+        sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> futuresdr::runtime::Result<()> {
+        let i = sio.input(0).slice::<f32>();
+        let o = sio.output(0).slice::<f32>();
+
+        let m = std::cmp::min(i.len(), o.len());
+        o[..m].copy_from_slice(&i[..m]);
+        sio.input(0).consume(m);
+        sio.output(0).produce(m);
+
+        if sio.input(0).finished() && m == i.len() {
+            io.finished = true;
+        }
+        Ok(())
+    }
+}
+
+/// Wakers of the tasks that run two connected probes
+fn probe_wakers(fuse: bool) -> Result<(Waker, Waker)> {
+    let mut fg = Flowgraph::new();
+
+    let w0 = Arc::new(Mutex::new(None));
+    let w1 = Arc::new(Mutex::new(None));
+    let src = NullSource::<f32>::new();
+    let p0 = Probe::new(w0.clone());
+    let p1 = Probe::new(w1.clone());
+    let snk = NullSink::<f32>::new();
+
+    connect!(fg, src > p0 > p1 > snk);
+    if fuse {
+        assert_eq!(fg.optimize(), vec![vec![p0, p1]]);
+    }
+
+    let rt = Runtime::new();
+    let (task, mut handle) = rt.start_sync(fg);
+    block_on(async move {
+        assert_eq!(handle.callback(p0, "waker", Pmt::Null).await?, Pmt::Ok);
+        assert_eq!(handle.callback(p1, "waker", Pmt::Null).await?, Pmt::Ok);
+        handle.terminate_and_wait().await?;
+        task.await?;
+        Ok::<_, anyhow::Error>(())
+    })?;
+
+    let w0 = w0.lock().unwrap().take().unwrap();
+    let w1 = w1.lock().unwrap().take().unwrap();
+    Ok((w0, w1))
+}
+
+#[test]
+fn fuse_chain() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let src = NullSource::<f32>::new();
+    let head = Head::<f32>::new(100_000);
+    let add = Apply::new(|x: &f32| x + 1.0);
+    let copy = Copy::<f32>::new();
+    let mul = Apply::new(|x: &f32| x * 2.0);
+    let snk = VectorSinkBuilder::<f32>::new().build();
+
+    connect!(fg, src > head > add > copy > mul > snk);
+
+    // the vector sink is not synchronous and the source has no input
+    assert_eq!(fg.optimize(), vec![vec![head, add, copy, mul]]);
+    assert!(fg.optimize().is_empty());
+
+    let rt = Runtime::new();
+    let (task, mut handle) = rt.start_sync(fg);
+    let (desc, fg) = block_on(async move {
+        let desc = handle.description().await?;
+        Ok::<_, anyhow::Error>((desc, task.await?))
+    })?;
+    assert_eq!(desc.blocks.len(), 6);
+    assert!(desc
+        .stream_buffers
+        .iter()
+        .any(|(s, _, b)| *s == add && b.contains("16384")));
+
+    let snk = fg.kernel::<VectorSink<f32>>(snk).unwrap();
+    assert_eq!(snk.items().len(), 100_000);
+    assert!(snk.items().iter().all(|x| *x == 2.0));
+    Ok(())
+}
+
+#[test]
+fn no_fusion_of_fan_out() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let src = NullSource::<f32>::new();
+    let head = Head::<f32>::new(1000);
+    let copy = Copy::<f32>::new();
+    let snk0 = VectorSinkBuilder::<f32>::new().build();
+    let snk1 = VectorSinkBuilder::<f32>::new().build();
+
+    connect!(fg, src > head > copy > snk0; copy > snk1);
+
+    assert_eq!(fg.optimize(), vec![vec![head, copy]]);
+    Runtime::new().run(fg)?;
+    Ok(())
+}
+
+#[test]
+fn fused_chain_is_one_task() -> Result<()> {
+    let (w0, w1) = probe_wakers(true)?;
+    assert!(w0.will_wake(&w1));
+
+    let (w0, w1) = probe_wakers(false)?;
+    assert!(!w0.will_wake(&w1));
+    Ok(())
+}
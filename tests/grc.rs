@@ -0,0 +1,66 @@
+use anyhow::Result;
+use futuresdr::blocks;
+use futuresdr::runtime::BlockRegistry;
+use futuresdr::runtime::Grc;
+use futuresdr::runtime::Runtime;
+
+const GRC: &str = r#"
+blocks:
+- name: samp_rate
+  id: variable
+  parameters:
+    value: '32000'
+- name: src
+  id: analog_sig_source_x
+  parameters:
+    amp: '1'
+    freq: '1000'
+    offset: '0'
+    phase: '0'
+    samp_rate: samp_rate
+    type: complex
+    waveform: analog.GR_COS_WAVE
+- name: lpf
+  id: low_pass_filter
+  parameters:
+    beta: '6.76'
+    cutoff_freq: samp_rate / 8
+    decim: '2'
+    gain: '1'
+    interp: '1'
+    samp_rate: samp_rate
+    type: fir_filter_ccf
+    width: samp_rate / 16
+    win: window.WIN_HAMMING
+- name: head
+  id: blocks_head
+  parameters:
+    num_items: '4096'
+    type: complex
+    vlen: '1'
+- name: gui
+  id: qtgui_freq_sink_x
+  parameters:
+    nconnections: '1'
+    type: complex
+connections:
+- [src, '0', lpf, '0']
+- [lpf, '0', head, '0']
+- [head, '0', gui, '0']
+"#;
+
+#[test]
+fn run_imported() -> Result<()> {
+    let grc = Grc::parse(GRC)?;
+    assert!(grc.report().is_complete());
+    assert_eq!(grc.report().notes.len(), 1);
+
+    let mut registry = BlockRegistry::new();
+    blocks::register(&mut registry);
+    let fg = grc.to_flowgraph(&registry)?;
+    assert!(fg.block_id("lpf").is_some());
+
+    // terminates once head has forwarded all items
+    Runtime::new().run(fg)?;
+    Ok(())
+}
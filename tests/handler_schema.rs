@@ -0,0 +1,122 @@
+use anyhow::Result;
+use futuresdr::async_io::block_on;
+use futuresdr::macros::message_handler;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::HandlerSchema;
+use futuresdr::runtime::Kernel;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Pmt;
+use futuresdr::runtime::PmtKind;
+use futuresdr::runtime::Runtime;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::TypedBlock;
+use futuresdr::runtime::WorkIo;
+
+struct Tuner {
+    freq: f64,
+}
+
+impl Tuner {
+    fn new() -> TypedBlock<Self> {
+        TypedBlock::new(
+            BlockMetaBuilder::new("Tuner").build(),
+            StreamIoBuilder::new().build(),
+            MessageIoBuilder::new()
+                .add_input_with_schema("freq", Self::freq_schema(), Self::freq)
+                .add_input_with_schema(
+                    "reset",
+                    HandlerSchema::new().accepts([PmtKind::Null]),
+                    Self::reset,
+                )
+                .add_input("plain", Self::reset)
+                .build(),
+            Self { freq: 0.0 },
+        )
+    }
+
+    #[message_handler(
+        accepts(F64, U32, Null),
+        returns(Ok, F64),
+        range(70e6, 6e9),
+        description = "Center frequency in Hz"
+    )]
+    async fn freq(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null => Ok(Pmt::F64(self.freq)),
+            p => {
+                self.freq = p.try_into()?;
+                Ok(Pmt::Ok)
+            }
+        }
+    }
+
+    #[message_handler]
+    async fn reset(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+        _p: Pmt,
+    ) -> Result<Pmt> {
+        self.freq = 0.0;
+        Ok(Pmt::Ok)
+    }
+}
+
+impl Kernel for Tuner {}
+
+#[test]
+fn handler_schema_attribute() {
+    let schema = Tuner::freq_schema();
+    assert_eq!(
+        schema,
+        HandlerSchema {
+            accepts: vec![PmtKind::F64, PmtKind::U32, PmtKind::Null],
+            returns: vec![PmtKind::Ok, PmtKind::F64],
+            min: Some(70e6),
+            max: Some(6e9),
+            description: Some("Center frequency in Hz".to_string()),
+        }
+    );
+
+    assert!(schema.validate(&Pmt::Null).is_ok());
+    assert!(schema.validate(&Pmt::U32(100_000_000)).is_ok());
+    assert!(schema.validate(&Pmt::F64(1.0)).is_err());
+    assert!(schema.validate(&Pmt::F32(100e6)).is_err());
+}
+
+#[test]
+fn handler_schema_description() -> Result<()> {
+    let mut fg = Flowgraph::new();
+    let tuner = fg.add_block(Tuner::new())?;
+
+    let rt = Runtime::new();
+    let (task, mut handle) = rt.start_sync(fg);
+    block_on(async move {
+        let desc = handle.block_description(tuner).await?;
+        assert_eq!(desc.message_inputs, vec!["freq", "reset", "plain"]);
+        assert_eq!(desc.message_input_schemas.len(), 3);
+        assert_eq!(
+            desc.message_input_schema("freq"),
+            Some(&Tuner::freq_schema())
+        );
+        assert_eq!(
+            desc.message_input_schema("reset").unwrap().accepts,
+            vec![PmtKind::Null]
+        );
+        assert!(desc.message_input_schema("plain").is_none());
+
+        handle.terminate_and_wait().await?;
+        task.await?;
+        Ok(())
+    })
+}
@@ -0,0 +1,60 @@
+use anyhow::Result;
+use futuresdr::blocks::Copy;
+use futuresdr::blocks::Head;
+use futuresdr::blocks::NullSink;
+use futuresdr::blocks::NullSource;
+use futuresdr::macros::connect;
+use futuresdr::runtime::config;
+use futuresdr::runtime::Error;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::Runtime;
+use std::time::Duration;
+
+/// Restores the latency interval of the process-wide config, also if the test fails
+struct LatencyInterval(u64);
+
+impl LatencyInterval {
+    fn set(interval: u64) -> Result<Self> {
+        let previous = config::config().latency_interval as u64;
+        config::set("latency_interval", interval)?;
+        Ok(Self(previous))
+    }
+}
+
+impl Drop for LatencyInterval {
+    fn drop(&mut self) {
+        let _ = config::set("latency_interval", self.0);
+    }
+}
+
+#[test]
+fn latency_probes() -> Result<()> {
+    let _interval = LatencyInterval::set(4096)?;
+
+    let mut fg = Flowgraph::new();
+    let src = NullSource::<f32>::new();
+    let head = Head::<f32>::new(u64::MAX);
+    let copy = Copy::<f32>::new();
+    let snk = NullSink::<f32>::new();
+    connect!(fg, src > head > copy > snk);
+
+    let rt = Runtime::new();
+    let (_task, mut handle) = rt.start_sync(fg);
+    let latency = rt.block_on(async move {
+        loop {
+            let l = handle.latency().await?;
+            if l.get(&snk).is_some_and(|h| h.count > 10) {
+                handle.terminate_and_wait().await?;
+                break Ok::<_, Error>(l);
+            }
+            futuresdr::async_io::Timer::after(Duration::from_millis(10)).await;
+        }
+    })?;
+
+    assert_eq!(latency.len(), 1);
+    let h = latency.get(&snk).unwrap();
+    assert!(h.min_ns <= h.max_ns);
+    assert!(h.mean().is_some());
+    assert_eq!(h.buckets.iter().sum::<u64>(), h.count);
+    Ok(())
+}
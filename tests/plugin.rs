@@ -0,0 +1,59 @@
+use futuresdr::runtime::plugin;
+use futuresdr::runtime::plugin::PluginDeclaration;
+use futuresdr::runtime::BlockRegistry;
+use futuresdr::runtime::Error;
+
+#[test]
+fn missing_library() {
+    let mut registry = BlockRegistry::new();
+    let e = unsafe { plugin::load("does-not-exist.so", &mut registry) }.unwrap_err();
+    assert!(matches!(e, Error::PluginError(p, _) if p == "does-not-exist.so"));
+}
+
+#[test]
+fn empty_dir() {
+    let dir = std::env::temp_dir().join(format!("futuresdr-plugins-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("README.txt"), "not a plugin").unwrap();
+
+    let mut registry = BlockRegistry::new();
+    let plugins = unsafe { plugin::load_dir(&dir, &mut registry) }.unwrap();
+    assert!(plugins.is_empty());
+    assert_eq!(registry.type_names().count(), 0);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(unsafe { plugin::load_dir(&dir, &mut registry) }.is_err());
+}
+
+fn declaration() -> PluginDeclaration {
+    PluginDeclaration {
+        abi_version: plugin::ABI_VERSION,
+        rustc_version: plugin::RUSTC_VERSION,
+        futuresdr_version: plugin::FUTURESDR_VERSION,
+        build_fingerprint: plugin::BUILD_FINGERPRINT,
+        name: "test",
+        version: "0.0.0",
+        register: |_| {},
+    }
+}
+
+#[test]
+fn check_declaration() {
+    assert!(declaration().check().is_ok());
+
+    let mut d = declaration();
+    d.abi_version += 1;
+    assert!(d.check().unwrap_err().contains("ABI version"));
+
+    let mut d = declaration();
+    d.rustc_version = "rustc 0.0.0";
+    assert!(d.check().unwrap_err().contains("rustc 0.0.0"));
+
+    let mut d = declaration();
+    d.futuresdr_version = "0.0.0";
+    assert!(d.check().unwrap_err().contains("FutureSDR 0.0.0"));
+
+    let mut d = declaration();
+    d.build_fingerprint = "0000000000000000";
+    assert!(d.check().unwrap_err().contains("fingerprint"));
+}
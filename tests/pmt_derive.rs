@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+
+use futuresdr::macros::FromPmt;
+use futuresdr::macros::IntoPmt;
+use futuresdr::runtime::FromPmtError;
+use futuresdr::runtime::Pmt;
+
+#[derive(Debug, Clone, PartialEq, IntoPmt, FromPmt)]
+struct Config {
+    freq: f64,
+    #[pmt(rename = "sample_rate")]
+    rate: u32,
+    gain: Option<f32>,
+    #[pmt(default)]
+    antenna: String,
+    payload: Vec<u8>,
+    channels: Vec<Channel>,
+    filter: Filter,
+}
+
+#[derive(Debug, Clone, PartialEq, IntoPmt, FromPmt)]
+struct Filter {
+    taps: Vec<f32>,
+    decimation: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, IntoPmt, FromPmt)]
+enum Channel {
+    Off,
+    Rx(u32),
+    Tx { port: u32, power: f32 },
+    Pair(u32, u32),
+}
+
+#[derive(Debug, Clone, PartialEq, IntoPmt, FromPmt)]
+struct Id(u64);
+
+#[derive(Debug, Clone, PartialEq, IntoPmt, FromPmt)]
+struct Wrapper<T> {
+    value: T,
+}
+
+fn config() -> Config {
+    Config {
+        freq: 2.45e9,
+        rate: 1_000_000,
+        gain: None,
+        antenna: "RX2".to_string(),
+        payload: vec![1, 2, 3],
+        channels: vec![
+            Channel::Off,
+            Channel::Rx(1),
+            Channel::Tx {
+                port: 2,
+                power: 0.5,
+            },
+            Channel::Pair(3, 4),
+        ],
+        filter: Filter {
+            taps: vec![0.25, 0.5, 0.25],
+            decimation: 4,
+        },
+    }
+}
+
+#[test]
+fn round_trip() {
+    let c = config();
+    let p = Pmt::from(c.clone());
+    assert_eq!(Config::try_from(p), Ok(c));
+
+    assert_eq!(Pmt::from(Id(7)), Pmt::U64(7));
+    assert_eq!(Id::try_from(Pmt::U32(7)), Ok(Id(7)));
+
+    let w = Wrapper {
+        value: vec!["a".to_string()],
+    };
+    assert_eq!(Wrapper::try_from(Pmt::from(w.clone())), Ok(w));
+}
+
+#[test]
+fn pmt_layout() {
+    let p = Pmt::from(config());
+    let Pmt::MapStrPmt(m) = p else {
+        panic!("expected map");
+    };
+
+    assert_eq!(m.get("freq"), Some(&Pmt::F64(2.45e9)));
+    assert_eq!(m.get("sample_rate"), Some(&Pmt::U32(1_000_000)));
+    assert_eq!(m.get("gain"), Some(&Pmt::Null));
+    assert_eq!(m.get("payload"), Some(&Pmt::Blob(vec![1, 2, 3])));
+    assert_eq!(
+        m.get("channels"),
+        Some(&Pmt::VecPmt(vec![
+            Pmt::String("Off".to_string()),
+            Pmt::MapStrPmt(HashMap::from([("Rx".to_string(), Pmt::U32(1))])),
+            Pmt::MapStrPmt(HashMap::from([(
+                "Tx".to_string(),
+                Pmt::MapStrPmt(HashMap::from([
+                    ("port".to_string(), Pmt::U32(2)),
+                    ("power".to_string(), Pmt::F32(0.5)),
+                ]))
+            )])),
+            Pmt::MapStrPmt(HashMap::from([(
+                "Pair".to_string(),
+                Pmt::VecPmt(vec![Pmt::U32(3), Pmt::U32(4)])
+            )])),
+        ]))
+    );
+    assert_eq!(
+        m.get("filter"),
+        Some(&Pmt::MapStrPmt(HashMap::from([
+            ("taps".to_string(), Pmt::VecF32(vec![0.25, 0.5, 0.25])),
+            ("decimation".to_string(), Pmt::Usize(4)),
+        ])))
+    );
+}
+
+#[test]
+fn missing_fields() {
+    let Pmt::MapStrPmt(mut m) = Pmt::from(config()) else {
+        panic!("expected map");
+    };
+    m.remove("gain");
+    m.remove("antenna");
+    let c = Config::try_from(Pmt::MapStrPmt(m.clone())).unwrap();
+    assert_eq!(c.gain, None);
+    assert_eq!(c.antenna, "");
+
+    m.remove("freq");
+    assert_eq!(
+        Config::try_from(Pmt::MapStrPmt(m)).unwrap_err(),
+        FromPmtError::missing("freq")
+    );
+}
+
+#[test]
+fn errors() {
+    fn err(c: impl FnOnce(&mut HashMap<String, Pmt>)) -> String {
+        let Pmt::MapStrPmt(mut m) = Pmt::from(config()) else {
+            panic!("expected map");
+        };
+        c(&mut m);
+        Config::try_from(Pmt::MapStrPmt(m)).unwrap_err().to_string()
+    }
+
+    assert_eq!(
+        err(|m| {
+            m.insert("sample_rate".to_string(), Pmt::U64(1 << 40));
+        }),
+        "sample_rate: expected u32, found U64 (1099511627776)"
+    );
+    assert_eq!(
+        err(|m| {
+            m.insert("filter".to_string(), Pmt::Null);
+        }),
+        "filter: expected MapStrPmt (Filter), found Null"
+    );
+    assert_eq!(
+        err(|m| {
+            let Some(Pmt::VecPmt(v)) = m.get_mut("channels") else {
+                panic!("expected vec");
+            };
+            v[2] = Pmt::MapStrPmt(HashMap::from([(
+                "Tx".to_string(),
+                Pmt::MapStrPmt(HashMap::from([
+                    ("port".to_string(), Pmt::U32(2)),
+                    ("power".to_string(), Pmt::String("high".to_string())),
+                ])),
+            )]));
+        }),
+        "channels[2].Tx.power: expected f32, found String (high)"
+    );
+    assert_eq!(
+        err(|m| {
+            let Some(Pmt::VecPmt(v)) = m.get_mut("channels") else {
+                panic!("expected vec");
+            };
+            v[0] = Pmt::String("On".to_string());
+        }),
+        "channels[0]: expected variant of Channel, found String (On)"
+    );
+    assert_eq!(
+        err(|m| {
+            m.insert(
+                "payload".to_string(),
+                Pmt::VecPmt(vec![Pmt::U32(1), Pmt::U32(300)]),
+            );
+        }),
+        "payload[1]: expected u8, found U32 (300)"
+    );
+}
@@ -8,7 +8,10 @@ use futuresdr::blocks::Throttle;
 use futuresdr::blocks::VectorSink;
 use futuresdr::blocks::VectorSinkBuilder;
 use futuresdr::blocks::VectorSource;
+use futuresdr::runtime::scheduler::SmolScheduler;
+use futuresdr::runtime::Block;
 use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::Priority;
 use futuresdr::runtime::Runtime;
 use std::iter::repeat_with;
 
@@ -145,3 +148,33 @@ fn fg_rand_vec_multi_snk() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn fg_priorities() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let mut snks = Vec::new();
+    for priority in [Priority::Realtime, Priority::Normal, Priority::Background] {
+        let mut src = Block::from_typed(NullSource::<f32>::new());
+        src.set_priority(priority);
+        let mut head = Block::from_typed(Head::<f32>::new(100_000));
+        head.set_priority(priority);
+        let snk = VectorSinkBuilder::<f32>::new().build();
+
+        let src = fg.add_block(src)?;
+        let head = fg.add_block(head)?;
+        let snk = fg.add_block(snk)?;
+        fg.connect_stream(src, "out", head, "in")?;
+        fg.connect_stream(head, "out", snk, "in")?;
+        snks.push(snk);
+    }
+
+    // a single worker has to serve all classes
+    fg = Runtime::with_scheduler(SmolScheduler::new(1, false)).run(fg)?;
+
+    for snk in snks {
+        let snk = fg.kernel::<VectorSink<f32>>(snk).unwrap();
+        assert_eq!(snk.items().len(), 100_000);
+    }
+    Ok(())
+}
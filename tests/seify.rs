@@ -224,3 +224,40 @@ fn sink_config_cmd_map() -> Result<()> {
     }
     Ok(())
 }
+
+/// [`Config`] round trip through a [`Pmt::MapStrPmt`]
+#[test]
+fn config_pmt() -> Result<()> {
+    let cfg = Config {
+        freq: Some(102e6),
+        antenna: Some("RX2".to_string()),
+        ..Config::new()
+    };
+    let pmt = cfg.to_pmt();
+    match &pmt {
+        Pmt::MapStrPmt(m) => {
+            assert_eq!(m.get("freq").unwrap(), &Pmt::F64(102e6));
+            assert_eq!(m.get("antenna").unwrap(), &Pmt::String("RX2".to_string()));
+            assert_eq!(m.get("gain").unwrap(), &Pmt::Null);
+        }
+        o => panic!("unexpected pmt type {o:?}"),
+    }
+
+    let cfg: Config = pmt.try_into()?;
+    assert_eq!(cfg.freq, Some(102e6));
+    assert_eq!(cfg.antenna.as_deref(), Some("RX2"));
+    assert_eq!(cfg.gain, None);
+
+    let pmt = Pmt::MapStrPmt(HashMap::from([("gain".to_owned(), Pmt::U32(2))]));
+    let cfg: Config = pmt.try_into()?;
+    assert_eq!(cfg.gain, Some(2.0));
+    assert_eq!(cfg.freq, None);
+
+    let pmt = Pmt::Any(Box::new(Config {
+        gain: Some(3.0),
+        ..Config::new()
+    }));
+    let cfg: Config = pmt.try_into()?;
+    assert_eq!(cfg.gain, Some(3.0));
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use anyhow::Result;
+use futuresdr::blocks::Head;
+use futuresdr::blocks::NullSource;
+use futuresdr::blocks::VectorSink;
+use futuresdr::blocks::VectorSinkBuilder;
+use futuresdr::macros::connect;
+use futuresdr::runtime::Block;
+use futuresdr::runtime::BlockMeta;
+use futuresdr::runtime::BlockMetaBuilder;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::MessageIo;
+use futuresdr::runtime::MessageIoBuilder;
+use futuresdr::runtime::Runtime;
+use futuresdr::runtime::StreamIo;
+use futuresdr::runtime::StreamIoBuilder;
+use futuresdr::runtime::SyncKernel;
+use futuresdr::runtime::TypedBlock;
+use futuresdr::runtime::WorkIo;
+
+struct AddOne {
+    initialized: bool,
+}
+
+impl AddOne {
+    fn new() -> TypedBlock<Self> {
+        TypedBlock::new(
+            BlockMetaBuilder::new("AddOne").build(),
+            StreamIoBuilder::new()
+                .add_input::<f32>("in")
+                .add_output::<f32>("out")
+                .build(),
+            MessageIoBuilder::new().build(),
+            AddOne { initialized: false },
+        )
+    }
+}
+
+impl SyncKernel for AddOne {
+    fn init(
+        &mut self,
+        _s: &mut StreamIo,
+        _m: &mut MessageIo<Self>,
+        _b: &mut BlockMeta,
+    ) -> futuresdr::runtime::Result<()> {
+        self.initialized = true;
+        Ok(())
+    }
+
+    fn work(
+        &mut self,
+        io: &mut WorkIo,
+        sio: &mut StreamIo,
+        _mio: &mut MessageIo<Self>,
+        _meta: &mut BlockMeta,
+    ) -> futuresdr::runtime::Result<()> {
+        assert!(self.initialized);
+        let i = sio.input(0).slice::<f32>();
+        let o = sio.output(0).slice::<f32>();
+
+        let m = std::cmp::min(i.len(), o.len());
+        for (v, r) in i.iter().zip(o.iter_mut()).take(m) {
+            *r = v + 1.0;
+        }
+        sio.input(0).consume(m);
+        sio.output(0).produce(m);
+
+        if sio.input(0).finished() && m == i.len() {
+            io.finished = true;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn sync_kernel() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let src = NullSource::<f32>::new();
+    let head = Head::<f32>::new(100_000);
+    let add = AddOne::new();
+    let snk = VectorSinkBuilder::<f32>::new().build();
+
+    assert!(Block::from_typed(AddOne::new()).is_sync());
+    assert!(!Block::from_typed(VectorSinkBuilder::<f32>::new().build()).is_sync());
+
+    connect!(fg, src > head > add > snk);
+    fg = Runtime::new().run(fg)?;
+
+    let snk = fg.kernel::<VectorSink<f32>>(snk).unwrap();
+    assert_eq!(snk.items().len(), 100_000);
+    assert!(snk.items().iter().all(|x| *x == 1.0));
+    Ok(())
+}
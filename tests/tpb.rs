@@ -4,8 +4,11 @@ use futuresdr::blocks::Head;
 use futuresdr::blocks::NullSource;
 use futuresdr::blocks::VectorSink;
 use futuresdr::blocks::VectorSinkBuilder;
+use futuresdr::runtime::scheduler::ThreadPriority;
 use futuresdr::runtime::scheduler::TpbScheduler;
+use futuresdr::runtime::Block;
 use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::Priority;
 use futuresdr::runtime::Runtime;
 
 #[test]
@@ -38,3 +41,35 @@ fn flowgraph_tpb() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn tpb_priorities() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    let mut copy = Block::from_typed(Copy::<f32>::new());
+    copy.set_priority(Priority::Realtime);
+    let mut head = Block::from_typed(Head::<f32>::new(100_000));
+    head.set_priority(Priority::Background);
+    let null_source = NullSource::<f32>::new();
+    let vect_sink = VectorSinkBuilder::<f32>::new().build();
+
+    let copy = fg.add_block(copy)?;
+    let head = fg.add_block(head)?;
+    let null_source = fg.add_block(null_source)?;
+    let vect_sink = fg.add_block(vect_sink)?;
+
+    fg.connect_stream(null_source, "out", head, "in")?;
+    fg.connect_stream(head, "out", copy, "in")?;
+    fg.connect_stream(copy, "out", vect_sink, "in")?;
+
+    // requesting SCHED_FIFO falls back to default scheduling without permission
+    let scheduler = TpbScheduler::new()
+        .thread_priority(Priority::Realtime, ThreadPriority::Fifo(10))
+        .thread_priority(Priority::Background, ThreadPriority::Nice(5));
+    fg = Runtime::with_scheduler(scheduler).run(fg)?;
+
+    let snk = fg.kernel::<VectorSink<f32>>(vect_sink).unwrap();
+    assert_eq!(snk.items().len(), 100_000);
+
+    Ok(())
+}